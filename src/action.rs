@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use crossterm::event::KeyEvent;
 use serde::{Deserialize, Serialize};
 use strum::Display;
@@ -15,9 +17,14 @@ pub enum Action {
     ClearScreen,
     Error(String),
     Help,
+    ToggleLogs,
     // Raw Key Events
     Key(KeyEvent),
     ReloadVault,
+    /// Files of the vault created, modified or removed, only those are parsed again
+    FilesChanged(Vec<PathBuf>),
+    /// Every file of the vault was reloaded by `auto_reload_seconds`
+    VaultRefreshed,
     // Movements
     GotoToday,
     NextMonth,
@@ -41,18 +48,70 @@ pub enum Action {
     ViewDown,
     ViewLeft,
     ViewRight,
+    /// Widens the preview column of the explorer
+    GrowPreview,
+    /// Narrows the preview column of the explorer, hiding it past its smallest width
+    ShrinkPreview,
+    /// Hides or shows the preview column of the explorer
+    TogglePreview,
+    /// Hides or shows the parent column of the explorer
+    ToggleParentColumn,
     // Menus
     SwitchSortingMode,
     Escape,
     Search,
+    /// Empties the search of the Filter tab
+    ClearSearch,
     TabRight,
     TabLeft,
     Open,
+    OpenAttachment,
+    OpenInObsidian,
+    CreateTaskNote,
     Edit,
+    /// Adds a task to the note or header open in the Explorer tab
+    AddTask,
+    /// Renames the selected file, directory or header
+    Rename,
     MarkToDo,
     MarkDone,
     MarkCancel,
     MarkIncomplete,
+    /// Gives the selected task the next state, custom states included
+    CycleState,
+    /// Moves the selected task to another note, with its subtasks and description
+    MoveTask,
+    /// Removes the selected task from its note, with its subtasks and description
+    DeleteTask,
+    IncreasePriority,
+    DecreasePriority,
+    IncreaseCompletion,
+    DecreaseCompletion,
+    PostponeDay,
+    PostponeWeek,
+    PostponeToMonday,
+    /// Sent once a task was marked done
+    TaskCompleted,
+    CarryOver,
+    CopyAsMarkdown,
+    /// Lists the notes the selected task links to and the tasks linking to the selected note
+    ShowLinks,
+    /// Tracks the focus time of the selected task in the Time Management tab
+    TrackTime,
+    /// Sets the task whose focus sessions are logged
+    SetFocusedTask(String),
+    /// Shows the tasks of the selected entry in the Filter tab
+    FilterSection,
+    /// Replaces the search of the Filter tab
+    SetSearch(String),
+    /// Opens the list of `vaults` to switch to
+    SwitchVault,
+    /// Opens this vault of `vaults` in place of the current one
+    OpenVault(String),
+    /// Sets the count shown in the title of a tab
+    TabCount(Mode, String),
+    /// Timed due dates of the tasks matching the search of the Filter tab, as timestamps of local times
+    UpcomingDue(Vec<(i64, String)>),
     Focus(Mode),
 }
 impl PartialOrd for Action {
@@ -1,23 +1,32 @@
+use std::{path::PathBuf, sync::mpsc::Receiver, time::Duration};
+
 use color_eyre::Result;
 use crossterm::event::KeyEvent;
 use ratatui::prelude::Rect;
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 use crate::{
     action::Action,
-    cli::{Cli, Commands},
+    cli::{Cli, Commands, Tab},
     components::{
         calendar_tab::CalendarTab, explorer_tab::ExplorerTab, filter_tab::FilterTab,
-        fps::FpsCounter, home::Home, time_management_tab::TimeManagementTab, Component,
+        fps::FpsCounter, home::Home, inbox_tab::InboxTab, log_pane::LogPane, stats_tab::StatsTab,
+        time_management_tab::TimeManagementTab, vault_switcher::VaultSwitcher, Component,
     },
     config::Config,
     tui::{Event, Tui},
 };
+use vault_tasks_core::{lock::VaultLock, TaskManager};
+
+/// How often files modified by other programs are sent to the components, at most
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
 
 struct InitialState {
     tab: Action,
+    /// Search of the Filter tab given with `--query`
+    search: Option<String>,
 }
 
 pub struct App {
@@ -32,6 +41,12 @@ pub struct App {
     last_tick_key_events: Vec<KeyEvent>,
     action_tx: mpsc::UnboundedSender<Action>,
     action_rx: mpsc::UnboundedReceiver<Action>,
+    /// Released when the app exits, `None` in read-only mode
+    _lock: Option<VaultLock>,
+    /// Files changed outside of the app, `None` if `watch_vault` is not set
+    vault_watcher: Option<Receiver<Vec<PathBuf>>>,
+    /// Every file of the vault, every `auto_reload_seconds`, `None` if it is 0
+    auto_reloader: Option<Receiver<Vec<PathBuf>>>,
 }
 
 #[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -42,13 +57,18 @@ pub enum Mode {
     Filter,
     TimeManagement,
     Calendar,
+    Stats,
+    Inbox,
 }
 
 impl App {
     pub fn new(args: &Cli) -> Result<Self> {
-        let config = Config::new(args)?;
+        let mut config = Config::new(args)?;
+        let lock = Self::lock_vault(&mut config);
         let initial_state = Self::get_initial_state(args);
         let (action_tx, action_rx) = mpsc::unbounded_channel();
+        let vault_watcher = Self::vault_watcher(&config);
+        let auto_reloader = Self::auto_reloader(&config);
         Ok(Self {
             tick_rate: args.tick_rate,
             frame_rate: args.frame_rate,
@@ -57,8 +77,12 @@ impl App {
                 Box::<FpsCounter>::default(),
                 Box::new(ExplorerTab::new()),
                 Box::new(FilterTab::new()),
+                Box::new(InboxTab::new()),
                 Box::new(CalendarTab::new()),
                 Box::new(TimeManagementTab::new()),
+                Box::new(StatsTab::new()),
+                Box::new(VaultSwitcher::new()),
+                Box::new(LogPane::new()),
             ],
             should_quit: false,
             should_suspend: false,
@@ -68,13 +92,91 @@ impl App {
             action_tx,
             action_rx,
             initial_state,
+            _lock: lock,
+            vault_watcher,
+            auto_reloader,
         })
     }
+    fn vault_watcher(config: &Config) -> Option<Receiver<Vec<PathBuf>>> {
+        if !config.tasks_config.watch_vault {
+            return None;
+        }
+        TaskManager::watch(&config.tasks_config, WATCH_INTERVAL)
+            .inspect_err(|e| {
+                warn!("Failed to watch the vault: {e}, set `auto_reload_seconds` to reload it periodically");
+            })
+            .ok()
+    }
+    fn auto_reloader(config: &Config) -> Option<Receiver<Vec<PathBuf>>> {
+        let seconds = config.tasks_config.auto_reload_seconds;
+        (seconds > 0)
+            .then(|| TaskManager::auto_reload(&config.tasks_config, Duration::from_secs(seconds)))
+    }
+    /// Takes the lock of the vault, or sets the config read-only if another instance is writing to it.
+    fn lock_vault(config: &mut Config) -> Option<VaultLock> {
+        match VaultLock::acquire(&config.tasks_config) {
+            Ok(lock) => {
+                config.tasks_config.read_only = false;
+                Some(lock)
+            }
+            Err(e) => {
+                warn!("{e}, starting in read-only mode");
+                config.tasks_config.read_only = true;
+                None
+            }
+        }
+    }
+    /// Replaces the open vault by the vault `name` of `vaults`, reloading every component.
+    fn open_vault(&mut self, name: &str) -> Result<()> {
+        let Some(vault) = self.config.tasks_config.vaults.get(name) else {
+            error!("Unknown vault: {name}");
+            return Ok(());
+        };
+        if !vault.path.exists() {
+            error!("Vault path does not exist: {:?}", vault.path);
+            return Ok(());
+        }
+        info!("Opening vault {name} at {:?}", vault.path);
+        let mut config = self.config.clone();
+        config.tasks_config.vault_path.clone_from(&vault.path);
+        config.tasks_config.scope = PathBuf::new();
+        // The lock of the previous vault is released first, in case both are the same
+        self._lock = None;
+        self._lock = Self::lock_vault(&mut config);
+        self.vault_watcher = Self::vault_watcher(&config);
+        self.auto_reloader = Self::auto_reloader(&config);
+        self.config = config;
+        for component in &mut self.components {
+            component.register_config_handler(self.config.clone())?;
+        }
+        self.action_tx.send(Action::Focus(self.mode))?;
+        Ok(())
+    }
     fn get_initial_state(args: &Cli) -> InitialState {
-        let tab = match args.command {
+        let tab = match (args.tab, &args.query) {
+            (Some(tab), _) => Action::Focus(match tab {
+                Tab::Explorer => Mode::Explorer,
+                Tab::Filter => Mode::Filter,
+                Tab::TimeManagement => Mode::TimeManagement,
+                Tab::Calendar => Mode::Calendar,
+                Tab::Stats => Mode::Stats,
+                Tab::Inbox => Mode::Inbox,
+            }),
+            (None, Some(_)) => Action::Focus(Mode::Filter),
+            (None, None) => Self::get_command_tab(args),
+        };
+        InitialState {
+            tab,
+            search: args.query.clone(),
+        }
+    }
+    fn get_command_tab(args: &Cli) -> Action {
+        match args.command {
             Some(Commands::Filter) => Action::Focus(Mode::Filter),
+            Some(Commands::Inbox) => Action::Focus(Mode::Inbox),
             Some(Commands::TimeManagement) => Action::Focus(Mode::TimeManagement),
-            Some(Commands::Calendar) => Action::Focus(Mode::Calendar),
+            Some(Commands::Calendar { .. }) => Action::Focus(Mode::Calendar),
+            Some(Commands::Stats { .. }) => Action::Focus(Mode::Stats),
             Some(Commands::Explorer | Commands::GenerateConfig { path: _ }) | None => {
                 Action::Focus(Mode::Explorer)
             }
@@ -82,8 +184,7 @@ impl App {
                 error!("Unhandled command: {:?}", args.command);
                 Action::Focus(Mode::Explorer)
             }
-        };
-        InitialState { tab }
+        }
     }
     pub async fn run(&mut self) -> Result<()> {
         let mut tui = Tui::new()?
@@ -104,6 +205,9 @@ impl App {
 
         let action_tx = self.action_tx.clone();
 
+        if let Some(search) = self.initial_state.search.take() {
+            action_tx.send(Action::SetSearch(search))?;
+        }
         action_tx.send(self.initial_state.tab.clone())?;
 
         loop {
@@ -192,6 +296,7 @@ impl App {
                 Action::Focus(mode) => self.mode = mode,
                 Action::Tick => {
                     self.last_tick_key_events.drain(..);
+                    self.send_changed_files()?;
                 }
                 Action::Quit => self.should_quit = true,
                 Action::Suspend => self.should_suspend = true,
@@ -199,6 +304,7 @@ impl App {
                 Action::ClearScreen => tui.terminal.clear()?,
                 Action::Resize(w, h) => self.handle_resize(tui, w, h)?,
                 Action::Render => self.render(tui)?,
+                Action::OpenVault(ref name) => self.open_vault(name)?,
                 _ => {}
             }
             for component in &mut self.components {
@@ -210,6 +316,38 @@ impl App {
         Ok(())
     }
 
+    /// Sends the files changed outside of the app since the last tick to the components.
+    fn send_changed_files(&self) -> Result<()> {
+        let mut paths = self
+            .vault_watcher
+            .iter()
+            .flat_map(Receiver::try_iter)
+            .flatten()
+            .collect::<Vec<PathBuf>>();
+        let reloaded = self
+            .auto_reloader
+            .iter()
+            .flat_map(Receiver::try_iter)
+            .flatten()
+            .collect::<Vec<PathBuf>>();
+        let refreshed = !reloaded.is_empty();
+        paths.extend(reloaded);
+        if !paths.is_empty() {
+            paths.sort();
+            paths.dedup();
+            if refreshed {
+                debug!("Reloading {} files", paths.len());
+            } else {
+                info!("Files changed: {paths:?}");
+            }
+            self.action_tx.send(Action::FilesChanged(paths))?;
+        }
+        if refreshed {
+            self.action_tx.send(Action::VaultRefreshed)?;
+        }
+        Ok(())
+    }
+
     fn handle_resize(&mut self, tui: &mut Tui, w: u16, h: u16) -> Result<()> {
         tui.resize(Rect::new(0, 0, w, h))?;
         self.render(tui)?;
@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use clap::{ArgAction, Parser, Subcommand};
+use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
 
 use crate::config::{get_config_dir, get_data_dir};
 
@@ -10,6 +10,9 @@ pub struct Cli {
     /// Vault to open (can be a single file or a directory)
     #[arg(short, long, value_name = "PATH")]
     pub vault_path: Option<PathBuf>,
+    /// Only parse this file or directory, relative to the vault
+    #[arg(long, value_name = "PATH")]
+    pub scope: Option<PathBuf>,
     /// Show frame rate and tick rate
     #[arg(short, long, action = ArgAction::SetTrue)]
     pub show_fps: bool,
@@ -22,6 +25,16 @@ pub struct Cli {
     /// Use a custom config file
     #[arg(short, long, value_name = "PATH")]
     pub config_path: Option<PathBuf>,
+    /// Log filter, e.g. `warn` or `info,vault_tasks_core=debug`, overrides `log_level` of the config
+    #[arg(long, value_name = "DIRECTIVES")]
+    pub log_level: Option<String>,
+    /// Tab to open, e.g. for desktop launchers
+    #[arg(long, value_enum)]
+    pub tab: Option<Tab>,
+    /// Search of the Filter tab, which is opened unless `--tab` is given, e.g. `"#work today"`
+    #[arg(long, value_name = "SEARCH")]
+    pub query: Option<String>,
+
     /// Optional subcommand to run
     #[command(subcommand)]
     pub command: Option<Commands>,
@@ -34,16 +47,468 @@ pub enum Commands {
     /// Open filter view
     #[command(alias = "flt")]
     Filter,
+    /// Open Inbox view, to triage the tasks of `drop_file`
+    Inbox,
     /// Open Time Management view
     #[command(alias = "time")]
     TimeManagement,
-    /// Open Calendar view
+    /// Open Calendar view, or print a month with `--month` or `--format`
     #[command(alias = "cld")]
-    Calendar,
+    Calendar {
+        /// Month to print, e.g. `2025-03` (current month by default)
+        #[arg(short, long, value_name = "YYYY-MM")]
+        month: Option<String>,
+        #[arg(short, long, value_enum)]
+        format: Option<CalendarFormat>,
+    },
+    /// Print the tasks due today, or in the coming week or month, grouped by day after the overdue tasks
+    Agenda {
+        /// Print the next 7 days
+        #[arg(short, long, action = ArgAction::SetTrue, conflicts_with = "month")]
+        week: bool,
+        /// Print the next month
+        #[arg(short, long, action = ArgAction::SetTrue)]
+        month: bool,
+    },
+    /// Open Stats view, or print statistics of the vault with `--format`
+    Stats {
+        /// Print the focus time of today, this week and the last days instead
+        #[arg(long, action = ArgAction::SetTrue)]
+        focus: bool,
+        /// Print the tasks per state, tag and path, and the overdue tasks
+        #[arg(short, long, value_enum)]
+        format: Option<StatsFormat>,
+    },
     /// Generates a new configuration file from the default one
     GenerateConfig { path: Option<PathBuf> },
     /// Write tasks to STDOUT
-    Stdout,
+    Stdout {
+        #[command(flatten)]
+        sort: SortArgs,
+        /// Only write entries up to this depth, the vault or `--path` being at depth 0
+        #[arg(long)]
+        depth: Option<usize>,
+        /// Only write tasks, or only directories, files and headers
+        #[arg(long, value_enum)]
+        only: Option<EntryKind>,
+        /// Only write unfinished or finished tasks
+        #[arg(long, value_enum)]
+        state: Option<StateFilter>,
+        /// Only write this file or directory, relative to the vault
+        #[arg(long, value_name = "PATH")]
+        path: Option<PathBuf>,
+    },
+    /// List files modified in the last days and their tasks
+    Recent {
+        /// Number of days to look back
+        #[arg(short, long, default_value_t = 7)]
+        days: u32,
+    },
+    /// Carry unfinished tasks of the previous daily note over to today's note
+    CarryOver {
+        /// Move tasks instead of copying them, regardless of `carry_over_mode`
+        #[arg(long = "move", action = ArgAction::SetTrue)]
+        move_tasks: bool,
+        /// Show the changes as a diff and ask before writing each file
+        #[arg(long, action = ArgAction::SetTrue)]
+        diff: bool,
+    },
+    /// Write a time-blocked schedule of today's planned tasks (`@today` or due today) to today's daily note
+    Schedule {
+        /// Time the tasks without a due time start from, e.g. `09:00`, now by default
+        #[arg(long, value_name = "HH:MM")]
+        start: Option<String>,
+        /// Print the changes as a diff instead of writing them
+        #[arg(long, action = ArgAction::SetTrue)]
+        dry_run: bool,
+    },
+    /// Log an already completed task, e.g. `vault-tasks did "reviewed PRs #work"`
+    Did {
+        /// The task, tags and other tokens are supported
+        #[arg(required = true, num_args = 1..)]
+        task: Vec<String>,
+    },
+    /// Add tasks to `drop_file`, e.g. `echo "call bank #home" | vault-tasks add -`
+    Add {
+        /// The task, or `-` to read one task per line from STDIN
+        task: Vec<String>,
+        /// Read one task per line from this file
+        #[arg(long, value_name = "PATH")]
+        file: Option<PathBuf>,
+        /// Add the tasks even if similar unfinished tasks already exist
+        #[arg(long)]
+        force: bool,
+    },
+    /// Count unfinished tasks that are overdue or due today, for status bars
+    Count {
+        /// Only count tasks matching this search
+        #[arg(default_value_t = String::new())]
+        filter: String,
+        /// Only count tasks matching this saved search of `filters`
+        #[arg(long, value_name = "NAME")]
+        filter_name: Option<String>,
+        #[arg(short, long, value_enum, default_value_t = CountFormat::Number)]
+        format: CountFormat,
+    },
+    /// List tasks matching a search, e.g. for scripts and status bars
+    #[command(alias = "ls")]
+    List {
+        /// Only list tasks matching this search
+        #[arg(default_value_t = String::new())]
+        filter: String,
+        /// Only list tasks matching this saved search of `filters`
+        #[arg(long, value_name = "NAME")]
+        filter_name: Option<String>,
+        #[arg(short, long, value_enum, default_value_t = ListFormat::Text)]
+        format: ListFormat,
+        #[command(flatten)]
+        sort: SortArgs,
+    },
+    /// Fuzzy find a task and print its `file:line`, e.g. to open it in `$EDITOR`
+    Pick,
+    /// Print the most urgent unfinished tasks with their urgency, see `urgency` in the config
+    Next {
+        /// Number of tasks to print
+        #[arg(short = 'n', long, default_value_t = 5)]
+        count: usize,
+        /// Only rank tasks matching this search
+        #[arg(default_value_t = String::new())]
+        filter: String,
+        /// Only rank tasks matching this saved search of `filters`
+        #[arg(long, value_name = "NAME")]
+        filter_name: Option<String>,
+    },
+    /// List every tag with the number of open and done tasks using it
+    Tags {
+        /// Only count the tasks of this file or directory, relative to the vault
+        path: Option<PathBuf>,
+    },
+    /// List the tasks linking to a note with wiki-links, subtasks included
+    Links {
+        /// The note, relative to the vault or by name like a wiki-link, e.g. `Work/Project` or `Project`
+        note: String,
+    },
+    /// Check the vault for problems and report hygiene statistics
+    Check {
+        /// Only print the summary, not every problem found
+        #[arg(long, action = ArgAction::SetTrue)]
+        summary: bool,
+        /// Only list the days with more work due than `daily_task_capacity` or `daily_estimate_capacity`
+        #[arg(long, action = ArgAction::SetTrue)]
+        overload: bool,
+    },
+    /// Print the details of a task: description, subtasks, location and dates
+    Show {
+        /// Block identifier of the task (`^id`), or a search matching it
+        #[arg(required = true, num_args = 1..)]
+        query: Vec<String>,
+        /// Print tasks as a JSON array
+        #[arg(long, action = ArgAction::SetTrue)]
+        json: bool,
+    },
+    /// Change the state of tasks, e.g. `vault-tasks mark done ^standup`
+    Mark {
+        /// `todo`, `done`, `incomplete`, `canceled` or the name of a custom state of `task_state_markers.custom`
+        state: String,
+        /// Block identifier of the tasks (`^id`), or a search matching them
+        #[arg(required = true, num_args = 1..)]
+        query: Vec<String>,
+        /// Only mark the tasks of this file, relative to the vault
+        #[arg(long, value_name = "PATH")]
+        file: Option<PathBuf>,
+        /// Print the changes as a diff instead of writing them
+        #[arg(long, action = ArgAction::SetTrue)]
+        dry_run: bool,
+    },
+    /// Change attributes of tasks, e.g. `vault-tasks edit "#pr" --set tomorrow --add-tag review`
+    Edit {
+        /// Block identifier of the tasks (`^id`), or a search matching them
+        #[arg(long, required = true)]
+        filter: String,
+        /// Task tokens replacing the ones of the tasks, e.g. `tomorrow`, `est:1h` or `"every week"`
+        #[arg(long, value_name = "TOKENS")]
+        set: Vec<String>,
+        #[arg(long, value_name = "TAG")]
+        add_tag: Vec<String>,
+        #[arg(long, value_name = "TAG")]
+        remove_tag: Vec<String>,
+        /// New priority, `0` removes it
+        #[arg(long)]
+        priority: Option<usize>,
+        /// Only edit the tasks of this file, relative to the vault
+        #[arg(long, value_name = "PATH")]
+        file: Option<PathBuf>,
+        /// Print the changes as a diff instead of writing them
+        #[arg(long, action = ArgAction::SetTrue)]
+        dry_run: bool,
+    },
+    /// Remove tasks from their notes, with their subtasks and descriptions
+    Delete {
+        /// Block identifier of the tasks (`^id`), or a search matching them
+        #[arg(required = true, num_args = 1..)]
+        query: Vec<String>,
+        /// Only delete the tasks of this file, relative to the vault
+        #[arg(long, value_name = "PATH")]
+        file: Option<PathBuf>,
+        /// Print the changes as a diff instead of writing them
+        #[arg(long, action = ArgAction::SetTrue)]
+        dry_run: bool,
+    },
+    /// Move done and canceled tasks to `archive_file`, under a header showing where they come from
+    Archive {
+        /// Only archive the tasks with this block identifier (`^id`) or matching this search
+        query: Vec<String>,
+        /// Only archive the tasks of this file, relative to the vault
+        #[arg(long, value_name = "PATH")]
+        file: Option<PathBuf>,
+        /// Print the changes as a diff instead of writing them
+        #[arg(long, action = ArgAction::SetTrue)]
+        dry_run: bool,
+    },
+    /// Turn a tag into a project: move the tasks carrying it to `projects/<tag>.md`, under sub-headers following where they come from, and remove the tag
+    TagToProject {
+        /// The tag, with or without the leading `#`
+        tag: String,
+        /// Print the changes as a diff instead of writing them
+        #[arg(long, action = ArgAction::SetTrue)]
+        dry_run: bool,
+    },
+    /// Apply the `maintenance` policies of the config in one pass and print what was done, e.g. from cron
+    Maintain,
+    /// Compare the states of tasks with another copy of the vault, e.g. before syncing two devices
+    Diff {
+        /// The other copy of the vault
+        other: PathBuf,
+        /// Write the state of tasks only finished in the other copy to this vault
+        #[arg(long, action = ArgAction::SetTrue)]
+        apply: bool,
+    },
+    /// Export tasks to another format
+    Export {
+        #[command(subcommand)]
+        format: ExportFormat,
+    },
+    /// Import tasks from another format
+    Import {
+        #[command(subcommand)]
+        format: ImportFormat,
+    },
+    /// Inspect the remote items tasks are synced with by sync backends, in the data directory
+    Sync {
+        #[command(subcommand)]
+        command: SyncCommand,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum SyncCommand {
+    /// List the remote item of every synced task
+    List {
+        /// Only list the tasks synced by this backend
+        backend: Option<String>,
+    },
+    /// Map a task to a remote item, e.g. one that was synced before
+    Link {
+        backend: String,
+        /// Block identifier of the task (`^id`)
+        task: String,
+        remote_id: String,
+        #[arg(long)]
+        etag: Option<String>,
+    },
+    /// Forget the remote item of a task, so the backend syncs it as a new one
+    Forget {
+        backend: String,
+        /// Block identifier of the task (`^id`)
+        task: String,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ExportFormat {
+    /// Markdown checklist, e.g. to paste in an email or a meeting note
+    Md {
+        /// Only export tasks matching this search, `sort:` keys order top level tasks
+        #[arg(default_value_t = String::new())]
+        filter: String,
+        /// Only export tasks matching this saved search of `filters`
+        #[arg(long, value_name = "NAME")]
+        filter_name: Option<String>,
+        /// Group tasks under headers showing the files and headers they come from
+        #[arg(short, long, action = ArgAction::SetTrue)]
+        breadcrumbs: bool,
+    },
+    /// iCalendar feed of the tasks with a due date, e.g. to see them in a phone calendar
+    Ical {
+        /// Only export tasks matching this search
+        #[arg(default_value_t = String::new())]
+        filter: String,
+        /// Only export tasks matching this saved search of `filters`
+        #[arg(long, value_name = "NAME")]
+        filter_name: Option<String>,
+        /// Write the feed to this file instead of STDOUT
+        #[arg(short, long, value_name = "PATH")]
+        out: Option<PathBuf>,
+    },
+    /// CSV table of tasks and subtasks, e.g. to open in a spreadsheet
+    Csv {
+        /// Only export tasks matching this search, `sort:` keys order the rows
+        #[arg(default_value_t = String::new())]
+        filter: String,
+        /// Only export tasks matching this saved search of `filters`
+        #[arg(long, value_name = "NAME")]
+        filter_name: Option<String>,
+        /// Columns to write, in order
+        #[arg(long, value_enum, value_delimiter = ',', default_values_t = CsvColumn::value_variants())]
+        columns: Vec<CsvColumn>,
+        /// Separate fields with tabs instead of commas
+        #[arg(long, action = ArgAction::SetTrue)]
+        tsv: bool,
+        /// Write the table to this file instead of STDOUT
+        #[arg(short, long, value_name = "PATH")]
+        out: Option<PathBuf>,
+    },
+    /// Agenda of a week to print or share, every day with its tasks after the unfinished tasks carried over
+    Agenda {
+        /// Week to export, e.g. `2025-W11` (current week by default)
+        #[arg(short, long, value_name = "YYYY-Www")]
+        week: Option<String>,
+        #[arg(short, long, value_enum, default_value_t)]
+        format: AgendaFormat,
+        /// Write the agenda to this file instead of STDOUT
+        #[arg(short, long, value_name = "PATH")]
+        out: Option<PathBuf>,
+    },
+    /// Tasks created and completed per week as CSV, from `created:` and `done:` tokens
+    StatsCsv {
+        /// Number of weeks to look back
+        #[arg(short, long, default_value_t = 12)]
+        weeks: usize,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ImportFormat {
+    /// todo.txt file, projects and contexts becoming tags
+    Todotxt {
+        /// The todo.txt file, or `-` to read it from STDIN
+        file: PathBuf,
+        /// Note to add the tasks to, relative to the vault, `drop_file` or today's daily note by default
+        #[arg(long, value_name = "PATH")]
+        into: Option<PathBuf>,
+        /// Add the tasks at the end of the section under this header, appended to the note if missing
+        #[arg(long, value_name = "HEADING")]
+        heading: Option<String>,
+        /// Print the changes as a diff instead of writing them
+        #[arg(long, action = ArgAction::SetTrue)]
+        dry_run: bool,
+    },
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum Tab {
+    Explorer,
+    Filter,
+    Inbox,
+    #[value(alias = "time")]
+    TimeManagement,
+    Calendar,
+    Stats,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, Default)]
+pub enum CalendarFormat {
+    /// Markdown table, with the tasks of each day under a header
+    #[default]
+    Md,
+    /// Plain text grid, e.g. to print
+    Txt,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, Default)]
+pub enum AgendaFormat {
+    /// Plain text, one section per day
+    #[default]
+    Txt,
+    /// Standalone HTML page, e.g. to print from a browser
+    Html,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum StatsFormat {
+    /// Totals followed by tables of tags and paths
+    Table,
+    Json,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum EntryKind {
+    Tasks,
+    Headers,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum StateFilter {
+    /// To do and incomplete tasks
+    Open,
+    /// Done and canceled tasks
+    Done,
+}
+
+#[derive(Args, Debug, Clone, Copy)]
+pub struct SortArgs {
+    /// Order of the tasks, `output_sort` of the config by default
+    #[arg(long, value_enum)]
+    pub sort: Option<SortOrder>,
+    /// Reverse the order, `output_sort_reverse` of the config by default
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub reverse: bool,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum SortOrder {
+    /// Order of the notes
+    File,
+    /// Soonest due first
+    Due,
+    /// Lowest priority number first
+    Priority,
+    Name,
+    /// Overdue, due soon and `p1` tasks first
+    Urgency,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum CsvColumn {
+    Name,
+    /// `todo`, `done`, `incomplete`, `canceled` or the lowercased name of a custom state
+    State,
+    /// `2025-03-01`, or `2025-03-01 09:30` with a time
+    Due,
+    /// Own and inherited tags, separated by spaces
+    Tags,
+    Priority,
+    /// File of the task, relative to the vault
+    Path,
+    Line,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum ListFormat {
+    /// `file:line: task`, one task per line
+    Text,
+    /// JSON array of tasks with their location and subtasks
+    Json,
+    /// One JSON object per line
+    Ndjson,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum CountFormat {
+    /// Total number of overdue and due today tasks
+    Number,
+    /// `overdue!/due today`, e.g. `3!/7`
+    Badge,
 }
 
 const VERSION_MESSAGE: &str = env!("CARGO_PKG_VERSION");
@@ -65,3 +530,27 @@ Config directory: {config_dir_path}
 Data directory: {data_dir_path}"
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::{Cli, Commands};
+
+    #[test]
+    fn test_parse_next() {
+        let parse = |args: &[&str]| match Cli::try_parse_from(args).unwrap().command {
+            Some(Commands::Next { count, filter, .. }) => (count, filter),
+            _ => panic!(),
+        };
+        assert_eq!(parse(&["vault-tasks", "next"]), (5, String::new()));
+        assert_eq!(
+            parse(&["vault-tasks", "next", "#work"]),
+            (5, "#work".to_owned())
+        );
+        assert_eq!(
+            parse(&["vault-tasks", "next", "-n", "3", "#work"]),
+            (3, "#work".to_owned())
+        );
+    }
+}
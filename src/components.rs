@@ -17,7 +17,11 @@ pub mod explorer_tab;
 pub mod filter_tab;
 pub mod fps;
 pub mod home;
+pub mod inbox_tab;
+pub mod log_pane;
+pub mod stats_tab;
 pub mod time_management_tab;
+pub mod vault_switcher;
 
 /// `Component` is a trait that represents a visual and interactive element of the user interface.
 ///
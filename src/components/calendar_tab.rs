@@ -1,15 +1,17 @@
-use std::collections::hash_map::Entry;
+use std::{collections::hash_map::Entry, path::PathBuf};
 
 use ::time::{Date, OffsetDateTime};
-use chrono::{Datelike, Duration, NaiveDate, NaiveTime};
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use notify_rust::Notification;
 use ratatui::{
     layout::{Constraint, Layout, Rect},
     style::{Color, Modifier, Style, Stylize},
-    text::{Line, Span, ToSpan},
-    widgets::{calendar::CalendarEventStore, StatefulWidget, Widget},
+    text::{Line, Span, Text, ToSpan},
+    widgets::{calendar::CalendarEventStore, Block, List, Paragraph, StatefulWidget, Widget},
     Frame,
 };
 use time::{util::days_in_year, Weekday};
+use tokio::sync::mpsc::UnboundedSender;
 use tracing::error;
 use tui_scrollview::ScrollViewState;
 
@@ -17,14 +19,18 @@ use crate::{
     action::Action,
     app::Mode,
     config::Config,
-    core::{
-        filter::{filter_to_vec, Filter},
-        sorter::SortingMode,
-        task::{DueDate, State, Task},
-        vault_data::VaultData,
-        TaskManager,
+    widgets::{
+        empty_state::EmptyState, help_menu::HelpMenu, hint_bar::HintBar,
+        styled_calendar::StyledCalendar, task_list::TaskList,
     },
-    widgets::{help_menu::HelpMenu, styled_calendar::StyledCalendar, task_list::TaskList},
+};
+use vault_tasks_core::{
+    calendar::calendar_tasks,
+    overload::overloaded_days,
+    schedule::{day_schedule, ScheduledTask},
+    task::{DueDate, Task},
+    vault_data::VaultData,
+    TaskManager,
 };
 
 use super::Component;
@@ -34,12 +40,14 @@ struct CalendarTabArea {
     date: Rect,
     calendar: Rect,
     legend: Rect,
+    reminders: Rect,
     footer: Rect,
     timeline: Rect,
 }
 
 pub struct CalendarTab<'a> {
     // Utils
+    command_tx: Option<UnboundedSender<Action>>,
     config: Config,
     is_focused: bool,
     task_mgr: TaskManager,
@@ -52,11 +60,20 @@ pub struct CalendarTab<'a> {
     // Whether the help panel is open or not
     show_help: bool,
     help_menu_wigdet: HelpMenu<'a>,
+    /// Upcoming reminders
+    reminders: Vec<(NaiveDateTime, Task)>,
+    /// Reminders set before this were already notified
+    last_reminder_check: NaiveDateTime,
+    /// Timed tasks of the selected date
+    schedule: Vec<ScheduledTask>,
+    /// Days from today with more work due than the configured capacity
+    overloaded_days: Vec<NaiveDate>,
 }
 impl Default for CalendarTab<'_> {
     fn default() -> Self {
         Self {
             selected_date: OffsetDateTime::now_local().unwrap().date(),
+            command_tx: None,
             config: Config::default(),
             is_focused: false,
             show_help: false,
@@ -66,6 +83,10 @@ impl Default for CalendarTab<'_> {
             task_list_widget_state: ScrollViewState::new(),
             entries_list: TaskList::default(),
             events: CalendarEventStore::default(),
+            reminders: vec![],
+            last_reminder_check: chrono::Local::now().naive_local(),
+            schedule: vec![],
+            overloaded_days: vec![],
         }
     }
 }
@@ -84,15 +105,22 @@ impl CalendarTab<'_> {
     const TASK_TODO: Style = Style::new()
         .fg(Color::Red)
         .add_modifier(Modifier::UNDERLINED);
+    const TASK_PROJECTED: Style = Style::new()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::ITALIC);
+    const OVERLOADED: Style = Style::new()
+        .fg(Color::White)
+        .bg(Color::Magenta)
+        .add_modifier(Modifier::UNDERLINED);
     pub fn new() -> Self {
         Self::default()
     }
-    fn split_frame(area: Rect) -> CalendarTabArea {
+    fn split_frame(&self, area: Rect) -> CalendarTabArea {
         let [_header, content, footer, _tab_footera] = Layout::vertical([
-            Constraint::Length(1), // tabs
-            Constraint::Min(0),    // content
-            Constraint::Length(1), //footer
-            Constraint::Length(1), // home footer
+            Constraint::Length(1),                           // tabs
+            Constraint::Min(0),                              // content
+            Constraint::Length(self.config.footer.height()), //footer
+            Constraint::Length(self.config.footer.height()), // home footer
         ])
         .areas(area);
 
@@ -101,9 +129,10 @@ impl CalendarTab<'_> {
             Constraint::Min(0),                // timeline
         ])
         .areas(content);
-        let [calendar, legend] = Layout::vertical([
+        let [calendar, legend, reminders] = Layout::vertical([
             Constraint::Length(7 * 3 + 5), // calendar
-            Constraint::Min(0),            // legend
+            Constraint::Length(7),         // legend
+            Constraint::Min(0),            // reminders
         ])
         .areas(calendar);
 
@@ -117,21 +146,104 @@ impl CalendarTab<'_> {
             date,
             calendar,
             legend,
+            reminders,
             footer,
             timeline,
         }
     }
-    fn render_footer(area: Rect, frame: &mut Frame) {
+    fn render_footer(&self, area: Rect, frame: &mut Frame) {
         ratatui::widgets::Widget::render(
-            Line::raw("Navigate: <hjkl|◄▼▲▶> | Month: Shift+<jk|▼▲> | Goto Today: <t>").centered(),
+            HintBar::new(&self.config, Mode::Calendar),
             area,
             frame.buffer_mut(),
         );
     }
     fn update_tasks(&mut self) {
-        // Gather tasks to vector
-        self.tasks = filter_to_vec(&self.task_mgr.tasks, &Filter::default());
-        self.tasks.sort_by(SortingMode::cmp_due_date);
+        self.tasks = calendar_tasks(&self.task_mgr.tasks, &self.config.tasks_config);
+        self.overloaded_days = overloaded_days(
+            &self.task_mgr.tasks,
+            &self.config.tasks_config,
+            chrono::Local::now().date_naive(),
+        )
+        .iter()
+        .map(|d| d.date)
+        .collect();
+        self.reminders = self.task_mgr.get_reminders(
+            self.last_reminder_check,
+            self.config.tasks_config.default_due_time(),
+        );
+
+        if let Some(tx) = &self.command_tx {
+            let count = Self::count_due_on(&self.tasks, chrono::Local::now().date_naive());
+            if let Err(e) = tx.send(Action::TabCount(Mode::Calendar, format!("{count} today"))) {
+                error!("Could not send the task count: {e}");
+            }
+        }
+    }
+    /// Reloads the vault, or only the `changed` files, the task list stays scrolled where it was.
+    fn reload(&mut self, changed: Option<&[PathBuf]>) -> color_eyre::eyre::Result<()> {
+        match changed {
+            Some(paths) => self
+                .task_mgr
+                .reload_files(&self.config.tasks_config, paths)?,
+            None => self.task_mgr.reload(&self.config.tasks_config)?,
+        }
+        let offset = self.task_list_widget_state.offset();
+        self.update_tasks();
+        self.updated_date();
+        self.task_list_widget_state.set_offset(offset);
+        Ok(())
+    }
+    /// Counts the unfinished tasks due on `date`, projected occurrences excluded.
+    fn count_due_on(tasks: &[Task], date: NaiveDate) -> usize {
+        tasks
+            .iter()
+            .filter(|t| {
+                !t.projected
+                    && !t.state.is_finished()
+                    && t.due_date.to_naive_date_time().map(|d| d.date()) == Some(date)
+            })
+            .count()
+    }
+    /// Sends a notification for every reminder set since the last check.
+    fn notify_reminders(&mut self) {
+        let now = chrono::Local::now().naive_local();
+        for (_, task) in self
+            .reminders
+            .iter()
+            .filter(|(reminder, _)| self.last_reminder_check < *reminder && *reminder <= now)
+        {
+            if Notification::new()
+                .summary("VaultTasks")
+                .body(&format!(
+                    "{} {}",
+                    self.config.tasks_config.pretty_symbols.reminder, task.name
+                ))
+                .show()
+                .is_err()
+            {
+                error!("Failed to send notification"); // Don't crash for this
+            }
+        }
+        self.last_reminder_check = now;
+    }
+    fn render_reminders(&self, area: Rect, frame: &mut Frame<'_>) {
+        let date_format = self.config.tasks_config.date_display_format();
+        let reminders = self
+            .reminders
+            .iter()
+            .filter(|(reminder, _)| self.last_reminder_check < *reminder)
+            .map(|(reminder, task)| {
+                Text::from(vec![
+                    Line::from(DueDate::DayTime(*reminder).to_display_string(&date_format)).bold(),
+                    Line::from(task.name.as_str()),
+                ])
+            });
+        Widget::render(
+            List::new(reminders).block(Block::bordered().title("Upcoming Reminders")),
+            area,
+            frame.buffer_mut(),
+        );
     }
     fn updated_date(&mut self) {
         // Find a task to preview
@@ -164,27 +276,108 @@ impl CalendarTab<'_> {
             }
         }
 
-        // Build preview task list
-        let tasks_to_preview = if self.tasks.get(index_closest_task).is_some() {
-            &filter_to_vec(
-                &self.task_mgr.tasks,
-                &Filter::new(
-                    Task {
-                        due_date: self.tasks[index_closest_task].due_date.clone(),
-                        ..Default::default()
-                    },
-                    None,
-                ),
-            )
-            .iter()
-            .map(|t| VaultData::Task(t.clone()))
-            .collect::<Vec<VaultData>>()
+        // Build preview task list, tasks of the same day sorted by time
+        let tasks_to_preview = if let Some(closest) = self
+            .tasks
+            .get(index_closest_task)
+            .and_then(|t| t.due_date.to_naive_date_time())
+        {
+            &self
+                .tasks
+                .iter()
+                .filter(|t| {
+                    t.due_date
+                        .to_naive_date_time()
+                        .is_some_and(|d| d.date() == closest.date())
+                })
+                .map(|t| VaultData::Task(t.clone()))
+                .collect::<Vec<VaultData>>()
         } else {
             &vec![]
         };
         self.entries_list = TaskList::new(&self.config, tasks_to_preview, true);
         self.task_list_widget_state.scroll_to_top(); // reset view
         self.tasks_to_events(self.tasks.clone().get(index_closest_task));
+        self.schedule = day_schedule(&self.tasks, Self::date_to_naive_date(self.selected_date));
+    }
+    fn date_to_naive_date(date: Date) -> NaiveDate {
+        NaiveDate::from_ymd_opt(date.year(), date.month() as u32, u32::from(date.day())).unwrap()
+    }
+    /// Renders the schedule of the selected date, if any, next to its tasks.
+    fn render_timeline(&mut self, area: Rect, frame: &mut Frame<'_>) {
+        let timeline = if self.schedule.is_empty() {
+            area
+        } else {
+            let [schedule, timeline] =
+                Layout::horizontal([Constraint::Percentage(40), Constraint::Fill(1)]).areas(area);
+            self.render_schedule(schedule, frame);
+            timeline
+        };
+        self.entries_list.clone().render(
+            timeline,
+            frame.buffer_mut(),
+            &mut self.task_list_widget_state,
+        );
+    }
+    /// Renders the timed tasks of the selected date on an hourly timeline, overlapping tasks are highlighted.
+    fn render_schedule(&self, area: Rect, frame: &mut Frame<'_>) {
+        let first_hour = self
+            .schedule
+            .iter()
+            .map(|t| t.start.hour())
+            .min()
+            .unwrap_or_default()
+            .min(8);
+        let last_hour = self
+            .schedule
+            .iter()
+            .map(|t| {
+                if t.end.date() == t.start.date() {
+                    t.end.hour()
+                } else {
+                    23
+                }
+            })
+            .max()
+            .unwrap_or_default()
+            .max(18);
+        let conflict_symbol = if self.config.tasks_config.ascii_mode {
+            "[!]"
+        } else {
+            "⚠️"
+        };
+
+        let mut lines = vec![];
+        for hour in first_hour..=last_hour {
+            let hour_start = Self::date_to_naive_date(self.selected_date)
+                .and_hms_opt(hour, 0, 0)
+                .unwrap();
+            let hour_end = hour_start + Duration::hours(1);
+            let tasks = self
+                .schedule
+                .iter()
+                .filter(|t| t.start < hour_end && t.end > hour_start);
+            let mut line = vec![Span::raw(format!("{hour:02}:00 ")).dim()];
+            for (i, t) in tasks.enumerate() {
+                if i > 0 {
+                    line.push(Span::raw(", "));
+                }
+                let starts_now = t.start >= hour_start;
+                let span = if starts_now {
+                    Span::raw(format!("{} {}", t.start.format("%H:%M"), t.task.name))
+                } else {
+                    Span::raw(format!("│ {}", t.task.name)).dim()
+                };
+                line.push(if t.conflict { span.red() } else { span });
+                if t.conflict && starts_now {
+                    line.push(Span::raw(format!(" {conflict_symbol}")).red());
+                }
+            }
+            lines.push(Line::from(line));
+        }
+        Paragraph::new(lines)
+            .block(Block::bordered().title("Schedule"))
+            .render(area, frame.buffer_mut());
     }
     #[allow(clippy::cast_possible_truncation)]
     fn naive_date_to_date(naive_date: NaiveDate) -> Date {
@@ -236,9 +429,12 @@ impl CalendarTab<'_> {
                     Some(Self::naive_date_to_date(naive_datetime.date()))
                 }
             };
-            let theme = match task.state {
-                State::ToDo | State::Incomplete => Self::TASK_TODO,
-                State::Done | State::Canceled => Self::TASK_DONE,
+            let theme = if task.projected {
+                Self::TASK_PROJECTED
+            } else if task.state.is_finished() {
+                Self::TASK_DONE
+            } else {
+                Self::TASK_TODO
             };
             if let Some(date) = next {
                 // Already marked as selected
@@ -278,10 +474,23 @@ impl CalendarTab<'_> {
                 }
             }
         }
+        // Selected and previewed dates stay visible
+        for date in &self.overloaded_days {
+            let date = Self::naive_date_to_date(*date);
+            if date != self.selected_date
+                && self
+                    .events
+                    .0
+                    .get(&date)
+                    .is_none_or(|&t| t != Self::PREVIEWED)
+            {
+                self.events.add(date, Self::OVERLOADED);
+            }
+        }
     }
     fn render_legend(areas: &CalendarTabArea, frame: &mut Frame<'_>) {
-        let [todo, done, selected, previewed, today] =
-            Layout::vertical([Constraint::Length(1); 5]).areas(areas.legend);
+        let [todo, done, projected, overloaded, selected, previewed, today] =
+            Layout::vertical([Constraint::Length(1); 7]).areas(areas.legend);
         ratatui::widgets::Widget::render(
             Span::raw("Todo")
                 .style(Self::TASK_TODO)
@@ -296,6 +505,20 @@ impl CalendarTab<'_> {
             done,
             frame.buffer_mut(),
         );
+        ratatui::widgets::Widget::render(
+            Span::raw("Projected")
+                .style(Self::TASK_PROJECTED)
+                .into_left_aligned_line(),
+            projected,
+            frame.buffer_mut(),
+        );
+        ratatui::widgets::Widget::render(
+            Span::raw("Overloaded")
+                .style(Self::OVERLOADED)
+                .into_left_aligned_line(),
+            overloaded,
+            frame.buffer_mut(),
+        );
         ratatui::widgets::Widget::render(
             Span::raw("Selected")
                 .style(Self::SELECTED)
@@ -324,6 +547,14 @@ impl CalendarTab<'_> {
     }
 }
 impl Component for CalendarTab<'_> {
+    fn register_action_handler(
+        &mut self,
+        tx: UnboundedSender<Action>,
+    ) -> color_eyre::eyre::Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
     fn register_config_handler(&mut self, config: Config) -> color_eyre::eyre::Result<()> {
         self.task_mgr = TaskManager::load_from_config(&config.tasks_config)?;
         self.config = config;
@@ -339,13 +570,13 @@ impl Component for CalendarTab<'_> {
         _tui: Option<&mut crate::tui::Tui>,
         action: crate::action::Action,
     ) -> color_eyre::eyre::Result<Option<crate::action::Action>> {
+        if action == Action::Tick {
+            self.notify_reminders();
+        }
         if !self.is_focused {
             match action {
-                Action::ReloadVault => {
-                    self.task_mgr.reload(&self.config.tasks_config)?;
-                    self.update_tasks();
-                    self.updated_date();
-                }
+                Action::ReloadVault => self.reload(None)?,
+                Action::FilesChanged(paths) => self.reload(Some(&paths))?,
                 Action::Focus(Mode::Calendar) => self.is_focused = true,
                 Action::Focus(mode) if !(mode == Mode::Calendar) => self.is_focused = false,
                 _ => (),
@@ -368,11 +599,8 @@ impl Component for CalendarTab<'_> {
                     self.selected_date = OffsetDateTime::now_local().unwrap().date();
                     self.updated_date();
                 }
-                Action::ReloadVault => {
-                    self.task_mgr.reload(&self.config.tasks_config)?;
-                    self.update_tasks();
-                    self.updated_date();
-                }
+                Action::ReloadVault => self.reload(None)?,
+                Action::FilesChanged(paths) => self.reload(Some(&paths))?,
                 Action::Left => {
                     self.selected_date -= time::Duration::days(1);
 
@@ -435,7 +663,7 @@ impl Component for CalendarTab<'_> {
             return Ok(());
         }
 
-        let areas = Self::split_frame(area);
+        let areas = self.split_frame(area);
 
         // Calendar
         StyledCalendar::render_quarter(frame, areas.calendar, self.selected_date, &self.events);
@@ -443,6 +671,9 @@ impl Component for CalendarTab<'_> {
         // Legend
         Self::render_legend(&areas, frame);
 
+        // Reminders
+        self.render_reminders(areas.reminders, frame);
+
         // Date
         self.selected_date
             .to_span()
@@ -450,14 +681,19 @@ impl Component for CalendarTab<'_> {
             .render(areas.date, frame.buffer_mut());
 
         // Timeline
-        self.entries_list.clone().render(
-            areas.timeline,
-            frame.buffer_mut(),
-            &mut self.task_list_widget_state,
-        );
+        if self.tasks.iter().all(|t| t.due_date == DueDate::NoDate) {
+            EmptyState::new(
+                "No task has a due date",
+                "Add a date to a task to see it here, like `- [ ] Pay rent 01/03/2025` or `- [ ] Call mom tomorrow`",
+            )
+            .hint(&self.config, Mode::Calendar, &Action::ReloadVault, "reload the vault")
+            .render(areas.timeline, frame.buffer_mut());
+        } else {
+            self.render_timeline(areas.timeline, frame);
+        }
 
         // Footer
-        Self::render_footer(areas.footer, frame);
+        self.render_footer(areas.footer, frame);
         // Help
         if self.show_help {
             self.help_menu_wigdet.clone().render(
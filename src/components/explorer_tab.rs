@@ -1,37 +1,70 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+
 use color_eyre::eyre::eyre;
 use color_eyre::Result;
-use crossterm::event::Event;
 use layout::Flex;
 use ratatui::prelude::*;
-use ratatui::widgets::{Block, Borders, Paragraph};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
 use tokio::sync::mpsc::UnboundedSender;
 use tracing::{debug, error, info};
 
-use crate::core::task::State;
-use tui_input::backend::crossterm::EventHandler;
 use tui_input::Input;
 use tui_scrollview::ScrollViewState;
 use tui_widget_list::{ListBuilder, ListState, ListView};
+use vault_tasks_core::task::{DueDate, State};
 
 use super::Component;
 
 use crate::app::Mode;
-use crate::core::filter::parse_search_input;
-use crate::core::parser::task::parse_task;
-use crate::core::vault_data::VaultData;
-use crate::core::TaskManager;
-use crate::tui::Tui;
+use crate::config::get_data_dir;
+use crate::tui::{copy_to_clipboard, Tui};
+use crate::widgets::diff_view::DiffView;
+use crate::widgets::empty_state::EmptyState;
 use crate::widgets::help_menu::HelpMenu;
+use crate::widgets::hint_bar::HintBar;
 use crate::widgets::input_bar::InputBar;
+use crate::widgets::links_view::LinksView;
 use crate::widgets::task_list::TaskList;
+use crate::widgets::text_utils::{ellipsis, truncate};
 use crate::{action::Action, config::Config};
+use vault_tasks_core::daily_notes::plan_carry_over;
+use vault_tasks_core::export::to_markdown;
+use vault_tasks_core::filter::{parse_tab_search, path_search, Filter};
+use vault_tasks_core::journal::{Journal, JournalEntry};
+use vault_tasks_core::parser::task::parse_task;
+use vault_tasks_core::sorter::ExplorerSortingMode;
+use vault_tasks_core::vault_data::VaultData;
+use vault_tasks_core::{is_canvas_file, TaskManager};
 
+mod add_task;
 mod entry_list;
+mod links;
 mod utils;
 
 pub const FILE_EMOJI: &str = "📄";
 pub const DIRECTORY_EMOJI: &str = "📁";
 pub const WARNING_EMOJI: &str = "⚠️";
+pub const FILE_ASCII: &str = "[F]";
+pub const DIRECTORY_ASCII: &str = "[D]";
+pub const WARNING_ASCII: &str = "[!]";
+/// Percentage added or removed by the completion actions
+const COMPLETION_STEP: i16 = 10;
+/// Journal of the edit in progress, in the data directory
+const JOURNAL_FILE_NAME: &str = "journal.json";
+/// Largest ratio the preview column can be grown to
+const MAX_COLUMN_RATIO: u16 = 20;
+
+/// Entry of the center view being renamed
+enum RenameTarget {
+    /// A file or a directory
+    File(PathBuf),
+    Header {
+        path: PathBuf,
+        level: usize,
+        name: String,
+    },
+}
 
 /// Struct that helps with drawing the component
 struct ExplorerArea {
@@ -59,6 +92,22 @@ pub struct ExplorerTab<'a> {
     show_help: bool,
     help_menu_wigdet: HelpMenu<'a>,
     edit_task_bar: InputBar<'a>,
+    add_task_bar: InputBar<'a>,
+    /// Due day suggested for the tasks added to the current note or header
+    due_suggestion: Option<String>,
+    rename_bar: InputBar<'a>,
+    renaming: Option<RenameTarget>,
+    sorting_mode: ExplorerSortingMode,
+    /// Relative widths of the previous, current and preview columns, 0 hides a column
+    column_ratios: [u16; 3],
+    /// Changes of a bulk operation waiting to be accepted
+    diff_view: Option<DiffView>,
+    /// Links of the selected note and task, open until an entry is followed
+    links_view: Option<LinksView>,
+    /// Keeps the edit in progress in case the app crashes
+    journal: Journal,
+    /// Operation left in progress by a previous session, waiting to be replayed or discarded
+    recovered: Option<JournalEntry>,
 }
 
 impl ExplorerTab<'_> {
@@ -78,12 +127,15 @@ impl ExplorerTab<'_> {
                 .task_mgr
                 .get_path_layer_entries(&self.current_path[0..self.current_path.len() - 1])
             {
-                Ok(res) => Self::vault_data_to_entry_list(&res),
-                Err(e) => vec![(String::from(WARNING_EMOJI), (e.to_string()))],
+                Ok(res) => self.vault_data_to_entry_list(
+                    &res,
+                    &self.current_path[0..self.current_path.len() - 1],
+                ),
+                Err(e) => vec![(String::from(self.warning_prefix()), (e.to_string()))],
             };
         }
         self.entries_center_view = match self.task_mgr.get_path_layer_entries(&self.current_path) {
-            Ok(res) => Self::vault_data_to_entry_list(&res),
+            Ok(res) => self.vault_data_to_entry_list(&res, &self.current_path),
             Err(_e) => {
                 // If no entries are found, go to parent object
                 while self
@@ -94,11 +146,12 @@ impl ExplorerTab<'_> {
                 {
                     self.leave_selected_entry()?;
                 }
-                Self::vault_data_to_entry_list(
+                self.vault_data_to_entry_list(
                     &self
                         .task_mgr
                         .get_path_layer_entries(&self.current_path)
                         .unwrap_or_default(),
+                    &self.current_path,
                 )
             }
         };
@@ -108,12 +161,29 @@ impl ExplorerTab<'_> {
             self.select_previous_left_entry();
         }
         if self.state_center_view.selected.unwrap_or_default() >= self.entries_center_view.len() {
-            self.state_center_view.select(Some(0));
+            self.state_center_view
+                .select(Some(self.entries_center_view.len().saturating_sub(1)));
         }
         self.update_preview();
         Ok(())
     }
 
+    /// Reloads the vault, or only the `changed` files, keeping the selected entry.
+    fn reload(&mut self, changed: Option<&[PathBuf]>) -> Result<()> {
+        match changed {
+            Some(paths) => self
+                .task_mgr
+                .reload_files(&self.config.tasks_config, paths)?,
+            None => self.task_mgr.reload(&self.config.tasks_config)?,
+        }
+        self.update_entries_keeping_selection()
+    }
+
+    /// Returns the action reparsing the file of the selected entry after it was edited.
+    fn reload_current_file(&self) -> Action {
+        Action::FilesChanged(vec![self.get_current_path_to_file()])
+    }
+
     pub(super) fn update_preview(&mut self) {
         debug!("Updating preview");
         let Ok(path_to_preview) = self.get_preview_path() else {
@@ -128,15 +198,20 @@ impl ExplorerTab<'_> {
         };
         self.task_list_widget_state.scroll_up();
     }
-    pub(super) fn build_list(
+    pub(super) fn build_list<'a>(
         entries_to_display: Vec<String>,
-        surrouding_block: Block<'_>,
+        surrouding_block: Block<'a>,
         highlighted_style: Style,
-    ) -> ListView<'_, Paragraph<'_>> {
+        ellipsis: &'static str,
+    ) -> ListView<'a, Paragraph<'a>> {
         let item_count = entries_to_display.len();
 
         let builder = ListBuilder::new(move |context| {
-            let mut item = Paragraph::new(entries_to_display[context.index].clone());
+            let mut item = Paragraph::new(truncate(
+                &entries_to_display[context.index],
+                context.cross_axis_size as usize,
+                ellipsis,
+            ));
             if context.is_selected {
                 item = item.style(highlighted_style);
             };
@@ -166,12 +241,12 @@ impl ExplorerTab<'_> {
         )
     }
 
-    fn split_frame(area: Rect) -> ExplorerArea {
+    fn split_frame(&self, area: Rect) -> ExplorerArea {
         let vertical = Layout::vertical([
             Constraint::Length(1),
             Constraint::Min(0),
-            Constraint::Length(1),
-            Constraint::Length(1),
+            Constraint::Length(self.config.footer.height()),
+            Constraint::Length(self.config.footer.height()),
         ]);
         let [_header, inner, footer, _tab_footer] = vertical.areas(area);
 
@@ -183,11 +258,13 @@ impl ExplorerTab<'_> {
                 .areas(search_path);
 
         // Main Layout
-        let [previous, current, preview] = Layout::horizontal(vec![
-            Constraint::Percentage(10),
-            Constraint::Percentage(30),
-            Constraint::Percentage(60),
-        ])
+        let [previous, current, preview] = Layout::horizontal(self.column_ratios.map(|ratio| {
+            if ratio == 0 {
+                Constraint::Length(0)
+            } else {
+                Constraint::Fill(ratio)
+            }
+        }))
         .areas(explorer);
         ExplorerArea {
             path,
@@ -199,10 +276,33 @@ impl ExplorerTab<'_> {
         }
     }
 
-    pub fn render_footer(area: Rect, frame: &mut Frame) {
-        Line::raw("Navigate: <hjkl|◄▼▲▶> | Open in editor: o | Quick edit: e | Filter: s")
-            .centered()
-            .render(area, frame.buffer_mut());
+    /// Grows or shrinks the preview column, shrinking it past 1 hides it.
+    fn resize_preview(&mut self, grow: bool) {
+        let ratio = &mut self.column_ratios[2];
+        *ratio = if grow {
+            (*ratio + 1).min(MAX_COLUMN_RATIO)
+        } else {
+            ratio.saturating_sub(1)
+        };
+    }
+
+    /// Hides the previous (0) or preview (2) column, or shows it again with its configured ratio.
+    fn toggle_column(&mut self, column: usize) {
+        self.column_ratios[column] = if self.column_ratios[column] == 0 {
+            [
+                self.config.tasks_config.explorer_column_ratios[column],
+                Config::default().tasks_config.explorer_column_ratios[column],
+            ]
+            .into_iter()
+            .find(|ratio| *ratio > 0)
+            .unwrap_or(1)
+        } else {
+            0
+        };
+    }
+
+    pub fn render_footer(&self, area: Rect, frame: &mut Frame) {
+        HintBar::new(&self.config, Mode::Explorer).render(area, frame.buffer_mut());
     }
 
     fn render_search_bar(&mut self, frame: &mut Frame, area: Rect) {
@@ -243,6 +343,24 @@ impl ExplorerTab<'_> {
         // If we have tasks, then render a TaskList widget
         match self.entries_right_view.first() {
             Some(VaultData::Task(_) | VaultData::Header(_, _, _)) => {
+                let summary = self
+                    .entries_center_view
+                    .get(self.state_center_view.selected.unwrap_or_default())
+                    .and_then(|(_, name)| {
+                        VaultData::Directory(String::new(), self.entries_right_view.clone())
+                            .estimate_summary()
+                            .describe(name)
+                    });
+                let area = if let Some(summary) = summary {
+                    let [summary_area, area] =
+                        Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(area);
+                    Line::from(summary)
+                        .bold()
+                        .render(summary_area, frame.buffer_mut());
+                    area
+                } else {
+                    area
+                };
                 TaskList::new(&self.config, &self.entries_right_view, false).render(
                     area,
                     frame.buffer_mut(),
@@ -250,60 +368,132 @@ impl ExplorerTab<'_> {
                 );
             }
             // Else render a ListView widget
-            Some(VaultData::Directory(_, _)) => Self::build_list(
-                Self::apply_prefixes(&Self::vault_data_to_entry_list(
-                    &self
-                        .task_mgr
-                        .get_path_layer_entries(
+            Some(VaultData::Directory(_, _)) => {
+                let preview_path = self
+                    .get_preview_path()
+                    .unwrap_or_else(|_| self.current_path.clone());
+                Self::build_list(
+                    Self::apply_prefixes(
+                        &self.vault_data_to_entry_list(
                             &self
-                                .get_preview_path()
-                                .unwrap_or_else(|_| self.current_path.clone()),
-                        )
-                        .unwrap_or_default(),
-                )),
-                Block::new(),
-                highlighted_style,
-            )
-            .render(area, frame.buffer_mut(), &mut ListState::default()),
+                                .task_mgr
+                                .get_path_layer_entries(&preview_path)
+                                .unwrap_or_default(),
+                            &preview_path,
+                        ),
+                    ),
+                    Block::new(),
+                    highlighted_style,
+                    ellipsis(self.config.tasks_config.ascii_mode),
+                )
+                .render(area, frame.buffer_mut(), &mut ListState::default());
+            }
             None => (),
         }
     }
+    /// Asks whether an operation left in progress by a previous session should be replayed.
+    fn render_recovery_prompt(entry: &JournalEntry, frame: &mut Frame, area: Rect) {
+        let vertical = Layout::vertical([Constraint::Length(4)]).flex(Flex::Center);
+        let horizontal = Layout::horizontal([Constraint::Percentage(75)]).flex(Flex::Center);
+        let [area] = vertical.areas(area);
+        let [area] = horizontal.areas(area);
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Paragraph::new(vec![
+                Line::raw(entry.to_string()),
+                Line::raw("Enter: apply, Esc: discard").dim(),
+            ])
+            .block(Block::bordered().title("Recover unsaved changes?")),
+            area,
+        );
+    }
     fn render_edit_bar(&mut self, frame: &mut Frame, area: Rect) {
+        let style = self.highlighted_bar_style();
+        Self::render_popup_bar(&mut self.edit_task_bar, "Edit", style, frame, area);
+    }
+
+    fn render_rename_bar(&mut self, frame: &mut Frame, area: Rect) {
+        let style = self.highlighted_bar_style();
+        Self::render_popup_bar(&mut self.rename_bar, "Rename", style, frame, area);
+    }
+
+    fn highlighted_bar_style(&self) -> Style {
+        *self
+            .config
+            .styles
+            .get(&crate::app::Mode::Home)
+            .unwrap()
+            .get("highlighted_bar_style")
+            .unwrap()
+    }
+
+    /// Renders an input bar in the middle of `area`.
+    fn render_popup_bar(
+        bar: &mut InputBar,
+        title: &str,
+        style: Style,
+        frame: &mut Frame,
+        area: Rect,
+    ) {
         let vertical = Layout::vertical([Constraint::Length(3)]).flex(Flex::Center);
         let horizontal = Layout::horizontal([Constraint::Percentage(75)]).flex(Flex::Center);
         let [area] = vertical.areas(area);
         let [area] = horizontal.areas(area);
 
         let width = area.width.max(3) - 3; // 2 for borders, 1 for cursor
-        let scroll = self.edit_task_bar.input.visual_scroll(width as usize);
+        let scroll = bar.input.visual_scroll(width as usize);
 
         // Make the cursor visible and ask tui-rs to put it at the specified coordinates after rendering
         frame.set_cursor_position((
             // Put cursor past the end of the input text
-            area.x.saturating_add(
-                ((self.edit_task_bar.input.visual_cursor()).max(scroll) - scroll) as u16,
-            ) + 1,
+            area.x
+                .saturating_add(((bar.input.visual_cursor()).max(scroll) - scroll) as u16)
+                + 1,
             // Move one line down, from the border to the input line
             area.y + 1,
         ));
 
-        self.edit_task_bar.block = Some(
-            Block::bordered().title("Edit").style(
-                *self
-                    .config
-                    .styles
-                    .get(&crate::app::Mode::Home)
-                    .unwrap()
-                    .get("highlighted_bar_style")
-                    .unwrap(),
-            ),
-        );
-        self.edit_task_bar.clone().render(area, frame.buffer_mut());
+        bar.block = Some(Block::bordered().title(title.to_owned()).style(style));
+        bar.clone().render(area, frame.buffer_mut());
+        bar.render_completion(area, frame.buffer_mut());
     }
 
     fn edit_selected_task_state(&mut self, new_state: State) -> Result<()> {
         if let Some(mut task) = self.get_selected_task() {
-            task.state = new_state;
+            task.set_state(
+                new_state,
+                &self.config.tasks_config,
+                chrono::Local::now().date_naive(),
+            );
+            task.fix_task_attributes(&self.config.tasks_config, &self.get_current_path_to_file())?;
+            return Ok(());
+        }
+        Err(eyre!("No selected task"))
+    }
+    /// Changes the due date of the selected task.
+    fn edit_selected_task_due_date(&mut self, nudge: impl Fn(&DueDate) -> DueDate) -> Result<()> {
+        if let Some(mut task) = self.get_selected_task() {
+            task.due_date = nudge(&task.due_date);
+            task.fix_task_attributes(&self.config.tasks_config, &self.get_current_path_to_file())?;
+            return Ok(());
+        }
+        Err(eyre!("No selected task"))
+    }
+    /// Adds `delta` to the completion percentage of the selected task, a completion of 0 removes it.
+    fn edit_selected_task_completion(&mut self, delta: i16) -> Result<()> {
+        if let Some(mut task) = self.get_selected_task() {
+            let completion = (i16::from(task.completion.unwrap_or_default()) + delta).clamp(0, 100);
+            task.completion = u8::try_from(completion).ok().filter(|c| *c > 0);
+            task.computed_completion = false;
+            task.fix_task_attributes(&self.config.tasks_config, &self.get_current_path_to_file())?;
+            return Ok(());
+        }
+        Err(eyre!("No selected task"))
+    }
+    /// Adds `delta` to the priority of the selected task, a priority of 0 means no priority.
+    fn edit_selected_task_priority(&mut self, delta: isize) -> Result<()> {
+        if let Some(mut task) = self.get_selected_task() {
+            task.shift_priority(delta);
             task.fix_task_attributes(&self.config.tasks_config, &self.get_current_path_to_file())?;
             return Ok(());
         }
@@ -320,6 +510,8 @@ impl Component for ExplorerTab<'_> {
     fn register_config_handler(&mut self, config: Config) -> Result<()> {
         self.task_mgr = TaskManager::load_from_config(&config.tasks_config)?;
         self.config = config;
+        self.sorting_mode = self.config.tasks_config.explorer_sorting_mode;
+        self.column_ratios = self.config.tasks_config.explorer_column_ratios;
         self.help_menu_wigdet = HelpMenu::new(Mode::Explorer, &self.config);
         self.search_bar_widget.input = self.search_bar_widget.input.clone().with_value(
             self.config
@@ -327,13 +519,17 @@ impl Component for ExplorerTab<'_> {
                 .explorer_default_search_string
                 .clone(),
         );
-        self.task_mgr.current_filter = Some(parse_search_input(
+        self.task_mgr.current_filter = Some(parse_tab_search(
             self.search_bar_widget.input.value(),
             &self.config.tasks_config,
         ));
         self.update_entries()?;
         self.state_center_view.selected = Some(0);
 
+        self.journal = Journal::new(&get_data_dir().join(JOURNAL_FILE_NAME));
+        if !self.config.tasks_config.read_only {
+            self.recovered = self.journal.load();
+        }
         Ok(())
     }
 
@@ -344,7 +540,10 @@ impl Component for ExplorerTab<'_> {
         self.is_focused
             && (self.search_bar_widget.is_focused
                 || self.show_help
-                || self.edit_task_bar.is_focused)
+                || self.edit_task_bar.is_focused
+                || self.add_task_bar.is_focused
+                || self.renaming.is_some()
+                || self.recovered.is_some())
     }
 
     #[allow(clippy::too_many_lines)]
@@ -354,15 +553,52 @@ impl Component for ExplorerTab<'_> {
                 Action::Focus(Mode::Explorer) => {
                     self.is_focused = true;
                 }
-                Action::ReloadVault => {
-                    self.task_mgr.reload(&self.config.tasks_config)?;
-                    self.update_entries()?;
-                }
+                Action::ReloadVault => self.reload(None)?,
+                Action::FilesChanged(paths) => self.reload(Some(&paths))?,
                 _ => (),
             }
             return Ok(None);
         }
-        if self.edit_task_bar.is_focused {
+        if let Some(entry) = &self.recovered {
+            match action {
+                Action::Focus(mode) if mode != Mode::Explorer => self.is_focused = false,
+                Action::Enter => {
+                    match entry.replay(&self.config.tasks_config) {
+                        Ok(()) => info!("Recovered {entry}"),
+                        Err(e) => error!("Failed to recover {entry}: {e}"),
+                    }
+                    self.recovered = None;
+                    self.clear_journal();
+                    return Ok(Some(Action::ReloadVault));
+                }
+                Action::Escape => {
+                    self.recovered = None;
+                    self.clear_journal();
+                }
+                _ => (),
+            }
+        } else if self.renaming.is_some() {
+            match action {
+                Action::Enter => {
+                    let res = self.renaming.take().map(|target| self.rename(&target));
+                    self.rename_bar.input.reset();
+                    match res {
+                        Some(Ok(action)) => return Ok(Some(action)),
+                        Some(Err(e)) => error!("Failed to rename: {e}"),
+                        None => (),
+                    }
+                }
+                Action::Escape => {
+                    self.renaming = None;
+                    self.rename_bar.input.reset();
+                }
+                Action::Key(key_event) => self.rename_bar.handle_key(key_event, &HashSet::new()),
+                _ => (),
+            }
+        } else if self.edit_task_bar.is_focused {
+            if matches!(action, Action::Enter | Action::Escape) {
+                self.edit_task_bar.completion.clear();
+            }
             match action {
                 Action::Enter => {
                     // We're already sure it exists since we entered the task editing mode
@@ -388,25 +624,48 @@ impl Component for ExplorerTab<'_> {
                         };
                         // Write changes
                         parsed_task.line_number = task.line_number;
-                        parsed_task.fix_task_attributes(
+                        if let Err(e) = parsed_task.fix_task_attributes(
                             &self.config.tasks_config,
                             &self.get_current_path_to_file(),
-                        )?;
+                        ) {
+                            error!("Failed to edit task: {e}");
+                        }
+                        self.clear_journal();
                         // Quit editing mode
                         self.edit_task_bar.is_focused = !self.edit_task_bar.is_focused;
-                        // Reload vault
-                        return Ok(Some(Action::ReloadVault));
+                        return Ok(Some(self.reload_current_file()));
                     }
                 }
                 Action::Escape => {
                     // Cancel editing
                     self.edit_task_bar.input.reset();
                     self.edit_task_bar.is_focused = !self.edit_task_bar.is_focused;
+                    self.clear_journal();
                 }
                 Action::Key(key_event) => {
                     self.edit_task_bar
-                        .input
-                        .handle_event(&Event::Key(key_event));
+                        .handle_key(key_event, &self.task_mgr.tags);
+                    self.journal_edit();
+                }
+                _ => (),
+            }
+        } else if self.add_task_bar.is_focused {
+            match action {
+                Action::Enter => {
+                    self.add_task_bar.is_focused = false;
+                    self.add_task_bar.completion.clear();
+                    return self.submit_add_task().or_else(|e| {
+                        error!("Failed to add task: {e}");
+                        Ok(None)
+                    });
+                }
+                Action::Escape => {
+                    self.add_task_bar.is_focused = false;
+                    self.add_task_bar.completion.clear();
+                }
+                Action::Key(key_event) => {
+                    self.add_task_bar.handle_key(key_event, &self.task_mgr.tags);
+                    self.update_add_task_ghost();
                 }
                 _ => (),
             }
@@ -414,14 +673,14 @@ impl Component for ExplorerTab<'_> {
             match action {
                 Action::Enter | Action::Escape => {
                     self.search_bar_widget.is_focused = !self.search_bar_widget.is_focused;
+                    self.search_bar_widget.completion.clear();
                 }
                 Action::Key(key_event) => {
                     self.search_bar_widget
-                        .input
-                        .handle_event(&Event::Key(key_event));
+                        .handle_key(key_event, &self.task_mgr.tags);
 
                     // Update search input in TaskManager
-                    self.task_mgr.current_filter = Some(parse_search_input(
+                    self.task_mgr.current_filter = Some(parse_tab_search(
                         self.search_bar_widget.input.value(),
                         &self.config.tasks_config,
                     ));
@@ -429,6 +688,38 @@ impl Component for ExplorerTab<'_> {
                 }
                 _ => (),
             }
+        } else if let Some(links_view) = &mut self.links_view {
+            match action {
+                Action::Up => links_view.previous(),
+                Action::Down => links_view.next(),
+                Action::Enter => self.follow_link(tui)?,
+                Action::Escape | Action::ShowLinks => self.links_view = None,
+                _ => (),
+            }
+        } else if let Some(diff_view) = &mut self.diff_view {
+            match action {
+                Action::Up => diff_view.previous_file(),
+                Action::Down => diff_view.next_file(),
+                Action::Left => diff_view.set_selected_accepted(false),
+                Action::Right => diff_view.set_selected_accepted(true),
+                Action::ViewUp => diff_view.scroll_up(),
+                Action::ViewDown => diff_view.scroll_down(),
+                Action::Enter => {
+                    let changes = diff_view.accepted_changes();
+                    self.diff_view = None;
+                    match changes.apply() {
+                        Ok(()) => {
+                            info!("Wrote {} files", changes.changes.len());
+                            return Ok(Some(Action::FilesChanged(
+                                changes.changes.into_iter().map(|c| c.path).collect(),
+                            )));
+                        }
+                        Err(e) => error!("Failed to write changes: {e}"),
+                    }
+                }
+                Action::Escape => self.diff_view = None,
+                _ => (),
+            }
         } else if self.show_help {
             match action {
                 Action::ViewUp | Action::Up => self.help_menu_wigdet.scroll_up(),
@@ -447,35 +738,168 @@ impl Component for ExplorerTab<'_> {
                     self.search_bar_widget.is_focused = !self.search_bar_widget.is_focused;
                 }
                 Action::MarkDone => {
+                    let was_done = self
+                        .get_selected_task()
+                        .is_some_and(|t| t.state == State::Done);
                     if self.edit_selected_task_state(State::Done).is_ok() {
-                        return Ok(Some(Action::ReloadVault));
+                        if let (false, Some(tx)) = (was_done, &self.command_tx) {
+                            tx.send(Action::TaskCompleted)?;
+                        }
+                        return Ok(Some(self.reload_current_file()));
                     }
                 }
                 Action::MarkCancel => {
                     if self.edit_selected_task_state(State::Canceled).is_ok() {
-                        return Ok(Some(Action::ReloadVault));
+                        return Ok(Some(self.reload_current_file()));
                     }
                 }
                 Action::MarkToDo => {
                     if self.edit_selected_task_state(State::ToDo).is_ok() {
-                        return Ok(Some(Action::ReloadVault));
+                        return Ok(Some(self.reload_current_file()));
                     }
                 }
                 Action::MarkIncomplete => {
                     if self.edit_selected_task_state(State::Incomplete).is_ok() {
-                        return Ok(Some(Action::ReloadVault));
+                        return Ok(Some(self.reload_current_file()));
+                    }
+                }
+                Action::CycleState => {
+                    if let Some(task) = self.get_selected_task() {
+                        let state = task
+                            .state
+                            .next(&self.config.tasks_config.task_state_markers);
+                        let completed = state == State::Done;
+                        if self.edit_selected_task_state(state).is_ok() {
+                            if let (true, Some(tx)) = (completed, &self.command_tx) {
+                                tx.send(Action::TaskCompleted)?;
+                            }
+                            return Ok(Some(self.reload_current_file()));
+                        }
+                    }
+                }
+                Action::PostponeDay | Action::PostponeWeek | Action::PostponeToMonday => {
+                    let today = chrono::Local::now().date_naive();
+                    let res = match action {
+                        Action::PostponeDay => {
+                            self.edit_selected_task_due_date(|d| d.shifted(1, today))
+                        }
+                        Action::PostponeWeek => {
+                            self.edit_selected_task_due_date(|d| d.shifted(7, today))
+                        }
+                        _ => self.edit_selected_task_due_date(|d| {
+                            d.next_weekday(chrono::Weekday::Mon, today)
+                        }),
+                    };
+                    match res {
+                        Ok(()) => return Ok(Some(self.reload_current_file())),
+                        Err(e) => error!("Failed to edit due date: {e}"),
+                    }
+                }
+                Action::IncreaseCompletion | Action::DecreaseCompletion => {
+                    let delta = if action == Action::IncreaseCompletion {
+                        COMPLETION_STEP
+                    } else {
+                        -COMPLETION_STEP
+                    };
+                    match self.edit_selected_task_completion(delta) {
+                        Ok(()) => return Ok(Some(self.reload_current_file())),
+                        Err(e) => error!("Failed to edit completion: {e}"),
+                    }
+                }
+                Action::IncreasePriority | Action::DecreasePriority => {
+                    let delta = if action == Action::IncreasePriority {
+                        1
+                    } else {
+                        -1
+                    };
+                    match self.edit_selected_task_priority(delta) {
+                        Ok(()) => return Ok(Some(self.reload_current_file())),
+                        Err(e) => error!("Failed to edit priority: {e}"),
+                    }
+                }
+                Action::TrackTime => {
+                    if let (Some(task), Some(tx)) = (self.get_selected_task(), &self.command_tx) {
+                        tx.send(Action::SetFocusedTask(task.name))?;
+                        return Ok(Some(Action::Focus(Mode::TimeManagement)));
+                    }
+                    info!("Only tasks can be tracked");
+                }
+                Action::SwitchSortingMode => {
+                    self.sorting_mode = self.sorting_mode.next();
+                    info!("Sorting explorer entries by {}", self.sorting_mode);
+                    self.update_entries_keeping_selection()?;
+                }
+                Action::CarryOver => {
+                    let tasks_config = &self.config.tasks_config;
+                    match tasks_config.check_writable().and_then(|()| {
+                        plan_carry_over(
+                            tasks_config,
+                            chrono::Local::now().date_naive(),
+                            tasks_config.carry_over_mode,
+                        )
+                    }) {
+                        Ok((count, changes)) if count > 0 => {
+                            info!("{count} tasks to carry over");
+                            self.diff_view = Some(DiffView::new(changes));
+                        }
+                        Ok(_) => info!("Nothing to carry over"),
+                        Err(e) => error!("Failed to carry over tasks: {e}"),
+                    }
+                }
+                Action::FilterSection => {
+                    // A task is filtered by the section it belongs to
+                    let path = if self.get_selected_task().is_some() {
+                        Ok(self.current_path.clone())
+                    } else {
+                        self.get_preview_path()
+                    };
+                    match (path, &self.command_tx) {
+                        (Ok(path), Some(tx)) => {
+                            tx.send(Action::SetSearch(path_search(&path)))?;
+                            return Ok(Some(Action::Focus(Mode::Filter)));
+                        }
+                        (Err(e), _) => error!("Failed to filter section: {e}"),
+                        (Ok(_), None) => (),
+                    }
+                }
+                Action::CopyAsMarkdown => {
+                    let preview =
+                        VaultData::Directory(String::new(), self.entries_right_view.clone());
+                    let content = to_markdown(
+                        &preview,
+                        &Filter::default(),
+                        &self.config.tasks_config,
+                        true,
+                    );
+                    match copy_to_clipboard(&content) {
+                        Ok(()) => info!("Copied preview as Markdown"),
+                        Err(e) => error!("Failed to copy to clipboard: {e}"),
                     }
                 }
                 Action::Edit => {
+                    if is_canvas_file(&self.get_current_path_to_file()) {
+                        info!("Canvas files are read-only");
+                        return Ok(None);
+                    }
                     if let Some(task) = self.get_selected_task() {
                         self.edit_task_bar.input =
                             Input::new(task.get_fixed_attributes(&self.config.tasks_config, 0));
                         self.edit_task_bar.is_focused = !self.edit_task_bar.is_focused;
+                        self.journal_edit();
                     } else {
                         info!("Only tasks can be edited");
                         return Ok(None);
                     }
                 }
+                Action::AddTask => self.open_add_task_bar(),
+                Action::Rename => match self.get_rename_target() {
+                    Ok(Some((target, name))) => {
+                        self.rename_bar.input = Input::new(name);
+                        self.renaming = Some(target);
+                    }
+                    Ok(None) => info!("Only files, directories and headers can be renamed"),
+                    Err(e) => error!("Failed to rename: {e}"),
+                },
 
                 // Navigation
                 Action::Up => {
@@ -495,13 +919,31 @@ impl Component for ExplorerTab<'_> {
                 Action::ViewPageDown => self.task_list_widget_state.scroll_page_down(),
                 Action::ViewRight => self.task_list_widget_state.scroll_right(),
                 Action::ViewLeft => self.task_list_widget_state.scroll_left(),
+                Action::GrowPreview => self.resize_preview(true),
+                Action::ShrinkPreview => self.resize_preview(false),
+                Action::TogglePreview => self.toggle_column(2),
+                Action::ToggleParentColumn => self.toggle_column(0),
                 // Commands
                 Action::Help => self.show_help = !self.show_help,
                 Action::Open => self.open_current_file(tui)?,
-                Action::ReloadVault => {
-                    self.task_mgr.reload(&self.config.tasks_config)?;
-                    self.update_entries()?;
+                Action::ShowLinks => self.open_links_view()?,
+                Action::CreateTaskNote => {
+                    if let Err(e) = self.open_selected_task_note(tui) {
+                        error!("Failed to create task note: {e}");
+                    }
+                }
+                Action::OpenAttachment => {
+                    if let Err(e) = self.open_selected_attachment() {
+                        error!("Failed to open attachment: {e}");
+                    }
+                }
+                Action::OpenInObsidian => {
+                    if let Err(e) = self.open_in_obsidian() {
+                        error!("Failed to open in Obsidian: {e}");
+                    }
                 }
+                Action::ReloadVault => self.reload(None)?,
+                Action::FilesChanged(paths) => self.reload(Some(&paths))?,
                 _ => (),
             }
         }
@@ -514,12 +956,11 @@ impl Component for ExplorerTab<'_> {
             return Ok(());
         }
         if self.entries_center_view.is_empty() {
-            error!("Center view is empty"); // is it always an error ?
             self.update_entries()?;
             self.state_center_view.selected = Some(0);
         }
-        let areas = Self::split_frame(area);
-        Self::render_footer(areas.footer, frame);
+        let areas = self.split_frame(area);
+        self.render_footer(areas.footer, frame);
 
         // Search Bar
         self.render_search_bar(frame, areas.search);
@@ -527,6 +968,20 @@ impl Component for ExplorerTab<'_> {
         // Current Path
         frame.render_widget(self.path_to_paragraph(), areas.path);
 
+        // Still empty at the root of the vault
+        if self.entries_center_view.is_empty() {
+            EmptyState::new(
+                "The vault is empty",
+                format!(
+                    "No note with tasks was found in {}. Tasks are list items with a checkbox, like `- [ ] Water the plants`",
+                    self.config.tasks_config.vault_path.display()
+                ),
+            )
+            .hint(&self.config, Mode::Explorer, &Action::ReloadVault, "reload the vault")
+            .render(areas.previous.union(areas.current).union(areas.preview), frame.buffer_mut());
+            return Ok(());
+        }
+
         let highlighted_style = *self
             .config
             .styles
@@ -540,6 +995,7 @@ impl Component for ExplorerTab<'_> {
             Self::apply_prefixes(&self.entries_left_view),
             Block::default().borders(Borders::RIGHT),
             highlighted_style,
+            ellipsis(self.config.tasks_config.ascii_mode),
         );
         let state = &mut self.state_left_view;
         left_entries_list.render(areas.previous, frame.buffer_mut(), state);
@@ -549,12 +1005,15 @@ impl Component for ExplorerTab<'_> {
             Self::apply_prefixes(&self.entries_center_view),
             Block::default().borders(Borders::RIGHT),
             highlighted_style,
+            ellipsis(self.config.tasks_config.ascii_mode),
         );
         let state = &mut self.state_center_view;
         lateral_entries_list.render(areas.current, frame.buffer_mut(), state);
 
         // Right Block
-        self.render_preview(frame, areas.preview, highlighted_style);
+        if areas.preview.width > 0 {
+            self.render_preview(frame, areas.preview, highlighted_style);
+        }
 
         // Help Menu
         if self.show_help {
@@ -564,10 +1023,83 @@ impl Component for ExplorerTab<'_> {
                 &mut self.help_menu_wigdet.state,
             );
         }
+        self.search_bar_widget
+            .render_completion(areas.search, frame.buffer_mut());
         if self.edit_task_bar.is_focused {
             self.render_edit_bar(frame, area);
         }
+        if self.add_task_bar.is_focused {
+            let style = self.highlighted_bar_style();
+            Self::render_popup_bar(&mut self.add_task_bar, "Add task", style, frame, area);
+        }
+        if self.renaming.is_some() {
+            self.render_rename_bar(frame, area);
+        }
+        if let Some(diff_view) = &mut self.diff_view {
+            diff_view
+                .clone()
+                .render(area, frame.buffer_mut(), &mut diff_view.state);
+        }
+        if let Some(links_view) = &self.links_view {
+            let [popup] = Layout::horizontal([Constraint::Percentage(70)])
+                .flex(Flex::Center)
+                .areas(areas.current.union(areas.preview));
+            let [popup] = Layout::vertical([Constraint::Percentage(60)])
+                .flex(Flex::Center)
+                .areas(popup);
+            links_view.render(popup, frame.buffer_mut());
+        }
+        if let Some(entry) = &self.recovered {
+            Self::render_recovery_prompt(entry, frame, area);
+        }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use ratatui::layout::Rect;
+
+    use super::ExplorerTab;
+    use crate::config::Config;
+
+    #[test]
+    fn test_resize_columns() {
+        let mut explorer = ExplorerTab::new();
+        explorer.config = Config::default();
+        explorer.column_ratios = [1, 3, 6];
+        let area = Rect::new(0, 0, 100, 20);
+        let widths = |explorer: &ExplorerTab| {
+            let areas = explorer.split_frame(area);
+            [
+                areas.previous.width,
+                areas.current.width,
+                areas.preview.width,
+            ]
+        };
+        assert_eq!(widths(&explorer), [10, 30, 60]);
+
+        explorer.resize_preview(false);
+        assert_eq!(explorer.column_ratios, [1, 3, 5]);
+        explorer.toggle_column(0);
+        assert_eq!(widths(&explorer), [0, 38, 62]);
+
+        explorer.toggle_column(2);
+        assert_eq!(widths(&explorer), [0, 100, 0]);
+        // Shown again with its configured ratio
+        explorer.toggle_column(2);
+        explorer.toggle_column(0);
+        assert_eq!(explorer.column_ratios, [1, 3, 6]);
+
+        explorer.column_ratios[2] = 1;
+        explorer.resize_preview(false);
+        explorer.resize_preview(false);
+        assert_eq!(explorer.column_ratios[2], 0);
+        for _ in 0..30 {
+            explorer.resize_preview(true);
+        }
+        assert_eq!(explorer.column_ratios[2], super::MAX_COLUMN_RATIO);
+    }
+}
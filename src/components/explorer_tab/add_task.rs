@@ -0,0 +1,76 @@
+use std::path::PathBuf;
+
+use color_eyre::{eyre::bail, Result};
+use tracing::info;
+use tui_input::Input;
+
+use super::ExplorerTab;
+use crate::action::Action;
+use vault_tasks_core::{
+    daily_notes::parse_new_tasks, import::plan_import, is_canvas_file, is_markdown_file,
+    stats::suggest_due_day, task::DueDate,
+};
+
+impl ExplorerTab<'_> {
+    /// Returns the note of the current path, relative to the vault, and the header tasks are added under.
+    fn add_task_target(&self) -> Option<(PathBuf, Option<&str>)> {
+        let vault_path = &self.config.tasks_config.vault_path;
+        let file_index = self.current_path.iter().enumerate().position(|(i, _)| {
+            let path = vault_path.join(self.current_path[..=i].iter().collect::<PathBuf>());
+            is_markdown_file(&path) || is_canvas_file(&path)
+        })?;
+        Some((
+            self.current_path[..=file_index].iter().collect(),
+            self.current_path
+                .get(file_index + 1..)
+                .and_then(<[String]>::last)
+                .map(String::as_str),
+        ))
+    }
+
+    /// Opens the bar adding a task to the current note or header, suggesting a due day from its tasks.
+    pub(super) fn open_add_task_bar(&mut self) {
+        match self.add_task_target() {
+            None => info!("Open a note to add a task to it"),
+            Some((note, _)) if is_canvas_file(&note) => info!("Canvas files are read-only"),
+            Some(_) => {
+                self.due_suggestion = suggest_due_day(&self.task_mgr.tasks, &self.current_path);
+                self.add_task_bar.input = Input::default();
+                self.add_task_bar.ghost = None;
+                self.add_task_bar.is_focused = true;
+            }
+        }
+    }
+
+    /// Shows the suggested due day while the task being added has a name but no due date.
+    pub(super) fn update_add_task_ghost(&mut self) {
+        let input = self.add_task_bar.input.value();
+        let has_due_date = parse_new_tasks(&self.config.tasks_config, &[input])
+            .is_ok_and(|tasks| tasks.iter().any(|t| t.due_date != DueDate::NoDate));
+        self.add_task_bar.ghost = self
+            .due_suggestion
+            .clone()
+            .filter(|_| !input.trim().is_empty() && !has_due_date);
+    }
+
+    /// Adds the task of the bar at the end of the current note or header.
+    pub(super) fn submit_add_task(&self) -> Result<Option<Action>> {
+        let Some((note, heading)) = self.add_task_target() else {
+            bail!("No note to add the task to");
+        };
+        let tasks_config = &self.config.tasks_config;
+        tasks_config.check_writable()?;
+        let today = chrono::Local::now().date_naive();
+        let mut tasks = parse_new_tasks(tasks_config, &[self.add_task_bar.input.value()])?;
+        if tasks_config.creation_date {
+            for task in &mut tasks {
+                task.created = task.created.or(Some(today));
+            }
+        }
+        let changes = plan_import(tasks_config, &tasks, &note, heading)?;
+        changes.apply()?;
+        info!("Added {:?} to {}", tasks[0].name, note.display());
+        let path = tasks_config.vault_path.join(note);
+        Ok(Some(Action::FilesChanged(vec![path])))
+    }
+}
@@ -50,6 +50,49 @@ impl ExplorerTab<'_> {
         self.update_entries()
     }
 
+    /// Updates entries and selects the entry that was selected before, even if it moved.
+    ///
+    /// Entries are found by name, the n-th entry of a duplicated name stays selected. If the entry is gone, the index is kept.
+    pub(super) fn update_entries_keeping_selection(&mut self) -> Result<()> {
+        let selected = self.state_center_view.selected.unwrap_or_default();
+        let previous = self.entries_center_view.get(selected).map(|(_, name)| {
+            (
+                name.clone(),
+                self.entries_center_view[..selected]
+                    .iter()
+                    .filter(|(_, n)| n == name)
+                    .count(),
+            )
+        });
+
+        self.update_entries()?;
+
+        if let Some(index) = previous.and_then(|(name, nth)| {
+            self.entries_center_view
+                .iter()
+                .enumerate()
+                .filter(|(_, (_, n))| *n == name)
+                .nth(nth)
+                .map(|(i, _)| i)
+        }) {
+            self.state_center_view.select(Some(index));
+            self.update_preview();
+        }
+        Ok(())
+    }
+
+    /// Shows the layer at `path` and selects its entry `name`, the closest existing parent layer being shown if `path` is gone.
+    pub(super) fn goto_entry(&mut self, path: Vec<String>, name: &str) -> Result<()> {
+        self.current_path = path;
+        self.state_center_view.select(Some(0));
+        self.update_entries()?;
+        if let Some(index) = self.entries_center_view.iter().position(|(_, n)| n == name) {
+            self.state_center_view.select(Some(index));
+            self.update_preview();
+        }
+        Ok(())
+    }
+
     pub(super) fn select_previous_left_entry(&mut self) {
         if let Some(new_previous_entry) = self.current_path.last() {
             self.state_left_view.select(Some(
@@ -64,3 +107,45 @@ impl ExplorerTab<'_> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::components::explorer_tab::ExplorerTab;
+    use vault_tasks_core::{vault_data::VaultData, TaskManager};
+
+    fn task_mgr(files: &[&str]) -> TaskManager {
+        TaskManager {
+            tasks: VaultData::Directory(
+                "vault".to_owned(),
+                files
+                    .iter()
+                    .map(|f| VaultData::Directory((*f).to_owned(), vec![]))
+                    .collect(),
+            ),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_update_entries_keeping_selection() {
+        let mut explorer = ExplorerTab::new();
+        explorer.task_mgr = task_mgr(&["a.md", "b.md", "c.md"]);
+        explorer.update_entries().unwrap();
+        explorer.state_center_view.select(Some(1));
+
+        // The selected entry moved
+        explorer.task_mgr = task_mgr(&["0.md", "a.md", "b.md", "c.md"]);
+        explorer.update_entries_keeping_selection().unwrap();
+        assert_eq!(explorer.state_center_view.selected, Some(2));
+
+        // The selected entry is gone, the index is kept
+        explorer.task_mgr = task_mgr(&["0.md", "a.md", "c.md"]);
+        explorer.update_entries_keeping_selection().unwrap();
+        assert_eq!(explorer.state_center_view.selected, Some(2));
+
+        // The last entry is gone, the new last entry is selected
+        explorer.task_mgr = task_mgr(&["0.md", "a.md"]);
+        explorer.update_entries_keeping_selection().unwrap();
+        assert_eq!(explorer.state_center_view.selected, Some(1));
+    }
+}
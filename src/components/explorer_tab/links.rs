@@ -0,0 +1,107 @@
+use std::path::Path;
+
+use color_eyre::{eyre::bail, Result};
+use tracing::info;
+
+use super::ExplorerTab;
+use crate::{
+    tui::Tui,
+    widgets::links_view::{LinkSection, LinkTarget, LinksView},
+};
+use vault_tasks_core::{details::TaskDetails, is_markdown_file, links::LinkGraph};
+
+impl ExplorerTab<'_> {
+    /// Returns `path` relative to the vault as a path of the explorer.
+    fn explorer_path(&self, path: &Path) -> Vec<String> {
+        path.strip_prefix(&self.config.tasks_config.vault_path)
+            .unwrap_or(path)
+            .iter()
+            .map(|c| c.to_string_lossy().to_string())
+            .collect()
+    }
+
+    /// Shows the notes the selected task links to and the tasks linking to the note of the selected entry.
+    pub(super) fn open_links_view(&mut self) -> Result<()> {
+        let tasks_config = &self.config.tasks_config;
+        let graph = LinkGraph::new(tasks_config, &self.task_mgr.tasks)?;
+        let file = self.get_current_path_to_file();
+        let relative = |path: &Path| {
+            path.strip_prefix(&tasks_config.vault_path)
+                .unwrap_or(path)
+                .display()
+                .to_string()
+        };
+
+        let mut sections = vec![];
+        if let Some(task) = self.get_selected_task() {
+            sections.push(LinkSection {
+                title: format!("Notes linked from `{}`", task.name),
+                entries: graph
+                    .referenced_by(&file, task.line_number)
+                    .iter()
+                    .map(|note| (relative(note), LinkTarget::Note(note.clone())))
+                    .collect(),
+            });
+        }
+        if is_markdown_file(&file) {
+            sections.push(LinkSection {
+                title: format!("Tasks linking to {}", relative(&file)),
+                entries: graph
+                    .referencing(&file)
+                    .into_iter()
+                    .map(|details| {
+                        (
+                            format!(
+                                "{}:{}: {}",
+                                relative(&details.file),
+                                details.task.line_number,
+                                details.task.name
+                            ),
+                            LinkTarget::Task(Box::new(details.clone())),
+                        )
+                    })
+                    .collect(),
+            });
+        }
+        if sections.is_empty() {
+            info!("Select a note or a task to see its links");
+            return Ok(());
+        }
+        self.links_view = Some(LinksView::new(sections));
+        Ok(())
+    }
+
+    /// Selects the note or the task of the selected entry of the links view.
+    ///
+    /// Notes without tasks are not in the explorer, they are opened in the editor.
+    pub(super) fn follow_link(&mut self, tui: Option<&mut Tui>) -> Result<()> {
+        let Some(target) = self.links_view.take().and_then(|v| v.selected().cloned()) else {
+            return Ok(());
+        };
+        match target {
+            LinkTarget::Note(note) => {
+                let mut path = self.explorer_path(&note);
+                if self.task_mgr.can_enter(&path) {
+                    let name = path.pop().unwrap_or_default();
+                    self.goto_entry(path, &name)
+                } else {
+                    let Some(tui) = tui else {
+                        bail!("Could not open {note:?}, Tui was None")
+                    };
+                    Self::open_in_editor(tui, &note, vec![], self.command_tx.as_ref())
+                }
+            }
+            LinkTarget::Task(details) => {
+                let TaskDetails {
+                    breadcrumb,
+                    file,
+                    task,
+                } = *details;
+                let mut path = self.explorer_path(&file);
+                // The breadcrumb holds the directories and the file, then the headers
+                path.extend(breadcrumb.into_iter().skip(path.len()));
+                self.goto_entry(path, &task.name)
+            }
+        }
+    }
+}
@@ -1,12 +1,29 @@
-use crate::core::task::Task;
+use crate::action::Action;
 use crate::tui::Tui;
-use crate::{action::Action, core::vault_data::VaultData};
+use vault_tasks_core::attachments::resolve_attachment;
+use vault_tasks_core::journal::JournalEntry;
+use vault_tasks_core::obsidian::obsidian_uri;
+use vault_tasks_core::rename::{rename_file, rename_header};
+use vault_tasks_core::sorter::ExplorerSortingMode;
+use vault_tasks_core::task::Task;
+use vault_tasks_core::task_notes::create_task_note;
+use vault_tasks_core::vault_data::VaultData;
+use vault_tasks_core::{is_canvas_file, is_markdown_file};
 
-use super::{ExplorerTab, DIRECTORY_EMOJI, FILE_EMOJI};
+use super::{
+    ExplorerTab, RenameTarget, DIRECTORY_ASCII, DIRECTORY_EMOJI, FILE_ASCII, FILE_EMOJI,
+    WARNING_ASCII, WARNING_EMOJI,
+};
 use color_eyre::eyre::bail;
 use color_eyre::Result;
 use std::cmp::Ordering;
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::UNIX_EPOCH;
+use tokio::sync::mpsc::UnboundedSender;
 use tracing::{error, info};
 
 impl ExplorerTab<'_> {
@@ -17,46 +34,123 @@ impl ExplorerTab<'_> {
             .collect()
     }
 
-    fn vault_data_to_prefix_name(vd: &VaultData) -> (String, String) {
+    pub(super) const fn file_prefix(&self) -> &'static str {
+        if self.config.tasks_config.ascii_mode {
+            FILE_ASCII
+        } else {
+            FILE_EMOJI
+        }
+    }
+    pub(super) const fn directory_prefix(&self) -> &'static str {
+        if self.config.tasks_config.ascii_mode {
+            DIRECTORY_ASCII
+        } else {
+            DIRECTORY_EMOJI
+        }
+    }
+    pub(super) const fn warning_prefix(&self) -> &'static str {
+        if self.config.tasks_config.ascii_mode {
+            WARNING_ASCII
+        } else {
+            WARNING_EMOJI
+        }
+    }
+
+    fn vault_data_to_prefix_name(&self, vd: &VaultData) -> (String, String) {
         match vd {
             VaultData::Directory(name, _) => (
-                if name.contains(".md") {
-                    FILE_EMOJI.to_owned()
+                if name.contains(".md") || is_canvas_file(Path::new(name)) {
+                    self.file_prefix().to_owned()
                 } else {
-                    DIRECTORY_EMOJI.to_owned()
+                    self.directory_prefix().to_owned()
                 },
                 name.clone(),
             ),
             VaultData::Header(level, name, _) => ("#".repeat(*level).clone(), name.clone()),
-            VaultData::Task(task) => (task.state.to_string(), task.name.clone()),
+            VaultData::Task(task) => (
+                task.state
+                    .display(self.config.tasks_config.pretty_symbols.clone()),
+                task.name.clone(),
+            ),
         }
     }
 
-    pub(super) fn vault_data_to_entry_list(vd: &[VaultData]) -> Vec<(String, String)> {
+    pub(super) fn vault_data_to_entry_list(
+        &self,
+        vd: &[VaultData],
+        layer_path: &[String],
+    ) -> Vec<(String, String)> {
         let mut res = vd
             .iter()
-            .map(Self::vault_data_to_prefix_name)
+            .map(|vd| self.vault_data_to_prefix_name(vd))
             .collect::<Vec<(String, String)>>();
 
         if let Some(entry) = res.first() {
-            if entry.0 == DIRECTORY_EMOJI || entry.0 == FILE_EMOJI {
+            let directory_prefix = self.directory_prefix();
+            if entry.0 == directory_prefix || entry.0 == self.file_prefix() {
+                let sort_keys = self.get_sort_keys(layer_path);
                 res.sort_by(|a, b| {
-                    if a.0 == DIRECTORY_EMOJI {
-                        if b.0 == DIRECTORY_EMOJI {
-                            a.1.cmp(&b.1)
+                    if a.0 == directory_prefix {
+                        if b.0 == directory_prefix {
+                            self.cmp_entries(&a.1, &b.1, &sort_keys)
                         } else {
                             Ordering::Less
                         }
-                    } else if b.0 == DIRECTORY_EMOJI {
+                    } else if b.0 == directory_prefix {
                         Ordering::Greater
                     } else {
-                        a.1.cmp(&b.1)
+                        self.cmp_entries(&a.1, &b.1, &sort_keys)
                     }
                 });
             }
         }
         res
     }
+    /// Computes the keys used by the current sorting mode for every entry of the layer.
+    /// Higher keys come first.
+    fn get_sort_keys(&self, layer_path: &[String]) -> HashMap<String, u64> {
+        match self.sorting_mode {
+            ExplorerSortingMode::Name => HashMap::new(),
+            ExplorerSortingMode::ModifiedTime => {
+                let mut layer_dir = self.config.tasks_config.vault_path.clone();
+                layer_dir.extend(layer_path);
+                layer_dir
+                    .read_dir()
+                    .map(|entries| {
+                        entries
+                            .flatten()
+                            .filter_map(|e| {
+                                let modified = e.metadata().and_then(|m| m.modified()).ok()?;
+                                let secs = modified.duration_since(UNIX_EPOCH).ok()?.as_secs();
+                                Some((e.file_name().to_string_lossy().to_string(), secs))
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default()
+            }
+            ExplorerSortingMode::TaskCount => self
+                .task_mgr
+                .get_explorer_entries(layer_path)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|vd| match vd {
+                    VaultData::Directory(name, _) => Some((name.clone(), vd.count_tasks() as u64)),
+                    _ => None,
+                })
+                .collect(),
+        }
+    }
+    fn cmp_entries(&self, a: &str, b: &str, sort_keys: &HashMap<String, u64>) -> Ordering {
+        let tasks_config = &self.config.tasks_config;
+        sort_keys.get(b).cmp(&sort_keys.get(a)).then_with(|| {
+            ExplorerSortingMode::cmp_names(
+                a,
+                b,
+                tasks_config.explorer_natural_sort,
+                tasks_config.explorer_case_insensitive_sort,
+            )
+        })
+    }
     pub(super) fn get_preview_path(&self) -> Result<Vec<String>> {
         let mut path_to_preview = self.current_path.clone();
         if self.entries_center_view.is_empty() {
@@ -80,18 +174,24 @@ impl ExplorerTab<'_> {
             bail!("Could not open current entry, Tui was None")
         };
         let path = self.get_current_path_to_file();
+        Self::open_in_editor(tui, &path, vec![path.clone()], self.command_tx.as_ref())
+    }
+    /// Opens a file in the default editor and reparses the `changed` files once it is closed.
+    pub(super) fn open_in_editor(
+        tui: &mut Tui,
+        path: &Path,
+        changed: Vec<PathBuf>,
+        command_tx: Option<&UnboundedSender<Action>>,
+    ) -> Result<()> {
         info!("Opening {:?} in default editor.", path);
-        if let Some(tx) = &self.command_tx {
-            tui.exit()?;
-            edit::edit_file(path)?;
-            tui.enter()?;
-            tx.send(Action::ClearScreen)?;
-        } else {
-            bail!("Failed to open current path")
-        }
-        if let Some(tx) = self.command_tx.clone() {
-            tx.send(Action::ReloadVault)?;
-        }
+        let Some(tx) = command_tx else {
+            bail!("Failed to open {path:?}")
+        };
+        tui.exit()?;
+        edit::edit_file(path)?;
+        tui.enter()?;
+        tx.send(Action::ClearScreen)?;
+        tx.send(Action::FilesChanged(changed))?;
         Ok(())
     }
     pub(super) fn get_current_path_to_file(&self) -> PathBuf {
@@ -100,10 +200,7 @@ impl ExplorerTab<'_> {
             .get_preview_path()
             .unwrap_or_else(|_| self.current_path.clone())
         {
-            if path
-                .extension()
-                .is_some_and(|ext| ext.eq_ignore_ascii_case("md"))
-            {
+            if is_markdown_file(&path) || is_canvas_file(&path) {
                 break;
             }
             path.push(e);
@@ -130,4 +227,189 @@ impl ExplorerTab<'_> {
             None
         }
     }
+    /// Creates the note of the selected task, links it from the task and opens it.
+    pub(super) fn open_selected_task_note(&self, tui_opt: Option<&mut Tui>) -> Result<()> {
+        let Some(tui) = tui_opt else {
+            bail!("Could not open task note, Tui was None")
+        };
+        let path = self.get_current_path_to_file();
+        if is_canvas_file(&path) {
+            info!("Canvas files are read-only");
+            return Ok(());
+        }
+        let Some(task) = self.get_selected_task() else {
+            info!("Only tasks can have a note");
+            return Ok(());
+        };
+        let note_path = create_task_note(
+            &self.config.tasks_config,
+            &task,
+            &path,
+            chrono::Local::now().date_naive(),
+        )?;
+        Self::open_in_editor(
+            tui,
+            &note_path,
+            vec![path, note_path.clone()],
+            self.command_tx.as_ref(),
+        )
+    }
+    /// Saves the edit in progress so it can be recovered if the app crashes.
+    pub(super) fn journal_edit(&self) {
+        let Some(task) = self.get_selected_task() else {
+            return;
+        };
+        let path = self.get_current_path_to_file();
+        let Some(original) = fs::read_to_string(&path).ok().and_then(|content| {
+            content
+                .split('\n')
+                .nth(task.line_number - 1)
+                .map(str::to_owned)
+        }) else {
+            return;
+        };
+        let entry = JournalEntry::EditTask {
+            path,
+            line_number: task.line_number,
+            original,
+            input: self.edit_task_bar.input.value().to_owned(),
+        };
+        if let Err(e) = self.journal.record(&entry) {
+            error!("Failed to write journal: {e}");
+        }
+    }
+    pub(super) fn clear_journal(&self) {
+        if let Err(e) = self.journal.clear() {
+            error!("Failed to clear journal: {e}");
+        }
+    }
+    /// Opens the first attachment of the selected task with the default application.
+    pub(super) fn open_selected_attachment(&self) -> Result<()> {
+        let Some(task) = self.get_selected_task() else {
+            info!("Only tasks have attachments");
+            return Ok(());
+        };
+        let attachments = task.attachments();
+        let Some(target) = attachments.first() else {
+            info!("Selected task has no attachment");
+            return Ok(());
+        };
+        let file_path = self.get_current_path_to_file();
+        let note_dir = file_path.parent().unwrap_or(&file_path);
+        let Some(path) = resolve_attachment(&self.config.tasks_config, note_dir, target) else {
+            bail!("Attachment not found: {target}")
+        };
+        if attachments.len() > 1 {
+            info!(
+                "Task has {} attachments, opening the first one",
+                attachments.len()
+            );
+        }
+        info!("Opening {:?} in default application.", path);
+        open_with_default_app(&path)
+    }
+    /// Returns what renaming the selected entry would rename, along with its current name.
+    pub(super) fn get_rename_target(&self) -> Result<Option<(RenameTarget, String)>> {
+        let Some((prefix, name)) = self
+            .entries_center_view
+            .get(self.state_center_view.selected.unwrap_or_default())
+        else {
+            bail!("No entry selected")
+        };
+        if prefix == self.file_prefix() || prefix == self.directory_prefix() {
+            let mut path = self.config.tasks_config.vault_path.clone();
+            path.extend(self.get_preview_path()?);
+            let name = if is_markdown_file(&path) {
+                path.file_stem()
+                    .map_or_else(|| name.clone(), |s| s.to_string_lossy().to_string())
+            } else {
+                name.clone()
+            };
+            return Ok(Some((RenameTarget::File(path), name)));
+        }
+        if !prefix.is_empty() && prefix.chars().all(|c| c == '#') {
+            let path = self.get_current_path_to_file();
+            if is_canvas_file(&path) {
+                info!("Canvas files are read-only");
+                return Ok(None);
+            }
+            return Ok(Some((
+                RenameTarget::Header {
+                    path,
+                    level: prefix.len(),
+                    name: name.clone(),
+                },
+                name.clone(),
+            )));
+        }
+        Ok(None)
+    }
+    /// Renames the selected entry to the content of the rename bar and returns the action reloading what changed.
+    pub(super) fn rename(&mut self, target: &RenameTarget) -> Result<Action> {
+        let tasks_config = &self.config.tasks_config;
+        let new_name = self.rename_bar.input.value();
+        let (entry_name, action) = match target {
+            RenameTarget::File(path) => {
+                let (new_path, changed) = rename_file(tasks_config, path, new_name)?;
+                info!(
+                    "Renamed {path:?} to {new_path:?}, updated links in {} files",
+                    changed.len()
+                );
+                (
+                    new_path
+                        .file_name()
+                        .map(|s| s.to_string_lossy().to_string()),
+                    Action::ReloadVault,
+                )
+            }
+            RenameTarget::Header { path, level, name } => {
+                let changed = rename_header(tasks_config, path, *level, name, new_name)?;
+                info!("Renamed header {name:?}, updated {} files", changed.len());
+                (
+                    Some(new_name.trim().to_owned()),
+                    Action::FilesChanged(changed),
+                )
+            }
+        };
+        // Keeps the renamed entry selected once reloaded
+        if let (Some(new_name), Some(entry)) = (
+            entry_name,
+            self.entries_center_view
+                .get_mut(self.state_center_view.selected.unwrap_or_default()),
+        ) {
+            entry.1 = new_name;
+        }
+        Ok(action)
+    }
+    /// Opens the current file in Obsidian, at the selected task if it has a block identifier.
+    pub(super) fn open_in_obsidian(&self) -> Result<()> {
+        let path = self.get_current_path_to_file();
+        let task = self.get_selected_task();
+        let Some(uri) = obsidian_uri(&self.config.tasks_config.vault_path, &path, task.as_ref())
+        else {
+            bail!("Could not build an Obsidian link to {path:?}")
+        };
+        info!("Opening {uri}");
+        open_with_default_app(&uri)
+    }
+}
+
+/// Opens a file or an URI with the default application of the system, without waiting for it to exit.
+fn open_with_default_app(target: impl AsRef<OsStr>) -> Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    command
+        .arg(target)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    Ok(())
 }
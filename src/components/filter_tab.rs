@@ -1,34 +1,44 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 
-use color_eyre::Result;
-use crossterm::event::Event;
+use chrono::TimeDelta;
+use color_eyre::{eyre::bail, Result};
 use ratatui::widgets::{List, Tabs};
 use ratatui::{prelude::*, widgets::Block};
 use strum::IntoEnumIterator;
 use tokio::sync::mpsc::UnboundedSender;
-use tracing::debug;
+use tracing::{debug, error, info};
 use tui_scrollview::ScrollViewState;
 
 use super::Component;
 
 use crate::app::Mode;
-use crate::core::filter::{self, filter_to_vec, parse_search_input};
-use crate::core::sorter::SortingMode;
-use crate::core::task::Task;
-use crate::core::vault_data::VaultData;
-use crate::core::TaskManager;
-use crate::tui::Tui;
+use crate::tui::{copy_to_clipboard, Tui};
+use crate::widgets::empty_state::EmptyState;
 use crate::widgets::help_menu::HelpMenu;
+use crate::widgets::hint_bar::HintBar;
 use crate::widgets::input_bar::InputBar;
 use crate::widgets::task_list::TaskList;
-use crate::{action::Action, config::Config};
-use tui_input::backend::crossterm::EventHandler;
+use crate::widgets::text_utils::tag_spans;
+use crate::{
+    action::Action,
+    config::{Config, FooterHint},
+};
+use vault_tasks_core::details::{filter_to_details, TaskDetails};
+use vault_tasks_core::export::to_markdown;
+use vault_tasks_core::filter::{self, filter_to_vec_with_paths, parse_tab_search, Filter};
+use vault_tasks_core::mark::{mark, write_changes};
+use vault_tasks_core::sorter::{SortKey, SortingMode};
+use vault_tasks_core::task::{DueDate, State, Task};
+use vault_tasks_core::vault_data::VaultData;
+use vault_tasks_core::TaskManager;
 
 /// Struct that helps with drawing the component
 struct FilterTabArea {
     search: Rect,
     sorting_modes_list: Rect,
     tag_list: Rect,
+    recent_files_list: Rect,
     task_list: Rect,
     footer: Rect,
 }
@@ -42,6 +52,8 @@ pub struct FilterTab<'a> {
     matching_tasks: Vec<Task>,
     /// Tags that match the current input in the filter bar
     matching_tags: Vec<String>,
+    /// Files modified recently and how long ago
+    recent_files: Vec<(String, String)>,
     /// Input bar used to apply a filter
     input_bar_widget: InputBar<'a>,
     task_mgr: TaskManager,
@@ -50,6 +62,12 @@ pub struct FilterTab<'a> {
     show_help: bool,
     help_menu_wigdet: HelpMenu<'a>,
     sorting_mode: SortingMode,
+    /// Names of the parent tasks of every subtask of the vault, see `VaultData::parent_tasks`
+    parent_tasks: HashMap<(String, usize), Vec<String>>,
+    /// Index of the selected task of the list
+    selected: Option<usize>,
+    /// Tasks entered to list their subtasks, along with the selection to restore when leaving them
+    entered: Vec<(Task, Option<usize>)>,
 }
 
 impl FilterTab<'_> {
@@ -57,18 +75,182 @@ impl FilterTab<'_> {
         Self::default()
     }
     /// Updates tasks and tags with the current filter string
+    /// Returns the timed due dates of the unfinished matching tasks, soonest first.
+    fn upcoming_due(&self) -> Vec<(i64, String)> {
+        let mut res = self
+            .matching_tasks
+            .iter()
+            .filter(|t| !t.state.is_finished())
+            .filter_map(|t| match t.due_date {
+                DueDate::DayTime(date) => Some((date.and_utc().timestamp(), t.name.clone())),
+                _ => None,
+            })
+            .collect::<Vec<(i64, String)>>();
+        res.sort();
+        res
+    }
+    /// Returns the tasks of the list: the matching tasks, or the subtasks of the entered task.
+    fn displayed_tasks(&self) -> &[Task] {
+        self.entered
+            .last()
+            .map_or(&self.matching_tasks, |(task, _)| &task.subtasks)
+    }
+    /// Tells why no task is listed and how to get some, `None` if there are tasks to list.
+    fn empty_state(&self) -> Option<EmptyState> {
+        if !self.matching_tasks.is_empty() {
+            return None;
+        }
+        let res = if self.task_mgr.tasks.count_tasks() == 0 {
+            EmptyState::new(
+                "No task in the vault",
+                format!(
+                    "Tasks are list items with a checkbox, like `- [ ] Water the plants`, in the notes of {}",
+                    self.config.tasks_config.vault_path.display()
+                ),
+            )
+            .hint(&self.config, Mode::Filter, &Action::ReloadVault, "reload the vault")
+        } else {
+            EmptyState::new(
+                "No task matches the search",
+                format!("Nothing matches `{}`", self.input_bar_widget.input.value()),
+            )
+            .hint(
+                &self.config,
+                Mode::Filter,
+                &Action::Search,
+                "edit the search",
+            )
+            .hint(
+                &self.config,
+                Mode::Filter,
+                &Action::ClearSearch,
+                "clear the search",
+            )
+        };
+        Some(res)
+    }
+    /// Returns the names of the parent tasks of a subtask, e.g. `Release › Tests`.
+    fn breadcrumb(&self, task: &Task) -> Option<String> {
+        let separator = if self.config.tasks_config.ascii_mode {
+            " > "
+        } else {
+            " › "
+        };
+        self.parent_tasks
+            .get(&(task.filename.clone(), task.line_number))
+            .map(|parents| parents.join(separator))
+    }
+    fn select_previous(&mut self) {
+        self.selected = Some(self.selected.map_or(0, |i| i.saturating_sub(1)));
+    }
+    fn select_next(&mut self) {
+        let last = self.displayed_tasks().len().saturating_sub(1);
+        self.selected = Some(self.selected.map_or(0, |i| (i + 1).min(last)));
+    }
+    /// Lists the subtasks of the selected task.
+    fn enter_selected_task(&mut self) {
+        let Some(task) = self
+            .selected
+            .and_then(|i| self.displayed_tasks().get(i))
+            .cloned()
+        else {
+            return;
+        };
+        if task.subtasks.is_empty() {
+            info!("Selected task has no subtasks");
+            return;
+        }
+        self.entered.push((task, self.selected));
+        self.selected = Some(0);
+        self.task_list_widget_state.scroll_to_top();
+    }
+    /// Finds the selected task in the vault, with the file holding it.
+    fn selected_task_details(&self) -> Result<Option<TaskDetails>> {
+        let Some(task) = self.selected.and_then(|i| self.displayed_tasks().get(i)) else {
+            return Ok(None);
+        };
+        let Some(details) = filter_to_details(&self.task_mgr.tasks, &Filter::default())
+            .into_iter()
+            .find(|details| {
+                details.task.filename == task.filename
+                    && details.task.line_number == task.line_number
+            })
+        else {
+            bail!("Task {:?} not found in the vault", task.name);
+        };
+        Ok(Some(details))
+    }
+    /// Changes the selected task with `change` and writes it, returns the files to reload.
+    fn change_selected_task(&self, change: &impl Fn(&mut Task)) -> Result<Option<Action>> {
+        self.config.tasks_config.check_writable()?;
+        let Some(details) = self.selected_task_details()? else {
+            return Ok(None);
+        };
+        write_changes(
+            &self.config.tasks_config,
+            std::slice::from_ref(&details),
+            change,
+        )?;
+        Ok(Some(Action::FilesChanged(vec![details.file])))
+    }
+    /// Gives the selected task the next state of `task_state_markers.cycle` and writes it to its note.
+    fn cycle_selected_task_state(&self) -> Result<Option<Action>> {
+        let tasks_config = &self.config.tasks_config;
+        tasks_config.check_writable()?;
+        let Some(details) = self.selected_task_details()? else {
+            return Ok(None);
+        };
+        let state = details.task.state.next(&tasks_config.task_state_markers);
+        mark(tasks_config, std::slice::from_ref(&details), &state)?;
+        if let (State::Done, Some(tx)) = (&state, &self.command_tx) {
+            tx.send(Action::TaskCompleted)?;
+        }
+        Ok(Some(Action::FilesChanged(vec![details.file])))
+    }
+    fn leave_entered_task(&mut self) {
+        if let Some((_, selected)) = self.entered.pop() {
+            self.selected = selected;
+        }
+    }
     fn update_matching_entries(&mut self) {
-        let filter_task = parse_search_input(
+        let filter_task = parse_tab_search(
             self.input_bar_widget.input.value(),
             &self.config.tasks_config,
         );
 
-        // Filter tasks
-        self.matching_tasks = filter_to_vec(&self.task_mgr.tasks, &filter_task);
-        SortingMode::sort(&mut self.matching_tasks, self.sorting_mode);
+        // Filter tasks, sort keys of the search take precedence over the sorting mode
+        let mut matching_tasks = filter_to_vec_with_paths(&self.task_mgr.tasks, &filter_task);
+        if filter_task.sort.is_empty() {
+            self.matching_tasks = matching_tasks.into_iter().map(|(_, t)| t).collect();
+            SortingMode::sort(
+                &mut self.matching_tasks,
+                self.sorting_mode,
+                self.config.tasks_config.untimed_tasks_last,
+            );
+        } else {
+            SortKey::sort(
+                &mut matching_tasks,
+                &filter_task.sort,
+                self.config.tasks_config.untimed_tasks_last,
+            );
+            self.matching_tasks = matching_tasks.into_iter().map(|(_, t)| t).collect();
+        }
+        self.entered.clear();
+        self.selected = self
+            .selected
+            .map(|i| i.min(self.matching_tasks.len().saturating_sub(1)));
 
-        // Reset ScrollViewState
-        self.task_list_widget_state.scroll_to_top();
+        if let Some(tx) = &self.command_tx {
+            if let Err(e) = tx.send(Action::TabCount(
+                Mode::Filter,
+                self.matching_tasks.len().to_string(),
+            )) {
+                error!("Could not send the task count: {e}");
+            }
+            if let Err(e) = tx.send(Action::UpcomingDue(self.upcoming_due())) {
+                error!("Could not send the upcoming due tasks: {e}");
+            }
+        }
 
         // Filter tags
         if !self.matching_tasks.is_empty() {
@@ -84,25 +266,71 @@ impl FilterTab<'_> {
             self.matching_tags.sort();
         }
     }
-    fn split_frame(area: Rect) -> FilterTabArea {
+    /// Reloads the vault, or only the `changed` files, the task list stays scrolled where it was.
+    fn reload(&mut self, changed: Option<&[PathBuf]>) -> Result<()> {
+        match changed {
+            Some(paths) => self
+                .task_mgr
+                .reload_files(&self.config.tasks_config, paths)?,
+            None => self.task_mgr.reload(&self.config.tasks_config)?,
+        }
+        self.parent_tasks = self.task_mgr.tasks.parent_tasks();
+        self.update_matching_entries();
+        self.update_recent_files();
+        Ok(())
+    }
+    /// Updates the list of files modified in the last `recent_days` days
+    fn update_recent_files(&mut self) {
+        let tasks_config = &self.config.tasks_config;
+        let since =
+            chrono::Local::now().naive_local() - TimeDelta::days(tasks_config.recent_days.into());
+        self.recent_files = self
+            .task_mgr
+            .recent_files(since)
+            .iter()
+            .map(|(path, modified)| {
+                (
+                    path.file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .to_string(),
+                    DueDate::DayTime(*modified)
+                        .get_relative_str()
+                        .unwrap_or_default(),
+                )
+            })
+            .collect();
+    }
+    fn split_frame(&self, area: Rect) -> FilterTabArea {
         let vertical = Layout::vertical([
             Constraint::Length(1),
             Constraint::Length(3),
             Constraint::Min(0),
-            Constraint::Length(1),
-            Constraint::Length(1),
+            Constraint::Length(self.config.footer.height()),
+            Constraint::Length(self.config.footer.height()),
         ]);
         let [_header, search, content, footer, _tab_footera] = vertical.areas(area);
 
         let [lateral_lists, task_list] =
             Layout::horizontal([Constraint::Length(16), Constraint::Min(0)]).areas(content);
 
-        let [sorting_modes_list, tag_list] =
-            Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(lateral_lists);
+        let recent_files_height = if self.recent_files.is_empty() {
+            0
+        } else {
+            // Two lines per file and two for the borders
+            (2 * self.recent_files.len() + 2).min(12) as u16
+        };
+        let [sorting_modes_list, tag_list, recent_files_list] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(recent_files_height),
+        ])
+        .areas(lateral_lists);
         FilterTabArea {
             search,
             sorting_modes_list,
             tag_list,
+            recent_files_list,
             task_list,
             footer,
         }
@@ -130,11 +358,21 @@ impl FilterTab<'_> {
     }
     pub fn render_footer(&self, area: Rect, frame: &mut Frame) {
         if self.input_bar_widget.is_focused {
-            Line::raw("Stop Searching: <enter|esc>")
+            let stop_searching = |action| FooterHint {
+                action,
+                label: String::from("Stop Searching"),
+            };
+            HintBar::from_hints(
+                &self.config,
+                &[Mode::Filter],
+                &[
+                    stop_searching(Action::Enter),
+                    stop_searching(Action::Escape),
+                ],
+            )
         } else {
-            Line::raw("Search: <s|enter|esc> | Cycle sorting modes: Shift-s")
+            HintBar::new(&self.config, Mode::Filter)
         }
-        .centered()
         .render(area, frame.buffer_mut());
     }
 }
@@ -155,7 +393,9 @@ impl Component for FilterTab<'_> {
                 .clone(),
         );
         self.help_menu_wigdet = HelpMenu::new(Mode::Filter, &self.config);
+        self.parent_tasks = self.task_mgr.tasks.parent_tasks();
         self.update_matching_entries();
+        self.update_recent_files();
         Ok(())
     }
 
@@ -168,22 +408,29 @@ impl Component for FilterTab<'_> {
     fn update(&mut self, _tui: Option<&mut Tui>, action: Action) -> Result<Option<Action>> {
         if !self.is_focused {
             match action {
-                Action::ReloadVault => {
-                    self.task_mgr.reload(&self.config.tasks_config)?;
-                    self.update_matching_entries();
-                }
+                Action::ReloadVault => self.reload(None)?,
+                Action::FilesChanged(paths) => self.reload(Some(&paths))?,
                 Action::Focus(Mode::Filter) => self.is_focused = true,
                 Action::Focus(mode) if mode != Mode::Filter => self.is_focused = false,
+                Action::SetSearch(search) => {
+                    self.input_bar_widget.input =
+                        self.input_bar_widget.input.clone().with_value(search);
+                    self.input_bar_widget.is_focused = false;
+                    self.update_matching_entries();
+                    self.task_list_widget_state.scroll_to_top();
+                }
                 _ => (),
             }
         } else if self.input_bar_widget.is_focused {
             match action {
                 Action::Enter | Action::Escape => {
                     self.input_bar_widget.is_focused = !self.input_bar_widget.is_focused;
+                    self.input_bar_widget.completion.clear();
                 }
                 Action::Key(key) => {
-                    self.input_bar_widget.input.handle_event(&Event::Key(key));
+                    self.input_bar_widget.handle_key(key, &self.task_mgr.tags);
                     self.update_matching_entries();
+                    self.task_list_widget_state.scroll_to_top();
                 }
                 _ => (),
             }
@@ -200,18 +447,77 @@ impl Component for FilterTab<'_> {
             match action {
                 Action::Focus(mode) if mode != Mode::Filter => self.is_focused = false,
                 Action::Focus(Mode::Filter) => self.is_focused = true,
+                Action::Up => self.select_previous(),
+                Action::Down => self.select_next(),
+                Action::Right => self.enter_selected_task(),
+                Action::Left => self.leave_entered_task(),
+                Action::Cancel | Action::Escape if !self.entered.is_empty() => {
+                    self.leave_entered_task();
+                }
                 Action::Enter | Action::Search | Action::Cancel | Action::Escape => {
                     self.input_bar_widget.is_focused = !self.input_bar_widget.is_focused;
                 }
                 Action::SwitchSortingMode => {
                     self.sorting_mode = self.sorting_mode.next();
                     self.update_matching_entries();
+                    self.task_list_widget_state.scroll_to_top();
                 }
-                Action::Help => self.show_help = !self.show_help,
-                Action::ReloadVault => {
-                    self.task_mgr.reload(&self.config.tasks_config)?;
+                Action::ClearSearch => {
+                    self.input_bar_widget.input.reset();
                     self.update_matching_entries();
+                    self.task_list_widget_state.scroll_to_top();
+                }
+                Action::Help => self.show_help = !self.show_help,
+                Action::CycleState => match self.cycle_selected_task_state() {
+                    Ok(action) => return Ok(action),
+                    Err(e) => error!("Failed to change the state: {e}"),
+                },
+                Action::PostponeDay | Action::PostponeWeek | Action::PostponeToMonday => {
+                    let today = chrono::Local::now().date_naive();
+                    let res = match action {
+                        Action::PostponeDay => self.change_selected_task(&|task| {
+                            task.due_date = task.due_date.shifted(1, today);
+                        }),
+                        Action::PostponeWeek => self.change_selected_task(&|task| {
+                            task.due_date = task.due_date.shifted(7, today);
+                        }),
+                        _ => self.change_selected_task(&|task| {
+                            task.due_date = task.due_date.next_weekday(chrono::Weekday::Mon, today);
+                        }),
+                    };
+                    match res {
+                        Ok(action) => return Ok(action),
+                        Err(e) => error!("Failed to edit due date: {e}"),
+                    }
                 }
+                Action::IncreasePriority | Action::DecreasePriority => {
+                    let delta = if action == Action::IncreasePriority {
+                        1
+                    } else {
+                        -1
+                    };
+                    match self.change_selected_task(&|task| task.shift_priority(delta)) {
+                        Ok(action) => return Ok(action),
+                        Err(e) => error!("Failed to edit priority: {e}"),
+                    }
+                }
+                Action::CopyAsMarkdown => {
+                    let content = to_markdown(
+                        &self.task_mgr.tasks,
+                        &parse_tab_search(
+                            self.input_bar_widget.input.value(),
+                            &self.config.tasks_config,
+                        ),
+                        &self.config.tasks_config,
+                        true,
+                    );
+                    match copy_to_clipboard(&content) {
+                        Ok(()) => info!("Copied matching tasks as Markdown"),
+                        Err(e) => error!("Failed to copy to clipboard: {e}"),
+                    }
+                }
+                Action::ReloadVault => self.reload(None)?,
+                Action::FilesChanged(paths) => self.reload(Some(&paths))?,
                 Action::ViewUp => self.task_list_widget_state.scroll_up(),
                 Action::ViewDown => self.task_list_widget_state.scroll_down(),
                 Action::ViewPageUp => self.task_list_widget_state.scroll_page_up(),
@@ -230,7 +536,7 @@ impl Component for FilterTab<'_> {
             return Ok(());
         }
 
-        let areas = Self::split_frame(area);
+        let areas = self.split_frame(area);
         self.render_footer(areas.footer, frame);
 
         if self.input_bar_widget.is_focused {
@@ -265,28 +571,66 @@ impl Component for FilterTab<'_> {
             .clone()
             .render(areas.search, frame.buffer_mut());
 
-        let tag_list = List::new(self.matching_tags.iter().map(std::string::String::as_str))
-            .block(Block::bordered().title("Found Tags"));
+        let highlighted_style = *self
+            .config
+            .styles
+            .get(&crate::app::Mode::Home)
+            .unwrap()
+            .get("highlighted_style")
+            .unwrap();
+        let tag_list = List::new(self.matching_tags.iter().map(|tag| {
+            Line::from(tag_spans(
+                std::slice::from_ref(tag),
+                &self.config.tag_colors,
+                self.config.tasks_config.tag_pills,
+            ))
+        }))
+        .block(Block::bordered().title("Found Tags"));
 
+        let tasks = self.displayed_tasks();
         let entries_list = TaskList::new(
             &self.config,
-            &self
-                .matching_tasks
-                .clone()
+            &tasks
                 .iter()
                 .map(|t| VaultData::Task(t.clone()))
                 .collect::<Vec<VaultData>>(),
             true,
-        );
+        )
+        .breadcrumbs(
+            &tasks
+                .iter()
+                .map(|t| self.breadcrumb(t))
+                .collect::<Vec<Option<String>>>(),
+        )
+        .select(self.selected, highlighted_style);
 
-        Widget::render(tag_list, areas.tag_list, frame.buffer_mut());
-        self.render_sorting_modes(areas.sorting_modes_list, frame.buffer_mut());
+        let recent_files_list = List::new(self.recent_files.iter().map(|(name, modified)| {
+            Text::from(vec![
+                Line::from(name.as_str()),
+                Line::from(modified.as_str()).italic().dim(),
+            ])
+        }))
+        .block(Block::bordered().title("Recent Files"));
 
-        entries_list.render(
-            areas.task_list,
+        Widget::render(tag_list, areas.tag_list, frame.buffer_mut());
+        Widget::render(
+            recent_files_list,
+            areas.recent_files_list,
             frame.buffer_mut(),
-            &mut self.task_list_widget_state,
         );
+        self.render_sorting_modes(areas.sorting_modes_list, frame.buffer_mut());
+
+        if let Some(empty_state) = self.empty_state() {
+            empty_state.render(areas.task_list, frame.buffer_mut());
+        } else {
+            entries_list.render(
+                areas.task_list,
+                frame.buffer_mut(),
+                &mut self.task_list_widget_state,
+            );
+        }
+        self.input_bar_widget
+            .render_completion(areas.search, frame.buffer_mut());
         if self.show_help {
             debug!("showing help");
             self.help_menu_wigdet.clone().render(
@@ -1,16 +1,47 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
 use super::Component;
-use crate::{action::Action, app::Mode, config::Config, tui::Tui};
+use crate::{
+    action::Action,
+    app::Mode,
+    config::Config,
+    tui::{ring_bell, Tui},
+    widgets::hint_bar::HintBar,
+};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeDelta};
 use color_eyre::Result;
 use ratatui::{prelude::*, widgets::Tabs};
 use strum::{Display, EnumIter, FromRepr, IntoEnumIterator};
 use tokio::sync::mpsc::UnboundedSender;
 use tracing::error;
+use vault_tasks_core::CompletionFeedback;
+
+/// How long the celebration line stays in the status bar
+const CELEBRATION_DURATION: Duration = Duration::from_secs(3);
+/// Tasks due later than this are not counted down
+const NEXT_DUE_HORIZON: TimeDelta = TimeDelta::hours(24);
+/// Longer task names are truncated in the countdown
+const NEXT_DUE_MAX_NAME_LENGTH: usize = 30;
 
 #[derive(Default)]
 pub struct Home {
     command_tx: Option<UnboundedSender<Action>>,
     config: Config,
     selected_tab: SelectedTab,
+    /// Number of tasks completed on `completion_date` during this session
+    completed_today: usize,
+    completion_date: Option<NaiveDate>,
+    /// The celebration line is shown until then
+    celebration_end: Option<Instant>,
+    /// Counts shown in the tab titles, sent by the tabs
+    tab_counts: HashMap<Mode, String>,
+    /// Timed due dates of the tasks of the Filter tab, soonest first
+    upcoming_due: Vec<(NaiveDateTime, String)>,
+    /// When every file was last reloaded by `auto_reload_seconds`
+    last_refresh: Option<NaiveDateTime>,
 }
 
 impl Home {
@@ -20,12 +51,7 @@ impl Home {
 
     fn send_new_focused_tab_command(&self) {
         if let Some(tx) = &self.command_tx {
-            if let Err(e) = tx.send(match self.selected_tab {
-                SelectedTab::Explorer => Action::Focus(Mode::Explorer),
-                SelectedTab::Filter => Action::Focus(Mode::Filter),
-                SelectedTab::TimeManagement => Action::Focus(Mode::TimeManagement),
-                SelectedTab::Calendar => Action::Focus(Mode::Calendar),
-            }) {
+            if let Err(e) = tx.send(Action::Focus(self.selected_tab.mode())) {
                 error!("Could not focus selected tab: {e}");
             }
         }
@@ -40,9 +66,9 @@ impl Home {
         self.send_new_focused_tab_command();
     }
     fn render_tabs(&self, area: Rect, buf: &mut Buffer) {
-        let titles = SelectedTab::iter().map(SelectedTab::title);
+        let titles = SelectedTab::iter().map(|tab| tab.title(self.tab_counts.get(&tab.mode())));
 
-        let highlight_style = *self
+        let mut highlight_style = *self
             .config
             .styles
             .get(&crate::app::Mode::Home)
@@ -50,6 +76,28 @@ impl Home {
             .get("highlighted_style")
             .unwrap();
 
+        // The name of the open vault of `vaults` is shown on the right, in its accent color
+        let area = if let Some((name, vault)) = self.config.tasks_config.current_vault() {
+            let accent = (!vault.accent.is_empty())
+                .then(|| vault.accent.parse::<Color>().ok())
+                .flatten();
+            let [area, name_area] = Layout::horizontal([
+                Constraint::Min(0),
+                Constraint::Length(u16::try_from(name.chars().count() + 1).unwrap_or_default()),
+            ])
+            .areas(area);
+            let name = Line::raw(format!("{name} ")).right_aligned().bold();
+            if let Some(accent) = accent {
+                highlight_style = highlight_style.bg(accent);
+                name.fg(accent).render(name_area, buf);
+            } else {
+                name.render(name_area, buf);
+            }
+            area
+        } else {
+            area
+        };
+
         let selected_tab_index = self.selected_tab as usize;
         Tabs::new(titles)
             .select(selected_tab_index)
@@ -59,10 +107,153 @@ impl Home {
             .render(area, buf);
     }
 
-    pub fn render_footer(area: Rect, frame: &mut Frame) {
-        Line::raw("Change tab: Shift+<hl|◄►> | Quit: q | Help: ?")
-            .centered()
-            .render(area, frame.buffer_mut());
+    fn on_task_completed(&mut self, today: NaiveDate) {
+        if self.completion_date != Some(today) {
+            self.completion_date = Some(today);
+            self.completed_today = 0;
+        }
+        self.completed_today += 1;
+        match self.config.tasks_config.completion_feedback {
+            CompletionFeedback::None => (),
+            CompletionFeedback::Bell => {
+                if let Err(e) = ring_bell() {
+                    error!("Failed to ring the bell: {e}");
+                }
+            }
+            CompletionFeedback::Confetti => {
+                self.celebration_end = Some(Instant::now() + CELEBRATION_DURATION);
+            }
+        }
+    }
+
+    fn celebration_line(&self) -> Line<'static> {
+        if self.config.tasks_config.ascii_mode {
+            Line::raw("*** Task done! ***")
+        } else {
+            Line::raw("🎉 ✨ Task done! ✨ 🎉")
+        }
+        .centered()
+        .bold()
+    }
+
+    fn render_completion_counter(&self, area: Rect, frame: &mut Frame) {
+        Line::raw(format!(
+            "{} {} today ",
+            self.config.tasks_config.pretty_symbols.task_done, self.completed_today
+        ))
+        .right_aligned()
+        .render(area, frame.buffer_mut());
+    }
+
+    /// Returns the time of the last reload of `auto_reload_seconds`, e.g. `↻ 09:41:12`.
+    fn refresh_indicator(&self) -> Option<String> {
+        if self.config.tasks_config.auto_reload_seconds == 0 {
+            return None;
+        }
+        let symbol = if self.config.tasks_config.ascii_mode {
+            "reloaded"
+        } else {
+            "↻"
+        };
+        self.last_refresh
+            .map(|time| format!("{symbol} {} ", time.format("%T")))
+    }
+
+    /// Returns the countdown to the next task due after `now`, e.g. `Standup in 00:42`.
+    fn next_due_countdown(&self, now: NaiveDateTime) -> Option<String> {
+        let (due, name) = self.upcoming_due.iter().find(|(due, _)| *due > now)?;
+        let remaining = *due - now;
+        if remaining > NEXT_DUE_HORIZON {
+            return None;
+        }
+        let name = if name.chars().count() > NEXT_DUE_MAX_NAME_LENGTH {
+            format!(
+                "{}…",
+                name.chars()
+                    .take(NEXT_DUE_MAX_NAME_LENGTH - 1)
+                    .collect::<String>()
+            )
+        } else {
+            name.clone()
+        };
+        Some(format!(
+            "{name} in {:02}:{:02} ",
+            remaining.num_hours(),
+            remaining.num_minutes() % 60
+        ))
+    }
+
+    pub fn render_footer(&self, area: Rect, frame: &mut Frame) {
+        let area = if self.config.tasks_config.read_only {
+            let [area, read_only_area] =
+                Layout::horizontal([Constraint::Min(0), Constraint::Length(11)]).areas(area);
+            Line::raw("read-only ")
+                .right_aligned()
+                .fg(Color::Yellow)
+                .render(read_only_area, frame.buffer_mut());
+            area
+        } else {
+            area
+        };
+        let area = if self.config.tasks_config.show_completion_counter
+            && self.completed_today > 0
+            && self.completion_date == Some(chrono::Local::now().date_naive())
+        {
+            let [area, counter_area] =
+                Layout::horizontal([Constraint::Min(0), Constraint::Length(12)]).areas(area);
+            self.render_completion_counter(counter_area, frame);
+            area
+        } else {
+            area
+        };
+        let area = if let Some(refresh) = self.refresh_indicator() {
+            let [area, refresh_area] = Layout::horizontal([
+                Constraint::Min(0),
+                Constraint::Length(u16::try_from(refresh.chars().count()).unwrap_or_default()),
+            ])
+            .areas(area);
+            Line::raw(refresh)
+                .right_aligned()
+                .dim()
+                .render(refresh_area, frame.buffer_mut());
+            area
+        } else {
+            area
+        };
+        let countdown = if self.config.tasks_config.show_next_due {
+            self.next_due_countdown(chrono::Local::now().naive_local())
+        } else {
+            None
+        };
+        let area = if let Some(countdown) = countdown {
+            let [area, countdown_area] = Layout::horizontal([
+                Constraint::Min(0),
+                Constraint::Length(u16::try_from(countdown.chars().count()).unwrap_or_default()),
+            ])
+            .areas(area);
+            Line::raw(countdown)
+                .right_aligned()
+                .render(countdown_area, frame.buffer_mut());
+            area
+        } else {
+            area
+        };
+        if self.celebration_end.is_some_and(|end| Instant::now() < end) {
+            self.celebration_line().render(area, frame.buffer_mut());
+            return;
+        }
+        // Global hints are bound in the keymap of the focused tab
+        HintBar::from_hints(
+            &self.config,
+            &[self.selected_tab.mode(), Mode::Home],
+            self.config
+                .footer
+                .hints
+                .get(&Mode::Home)
+                .map(Vec::as_slice)
+                .unwrap_or_default(),
+        )
+        .render(area, frame.buffer_mut());
     }
 }
 impl Component for Home {
@@ -83,8 +274,23 @@ impl Component for Home {
             Action::TabLeft => self.previous_tab(),
             Action::Focus(Mode::Explorer) => self.selected_tab = SelectedTab::Explorer,
             Action::Focus(Mode::Filter) => self.selected_tab = SelectedTab::Filter,
+            Action::Focus(Mode::Inbox) => self.selected_tab = SelectedTab::Inbox,
             Action::Focus(Mode::TimeManagement) => self.selected_tab = SelectedTab::TimeManagement,
             Action::Focus(Mode::Calendar) => self.selected_tab = SelectedTab::Calendar,
+            Action::Focus(Mode::Stats) => self.selected_tab = SelectedTab::Stats,
+            Action::TabCount(mode, count) => {
+                self.tab_counts.insert(mode, count);
+            }
+            Action::UpcomingDue(upcoming_due) => {
+                self.upcoming_due = upcoming_due
+                    .into_iter()
+                    .filter_map(|(timestamp, name)| {
+                        Some((DateTime::from_timestamp(timestamp, 0)?.naive_utc(), name))
+                    })
+                    .collect();
+            }
+            Action::TaskCompleted => self.on_task_completed(chrono::Local::now().date_naive()),
+            Action::VaultRefreshed => self.last_refresh = Some(chrono::Local::now().naive_local()),
             _ => (),
         }
         Ok(None)
@@ -92,11 +298,11 @@ impl Component for Home {
 
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
         use Constraint::{Length, Min};
-        let vertical = Layout::vertical([Length(1), Min(0), Length(1)]);
+        let vertical = Layout::vertical([Length(1), Min(0), Length(self.config.footer.height())]);
         let [header_area, _inner_area, footer_area] = vertical.areas(area);
 
         self.render_tabs(header_area, frame.buffer_mut());
-        Self::render_footer(footer_area, frame);
+        self.render_footer(footer_area, frame);
         Ok(())
     }
 }
@@ -108,10 +314,14 @@ enum SelectedTab {
     Explorer,
     #[strum(to_string = "Filter")]
     Filter,
+    #[strum(to_string = "Inbox")]
+    Inbox,
     #[strum(to_string = "Calendar")]
     Calendar,
     #[strum(to_string = "Time Management")]
     TimeManagement,
+    #[strum(to_string = "Stats")]
+    Stats,
 }
 
 impl SelectedTab {
@@ -128,8 +338,21 @@ impl SelectedTab {
         let next_index = current_index.saturating_add(1);
         Self::from_repr(next_index).unwrap_or(self)
     }
-    fn title(self) -> Line<'static> {
-        format!("  {self}  ").into()
+    const fn mode(self) -> Mode {
+        match self {
+            Self::Explorer => Mode::Explorer,
+            Self::Filter => Mode::Filter,
+            Self::Inbox => Mode::Inbox,
+            Self::TimeManagement => Mode::TimeManagement,
+            Self::Calendar => Mode::Calendar,
+            Self::Stats => Mode::Stats,
+        }
+    }
+    fn title(self, count: Option<&String>) -> Line<'static> {
+        match count {
+            Some(count) => format!("  {self} ({count})  ").into(),
+            None => format!("  {self}  ").into(),
+        }
     }
 }
 
@@ -139,10 +362,15 @@ mod tests {
     use ratatui::{backend::TestBackend, Terminal};
     use tokio::sync::mpsc::unbounded_channel;
 
+    use chrono::{NaiveDate, TimeDelta};
+
     use crate::{
+        action::Action,
+        app::Mode,
         components::{home::Home, Component},
         config::Config,
     };
+    use vault_tasks_core::CompletionFeedback;
 
     #[test]
     fn test_render_home_component() {
@@ -159,4 +387,86 @@ mod tests {
             .unwrap();
         assert_snapshot!(terminal.backend());
     }
+
+    #[test]
+    fn test_render_tab_counts() {
+        let mut home = Home::new();
+        home.register_config_handler(Config::default()).unwrap();
+        home.update(None, Action::TabCount(Mode::Filter, "42".to_owned()))
+            .unwrap();
+        home.update(None, Action::TabCount(Mode::Calendar, "7 today".to_owned()))
+            .unwrap();
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        terminal
+            .draw(|frame| home.draw(frame, frame.area()).unwrap())
+            .unwrap();
+        assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_completion_counter() {
+        let mut home = Home::new();
+        let mut config = Config::default();
+        config.tasks_config.completion_feedback = CompletionFeedback::Confetti;
+        home.register_config_handler(config).unwrap();
+
+        let day = NaiveDate::from_ymd_opt(2025, 1, 2).unwrap();
+        home.on_task_completed(day);
+        home.on_task_completed(day);
+        assert_eq!(home.completed_today, 2);
+        assert!(home.celebration_end.is_some());
+
+        // The counter is reset every day
+        home.on_task_completed(day.succ_opt().unwrap());
+        assert_eq!(home.completed_today, 1);
+    }
+
+    #[test]
+    fn test_next_due_countdown() {
+        let mut home = Home::new();
+        home.register_config_handler(Config::default()).unwrap();
+        let at = |hour, min| {
+            NaiveDate::from_ymd_opt(2025, 1, 2)
+                .unwrap()
+                .and_hms_opt(hour, min, 0)
+                .unwrap()
+        };
+        assert_eq!(home.next_due_countdown(at(9, 0)), None);
+
+        home.update(
+            None,
+            Action::UpcomingDue(vec![
+                (at(9, 0).and_utc().timestamp(), "Review".to_owned()),
+                (at(10, 30).and_utc().timestamp(), "Standup".to_owned()),
+            ]),
+        )
+        .unwrap();
+        assert_eq!(
+            home.next_due_countdown(at(9, 48)),
+            Some("Standup in 00:42 ".to_owned())
+        );
+        assert_eq!(
+            home.next_due_countdown(at(8, 0)),
+            Some("Review in 01:00 ".to_owned())
+        );
+        // Tasks due in more than a day are not counted down
+        assert_eq!(home.next_due_countdown(at(8, 0) - TimeDelta::days(2)), None);
+        assert_eq!(home.next_due_countdown(at(11, 0)), None);
+    }
+
+    #[test]
+    fn test_refresh_indicator() {
+        let mut home = Home::new();
+        let mut config = Config::default();
+        home.register_config_handler(config.clone()).unwrap();
+        home.update(None, Action::VaultRefreshed).unwrap();
+        assert_eq!(home.refresh_indicator(), None);
+
+        config.tasks_config.auto_reload_seconds = 30;
+        home.register_config_handler(config).unwrap();
+        assert!(home
+            .refresh_indicator()
+            .is_some_and(|s| s.starts_with("↻ ")));
+    }
 }
@@ -0,0 +1,499 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::Result;
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::Stylize,
+    text::Line,
+    widgets::{Block, StatefulWidget, Widget},
+    Frame,
+};
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::{error, info};
+use tui_input::Input;
+use tui_scrollview::ScrollViewState;
+
+use super::Component;
+
+use crate::{
+    action::Action,
+    app::Mode,
+    config::{Config, FooterHint},
+    tui::Tui,
+    widgets::{
+        empty_state::EmptyState, help_menu::HelpMenu, hint_bar::HintBar, input_bar::InputBar,
+        task_list::TaskList,
+    },
+};
+use vault_tasks_core::{
+    archive::{plan_delete, plan_move},
+    changeset::Changeset,
+    daily_notes::drop_file_path,
+    details::TaskDetails,
+    edit::TaskEdit,
+    inbox::inbox_tasks,
+    mark::{mark, write_changes},
+    task::{State, Task},
+    vault_data::VaultData,
+    TaskManager, TasksConfig,
+};
+
+/// Struct that helps with drawing the component
+struct InboxTabArea {
+    title: Rect,
+    prompt: Rect,
+    task_list: Rect,
+    footer: Rect,
+}
+
+/// What the input bar of the tab asks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Prompt {
+    /// Note the selected task is moved to, relative to the vault
+    Move,
+    /// Task tokens applied to the selected task, e.g. `tomorrow #work p1`
+    Set,
+}
+
+impl Prompt {
+    const fn title(self) -> &'static str {
+        match self {
+            Self::Move => "Move to (e.g. Projects/garden.md)",
+            Self::Set => "Set (e.g. tomorrow #work p1)",
+        }
+    }
+}
+
+/// Unfinished tasks of `drop_file`, listed flat to be triaged until none is left.
+#[derive(Default)]
+pub struct InboxTab<'a> {
+    command_tx: Option<UnboundedSender<Action>>,
+    config: Config,
+    is_focused: bool,
+    task_mgr: TaskManager,
+    /// Path of the drop file, `None` if it is not configured
+    path: Option<PathBuf>,
+    tasks: Vec<TaskDetails>,
+    selected: usize,
+    task_list_widget_state: ScrollViewState,
+    prompt: Option<Prompt>,
+    input_bar_widget: InputBar<'a>,
+    /// Whether the help panel is open or not
+    show_help: bool,
+    help_menu_wigdet: HelpMenu<'a>,
+}
+
+impl InboxTab<'_> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Reloads the vault, or only the `changed` files, and lists the tasks of the drop file.
+    ///
+    /// The vault is only read, the tasks are fixed when the other tabs load it.
+    fn reload(&mut self, changed: Option<&[PathBuf]>) -> Result<()> {
+        let tasks_config = TasksConfig {
+            read_only: true,
+            ..self.config.tasks_config.clone()
+        };
+        match changed {
+            Some(paths) => self.task_mgr.reload_files(&tasks_config, paths)?,
+            None => self.task_mgr.reload(&tasks_config)?,
+        }
+        self.update_tasks();
+        Ok(())
+    }
+    fn update_tasks(&mut self) {
+        // The drop file can depend on the date
+        self.path =
+            drop_file_path(&self.config.tasks_config, chrono::Local::now().date_naive()).ok();
+        self.tasks = self
+            .path
+            .as_ref()
+            .map(|path| inbox_tasks(&self.task_mgr.tasks, path))
+            .unwrap_or_default();
+        self.selected = self.selected.min(self.tasks.len().saturating_sub(1));
+        if let Some(tx) = &self.command_tx {
+            if let Err(e) = tx.send(Action::TabCount(Mode::Inbox, self.tasks.len().to_string())) {
+                error!("Could not send the task count: {e}");
+            }
+        }
+    }
+    fn selected_task(&self) -> Option<&TaskDetails> {
+        self.tasks.get(self.selected)
+    }
+    /// Changes the selected task with `change` and writes it, returns the files to reload.
+    fn change_selected_task(&self, change: &impl Fn(&mut Task)) -> Result<Option<Action>> {
+        let Some(details) = self.selected_task() else {
+            return Ok(None);
+        };
+        self.config.tasks_config.check_writable()?;
+        write_changes(
+            &self.config.tasks_config,
+            std::slice::from_ref(details),
+            change,
+        )?;
+        Ok(Some(Action::FilesChanged(vec![details.file.clone()])))
+    }
+    /// Writes `changes`, returns the files to reload.
+    fn apply_changes(&self, changes: &Changeset) -> Result<Option<Action>> {
+        self.config.tasks_config.check_writable()?;
+        changes.apply()?;
+        Ok(Some(Action::FilesChanged(
+            changes.changes.iter().map(|c| c.path.clone()).collect(),
+        )))
+    }
+    fn mark_selected_task(&self, state: &State) -> Result<Option<Action>> {
+        let Some(details) = self.selected_task() else {
+            return Ok(None);
+        };
+        self.config.tasks_config.check_writable()?;
+        mark(
+            &self.config.tasks_config,
+            std::slice::from_ref(details),
+            state,
+        )?;
+        if *state == State::Done {
+            if let Some(tx) = &self.command_tx {
+                tx.send(Action::TaskCompleted)?;
+            }
+        }
+        Ok(Some(Action::FilesChanged(vec![details.file.clone()])))
+    }
+    fn delete_selected_task(&self) -> Result<Option<Action>> {
+        let Some(details) = self.selected_task() else {
+            return Ok(None);
+        };
+        let (_, changes) = plan_delete(std::slice::from_ref(details))?;
+        info!("Deleted {:?}", details.task.name);
+        self.apply_changes(&changes)
+    }
+    /// Applies the input of the prompt to the selected task.
+    fn submit_prompt(&self, prompt: Prompt) -> Result<Option<Action>> {
+        let Some(details) = self.selected_task() else {
+            return Ok(None);
+        };
+        let input = self.input_bar_widget.input.value().trim();
+        match prompt {
+            Prompt::Move => {
+                let (_, changes) = plan_move(
+                    &self.config.tasks_config,
+                    std::slice::from_ref(details),
+                    Path::new(input),
+                )?;
+                info!("Moved {:?} to {input}", details.task.name);
+                self.apply_changes(&changes)
+            }
+            Prompt::Set => {
+                let edit = TaskEdit::new(
+                    &self.config.tasks_config,
+                    &[input.to_owned()],
+                    &[],
+                    &[],
+                    None,
+                )?;
+                self.change_selected_task(&|task| edit.apply(task))
+            }
+        }
+    }
+    fn open_prompt(&mut self, prompt: Prompt) {
+        if self.selected_task().is_none() {
+            return;
+        }
+        self.input_bar_widget.input = Input::default();
+        self.input_bar_widget.is_focused = true;
+        self.prompt = Some(prompt);
+    }
+    fn close_prompt(&mut self) {
+        self.input_bar_widget.is_focused = false;
+        self.input_bar_widget.completion.clear();
+        self.prompt = None;
+    }
+    /// Logs the error of a triage action instead of leaving the app.
+    fn log_error(res: Result<Option<Action>>) -> Option<Action> {
+        res.unwrap_or_else(|e| {
+            error!("{e}");
+            None
+        })
+    }
+    fn split_frame(&self, area: Rect) -> InboxTabArea {
+        let [_header, title, prompt, task_list, footer, _tab_footer] = Layout::vertical([
+            Constraint::Length(1),                                         // tabs
+            Constraint::Length(1),                                         // title
+            Constraint::Length(if self.prompt.is_some() { 3 } else { 0 }), // prompt
+            Constraint::Min(0),                                            // tasks
+            Constraint::Length(self.config.footer.height()),               // footer
+            Constraint::Length(self.config.footer.height()),               // home footer
+        ])
+        .areas(area);
+        InboxTabArea {
+            title,
+            prompt,
+            task_list,
+            footer,
+        }
+    }
+    fn render_footer(&self, area: Rect, frame: &mut Frame) {
+        if self.prompt.is_some() {
+            HintBar::from_hints(
+                &self.config,
+                &[Mode::Inbox],
+                &[
+                    FooterHint {
+                        action: Action::Enter,
+                        label: String::from("Apply"),
+                    },
+                    FooterHint {
+                        action: Action::Escape,
+                        label: String::from("Cancel"),
+                    },
+                ],
+            )
+        } else {
+            HintBar::new(&self.config, Mode::Inbox)
+        }
+        .render(area, frame.buffer_mut());
+    }
+    fn render_prompt(&mut self, area: Rect, frame: &mut Frame) {
+        let Some(prompt) = self.prompt else {
+            return;
+        };
+        let width = area.width.max(3) - 3; // 2 for borders, 1 for cursor
+        let scroll = self.input_bar_widget.input.visual_scroll(width as usize);
+        frame.set_cursor_position((
+            area.x.saturating_add(
+                ((self.input_bar_widget.input.visual_cursor()).max(scroll) - scroll) as u16,
+            ) + 1,
+            area.y + 1,
+        ));
+        let style = *self
+            .config
+            .styles
+            .get(&Mode::Home)
+            .unwrap()
+            .get("highlighted_bar_style")
+            .unwrap();
+        self.input_bar_widget.block = Some(Block::bordered().title(prompt.title()).style(style));
+        self.input_bar_widget
+            .clone()
+            .render(area, frame.buffer_mut());
+    }
+    /// Tells why no task is listed, `None` if there are tasks to triage.
+    fn empty_state(&self) -> Option<EmptyState> {
+        if !self.tasks.is_empty() {
+            return None;
+        }
+        let res = match &self.path {
+            None => EmptyState::new(
+                "No drop file",
+                "Set `drop_file` in the configuration, or daily notes to use today's note, to collect tasks with `vault-tasks add`",
+            ),
+            Some(path) => EmptyState::new(
+                "Inbox zero",
+                format!(
+                    "Every task of {} is triaged, add more with `vault-tasks add`",
+                    path.strip_prefix(&self.config.tasks_config.vault_path)
+                        .unwrap_or(path)
+                        .display()
+                ),
+            ),
+        };
+        Some(res.hint(
+            &self.config,
+            Mode::Inbox,
+            &Action::ReloadVault,
+            "reload the vault",
+        ))
+    }
+}
+
+impl Component for InboxTab<'_> {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        self.update_tasks();
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        self.help_menu_wigdet = HelpMenu::new(Mode::Inbox, &self.config);
+        self.reload(None)
+    }
+
+    fn blocking_mode(&self) -> bool {
+        self.is_focused && (self.prompt.is_some() || self.show_help)
+    }
+    fn escape_blocking_mode(&self) -> Vec<Action> {
+        vec![Action::Enter, Action::Cancel, Action::Escape]
+    }
+
+    fn update(&mut self, _tui: Option<&mut Tui>, action: Action) -> Result<Option<Action>> {
+        if !self.is_focused {
+            match action {
+                Action::ReloadVault => self.reload(None)?,
+                Action::FilesChanged(paths) => self.reload(Some(&paths))?,
+                Action::Focus(Mode::Inbox) => self.is_focused = true,
+                _ => (),
+            }
+        } else if let Some(prompt) = self.prompt {
+            match action {
+                Action::Enter => {
+                    let res = Self::log_error(self.submit_prompt(prompt));
+                    self.close_prompt();
+                    return Ok(res);
+                }
+                Action::Escape | Action::Cancel => self.close_prompt(),
+                Action::Key(key) => {
+                    let tags = if prompt == Prompt::Set {
+                        self.task_mgr.tags.clone()
+                    } else {
+                        HashSet::new()
+                    };
+                    self.input_bar_widget.handle_key(key, &tags);
+                }
+                _ => (),
+            }
+        } else if self.show_help {
+            match action {
+                Action::ViewUp | Action::Up => self.help_menu_wigdet.scroll_up(),
+                Action::ViewDown | Action::Down => self.help_menu_wigdet.scroll_down(),
+                Action::Help | Action::Escape | Action::Enter => {
+                    self.show_help = !self.show_help;
+                }
+                _ => (),
+            }
+        } else {
+            let today = chrono::Local::now().date_naive();
+            let res = match action {
+                Action::Focus(mode) if mode != Mode::Inbox => {
+                    self.is_focused = false;
+                    Ok(None)
+                }
+                Action::Help => {
+                    self.show_help = !self.show_help;
+                    Ok(None)
+                }
+                Action::Up => {
+                    self.selected = self.selected.saturating_sub(1);
+                    Ok(None)
+                }
+                Action::Down => {
+                    self.selected = (self.selected + 1).min(self.tasks.len().saturating_sub(1));
+                    Ok(None)
+                }
+                Action::MarkDone => self.mark_selected_task(&State::Done),
+                Action::MarkCancel => self.mark_selected_task(&State::Canceled),
+                Action::MarkIncomplete => self.mark_selected_task(&State::Incomplete),
+                Action::MarkToDo => self.mark_selected_task(&State::ToDo),
+                Action::PostponeDay => self
+                    .change_selected_task(&|task| task.due_date = task.due_date.shifted(1, today)),
+                Action::PostponeWeek => self
+                    .change_selected_task(&|task| task.due_date = task.due_date.shifted(7, today)),
+                Action::PostponeToMonday => self.change_selected_task(&|task| {
+                    task.due_date = task.due_date.next_weekday(chrono::Weekday::Mon, today);
+                }),
+                Action::IncreasePriority => self.change_selected_task(&|task| {
+                    task.priority += 1;
+                    task.inherited_priority = false;
+                }),
+                Action::DecreasePriority => self.change_selected_task(&|task| {
+                    task.priority = task.priority.saturating_sub(1);
+                    task.inherited_priority = false;
+                }),
+                Action::DeleteTask => self.delete_selected_task(),
+                Action::MoveTask => {
+                    self.open_prompt(Prompt::Move);
+                    Ok(None)
+                }
+                Action::Edit => {
+                    self.open_prompt(Prompt::Set);
+                    Ok(None)
+                }
+                Action::ReloadVault => self.reload(None).map(|()| None),
+                Action::FilesChanged(paths) => self.reload(Some(&paths)).map(|()| None),
+                Action::ViewUp => {
+                    self.task_list_widget_state.scroll_up();
+                    Ok(None)
+                }
+                Action::ViewDown => {
+                    self.task_list_widget_state.scroll_down();
+                    Ok(None)
+                }
+                Action::ViewPageUp => {
+                    self.task_list_widget_state.scroll_page_up();
+                    Ok(None)
+                }
+                Action::ViewPageDown => {
+                    self.task_list_widget_state.scroll_page_down();
+                    Ok(None)
+                }
+                _ => Ok(None),
+            };
+            return Ok(Self::log_error(res));
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if !self.is_focused {
+            return Ok(());
+        }
+        let areas = self.split_frame(area);
+        self.render_footer(areas.footer, frame);
+
+        if let Some(path) = &self.path {
+            Line::from(vec![
+                path.strip_prefix(&self.config.tasks_config.vault_path)
+                    .unwrap_or(path)
+                    .display()
+                    .to_string()
+                    .bold(),
+                format!(" - {} tasks to triage", self.tasks.len()).into(),
+            ])
+            .centered()
+            .render(areas.title, frame.buffer_mut());
+        }
+
+        if let Some(empty_state) = self.empty_state() {
+            empty_state.render(areas.task_list, frame.buffer_mut());
+        } else {
+            let highlighted_style = *self
+                .config
+                .styles
+                .get(&Mode::Home)
+                .unwrap()
+                .get("highlighted_style")
+                .unwrap();
+            TaskList::new(
+                &self.config,
+                &self
+                    .tasks
+                    .iter()
+                    .map(|details| VaultData::Task(details.task.clone()))
+                    .collect::<Vec<VaultData>>(),
+                false,
+            )
+            .select(Some(self.selected), highlighted_style)
+            .render(
+                areas.task_list,
+                frame.buffer_mut(),
+                &mut self.task_list_widget_state,
+            );
+        }
+
+        self.render_prompt(areas.prompt, frame);
+        self.input_bar_widget
+            .render_completion(areas.prompt, frame.buffer_mut());
+
+        if self.show_help {
+            self.help_menu_wigdet.clone().render(
+                area,
+                frame.buffer_mut(),
+                &mut self.help_menu_wigdet.state,
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,72 @@
+use color_eyre::Result;
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Stylize},
+    text::Line,
+    widgets::{Block, Clear, Paragraph},
+    Frame,
+};
+
+use super::Component;
+
+use crate::{action::Action, logging::RECENT_LOGS, tui::Tui};
+
+/// Height of the pane, borders included
+const LOG_PANE_HEIGHT: u16 = 12;
+
+/// Toggleable pane showing recent warnings and errors over the current tab.
+#[derive(Default)]
+pub struct LogPane {
+    enabled: bool,
+}
+
+impl LogPane {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Component for LogPane {
+    fn update(&mut self, _tui: Option<&mut Tui>, action: Action) -> Result<Option<Action>> {
+        if action == Action::ToggleLogs {
+            self.enabled = !self.enabled;
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let [_, area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(LOG_PANE_HEIGHT)]).areas(area);
+        let visible = usize::from(area.height.saturating_sub(2));
+        let lines = RECENT_LOGS.lock().map_or_else(
+            |_| vec![],
+            |logs| {
+                logs.iter()
+                    .skip(logs.len().saturating_sub(visible))
+                    .map(|l| {
+                        let line = Line::raw(l.clone());
+                        if l.contains("ERROR") {
+                            line.fg(Color::Red)
+                        } else {
+                            line.fg(Color::Yellow)
+                        }
+                    })
+                    .collect::<Vec<Line>>()
+            },
+        );
+        let paragraph = if lines.is_empty() {
+            Paragraph::new("No warnings nor errors").dim()
+        } else {
+            Paragraph::new(lines)
+        };
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            paragraph.block(Block::bordered().title("Logs (warnings and errors)")),
+            area,
+        );
+        Ok(())
+    }
+}
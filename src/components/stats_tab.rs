@@ -0,0 +1,271 @@
+use std::time::Instant;
+
+use color_eyre::Result;
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style, Stylize},
+    symbols::Marker,
+    text::Line,
+    widgets::{Axis, Block, Chart, Dataset, GraphType, Paragraph, StatefulWidget, Widget, Wrap},
+    Frame,
+};
+
+use super::Component;
+
+use crate::{
+    action::Action,
+    app::Mode,
+    config::Config,
+    tui::Tui,
+    widgets::{empty_state::EmptyState, help_menu::HelpMenu, hint_bar::HintBar},
+};
+use vault_tasks_core::{
+    health::HealthReport,
+    stats::{weekly_stats, WeekStats},
+    TaskManager,
+};
+
+/// Struct that helps with drawing the component
+struct StatsTabArea {
+    summary: Rect,
+    chart: Rect,
+    health: Rect,
+    footer: Rect,
+}
+
+#[derive(Default)]
+pub struct StatsTab<'a> {
+    config: Config,
+    is_focused: bool,
+    task_mgr: TaskManager,
+    /// Number of weeks displayed
+    weeks: usize,
+    stats: Vec<WeekStats>,
+    health: HealthReport,
+    /// Whether the help panel is open or not
+    show_help: bool,
+    help_menu_wigdet: HelpMenu<'a>,
+}
+
+impl StatsTab<'_> {
+    const CREATED: Color = Color::Red;
+    const COMPLETED: Color = Color::Green;
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Loads the vault and checks its health.
+    fn load(&mut self) -> Result<()> {
+        let start = Instant::now();
+        self.task_mgr = TaskManager::load_read_only(&self.config.tasks_config)?;
+        let parse_time = start.elapsed().as_millis();
+        self.health = HealthReport {
+            parse_time,
+            ..HealthReport::from_task_manager(
+                &self.config.tasks_config,
+                &self.task_mgr,
+                chrono::Local::now().naive_local(),
+            )
+        };
+        self.update_stats();
+        Ok(())
+    }
+    fn update_stats(&mut self) {
+        self.stats = weekly_stats(
+            &self.task_mgr.tasks,
+            self.weeks,
+            chrono::Local::now().date_naive(),
+        );
+    }
+    fn split_frame(&self, area: Rect) -> StatsTabArea {
+        let [_header, summary, chart, footer, _tab_footer] = Layout::vertical([
+            Constraint::Length(1),                           // tabs
+            Constraint::Length(1),                           // summary
+            Constraint::Min(0),                              // chart
+            Constraint::Length(self.config.footer.height()), // footer
+            Constraint::Length(self.config.footer.height()), // home footer
+        ])
+        .areas(area);
+        let [chart, health] =
+            Layout::horizontal([Constraint::Fill(2), Constraint::Fill(1)]).areas(chart);
+        StatsTabArea {
+            summary,
+            chart,
+            health,
+            footer,
+        }
+    }
+    fn render_footer(&self, area: Rect, frame: &mut Frame) {
+        HintBar::new(&self.config, Mode::Stats).render(area, frame.buffer_mut());
+    }
+    /// Tells whether the backlog grew or shrank over the displayed weeks.
+    fn summary(&self) -> Line<'static> {
+        let created = self.stats.iter().map(|w| w.created).sum::<usize>();
+        let completed = self.stats.iter().map(|w| w.completed).sum::<usize>();
+        let trend = match created.cmp(&completed) {
+            std::cmp::Ordering::Greater => format!("Backlog grew by {}", created - completed),
+            std::cmp::Ordering::Less => format!("Backlog shrank by {}", completed - created),
+            std::cmp::Ordering::Equal => String::from("Backlog is stable"),
+        };
+        Line::from(vec![
+            format!("Last {} weeks: ", self.weeks).into(),
+            format!("{created} created").fg(Self::CREATED),
+            ", ".into(),
+            format!("{completed} completed").fg(Self::COMPLETED),
+            format!(" - {trend}").bold(),
+        ])
+        .centered()
+    }
+    fn render_health(&self, area: Rect, frame: &mut Frame) {
+        let lines = self
+            .health
+            .summary()
+            .into_iter()
+            .map(Line::raw)
+            .chain(
+                self.health
+                    .diagnostics
+                    .iter()
+                    .map(|d| Line::raw(d.to_string()).fg(Color::Yellow)),
+            )
+            .collect::<Vec<Line>>();
+        Paragraph::new(lines)
+            .block(Block::bordered().title("Vault health"))
+            .wrap(Wrap { trim: false })
+            .render(area, frame.buffer_mut());
+    }
+    fn render_chart(&self, area: Rect, frame: &mut Frame) {
+        if self
+            .stats
+            .iter()
+            .all(|w| w.created == 0 && w.completed == 0)
+        {
+            let block = Block::bordered().title("Tasks per week");
+            let inner = block.inner(area);
+            block.render(area, frame.buffer_mut());
+            EmptyState::new(
+                format!("No task was created or completed in the last {} weeks", self.weeks),
+                "Weeks are counted from the `created:` and `done:` dates of tasks, like `- [x] Water the plants done:01/03/2025`",
+            )
+            .hint(&self.config, Mode::Stats, &Action::Right, "look back one more week")
+            .hint(&self.config, Mode::Stats, &Action::ReloadVault, "reload the vault")
+            .render(inner, frame.buffer_mut());
+            return;
+        }
+        #[allow(clippy::cast_precision_loss)]
+        let points = |f: fn(&WeekStats) -> usize| {
+            self.stats
+                .iter()
+                .enumerate()
+                .map(|(i, w)| (i as f64, f(w) as f64))
+                .collect::<Vec<(f64, f64)>>()
+        };
+        let created = points(|w| w.created);
+        let completed = points(|w| w.completed);
+        let max = self
+            .stats
+            .iter()
+            .map(|w| w.created.max(w.completed))
+            .max()
+            .unwrap_or_default()
+            .max(1);
+
+        let x_labels = match (self.stats.first(), self.stats.last()) {
+            (Some(first), Some(last)) => vec![
+                first.start.format("%Y-%m-%d").to_string(),
+                last.start.format("%Y-%m-%d").to_string(),
+            ],
+            _ => vec![],
+        };
+        #[allow(clippy::cast_precision_loss)]
+        let chart = Chart::new(vec![
+            Dataset::default()
+                .name("Created")
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::new().fg(Self::CREATED))
+                .data(&created),
+            Dataset::default()
+                .name("Completed")
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::new().fg(Self::COMPLETED))
+                .data(&completed),
+        ])
+        .block(Block::bordered().title("Tasks per week"))
+        .x_axis(
+            Axis::default()
+                .bounds([0.0, self.weeks.saturating_sub(1) as f64])
+                .labels(x_labels),
+        )
+        .y_axis(
+            Axis::default()
+                .bounds([0.0, max as f64])
+                .labels(["0".to_owned(), max.to_string()]),
+        );
+        frame.render_widget(chart, area);
+    }
+}
+
+impl Component for StatsTab<'_> {
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.weeks = config.tasks_config.stats_weeks;
+        self.config = config;
+        self.load()?;
+        self.help_menu_wigdet = HelpMenu::new(Mode::Stats, &self.config);
+        Ok(())
+    }
+
+    fn update(&mut self, _tui: Option<&mut Tui>, action: Action) -> Result<Option<Action>> {
+        if !self.is_focused {
+            match action {
+                Action::ReloadVault | Action::FilesChanged(_) => self.load()?,
+                Action::Focus(Mode::Stats) => self.is_focused = true,
+                _ => (),
+            }
+        } else if self.show_help {
+            match action {
+                Action::ViewUp | Action::Up => self.help_menu_wigdet.scroll_up(),
+                Action::ViewDown | Action::Down => self.help_menu_wigdet.scroll_down(),
+                Action::Help | Action::Escape | Action::Enter => {
+                    self.show_help = !self.show_help;
+                }
+                _ => (),
+            }
+        } else {
+            match action {
+                Action::Focus(mode) if mode != Mode::Stats => self.is_focused = false,
+                Action::Help => self.show_help = !self.show_help,
+                Action::Left => {
+                    self.weeks = self.weeks.saturating_sub(1).max(2);
+                    self.update_stats();
+                }
+                Action::Right => {
+                    self.weeks += 1;
+                    self.update_stats();
+                }
+                Action::ReloadVault | Action::FilesChanged(_) => self.load()?,
+                _ => (),
+            }
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if !self.is_focused {
+            return Ok(());
+        }
+        let areas = self.split_frame(area);
+        self.render_footer(areas.footer, frame);
+        self.summary().render(areas.summary, frame.buffer_mut());
+        self.render_chart(areas.chart, frame);
+        self.render_health(areas.health, frame);
+        if self.show_help {
+            self.help_menu_wigdet.clone().render(
+                area,
+                frame.buffer_mut(),
+                &mut self.help_menu_wigdet.state,
+            );
+        }
+        Ok(())
+    }
+}
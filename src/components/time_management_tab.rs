@@ -1,4 +1,6 @@
+use crate::time_management::break_suggestions;
 use crate::time_management::flow_time::FlowTime;
+use crate::time_management::focus_log::{FocusLog, FocusSession, FocusStats, FOCUS_LOG_FILE_NAME};
 use crate::time_management::pomodoro::Pomodoro;
 use crate::time_management::time_management_technique::TimeManagementTechnique;
 use crate::time_management::{State, TimeManagementEngine};
@@ -8,7 +10,9 @@ use crossterm::event::Event;
 use layout::Flex;
 use notify_rust::Notification;
 use ratatui::prelude::*;
-use ratatui::widgets::{Block, Borders, Cell, List, ListItem, ListState, Row, Table, TableState};
+use ratatui::widgets::{
+    Block, Borders, Cell, List, ListItem, ListState, Paragraph, Row, Table, TableState,
+};
 use std::time::Duration;
 use strum::IntoEnumIterator;
 use tokio::sync::mpsc::UnboundedSender;
@@ -18,18 +22,23 @@ use tui_input::Input;
 
 use super::Component;
 use crate::app::Mode;
-use crate::config::{MethodSettingsValue, MethodsAvailable};
+use crate::config::{get_data_dir, MethodSettingsValue, MethodsAvailable};
 use crate::tui::Tui;
 use crate::widgets::help_menu::HelpMenu;
+use crate::widgets::hint_bar::HintBar;
 use crate::widgets::input_bar::InputBar;
 use crate::widgets::timer::{TimerState, TimerWidget};
 use crate::{action::Action, config::Config};
 
+/// Shorter focus sessions are not logged, e.g. when skipping segments
+const MIN_FOCUS_SESSION: Duration = Duration::from_secs(60);
+
 /// Struct that helps with drawing the component
 struct TimeManagementTabArea {
     timer: Rect,
     methods_list: Rect,
     method_settings: Rect,
+    focus_stats: Rect,
     footer: Rect,
 }
 
@@ -47,23 +56,31 @@ pub struct TimeManagementTab<'a> {
     // Whether the help panel is open or not
     show_help: bool,
     help_menu_wigdet: HelpMenu<'a>,
+    // Focus history
+    focus_log: FocusLog,
+    focus_stats: FocusStats,
+    /// Task worked on during focus sessions
+    focused_task: Option<String>,
+    break_suggestions: Vec<String>,
+    /// Activity suggested during the current or last break
+    break_suggestion: Option<String>,
 }
 impl TimeManagementTab<'_> {
     pub fn new() -> Self {
         Self::default()
     }
 
-    fn split_frame(area: Rect) -> TimeManagementTabArea {
+    fn split_frame(&self, area: Rect) -> TimeManagementTabArea {
         let vertical = Layout::vertical([
             Constraint::Length(1),
             Constraint::Max(10), // Label + Block
             Constraint::Min(0),
-            Constraint::Length(1),
-            Constraint::Length(1),
+            Constraint::Length(self.config.footer.height()),
+            Constraint::Length(self.config.footer.height()),
         ]);
         let [_header, clock, methods_area, footer, _tab_footera] = vertical.areas(area);
 
-        let [methods_list, methods_settings] = Layout::horizontal([
+        let [methods_list, methods_settings, focus_stats] = Layout::horizontal([
             Constraint::Length(
                 u16::try_from(
                     MethodsAvailable::iter()
@@ -74,6 +91,7 @@ impl TimeManagementTab<'_> {
                 .unwrap_or_default(),
             ),
             Constraint::Min(0),
+            Constraint::Length(30),
         ])
         .areas(methods_area);
 
@@ -81,6 +99,7 @@ impl TimeManagementTab<'_> {
             timer: clock,
             methods_list,
             method_settings: methods_settings,
+            focus_stats,
             footer,
         }
     }
@@ -91,15 +110,29 @@ impl TimeManagementTab<'_> {
             Ok(d) => d,
             Err(e) => bail!("{e}"),
         };
+        if matches!(self.tm_engine.state, Some(State::Focus(_))) && time_spent >= MIN_FOCUS_SESSION
+        {
+            self.log_focus_session(time_spent);
+        }
         let (to_spend, notification_body) = match self.tm_engine.switch(time_spent) {
-            State::Focus(d) => (d, "Time to focus!"),
-            State::Break(d) => (d, "Time for a break!"),
+            State::Focus(d) => (d, "Time to focus!".to_owned()),
+            State::Break(d) => {
+                self.break_suggestion = self.pick_break_suggestion();
+                (
+                    d,
+                    self.break_suggestion
+                        .as_ref()
+                        .map_or("Time for a break!".to_owned(), |suggestion| {
+                            format!("Time for a break! {suggestion}")
+                        }),
+                )
+            }
         };
         self.timer_state = TimerState::new(to_spend);
         if notify
             && Notification::new()
                 .summary("VaultTasks")
-                .body(notification_body)
+                .body(&notification_body)
                 .show()
                 .is_err()
         {
@@ -107,12 +140,52 @@ impl TimeManagementTab<'_> {
         }
         Ok(())
     }
-    fn render_footer(area: Rect, frame: &mut Frame) {
-        Line::raw(
-            "Next Segment: <space> | Pause: p | Edit setting: e (navigate: j|k | ▼|▲) | Cycle methods: tab|Shift-tab",
+    /// Picks a break suggestion, different from the previous one when possible.
+    fn pick_break_suggestion(&self) -> Option<String> {
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.subsec_nanos() as usize)
+            .unwrap_or_default();
+        break_suggestions::pick(
+            &self.break_suggestions,
+            self.break_suggestion.as_deref(),
+            seed,
         )
-        .centered()
-        .render(area, frame.buffer_mut());
+    }
+    /// Logs the focus session that just ended and updates the statistics.
+    fn log_focus_session(&mut self, time_spent: Duration) {
+        let end = chrono::Local::now().naive_local();
+        let session = FocusSession {
+            start: end - chrono::TimeDelta::from_std(time_spent).unwrap_or_default(),
+            end,
+            task: self.focused_task.clone(),
+            technique: MethodsAvailable::from_repr(
+                self.methods_list_state.selected().unwrap_or_default(),
+            )
+            .unwrap_or_default()
+            .to_string(),
+        };
+        if let Err(e) = self.focus_log.append(&session) {
+            error!("Failed to log focus session: {e}");
+        }
+        self.update_focus_stats();
+    }
+    fn update_focus_stats(&mut self) {
+        self.focus_stats =
+            FocusStats::new(&self.focus_log.load(), chrono::Local::now().date_naive());
+    }
+    fn render_footer(&self, area: Rect, frame: &mut Frame) {
+        HintBar::new(&self.config, Mode::TimeManagement).render(area, frame.buffer_mut());
+    }
+    fn render_focus_stats(&self, area: Rect, buffer: &mut Buffer) {
+        let mut lines = vec![Line::from(format!(
+            "Task: {}",
+            self.focused_task.as_deref().unwrap_or("none")
+        ))];
+        lines.extend(self.focus_stats.to_lines().into_iter().map(Line::from));
+        Paragraph::new(lines)
+            .block(Block::bordered().title(Line::raw("Focus").centered()))
+            .render(area, buffer);
     }
     /// Retrieve a settings value from its key.
     fn find_settings_value(&self, method: MethodsAvailable, key: &str) -> MethodSettingsValue {
@@ -187,6 +260,9 @@ impl Component for TimeManagementTab<'_> {
         self.config = config;
         self.methods_list_state.select(Some(0));
         self.help_menu_wigdet = HelpMenu::new(Mode::TimeManagement, &self.config);
+        self.focus_log = FocusLog::new(&get_data_dir().join(FOCUS_LOG_FILE_NAME));
+        self.update_focus_stats();
+        self.break_suggestions = break_suggestions::load_suggestions(&self.config.tasks_config);
         if self.config.time_management_methods_settings.is_empty() {
             error!("Time management settings are empty");
         } else {
@@ -203,6 +279,9 @@ impl Component for TimeManagementTab<'_> {
         if matches!(action, Action::Tick) && self.timer_state.tick() {
             self.time_management_method_switch(true)?;
         }
+        if let Action::SetFocusedTask(task) = &action {
+            self.focused_task = Some(task.clone());
+        }
 
         if !self.is_focused {
             match action {
@@ -316,10 +395,16 @@ impl Component for TimeManagementTab<'_> {
             return Ok(());
         }
 
-        let areas = Self::split_frame(area);
+        let areas = self.split_frame(area);
 
         // Timer
-        TimerWidget {}.render(areas.timer, frame.buffer_mut(), &mut self.timer_state);
+        TimerWidget {
+            title: self
+                .break_suggestion
+                .clone()
+                .filter(|_| matches!(self.tm_engine.state, Some(State::Break(_)))),
+        }
+        .render(areas.timer, frame.buffer_mut(), &mut self.timer_state);
 
         // Methods List
         self.render_methods_list(areas.methods_list, frame.buffer_mut());
@@ -327,11 +412,14 @@ impl Component for TimeManagementTab<'_> {
         // Method Settings
         self.render_methods_settings(areas.method_settings, frame.buffer_mut());
 
+        // Focus Statistics
+        self.render_focus_stats(areas.focus_stats, frame.buffer_mut());
+
         if self.edit_setting_bar.is_focused {
             self.render_edit_bar(frame, area);
         }
         // Footer
-        Self::render_footer(areas.footer, frame);
+        self.render_footer(areas.footer, frame);
         if self.show_help {
             debug!("showing help");
             self.help_menu_wigdet.clone().render(
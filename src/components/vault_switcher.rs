@@ -0,0 +1,104 @@
+use color_eyre::Result;
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    widgets::{Block, Clear},
+    Frame,
+};
+use tracing::error;
+
+use super::Component;
+
+use crate::{
+    action::Action,
+    config::Config,
+    tui::Tui,
+    widgets::picker::{Picker, PickerState},
+};
+
+/// Largest size of the popup, borders included
+const SWITCHER_WIDTH: u16 = 60;
+const SWITCHER_HEIGHT: u16 = 14;
+
+/// Popup listing the `vaults` of the config, the picked one replacing the open vault.
+#[derive(Default)]
+pub struct VaultSwitcher {
+    config: Config,
+    /// Names of the vaults, in the order of the picker
+    names: Vec<String>,
+    /// Open while picking a vault
+    picker: Option<Picker>,
+}
+
+impl VaultSwitcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn open(&mut self) {
+        let tasks_config = &self.config.tasks_config;
+        if tasks_config.vaults.is_empty() {
+            error!("No vault to switch to, add some to `vaults` in the configuration");
+            return;
+        }
+        let current = tasks_config.current_vault().map(|(name, _)| name);
+        self.names = tasks_config.vaults.keys().cloned().collect();
+        let items = tasks_config
+            .vaults
+            .iter()
+            .map(|(name, vault)| {
+                let open = if current == Some(name) { " (open)" } else { "" };
+                format!("{name}{open}  {}", vault.path.display())
+            })
+            .collect();
+        self.picker = Some(Picker::new(items));
+    }
+}
+
+impl Component for VaultSwitcher {
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+
+    fn blocking_mode(&self) -> bool {
+        self.picker.is_some()
+    }
+
+    fn escape_blocking_mode(&self) -> Vec<Action> {
+        vec![]
+    }
+
+    fn update(&mut self, _tui: Option<&mut Tui>, action: Action) -> Result<Option<Action>> {
+        match (&mut self.picker, action) {
+            (None, Action::SwitchVault) => self.open(),
+            (Some(picker), Action::Key(key)) => match picker.handle_key(key) {
+                PickerState::Picking => (),
+                PickerState::Picked(i) => {
+                    self.picker = None;
+                    return Ok(Some(Action::OpenVault(self.names[i].clone())));
+                }
+                PickerState::Canceled => self.picker = None,
+            },
+            _ => (),
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let Some(picker) = &self.picker else {
+            return Ok(());
+        };
+        let [area] = Layout::horizontal([Constraint::Max(SWITCHER_WIDTH)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [area] = Layout::vertical([Constraint::Max(SWITCHER_HEIGHT)])
+            .flex(Flex::Center)
+            .areas(area);
+        let block = Block::bordered().title("Switch vault");
+        let inner = block.inner(area);
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+        frame.render_widget(picker, inner);
+        Ok(())
+    }
+}
@@ -4,14 +4,13 @@ use std::{
     env,
     fs::{create_dir_all, File},
     io::Write,
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
 use chrono::NaiveTime;
 use std::{fmt::Display, time::Duration};
 use strum::{EnumIter, FromRepr};
 
-use crate::core::TasksConfig;
 use crate::widgets::timer::TimerWidget;
 use crate::{action::Action, app::Mode, cli::Cli};
 use color_eyre::{eyre::bail, Result};
@@ -23,6 +22,11 @@ use lazy_static::lazy_static;
 use ratatui::style::{Color, Modifier, Style};
 use serde::{de::Deserializer, Deserialize};
 use tracing::{debug, info};
+use vault_tasks_core::{
+    filter::{parse_search_input, Filter},
+    task::Task,
+    PrettySymbolsConfig, TasksConfig,
+};
 
 const CONFIG: &str = include_str!("../.config/config.toml");
 
@@ -34,6 +38,36 @@ pub struct AppConfig {
     pub data_dir: PathBuf,
     #[serde(default)]
     pub show_fps: bool,
+    /// Log filter, see `--log-level`
+    #[serde(default)]
+    pub log_level: String,
+}
+
+/// An entry of a hint bar: the keys bound to `action` are displayed after `label`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct FooterHint {
+    pub action: Action,
+    pub label: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Default)]
+pub struct FooterConfig {
+    /// Hides every hint bar.
+    #[serde(default)]
+    pub hidden: bool,
+    /// Hints of each mode, most important first.
+    #[serde(default)]
+    pub hints: HashMap<Mode, Vec<FooterHint>>,
+}
+impl FooterConfig {
+    /// Height of a hint bar, 0 if they are hidden.
+    pub const fn height(&self) -> u16 {
+        if self.hidden {
+            0
+        } else {
+            1
+        }
+    }
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -45,6 +79,12 @@ pub struct Config {
     #[serde(default)]
     pub styles: Styles,
     #[serde(default)]
+    pub footer: FooterConfig,
+    #[serde(default)]
+    pub style_rules: StyleRules,
+    #[serde(default)]
+    pub tag_colors: TagColors,
+    #[serde(default)]
     pub tasks_config: TasksConfig,
     #[serde(default)]
     pub time_management_methods_settings: HashMap<MethodsAvailable, Vec<MethodSettingsEntry>>,
@@ -121,6 +161,18 @@ impl Config {
                 user_styles.entry(style_key.clone()).or_insert(*style);
             }
         }
+        for (filter, style) in default_config.style_rules.iter() {
+            if !cfg.style_rules.iter().any(|(f, _)| f == filter) {
+                cfg.style_rules.push((filter.clone(), *style));
+            }
+        }
+        cfg.style_rules.sort_by(|a, b| a.0.cmp(&b.0));
+        for (mode, default_hints) in &default_config.footer.hints {
+            cfg.footer
+                .hints
+                .entry(*mode)
+                .or_insert_with(|| default_hints.clone());
+        }
         if let Entry::Vacant(e) = cfg
             .time_management_methods_settings
             .entry(MethodsAvailable::Pomodoro)
@@ -146,16 +198,41 @@ impl Config {
             );
         }
 
+        if cfg.tasks_config.vault_path.as_os_str().is_empty() {
+            if let Some(vault) = cfg.tasks_config.vaults.values().next() {
+                cfg.tasks_config.vault_path.clone_from(&vault.path);
+            }
+        }
         if let Some(path) = &args.vault_path {
-            cfg.tasks_config.vault_path.clone_from(path);
+            // A path inside the configured vault only scopes it
+            match Self::relative_to_vault(&cfg.tasks_config.vault_path, path) {
+                Some(scope) if args.scope.is_none() => cfg.tasks_config.scope = scope,
+                _ => cfg.tasks_config.vault_path.clone_from(path),
+            }
+        }
+        if let Some(scope) = &args.scope {
+            cfg.tasks_config.scope.clone_from(scope);
         }
 
         cfg.config.show_fps = args.show_fps;
+        crate::logging::apply_config_level(&cfg.config.log_level);
 
         cfg.check_config()?;
         debug!("{cfg:#?}");
         Ok(cfg)
     }
+    /// Returns `path` relative to `vault_path` if it is strictly inside it.
+    fn relative_to_vault(vault_path: &Path, path: &Path) -> Option<PathBuf> {
+        if vault_path.as_os_str().is_empty() {
+            return None;
+        }
+        let vault_path = vault_path.canonicalize().ok()?;
+        let path = path.canonicalize().ok()?;
+        path.strip_prefix(vault_path)
+            .ok()
+            .filter(|p| !p.as_os_str().is_empty())
+            .map(Path::to_path_buf)
+    }
     fn check_config(&mut self) -> Result<(), ConfigError> {
         if self
             .tasks_config
@@ -174,9 +251,19 @@ impl Config {
             )));
         }
 
+        if self.tasks_config.ascii_mode {
+            self.tasks_config.pretty_symbols = PrettySymbolsConfig::ascii();
+        }
+        if self.tasks_config.explorer_column_ratios[1] == 0 {
+            self.tasks_config.explorer_column_ratios =
+                Self::default().tasks_config.explorer_column_ratios;
+        }
         if self.tasks_config.indent_length == 0 {
             self.tasks_config.indent_length = Self::default().tasks_config.indent_length;
         }
+        if self.tasks_config.stats_weeks < 2 {
+            self.tasks_config.stats_weeks = Self::default().tasks_config.stats_weeks;
+        }
         Ok(())
     }
 
@@ -441,6 +528,94 @@ impl<'de> Deserialize<'de> for Styles {
     }
 }
 
+/// Styles applied to tasks matching a search filter, sorted by filter.
+#[derive(Clone, Debug, Default, Deref, DerefMut)]
+pub struct StyleRules(pub Vec<(String, Style)>);
+
+impl<'de> Deserialize<'de> for StyleRules {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let parsed_map = HashMap::<String, String>::deserialize(deserializer)?;
+
+        let mut rules = parsed_map
+            .into_iter()
+            .map(|(filter, style)| (filter, parse_style(&style)))
+            .collect::<Vec<(String, Style)>>();
+        rules.sort_by(|a, b| a.0.cmp(&b.0));
+
+        Ok(Self(rules))
+    }
+}
+
+impl StyleRules {
+    /// Parses the filters of every rule.
+    pub fn compile(&self, config: &TasksConfig) -> Vec<(Filter, Style)> {
+        self.iter()
+            .map(|(filter, style)| (parse_search_input(filter, config), *style))
+            .collect()
+    }
+}
+
+/// Colors tags without one in `[tag_colors]` are given, picked from their name.
+const TAG_PALETTE: [Color; 12] = [
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Green,
+    Color::Yellow,
+    Color::Red,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightRed,
+];
+
+/// Colors of tags in task lists.
+#[derive(Clone, Debug, Default, Deref, DerefMut)]
+pub struct TagColors(pub HashMap<String, Color>);
+
+impl<'de> Deserialize<'de> for TagColors {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let parsed_map = HashMap::<String, String>::deserialize(deserializer)?;
+        Ok(Self(
+            parsed_map
+                .into_iter()
+                .filter_map(|(tag, color)| Some((tag.to_lowercase(), parse_color(&color)?)))
+                .collect(),
+        ))
+    }
+}
+
+impl TagColors {
+    /// Returns the configured color of `tag`, or one of the palette always the same for a given tag.
+    pub fn color(&self, tag: &str) -> Color {
+        let tag = tag.to_lowercase();
+        self.get(&tag).copied().unwrap_or_else(|| {
+            let hash = tag.bytes().fold(0usize, |hash, b| {
+                hash.wrapping_mul(31).wrapping_add(b.into())
+            });
+            TAG_PALETTE[hash % TAG_PALETTE.len()]
+        })
+    }
+}
+
+/// Combines the styles of every rule matching `task`.
+pub fn task_style(rules: &[(Filter, Style)], task: &Task) -> Style {
+    rules
+        .iter()
+        .filter(|(filter, _)| filter.matches(task))
+        .fold(Style::default(), |style, (_, rule_style)| {
+            style.patch(*rule_style)
+        })
+}
+
 pub fn parse_style(line: &str) -> Style {
     let (foreground, background) =
         line.split_at(line.to_lowercase().find("on ").unwrap_or(line.len()));
@@ -464,7 +639,10 @@ fn process_color_string(color_str: &str) -> (String, Modifier) {
         .replace("bright ", "")
         .replace("bold ", "")
         .replace("underline ", "")
-        .replace("inverse ", "");
+        .replace("inverse ", "")
+        .replace("dim ", "")
+        .replace("italic ", "")
+        .replace("strikethrough ", "");
 
     let mut modifiers = Modifier::empty();
     if color_str.contains("underline") {
@@ -476,6 +654,15 @@ fn process_color_string(color_str: &str) -> (String, Modifier) {
     if color_str.contains("inverse") {
         modifiers |= Modifier::REVERSED;
     }
+    if color_str.contains("dim") {
+        modifiers |= Modifier::DIM;
+    }
+    if color_str.contains("italic") {
+        modifiers |= Modifier::ITALIC;
+    }
+    if color_str.contains("strikethrough") {
+        modifiers |= Modifier::CROSSED_OUT;
+    }
 
     (color, modifiers)
 }
@@ -656,6 +843,19 @@ mod tests {
         assert_eq!(style.bg, Some(Color::Indexed(4)));
     }
 
+    #[test]
+    fn test_tag_colors() {
+        let colors: TagColors = toml::from_str("Work = \"blue\"\nhome = \"rgb 1 2 3\"").unwrap();
+        assert_eq!(colors.color("work"), Color::Indexed(4));
+        assert_eq!(colors.color("Home"), Color::Rgb(1, 2, 3));
+        // Other tags always get the same color, whatever their case
+        assert_eq!(colors.color("errands"), colors.color("Errands"));
+        assert_eq!(
+            colors.color("errands"),
+            TagColors::default().color("errands")
+        );
+    }
+
     #[test]
     fn test_process_color_string() {
         let (color, modifiers) = process_color_string("underline bold inverse gray");
@@ -665,6 +865,33 @@ mod tests {
         assert!(modifiers.contains(Modifier::REVERSED));
     }
 
+    #[test]
+    fn test_parse_style_extra_modifiers() {
+        let style = parse_style("dim italic strikethrough green");
+        assert_eq!(style.fg, Some(Color::Indexed(2)));
+        assert!(style
+            .add_modifier
+            .contains(Modifier::DIM | Modifier::ITALIC | Modifier::CROSSED_OUT));
+    }
+
+    #[test]
+    fn test_task_style() {
+        let rules = StyleRules(vec![
+            (String::from("#waiting"), parse_style("dim")),
+            (String::from("p1"), parse_style("bold red")),
+        ])
+        .compile(&TasksConfig::default());
+        let task = Task {
+            tags: Some(vec![String::from("waiting")]),
+            priority: 1,
+            ..Default::default()
+        };
+        let style = task_style(&rules, &task);
+        assert_eq!(style.fg, Some(Color::Indexed(1)));
+        assert!(style.add_modifier.contains(Modifier::DIM | Modifier::BOLD));
+        assert_eq!(task_style(&rules, &Task::default()), Style::default());
+    }
+
     #[test]
     fn test_parse_color_rgb() {
         let color = parse_color("rgb 255 000 128");
@@ -1,28 +1,64 @@
+use std::{
+    collections::VecDeque,
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, OnceLock,
+    },
+};
+
 use color_eyre::Result;
-use tracing::debug;
+use tracing::{
+    field::{Field, Visit},
+    level_filters::LevelFilter,
+    Event, Subscriber,
+};
 use tracing_error::ErrorLayer;
-use tracing_subscriber::{fmt, prelude::*, EnvFilter};
+use tracing_subscriber::{fmt, layer::Context, prelude::*, reload, EnvFilter, Layer, Registry};
 
 use crate::config;
 
+/// Number of log lines kept for the log pane
+const RECENT_LOGS_CAPACITY: usize = 200;
+
 lazy_static::lazy_static! {
     pub static ref LOG_ENV: String = format!("{}_LOGLEVEL", config::PROJECT_NAME.clone());
     pub static ref LOG_FILE: String = format!("{}.log", env!("CARGO_PKG_NAME"));
+    /// Recent warnings and errors, oldest first
+    pub static ref RECENT_LOGS: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
 }
 
-pub fn init() -> Result<()> {
+/// Handle used to apply the `log_level` of the configuration once it is loaded
+static FILTER_HANDLE: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+/// Whether the filter was set by `--log-level` or an environment variable, which take precedence over the configuration
+static FILTER_IS_SET: AtomicBool = AtomicBool::new(false);
+
+/// Initializes logging to the log file.
+///
+/// `log_level` uses the `EnvFilter` syntax, e.g. `warn,vault_tasks_core=debug`. If it is `None`, the `RUST_LOG` or `LOG_ENV` environment variables are used, otherwise the `log_level` of the configuration (see `apply_config_level`).
+pub fn init(log_level: Option<&str>) -> Result<()> {
     let directory = config::get_data_dir();
     std::fs::create_dir_all(directory.clone())?;
     let log_path = directory.join(LOG_FILE.clone());
     let log_file = std::fs::File::create(log_path)?;
     let env_filter = EnvFilter::builder().with_default_directive(tracing::Level::INFO.into());
-    // If the `RUST_LOG` environment variable is set, use that as the default, otherwise use the
-    // value of the `LOG_ENV` environment variable. If the `LOG_ENV` environment variable contains
-    // errors, then this will return an error.
-    debug!("test");
-    let env_filter = env_filter
-        .try_from_env()
-        .or_else(|_| env_filter.with_env_var(LOG_ENV.clone()).from_env())?;
+    // `--log-level` comes first. Then if the `RUST_LOG` environment variable is set, use that as the
+    // default, otherwise use the value of the `LOG_ENV` environment variable. If the `LOG_ENV`
+    // environment variable contains errors, then this will return an error.
+    let env_filter = if let Some(log_level) = log_level {
+        FILTER_IS_SET.store(true, Ordering::Relaxed);
+        env_filter.parse(log_level)?
+    } else {
+        FILTER_IS_SET.store(
+            std::env::var_os("RUST_LOG").is_some() || std::env::var_os(LOG_ENV.clone()).is_some(),
+            Ordering::Relaxed,
+        );
+        env_filter
+            .try_from_env()
+            .or_else(|_| env_filter.with_env_var(LOG_ENV.clone()).from_env())?
+    };
+    let (env_filter, handle) = reload::Layer::new(env_filter);
+    let _ = FILTER_HANDLE.set(handle);
     let file_subscriber = fmt::layer()
         .with_file(true)
         .with_line_number(true)
@@ -32,7 +68,57 @@ pub fn init() -> Result<()> {
         .with_filter(env_filter);
     tracing_subscriber::registry()
         .with(file_subscriber)
+        .with(RecentLogsLayer.with_filter(LevelFilter::WARN))
         .with(ErrorLayer::default())
         .try_init()?;
     Ok(())
 }
+
+/// Applies the `log_level` of the configuration, unless the filter was set by `--log-level` or an environment variable.
+pub fn apply_config_level(log_level: &str) {
+    if log_level.is_empty() || FILTER_IS_SET.load(Ordering::Relaxed) {
+        return;
+    }
+    let Some(handle) = FILTER_HANDLE.get() else {
+        return;
+    };
+    match EnvFilter::builder().parse(log_level) {
+        Ok(filter) => {
+            if let Err(e) = handle.reload(filter) {
+                tracing::error!("Failed to apply `log_level`: {e}");
+            }
+        }
+        Err(e) => tracing::warn!("Invalid `log_level` {log_level:?}: {e}"),
+    }
+}
+
+/// Keeps the last events in `RECENT_LOGS`.
+struct RecentLogsLayer;
+
+impl<S: Subscriber> Layer<S> for RecentLogsLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        struct MessageVisitor(String);
+        impl Visit for MessageVisitor {
+            fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+                if field.name() == "message" {
+                    self.0 = format!("{value:?}");
+                }
+            }
+        }
+        let mut visitor = MessageVisitor(String::new());
+        event.record(&mut visitor);
+        let line = format!(
+            "{} {:>5} {}: {}",
+            chrono::Local::now().format("%H:%M:%S"),
+            event.metadata().level(),
+            event.metadata().target(),
+            visitor.0
+        );
+        if let Ok(mut logs) = RECENT_LOGS.lock() {
+            if logs.len() == RECENT_LOGS_CAPACITY {
+                logs.pop_front();
+            }
+            logs.push_back(line);
+        }
+    }
+}
@@ -1,11 +1,47 @@
-use core::TaskManager;
+use vault_tasks_core::{
+    archive::{plan_archive, plan_delete, plan_tag_to_project},
+    calendar::{
+        agenda_to_text, calendar_tasks, month_to_markdown, month_to_text, parse_month, parse_week,
+        week_agenda_to_html, week_agenda_to_text,
+    },
+    changeset::Changeset,
+    daily_notes::{
+        add_tasks, carry_over, daily_note_path, drop_file_path, log_done_task, parse_new_tasks,
+        plan_carry_over, CarryOverMode,
+    },
+    details::{filter_to_details, find_tasks, similar_tasks, TaskDetails},
+    edit::TaskEdit,
+    export,
+    filter::{parse_search_input, Filter, NAMED_FILTER_TOKEN},
+    health::HealthReport,
+    import,
+    links::{note_aliases, resolve_note, LinkGraph},
+    lock::VaultLock,
+    maintenance::maintain,
+    mark::{mark, plan_changes, plan_mark, write_changes},
+    rename::vault_notes,
+    schedule::plan_schedule,
+    sorter::OutputSort,
+    stats,
+    sync_map::{SyncEntry, SyncMap, SYNC_MAP_FILE_NAME},
+    task::{DueDate, Task},
+    urgency::next_tasks,
+    vault_data::{EntryKind, OutlineOptions, VaultData},
+    vault_diff::{apply_finished, compare_vaults},
+    TaskManager, TasksConfig,
+};
+
+use std::io::{IsTerminal, Read, Write};
+
+use chrono::{Datelike, NaiveTime, TimeDelta, Timelike};
 
 use clap::Parser;
 use cli::Cli;
-use color_eyre::Result;
-use config::Config;
+use color_eyre::{eyre::bail, Result};
+use config::{get_data_dir, Config};
+use time_management::focus_log::{FocusLog, FocusStats, FOCUS_LOG_FILE_NAME};
 
-use crate::app::App;
+use crate::{app::App, widgets::picker::Picker};
 
 mod action;
 mod app;
@@ -15,7 +51,6 @@ mod config;
 mod errors;
 mod logging;
 
-mod core;
 mod time_management;
 mod tui;
 mod widgets;
@@ -23,16 +58,909 @@ mod widgets;
 #[tokio::main]
 async fn main() -> Result<()> {
     crate::errors::init()?;
-    crate::logging::init()?;
-
     let args = Cli::parse();
+    crate::logging::init(args.log_level.as_deref())?;
 
     match args.command {
         Some(cli::Commands::GenerateConfig { path }) => Config::generate_config(path),
-        Some(cli::Commands::Stdout) => {
+        Some(cli::Commands::Stdout {
+            sort,
+            depth,
+            only,
+            state,
+            ref path,
+        }) => {
+            let config = Config::new(&args)?;
+            let _lock = VaultLock::acquire(&config.tasks_config)?;
+            let mut task_mgr = TaskManager::load_from_config(&config.tasks_config)?;
+            let (order, reverse) = output_sort(&config.tasks_config, sort);
+            let today = chrono::Local::now().date_naive();
+            task_mgr.tasks.sort_tasks(&|tasks: &mut [Task]| {
+                order.sort(tasks, |t| t, today, &config.tasks_config, reverse);
+            });
+            let entry = match path {
+                Some(path) => {
+                    let vault_path = &config.tasks_config.vault_path;
+                    let Some(entry) = task_mgr.get_file_entry(vault_path, &vault_path.join(path))
+                    else {
+                        bail!("{} not found in the vault", path.display());
+                    };
+                    entry
+                }
+                None => &task_mgr.tasks,
+            };
+            let options = OutlineOptions {
+                depth,
+                only: only.map(|only| match only {
+                    cli::EntryKind::Tasks => EntryKind::Task,
+                    cli::EntryKind::Headers => EntryKind::Header,
+                }),
+                open: state.map(|state| matches!(state, cli::StateFilter::Open)),
+            };
+            println!(
+                "{}",
+                entry
+                    .outline(&options)
+                    .iter()
+                    .map(|e| e.to_string_with_config(&config.tasks_config))
+                    .collect::<String>()
+            );
+            Ok(())
+        }
+        Some(cli::Commands::Recent { days }) => {
+            let config = Config::new(&args)?;
+            let task_mgr = TaskManager::load_read_only(&config.tasks_config)?;
+            let since = chrono::Local::now().naive_local() - TimeDelta::days(days.into());
+            for (path, modified) in task_mgr.recent_files(since) {
+                println!(
+                    "{} ({})",
+                    path.display(),
+                    DueDate::DayTime(modified)
+                        .get_relative_str()
+                        .unwrap_or_default()
+                );
+                if let Some(VaultData::Directory(_, children)) =
+                    task_mgr.get_file_entry(&config.tasks_config.vault_path, &path)
+                {
+                    children.iter().for_each(|c| {
+                        println!("{}", c.to_string_with_config(&config.tasks_config))
+                    });
+                }
+            }
+            Ok(())
+        }
+        Some(cli::Commands::Count {
+            ref filter,
+            ref filter_name,
+            format,
+        }) => {
+            let config = Config::new(&args)?;
+            let mut task_mgr = TaskManager::load_read_only(&config.tasks_config)?;
+            let filter = command_filter(
+                &mut task_mgr,
+                &config.tasks_config,
+                filter,
+                filter_name.as_deref(),
+            )?;
+            let count = task_mgr.count_due(&filter, chrono::Local::now().naive_local());
+            match format {
+                cli::CountFormat::Number => println!("{}", count.overdue + count.today),
+                cli::CountFormat::Badge => println!("{}!/{}", count.overdue, count.today),
+            }
+            Ok(())
+        }
+        Some(cli::Commands::Export {
+            format:
+                cli::ExportFormat::Md {
+                    ref filter,
+                    ref filter_name,
+                    breadcrumbs,
+                },
+        }) => {
+            let config = Config::new(&args)?;
+            let mut task_mgr = TaskManager::load_read_only(&config.tasks_config)?;
+            let filter = command_filter(
+                &mut task_mgr,
+                &config.tasks_config,
+                filter,
+                filter_name.as_deref(),
+            )?;
+            print!(
+                "{}",
+                export::to_markdown(&task_mgr.tasks, &filter, &config.tasks_config, breadcrumbs,)
+            );
+            Ok(())
+        }
+        Some(cli::Commands::Export {
+            format:
+                cli::ExportFormat::Ical {
+                    ref filter,
+                    ref filter_name,
+                    ref out,
+                },
+        }) => {
+            let config = Config::new(&args)?;
+            let mut task_mgr = TaskManager::load_read_only(&config.tasks_config)?;
+            let filter = command_filter(
+                &mut task_mgr,
+                &config.tasks_config,
+                filter,
+                filter_name.as_deref(),
+            )?;
+            let feed = export::to_ical(
+                &task_mgr.tasks,
+                &filter,
+                &config.tasks_config,
+                chrono::Utc::now().naive_utc(),
+            );
+            match out {
+                Some(path) => std::fs::write(path, feed)?,
+                None => print!("{feed}"),
+            }
+            Ok(())
+        }
+        Some(cli::Commands::Export {
+            format:
+                cli::ExportFormat::Csv {
+                    ref filter,
+                    ref filter_name,
+                    ref columns,
+                    tsv,
+                    ref out,
+                },
+        }) => {
+            let config = Config::new(&args)?;
+            let mut task_mgr = TaskManager::load_read_only(&config.tasks_config)?;
+            let filter = command_filter(
+                &mut task_mgr,
+                &config.tasks_config,
+                filter,
+                filter_name.as_deref(),
+            )?;
+            let columns = columns
+                .iter()
+                .map(|column| match column {
+                    cli::CsvColumn::Name => export::CsvColumn::Name,
+                    cli::CsvColumn::State => export::CsvColumn::State,
+                    cli::CsvColumn::Due => export::CsvColumn::Due,
+                    cli::CsvColumn::Tags => export::CsvColumn::Tags,
+                    cli::CsvColumn::Priority => export::CsvColumn::Priority,
+                    cli::CsvColumn::Path => export::CsvColumn::Path,
+                    cli::CsvColumn::Line => export::CsvColumn::Line,
+                })
+                .collect::<Vec<export::CsvColumn>>();
+            let table = export::to_csv(
+                &task_mgr.tasks,
+                &filter,
+                &config.tasks_config,
+                &columns,
+                if tsv { '\t' } else { ',' },
+            );
+            match out {
+                Some(path) => std::fs::write(path, table)?,
+                None => print!("{table}"),
+            }
+            Ok(())
+        }
+        Some(cli::Commands::Import {
+            format:
+                cli::ImportFormat::Todotxt {
+                    ref file,
+                    ref into,
+                    ref heading,
+                    dry_run,
+                },
+        }) => {
+            let config = Config::new(&args)?;
+            let content = if file.as_os_str() == "-" {
+                let mut input = String::new();
+                std::io::stdin().read_to_string(&mut input)?;
+                input
+            } else {
+                std::fs::read_to_string(file)?
+            };
+            let tasks = import::todotxt(&content);
+            let into = match into {
+                Some(into) => into.clone(),
+                None => drop_file_path(&config.tasks_config, chrono::Local::now().date_naive())?
+                    .strip_prefix(&config.tasks_config.vault_path)?
+                    .to_path_buf(),
+            };
+            let changes =
+                import::plan_import(&config.tasks_config, &tasks, &into, heading.as_deref())?;
+            if dry_run {
+                let highlight = std::io::stdout().is_terminal();
+                for change in changes.changes {
+                    print!("{}", change.to_unified_diff(highlight));
+                }
+            } else {
+                let _lock = VaultLock::acquire(&config.tasks_config)?;
+                changes.apply()?;
+                println!("Imported {} task(s) to {}", tasks.len(), into.display());
+            }
+            Ok(())
+        }
+        Some(cli::Commands::Sync {
+            command: cli::SyncCommand::List { ref backend },
+        }) => {
+            let map = SyncMap::load(&get_data_dir().join(SYNC_MAP_FILE_NAME))?;
+            for (name, task_id, entry) in map
+                .entries()
+                .filter(|(name, _, _)| backend.as_ref().is_none_or(|b| b == name))
+            {
+                println!(
+                    "{name} ^{task_id} {} ({}synced {})",
+                    entry.remote_id,
+                    entry
+                        .etag
+                        .as_ref()
+                        .map_or_else(String::new, |etag| format!("etag {etag}, ")),
+                    entry.last_sync.format("%Y-%m-%d %H:%M")
+                );
+            }
+            Ok(())
+        }
+        Some(cli::Commands::Sync {
+            command:
+                cli::SyncCommand::Link {
+                    ref backend,
+                    ref task,
+                    ref remote_id,
+                    ref etag,
+                },
+        }) => {
             let config = Config::new(&args)?;
-            let task_mgr = TaskManager::load_from_config(&config.tasks_config)?;
-            println!("{}", task_mgr.tasks);
+            let task_mgr = TaskManager::load_read_only(&config.tasks_config)?;
+            let task_id = task.trim().trim_start_matches('^');
+            if !find_tasks(&task_mgr.tasks, task_id, &config.tasks_config)
+                .iter()
+                .any(|details| details.task.block_id.as_deref() == Some(task_id))
+            {
+                bail!("No task has the block identifier ^{task_id}");
+            }
+            let mut map = SyncMap::load(&get_data_dir().join(SYNC_MAP_FILE_NAME))?;
+            if let Some(other) = map.task_id(backend, remote_id).filter(|t| *t != task_id) {
+                bail!("{remote_id} is already synced with ^{other}");
+            }
+            if let Some(old) = map.get(backend, task_id) {
+                println!("Replacing {}", old.remote_id);
+            }
+            map.set(
+                backend,
+                task_id,
+                SyncEntry {
+                    remote_id: remote_id.clone(),
+                    etag: etag.clone(),
+                    last_sync: chrono::Local::now().naive_local(),
+                },
+            );
+            map.save()
+        }
+        Some(cli::Commands::Sync {
+            command:
+                cli::SyncCommand::Forget {
+                    ref backend,
+                    ref task,
+                },
+        }) => {
+            let mut map = SyncMap::load(&get_data_dir().join(SYNC_MAP_FILE_NAME))?;
+            let task_id = task.trim().trim_start_matches('^');
+            let Some(entry) = map.remove(backend, task_id) else {
+                bail!("^{task_id} is not synced by {backend}");
+            };
+            println!("Forgot {}", entry.remote_id);
+            map.save()
+        }
+        Some(cli::Commands::Export {
+            format:
+                cli::ExportFormat::Agenda {
+                    ref week,
+                    format,
+                    ref out,
+                },
+        }) => {
+            let config = Config::new(&args)?;
+            let task_mgr = TaskManager::load_read_only(&config.tasks_config)?;
+            let monday = match week {
+                Some(week) => {
+                    let Some(monday) = parse_week(week) else {
+                        bail!("Invalid week `{week}`, expected YYYY-Www");
+                    };
+                    monday
+                }
+                None => {
+                    let today = chrono::Local::now().date_naive();
+                    today - TimeDelta::days(i64::from(today.weekday().num_days_from_monday()))
+                }
+            };
+            let tasks = calendar_tasks(&task_mgr.tasks, &config.tasks_config);
+            let agenda = match format {
+                cli::AgendaFormat::Txt => week_agenda_to_text(&tasks, monday, &config.tasks_config),
+                cli::AgendaFormat::Html => week_agenda_to_html(&tasks, monday),
+            };
+            match out {
+                Some(path) => std::fs::write(path, agenda)?,
+                None => print!("{agenda}"),
+            }
+            Ok(())
+        }
+        Some(cli::Commands::Export {
+            format: cli::ExportFormat::StatsCsv { weeks },
+        }) => {
+            let config = Config::new(&args)?;
+            let task_mgr = TaskManager::load_read_only(&config.tasks_config)?;
+            print!(
+                "{}",
+                stats::to_csv(&stats::weekly_stats(
+                    &task_mgr.tasks,
+                    weeks,
+                    chrono::Local::now().date_naive()
+                ))
+            );
+            Ok(())
+        }
+        Some(cli::Commands::List {
+            ref filter,
+            ref filter_name,
+            format,
+            sort,
+        }) => {
+            let config = Config::new(&args)?;
+            let mut task_mgr = TaskManager::load_read_only(&config.tasks_config)?;
+            let filter = command_filter(
+                &mut task_mgr,
+                &config.tasks_config,
+                filter,
+                filter_name.as_deref(),
+            )?;
+            let mut found = filter_to_details(&task_mgr.tasks, &filter);
+            let (order, reverse) = output_sort(&config.tasks_config, sort);
+            order.sort(
+                &mut found,
+                |details| &details.task,
+                chrono::Local::now().date_naive(),
+                &config.tasks_config,
+                reverse,
+            );
+            match format {
+                cli::ListFormat::Text => found
+                    .iter()
+                    .for_each(|t| println!("{}", t.to_line(&config.tasks_config))),
+                cli::ListFormat::Json => println!(
+                    "{}",
+                    serde_json::to_string_pretty(
+                        &found.iter().map(|t| t.to_json()).collect::<Vec<_>>()
+                    )?
+                ),
+                cli::ListFormat::Ndjson => {
+                    for details in &found {
+                        println!("{}", serde_json::to_string(&details.to_json())?);
+                    }
+                }
+            }
+            Ok(())
+        }
+        Some(cli::Commands::Pick) => {
+            let config = Config::new(&args)?;
+            if !std::io::stderr().is_terminal() {
+                bail!("Picking a task requires a terminal");
+            }
+            let task_mgr = TaskManager::load_read_only(&config.tasks_config)?;
+            let tasks = filter_to_details(&task_mgr.tasks, &Filter::default());
+            let vault_path = &config.tasks_config.vault_path;
+            let items = tasks
+                .iter()
+                .map(|details| {
+                    format!(
+                        "{}: {}",
+                        details
+                            .file
+                            .strip_prefix(vault_path)
+                            .unwrap_or(&details.file)
+                            .display(),
+                        details
+                            .task
+                            .get_fixed_attributes(&config.tasks_config, 0)
+                            .trim_start()
+                    )
+                })
+                .collect();
+            let Some(i) = Picker::new(items).run()? else {
+                std::process::exit(1);
+            };
+            println!("{}:{}", tasks[i].file.display(), tasks[i].task.line_number);
+            Ok(())
+        }
+        Some(cli::Commands::Next {
+            count,
+            ref filter,
+            ref filter_name,
+        }) => {
+            let config = Config::new(&args)?;
+            let mut task_mgr = TaskManager::load_read_only(&config.tasks_config)?;
+            let filter = command_filter(
+                &mut task_mgr,
+                &config.tasks_config,
+                filter,
+                filter_name.as_deref(),
+            )?;
+            for (urgency, details) in next_tasks(
+                filter_to_details(&task_mgr.tasks, &filter),
+                chrono::Local::now().date_naive(),
+                &config.tasks_config.urgency,
+                count,
+            ) {
+                println!("{urgency:>5.1} {}", details.to_line(&config.tasks_config));
+            }
+            Ok(())
+        }
+        Some(cli::Commands::Show { ref query, json }) => {
+            let config = Config::new(&args)?;
+            let task_mgr = TaskManager::load_read_only(&config.tasks_config)?;
+            let query = query.join(" ");
+            let found = find_tasks(&task_mgr.tasks, &query, &config.tasks_config);
+            if found.is_empty() {
+                bail!("No task matches `{query}`");
+            }
+            let graph = LinkGraph::new(&config.tasks_config, &task_mgr.tasks)?;
+            let links = |details: &TaskDetails| {
+                graph
+                    .referenced_by(&details.file, details.task.line_number)
+                    .iter()
+                    .map(|note| {
+                        note.strip_prefix(&config.tasks_config.vault_path)
+                            .unwrap_or(note)
+                            .display()
+                            .to_string()
+                    })
+                    .collect::<Vec<String>>()
+            };
+            if json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(
+                        &found
+                            .iter()
+                            .map(|t| {
+                                let mut res = t.to_json();
+                                res["links"] = serde_json::json!(links(t));
+                                res
+                            })
+                            .collect::<Vec<_>>()
+                    )?
+                );
+            } else {
+                println!(
+                    "{}",
+                    found
+                        .iter()
+                        .map(|t| {
+                            let mut res = t.to_pretty(&config.tasks_config);
+                            let links = links(t);
+                            if !links.is_empty() {
+                                res.push_str(&format!("\nLinks:     {}", links.join(", ")));
+                            }
+                            res
+                        })
+                        .collect::<Vec<String>>()
+                        .join("\n\n")
+                );
+            }
+            Ok(())
+        }
+        Some(cli::Commands::Links { ref note }) => {
+            let config = Config::new(&args)?;
+            let task_mgr = TaskManager::load_read_only(&config.tasks_config)?;
+            let notes = vault_notes(&config.tasks_config)?;
+            let aliases = note_aliases(&notes);
+            let Some(path) = resolve_note(&config.tasks_config, &notes, &aliases, note) else {
+                bail!("No note matches `{note}`");
+            };
+            let graph = LinkGraph::new(&config.tasks_config, &task_mgr.tasks)?;
+            for details in graph.referencing(&path) {
+                println!("{}", details.to_line(&config.tasks_config));
+            }
+            Ok(())
+        }
+        Some(cli::Commands::Mark {
+            ref state,
+            ref query,
+            ref file,
+            dry_run,
+        }) => {
+            let config = Config::new(&args)?;
+            let task_mgr = TaskManager::load_read_only(&config.tasks_config)?;
+            let query = query.join(" ");
+            let mut found = find_tasks(&task_mgr.tasks, &query, &config.tasks_config);
+            if let Some(file) = file {
+                found.retain(|details| details.file.ends_with(file));
+            }
+            if found.is_empty() {
+                bail!("No task matches `{query}`");
+            }
+            let Some(state) = config.tasks_config.task_state_markers.state_named(state) else {
+                bail!(
+                    "Unknown state `{state}`, custom states are set in `task_state_markers.custom`"
+                );
+            };
+            if dry_run {
+                let highlight = std::io::stdout().is_terminal();
+                for change in plan_mark(&config.tasks_config, &found, &state)?.changes {
+                    print!("{}", change.to_unified_diff(highlight));
+                }
+            } else {
+                let _lock = VaultLock::acquire(&config.tasks_config)?;
+                mark(&config.tasks_config, &found, &state)?;
+                for details in &found {
+                    println!("Marked {} in {}", details.task.name, details.file.display());
+                }
+            }
+            Ok(())
+        }
+        Some(cli::Commands::Edit {
+            ref filter,
+            ref set,
+            ref add_tag,
+            ref remove_tag,
+            priority,
+            ref file,
+            dry_run,
+        }) => {
+            let config = Config::new(&args)?;
+            let edit = TaskEdit::new(&config.tasks_config, set, add_tag, remove_tag, priority)?;
+            let task_mgr = TaskManager::load_read_only(&config.tasks_config)?;
+            let mut found = find_tasks(&task_mgr.tasks, filter, &config.tasks_config);
+            if let Some(file) = file {
+                found.retain(|details| details.file.ends_with(file));
+            }
+            if found.is_empty() {
+                bail!("No task matches `{filter}`");
+            }
+            if dry_run {
+                let highlight = std::io::stdout().is_terminal();
+                for change in
+                    plan_changes(&config.tasks_config, &found, &|t| edit.apply(t))?.changes
+                {
+                    print!("{}", change.to_unified_diff(highlight));
+                }
+            } else {
+                let _lock = VaultLock::acquire(&config.tasks_config)?;
+                write_changes(&config.tasks_config, &found, &|t| edit.apply(t))?;
+                for details in &found {
+                    println!("Edited {} in {}", details.task.name, details.file.display());
+                }
+            }
+            Ok(())
+        }
+        Some(
+            cli::Commands::Delete {
+                ref query,
+                ref file,
+                dry_run,
+            }
+            | cli::Commands::Archive {
+                ref query,
+                ref file,
+                dry_run,
+            },
+        ) => {
+            let config = Config::new(&args)?;
+            let task_mgr = TaskManager::load_read_only(&config.tasks_config)?;
+            let query = query.join(" ");
+            let mut found = if query.is_empty() {
+                filter_to_details(&task_mgr.tasks, &Filter::default())
+            } else {
+                find_tasks(&task_mgr.tasks, &query, &config.tasks_config)
+            };
+            if let Some(file) = file {
+                found.retain(|details| details.file.ends_with(file));
+            }
+            let archiving = matches!(args.command, Some(cli::Commands::Archive { .. }));
+            let (count, changes) = if archiving {
+                plan_archive(&config.tasks_config, &found)?
+            } else {
+                plan_delete(&found)?
+            };
+            if count == 0 && archiving {
+                bail!("No done or canceled task to archive");
+            }
+            if count == 0 {
+                bail!("No task matches `{query}`");
+            }
+            if dry_run {
+                let highlight = std::io::stdout().is_terminal();
+                for change in changes.changes {
+                    print!("{}", change.to_unified_diff(highlight));
+                }
+            } else {
+                let _lock = VaultLock::acquire(&config.tasks_config)?;
+                changes.apply()?;
+                println!(
+                    "{} {count} task{}",
+                    if archiving { "Archived" } else { "Deleted" },
+                    if count == 1 { "" } else { "s" }
+                );
+            }
+            Ok(())
+        }
+        Some(cli::Commands::TagToProject { ref tag, dry_run }) => {
+            let config = Config::new(&args)?;
+            let task_mgr = TaskManager::load_read_only(&config.tasks_config)?;
+            let (count, changes) = plan_tag_to_project(&config.tasks_config, &task_mgr.tasks, tag)?;
+            if count == 0 {
+                bail!("No task is tagged `{tag}`");
+            }
+            if dry_run {
+                let highlight = std::io::stdout().is_terminal();
+                for change in changes.changes {
+                    print!("{}", change.to_unified_diff(highlight));
+                }
+            } else {
+                let _lock = VaultLock::acquire(&config.tasks_config)?;
+                changes.apply()?;
+                println!("Moved {count} task{}", if count == 1 { "" } else { "s" });
+            }
+            Ok(())
+        }
+        Some(cli::Commands::Maintain) => {
+            let config = Config::new(&args)?;
+            let _lock = VaultLock::acquire(&config.tasks_config)?;
+            let report = maintain(&config.tasks_config, chrono::Local::now().date_naive())?;
+            report.summary().iter().for_each(|l| println!("{l}"));
+            Ok(())
+        }
+        Some(cli::Commands::Diff { ref other, apply }) => {
+            let config = Config::new(&args)?;
+            let other_config = TasksConfig {
+                vault_path: other.clone(),
+                ..config.tasks_config.clone()
+            };
+            let diffs = compare_vaults(
+                &TaskManager::load_read_only(&config.tasks_config)?.tasks,
+                &config.tasks_config.vault_path,
+                &TaskManager::load_read_only(&other_config)?.tasks,
+                other,
+            );
+            diffs.iter().for_each(|d| println!("{d}"));
+            if apply {
+                let _lock = VaultLock::acquire(&config.tasks_config)?;
+                let count = apply_finished(&config.tasks_config, &diffs)?;
+                println!("Finished {count} task{}", if count == 1 { "" } else { "s" });
+            }
+            Ok(())
+        }
+        Some(cli::Commands::Calendar { ref month, format })
+            if month.is_some() || format.is_some() =>
+        {
+            let config = Config::new(&args)?;
+            let task_mgr = TaskManager::load_read_only(&config.tasks_config)?;
+            let first_day = match month {
+                Some(month) => {
+                    let Some(first_day) = parse_month(month) else {
+                        bail!("Invalid month `{month}`, expected YYYY-MM");
+                    };
+                    first_day
+                }
+                None => chrono::Local::now()
+                    .date_naive()
+                    .with_day(1)
+                    .unwrap_or_default(),
+            };
+            let tasks = calendar_tasks(&task_mgr.tasks, &config.tasks_config);
+            print!(
+                "{}",
+                match format.unwrap_or_default() {
+                    cli::CalendarFormat::Md =>
+                        month_to_markdown(&tasks, first_day, &config.tasks_config),
+                    cli::CalendarFormat::Txt =>
+                        month_to_text(&tasks, first_day, &config.tasks_config),
+                }
+            );
+            Ok(())
+        }
+        Some(cli::Commands::Agenda { week, month }) => {
+            let config = Config::new(&args)?;
+            let task_mgr = TaskManager::load_read_only(&config.tasks_config)?;
+            let today = chrono::Local::now().date_naive();
+            let end = if month {
+                today + chrono::Months::new(1)
+            } else if week {
+                today + TimeDelta::days(7)
+            } else {
+                today + TimeDelta::days(1)
+            };
+            let tasks = calendar_tasks(&task_mgr.tasks, &config.tasks_config);
+            print!(
+                "{}",
+                agenda_to_text(&tasks, today, end, &config.tasks_config)
+            );
+            Ok(())
+        }
+        Some(cli::Commands::Stats { focus: true, .. }) => {
+            let log = FocusLog::new(&get_data_dir().join(FOCUS_LOG_FILE_NAME));
+            FocusStats::new(&log.load(), chrono::Local::now().date_naive())
+                .to_lines()
+                .iter()
+                .for_each(|l| println!("{l}"));
+            Ok(())
+        }
+        Some(cli::Commands::Stats {
+            format: Some(format),
+            ..
+        }) => {
+            let config = Config::new(&args)?;
+            let task_mgr = TaskManager::load_read_only(&config.tasks_config)?;
+            let stats = stats::VaultStats::new(&task_mgr.tasks, chrono::Local::now().naive_local());
+            match format {
+                cli::StatsFormat::Table => print!("{}", stats.to_table()),
+                cli::StatsFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&stats.to_json())?);
+                }
+            }
+            Ok(())
+        }
+        Some(cli::Commands::Tags { ref path }) => {
+            let config = Config::new(&args)?;
+            let task_mgr = TaskManager::load_read_only(&config.tasks_config)?;
+            let Some(tags) = task_mgr.tag_counts(&config.tasks_config, path.as_deref()) else {
+                bail!(
+                    "{} not found in the vault",
+                    path.clone().unwrap_or_default().display()
+                );
+            };
+            print!("{}", stats::tags_to_table(&tags));
+            Ok(())
+        }
+        Some(cli::Commands::Did { ref task }) => {
+            let config = Config::new(&args)?;
+            let _lock = VaultLock::acquire(&config.tasks_config)?;
+            let path = log_done_task(
+                &config.tasks_config,
+                &task.join(" "),
+                chrono::Local::now().date_naive(),
+            )?;
+            println!("Logged to {}", path.display());
+            Ok(())
+        }
+        Some(cli::Commands::Add {
+            ref task,
+            ref file,
+            force,
+        }) => {
+            let config = Config::new(&args)?;
+            let from_stdin = task.iter().map(String::as_str).eq(["-"]);
+            let mut input = if from_stdin {
+                let mut input = String::new();
+                std::io::stdin().read_to_string(&mut input)?;
+                input
+            } else {
+                task.join(" ")
+            };
+            if let Some(file) = file {
+                input.push('\n');
+                input.push_str(&std::fs::read_to_string(file)?);
+            }
+            let mut tasks =
+                parse_new_tasks(&config.tasks_config, &input.lines().collect::<Vec<&str>>())?;
+            if !force {
+                let task_mgr = TaskManager::load_read_only(&config.tasks_config)?;
+                // Asks before adding duplicates, scripts only get the warning
+                let interactive = !from_stdin && std::io::stdin().is_terminal();
+                let mut kept = vec![];
+                for task in tasks {
+                    let similar = similar_tasks(&task_mgr.tasks, &task.name);
+                    for details in &similar {
+                        eprintln!(
+                            "A similar open task exists in {}:{}: {}",
+                            details
+                                .file
+                                .strip_prefix(&config.tasks_config.vault_path)
+                                .unwrap_or(&details.file)
+                                .display(),
+                            details.task.line_number,
+                            details.task.name
+                        );
+                    }
+                    if !similar.is_empty() && interactive {
+                        eprint!("Add {:?} anyway? [y/N] ", task.name);
+                        let mut answer = String::new();
+                        std::io::stdin().read_line(&mut answer)?;
+                        if !matches!(answer.trim(), "y" | "Y" | "yes") {
+                            continue;
+                        }
+                    }
+                    kept.push(task);
+                }
+                tasks = kept;
+                if tasks.is_empty() {
+                    println!("Nothing added");
+                    return Ok(());
+                }
+            }
+            let _lock = VaultLock::acquire(&config.tasks_config)?;
+            let path = add_tasks(
+                &config.tasks_config,
+                &tasks,
+                chrono::Local::now().date_naive(),
+            )?;
+            println!("Added {} task(s) to {}", tasks.len(), path.display());
+            Ok(())
+        }
+        Some(cli::Commands::Check { summary, overload }) => {
+            let config = Config::new(&args)?;
+            let report =
+                HealthReport::check(&config.tasks_config, chrono::Local::now().naive_local())?;
+            if overload {
+                report.overloaded_days.iter().for_each(|d| println!("{d}"));
+                return Ok(());
+            }
+            if !summary {
+                report.diagnostics.iter().for_each(|d| println!("{d}"));
+                report
+                    .stale_files
+                    .iter()
+                    .for_each(|p| println!("{}: stale file", p.display()));
+            }
+            report.summary().iter().for_each(|l| println!("{l}"));
+            Ok(())
+        }
+        Some(cli::Commands::Schedule { ref start, dry_run }) => {
+            let config = Config::new(&args)?;
+            let task_mgr = TaskManager::load_read_only(&config.tasks_config)?;
+            let now = chrono::Local::now().naive_local();
+            let day_start = if let Some(start) = start {
+                let Ok(time) = NaiveTime::parse_from_str(start, "%H:%M") else {
+                    bail!("Invalid start time `{start}`, expected HH:MM");
+                };
+                time
+            } else {
+                // Next quarter of an hour
+                let minutes = (now.hour() * 60 + now.minute()).div_ceil(15) * 15;
+                NaiveTime::from_hms_opt(minutes / 60, minutes % 60, 0).unwrap_or(now.time())
+            };
+            let tasks = calendar_tasks(&task_mgr.tasks, &config.tasks_config);
+            let (count, changes) =
+                plan_schedule(&config.tasks_config, &tasks, now.date(), day_start)?;
+            if count == 0 {
+                bail!("No task planned today, flag tasks with @today or make them due today");
+            }
+            if dry_run {
+                let highlight = std::io::stdout().is_terminal();
+                for change in changes.changes {
+                    print!("{}", change.to_unified_diff(highlight));
+                }
+            } else {
+                let _lock = VaultLock::acquire(&config.tasks_config)?;
+                changes.apply()?;
+                println!(
+                    "Scheduled {count} task{} in {}",
+                    if count == 1 { "" } else { "s" },
+                    daily_note_path(&config.tasks_config, now.date()).display()
+                );
+            }
+            Ok(())
+        }
+        Some(cli::Commands::CarryOver { move_tasks, diff }) => {
+            let config = Config::new(&args)?;
+            let _lock = VaultLock::acquire(&config.tasks_config)?;
+            let mode = if move_tasks {
+                CarryOverMode::Move
+            } else {
+                config.tasks_config.carry_over_mode
+            };
+            let today = chrono::Local::now().date_naive();
+            if diff {
+                let (count, mut changes) = plan_carry_over(&config.tasks_config, today, mode)?;
+                review_changes(&mut changes)?;
+                changes.apply()?;
+                println!(
+                    "Carried over {count} tasks, {} files written",
+                    changes.changes.len()
+                );
+            } else {
+                let count = carry_over(&config.tasks_config, today, mode)?;
+                println!("Carried over {count} tasks");
+            }
             Ok(())
         }
         _ => {
@@ -41,3 +969,59 @@ async fn main() -> Result<()> {
         }
     }
 }
+
+/// Parses the search of a command, narrowed down by the saved search `filter_name`.
+fn command_filter(
+    task_mgr: &mut TaskManager,
+    config: &TasksConfig,
+    filter: &str,
+    filter_name: Option<&str>,
+) -> Result<Filter> {
+    let Some(name) = filter_name else {
+        return Ok(parse_search_input(filter, config));
+    };
+    task_mgr.apply_named_filter(config, name)?;
+    if filter.trim().is_empty() {
+        return Ok(task_mgr.current_filter.take().unwrap_or_default());
+    }
+    Ok(parse_search_input(
+        &format!("{NAMED_FILTER_TOKEN}{name} {filter}"),
+        config,
+    ))
+}
+
+/// Returns the order of printed tasks, from `--sort` or else from the config.
+fn output_sort(config: &TasksConfig, args: cli::SortArgs) -> (OutputSort, bool) {
+    let Some(order) = args.sort else {
+        return (
+            config.output_sort,
+            config.output_sort_reverse != args.reverse,
+        );
+    };
+    let order = match order {
+        cli::SortOrder::File => OutputSort::File,
+        cli::SortOrder::Due => OutputSort::Due,
+        cli::SortOrder::Priority => OutputSort::Priority,
+        cli::SortOrder::Name => OutputSort::Name,
+        cli::SortOrder::Urgency => OutputSort::Urgency,
+    };
+    (order, args.reverse)
+}
+
+/// Prints the diff of every file of `changes` and only keeps the files the user accepts.
+fn review_changes(changes: &mut Changeset) -> Result<()> {
+    let highlight = std::io::stdout().is_terminal();
+    let mut accepted = vec![];
+    for change in changes.changes.drain(..) {
+        print!("{}", change.to_unified_diff(highlight));
+        print!("Apply changes to {}? [y/N] ", change.path.display());
+        std::io::stdout().flush()?;
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        if matches!(answer.trim(), "y" | "Y" | "yes") {
+            accepted.push(change);
+        }
+    }
+    changes.changes = accepted;
+    Ok(())
+}
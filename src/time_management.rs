@@ -3,7 +3,9 @@ use std::time::Duration;
 use pomodoro::Pomodoro;
 use time_management_technique::TimeManagementTechnique;
 
+pub mod break_suggestions;
 pub mod flow_time;
+pub mod focus_log;
 pub mod pomodoro;
 pub mod time_management_technique;
 
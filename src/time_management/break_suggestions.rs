@@ -0,0 +1,107 @@
+use std::fs;
+
+use tracing::error;
+
+use vault_tasks_core::TasksConfig;
+
+/// Returns the activities suggested during breaks.
+///
+/// The list items of `break_suggestions_note` are used if it is set and has some, `break_suggestions` otherwise.
+#[must_use]
+pub fn load_suggestions(config: &TasksConfig) -> Vec<String> {
+    if !config.break_suggestions_note.as_os_str().is_empty() {
+        let path = config.vault_path.join(&config.break_suggestions_note);
+        match fs::read_to_string(&path) {
+            Ok(content) => {
+                let res = list_items(&content);
+                if !res.is_empty() {
+                    return res;
+                }
+            }
+            Err(e) => error!("Failed to read break suggestions from {path:?}: {e}"),
+        }
+    }
+    config.break_suggestions.clone()
+}
+
+/// Returns the text of the list items of a note, tasks are skipped.
+fn list_items(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .filter_map(|l| {
+            let l = l.trim_start();
+            l.strip_prefix("- ")
+                .or_else(|| l.strip_prefix("* "))
+                .or_else(|| l.strip_prefix("+ "))
+        })
+        .map(str::trim)
+        .filter(|item| !item.is_empty() && !item.starts_with('['))
+        .map(str::to_owned)
+        .collect()
+}
+
+/// Picks a suggestion from `seed`, different from `previous` when possible.
+#[must_use]
+pub fn pick(suggestions: &[String], previous: Option<&str>, seed: usize) -> Option<String> {
+    if suggestions.is_empty() {
+        return None;
+    }
+    let mut index = seed % suggestions.len();
+    if suggestions.len() > 1 && previous == Some(suggestions[index].as_str()) {
+        index = (index + 1) % suggestions.len();
+    }
+    Some(suggestions[index].clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{load_suggestions, pick};
+    use vault_tasks_core::TasksConfig;
+
+    #[test]
+    fn test_load_suggestions() {
+        let vault_path = std::env::temp_dir().join("vault-tasks-break-suggestions");
+        let _ = fs::remove_dir_all(&vault_path);
+        fs::create_dir_all(&vault_path).unwrap();
+        fs::write(
+            vault_path.join("Breaks.md"),
+            "# Breaks\n- Stretch\n  * Refill the kettle\n- [ ] not a suggestion\nSome text\n",
+        )
+        .unwrap();
+        let mut config = TasksConfig {
+            vault_path: vault_path.clone(),
+            break_suggestions: vec!["Drink some water".to_owned()],
+            ..Default::default()
+        };
+        assert_eq!(load_suggestions(&config), vec!["Drink some water"]);
+
+        config.break_suggestions_note = "Breaks.md".into();
+        assert_eq!(
+            load_suggestions(&config),
+            vec!["Stretch", "Refill the kettle"]
+        );
+
+        // Missing notes fall back to the list
+        config.break_suggestions_note = "Missing.md".into();
+        assert_eq!(load_suggestions(&config), vec!["Drink some water"]);
+    }
+
+    #[test]
+    fn test_pick() {
+        let suggestions = vec!["Stretch".to_owned(), "Walk".to_owned()];
+        assert_eq!(pick(&[], None, 3), None);
+        assert_eq!(pick(&suggestions, None, 3), Some("Walk".to_owned()));
+        assert_eq!(
+            pick(&suggestions, Some("Walk"), 3),
+            Some("Stretch".to_owned())
+        );
+        assert_eq!(
+            pick(&suggestions[..1], Some("Stretch"), 3),
+            Some("Stretch".to_owned())
+        );
+    }
+}
@@ -0,0 +1,235 @@
+use std::{
+    collections::HashMap,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime, TimeDelta};
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use vault_tasks_core::{date_time, task::format_estimate};
+
+/// History of the focus sessions, in the data directory
+pub const FOCUS_LOG_FILE_NAME: &str = "focus_log.jsonl";
+
+/// A completed focus session of the Time Management tab.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FocusSession {
+    #[serde(with = "date_time")]
+    pub start: NaiveDateTime,
+    #[serde(with = "date_time")]
+    pub end: NaiveDateTime,
+    /// Name of the task worked on, if one was attached
+    pub task: Option<String>,
+    /// Time management technique used, e.g. `Pomodoro`
+    pub technique: String,
+}
+
+impl FocusSession {
+    #[must_use]
+    pub fn duration(&self) -> TimeDelta {
+        self.end - self.start
+    }
+}
+
+/// History of the focus sessions, one JSON object per line.
+#[derive(Debug, Clone, Default)]
+pub struct FocusLog {
+    path: PathBuf,
+}
+
+impl FocusLog {
+    #[must_use]
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+        }
+    }
+
+    /// Appends a session to the log.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the log can't be written.
+    pub fn append(&self, session: &FocusSession) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(session)?)?;
+        Ok(())
+    }
+
+    /// Returns every session of the log, invalid lines are skipped.
+    #[must_use]
+    pub fn load(&self) -> Vec<FocusSession> {
+        fs::read_to_string(&self.path)
+            .unwrap_or_default()
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .filter_map(|l| match serde_json::from_str(l) {
+                Ok(session) => Some(session),
+                Err(e) => {
+                    debug!("Ignoring invalid focus session {l:?}: {e}");
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+/// Focus time of today, of the current week and of the last days.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FocusStats {
+    pub today: TimeDelta,
+    /// Since Monday
+    pub week: TimeDelta,
+    /// The last 7 days, oldest first
+    pub days: Vec<(NaiveDate, TimeDelta)>,
+    /// Focus time of the tasks worked on this week, longest first
+    pub tasks: Vec<(String, TimeDelta)>,
+}
+
+impl FocusStats {
+    /// Sessions are counted on the day they started.
+    #[must_use]
+    pub fn new(sessions: &[FocusSession], today: NaiveDate) -> Self {
+        let monday = today - TimeDelta::days(today.weekday().num_days_from_monday().into());
+        let on = |from: NaiveDate, to: NaiveDate| {
+            sessions
+                .iter()
+                .filter(|s| (from..=to).contains(&s.start.date()))
+                .map(FocusSession::duration)
+                .sum::<TimeDelta>()
+        };
+        let mut tasks = HashMap::<String, TimeDelta>::new();
+        for session in sessions.iter().filter(|s| s.start.date() >= monday) {
+            if let Some(task) = &session.task {
+                *tasks.entry(task.clone()).or_default() += session.duration();
+            }
+        }
+        let mut tasks = tasks.into_iter().collect::<Vec<(String, TimeDelta)>>();
+        tasks.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        Self {
+            today: on(today, today),
+            week: on(monday, today),
+            days: (0..7)
+                .rev()
+                .map(|i| {
+                    let day = today - TimeDelta::days(i);
+                    (day, on(day, day))
+                })
+                .collect(),
+            tasks,
+        }
+    }
+
+    /// Returns the statistics as lines of text, e.g. `Today: 1h30`.
+    #[must_use]
+    pub fn to_lines(&self) -> Vec<String> {
+        let format = |d: &TimeDelta| format_estimate(u32::try_from(d.num_minutes()).unwrap_or(0));
+        let mut res = vec![
+            format!("Today: {}", format(&self.today)),
+            format!("This week: {}", format(&self.week)),
+            String::new(),
+        ];
+        res.extend(
+            self.days
+                .iter()
+                .map(|(day, d)| format!("{} {}", day.format("%a %d"), format(d))),
+        );
+        if !self.tasks.is_empty() {
+            res.push(String::new());
+            res.extend(
+                self.tasks
+                    .iter()
+                    .map(|(task, d)| format!("{} {task}", format(d))),
+            );
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use chrono::{NaiveDate, TimeDelta};
+    use pretty_assertions::assert_eq;
+
+    use super::{FocusLog, FocusSession, FocusStats};
+
+    fn session(day: u32, hour: u32, minutes: i64, task: Option<&str>) -> FocusSession {
+        let start = NaiveDate::from_ymd_opt(2025, 3, day)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap();
+        FocusSession {
+            start,
+            end: start + TimeDelta::minutes(minutes),
+            task: task.map(str::to_owned),
+            technique: "Pomodoro".to_owned(),
+        }
+    }
+
+    #[test]
+    fn test_focus_log() {
+        let dir = std::env::temp_dir().join("vault-tasks-focus-log");
+        let _ = fs::remove_dir_all(&dir);
+        let log = FocusLog::new(&dir.join("focus_log.jsonl"));
+        assert!(log.load().is_empty());
+
+        let sessions = vec![
+            session(3, 9, 25, Some("write report")),
+            session(3, 10, 25, None),
+        ];
+        sessions.iter().for_each(|s| log.append(s).unwrap());
+        assert_eq!(log.load(), sessions);
+        assert!(fs::read_to_string(dir.join("focus_log.jsonl"))
+            .unwrap()
+            .starts_with(r#"{"start":"2025-03-03 09:00:00","end":"2025-03-03 09:25:00","#));
+    }
+
+    #[test]
+    fn test_focus_stats() {
+        // 2025-03-05 is a Wednesday
+        let today = NaiveDate::from_ymd_opt(2025, 3, 5).unwrap();
+        let sessions = vec![
+            // Last week
+            session(1, 9, 60, Some("old")),
+            session(3, 9, 25, Some("report")),
+            session(5, 9, 25, Some("report")),
+            session(5, 14, 50, Some("review")),
+            session(5, 16, 10, None),
+        ];
+        let stats = FocusStats::new(&sessions, today);
+        assert_eq!(stats.today, TimeDelta::minutes(85));
+        assert_eq!(stats.week, TimeDelta::minutes(110));
+        assert_eq!(stats.days.len(), 7);
+        assert_eq!(
+            stats.days[2],
+            (
+                NaiveDate::from_ymd_opt(2025, 3, 1).unwrap(),
+                TimeDelta::hours(1)
+            )
+        );
+        assert_eq!(
+            stats.tasks,
+            vec![
+                ("report".to_owned(), TimeDelta::minutes(50)),
+                ("review".to_owned(), TimeDelta::minutes(50)),
+            ]
+        );
+        assert_eq!(
+            stats.to_lines()[..3],
+            ["Today: 1h25", "This week: 1h50", ""]
+        );
+        assert_eq!(stats.to_lines().last().unwrap(), "50m review");
+    }
+}
@@ -6,6 +6,7 @@ use std::{
     time::Duration,
 };
 
+use base64::{engine::general_purpose::STANDARD, Engine};
 use color_eyre::Result;
 use crossterm::{
     cursor,
@@ -13,6 +14,7 @@ use crossterm::{
         DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
         Event as CrosstermEvent, EventStream, KeyEvent, KeyEventKind, MouseEvent,
     },
+    style::Print,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen},
 };
 use futures::{FutureExt, StreamExt};
@@ -214,6 +216,23 @@ impl Tui {
     }
 }
 
+/// Rings the terminal bell.
+pub fn ring_bell() -> Result<()> {
+    crossterm::execute!(stdout(), Print("\x07"))?;
+    Ok(())
+}
+
+/// Copies `content` to the system clipboard using the OSC 52 escape sequence.
+///
+/// This works over SSH but requires a terminal supporting OSC 52.
+pub fn copy_to_clipboard(content: &str) -> Result<()> {
+    crossterm::execute!(
+        stdout(),
+        Print(format!("\x1b]52;c;{}\x07", STANDARD.encode(content)))
+    )?;
+    Ok(())
+}
+
 impl Deref for Tui {
     type Target = ratatui::Terminal<Backend<Stdout>>;
 
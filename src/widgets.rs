@@ -1,6 +1,13 @@
+pub mod completion;
+pub mod diff_view;
+pub mod empty_state;
 pub mod help_menu;
+pub mod hint_bar;
 pub mod input_bar;
+pub mod links_view;
+pub mod picker;
 pub mod styled_calendar;
 pub mod task_list;
 pub mod task_list_item;
+pub mod text_utils;
 pub mod timer;
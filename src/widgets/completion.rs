@@ -0,0 +1,191 @@
+use std::collections::HashSet;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Block, Clear, Paragraph, Widget},
+};
+use tui_input::Input;
+
+/// Date keywords of the task syntax.
+const DATE_KEYWORDS: [&str; 10] = [
+    "today",
+    "tomorrow",
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+    "@today",
+];
+const MAX_CANDIDATES: usize = 5;
+
+/// Completion of tags, date keywords and priorities for the word under the cursor of an input.
+#[derive(Default, Clone)]
+pub struct Completion {
+    candidates: Vec<String>,
+    selected: usize,
+    /// Index of the first character of the completed word
+    word_start: usize,
+}
+
+/// Returns the completions of `word`, tags come from the vault.
+fn candidates(word: &str, tags: &HashSet<String>) -> Vec<String> {
+    let word = word.to_lowercase();
+    let mut res = if let Some(tag) = word.strip_prefix('#') {
+        let mut res = tags
+            .iter()
+            .filter(|t| t.to_lowercase().starts_with(tag))
+            .map(|t| format!("#{t}"))
+            .collect::<Vec<String>>();
+        res.sort();
+        res
+    } else if word == "p" {
+        (1..=MAX_CANDIDATES).map(|p| format!("p{p}")).collect()
+    } else if word.len() >= 2 {
+        DATE_KEYWORDS
+            .iter()
+            .filter(|k| k.starts_with(&word))
+            .map(ToString::to_string)
+            .collect()
+    } else {
+        vec![]
+    };
+    // Nothing left to complete
+    res.retain(|c| c.to_lowercase() != word);
+    res.truncate(MAX_CANDIDATES);
+    res
+}
+
+impl Completion {
+    /// Updates the candidates for the word before the cursor of `input`.
+    pub fn update(&mut self, input: &Input, tags: &HashSet<String>) {
+        let before = input
+            .value()
+            .chars()
+            .take(input.cursor())
+            .collect::<Vec<char>>();
+        self.word_start = before
+            .iter()
+            .rposition(|c| c.is_whitespace())
+            .map_or(0, |i| i + 1);
+        self.candidates = candidates(&before[self.word_start..].iter().collect::<String>(), tags);
+        self.selected = 0;
+    }
+    pub fn clear(&mut self) {
+        self.candidates.clear();
+    }
+    /// Handles keys navigating the candidates (`Up`, `Down`) or accepting one (`Tab`).
+    ///
+    /// Returns whether the key was used.
+    pub fn handle_key(&mut self, input: &mut Input, key: KeyEvent) -> bool {
+        if self.candidates.is_empty() {
+            return false;
+        }
+        match key.code {
+            KeyCode::Down => self.selected = (self.selected + 1) % self.candidates.len(),
+            KeyCode::Up => {
+                self.selected = (self.selected + self.candidates.len() - 1) % self.candidates.len();
+            }
+            KeyCode::Tab => {
+                *input = self.apply(input);
+                self.clear();
+            }
+            _ => return false,
+        }
+        true
+    }
+    /// Replaces the word before the cursor with the selected candidate.
+    fn apply(&self, input: &Input) -> Input {
+        let chars = input.value().chars().collect::<Vec<char>>();
+        let candidate = &self.candidates[self.selected];
+        let rest = chars[input.cursor()..].iter().collect::<String>();
+        let separator = if rest.starts_with(char::is_whitespace) {
+            ""
+        } else {
+            " "
+        };
+        let value = format!(
+            "{}{candidate}{separator}{rest}",
+            chars[..self.word_start].iter().collect::<String>(),
+        );
+        Input::new(value).with_cursor(self.word_start + candidate.chars().count() + 1)
+    }
+    /// Renders the candidates under `anchor`, the area of the input bar.
+    pub fn render(&self, anchor: Rect, scroll: usize, buf: &mut Buffer) {
+        if self.candidates.is_empty() {
+            return;
+        }
+        let width = self
+            .candidates
+            .iter()
+            .map(|c| c.chars().count())
+            .max()
+            .unwrap_or_default() as u16
+            + 2;
+        let x = (anchor.x + 1 + self.word_start.saturating_sub(scroll) as u16)
+            .min(buf.area.right().saturating_sub(width));
+        let area = Rect::new(x, anchor.bottom(), width, self.candidates.len() as u16 + 2)
+            .intersection(buf.area);
+
+        Clear.render(area, buf);
+        Paragraph::new(
+            self.candidates
+                .iter()
+                .enumerate()
+                .map(|(i, c)| {
+                    if i == self.selected {
+                        Line::from(c.as_str()).reversed()
+                    } else {
+                        Line::from(c.as_str())
+                    }
+                })
+                .collect::<Vec<Line>>(),
+        )
+        .block(Block::bordered().style(Style::reset()))
+        .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use pretty_assertions::assert_eq;
+    use tui_input::Input;
+
+    use super::{candidates, Completion};
+
+    #[test]
+    fn test_candidates() {
+        let tags = HashSet::from(["work".to_owned(), "Writing".to_owned(), "home".to_owned()]);
+        assert_eq!(candidates("#w", &tags), vec!["#Writing", "#work"]);
+        assert_eq!(candidates("#work", &tags), Vec::<String>::new());
+        assert_eq!(candidates("tu", &tags), vec!["tuesday"]);
+        assert_eq!(candidates("to", &tags), vec!["today", "tomorrow"]);
+        assert_eq!(candidates("p", &tags).len(), 5);
+        assert_eq!(candidates("t", &tags), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_completion() {
+        let tags = HashSet::from(["work".to_owned(), "writing".to_owned()]);
+        let mut input = Input::new("- [ ] report #w p1".to_owned()).with_cursor(15);
+        let mut completion = Completion::default();
+        completion.update(&input, &tags);
+
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        assert!(completion.handle_key(&mut input, key(KeyCode::Down)));
+        assert!(completion.handle_key(&mut input, key(KeyCode::Tab)));
+        assert_eq!(input.value(), "- [ ] report #writing p1");
+        assert_eq!(input.cursor(), 22);
+        // No more candidates
+        assert!(!completion.handle_key(&mut input, key(KeyCode::Tab)));
+    }
+}
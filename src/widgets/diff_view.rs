@@ -0,0 +1,139 @@
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Clear, Paragraph},
+};
+use tui_scrollview::{ScrollView, ScrollViewState};
+
+use vault_tasks_core::changeset::{Changeset, DiffLine, Segment};
+
+/// Scrollable preview of the changes of a bulk operation, each file can be accepted or rejected.
+#[derive(Default, Clone)]
+pub struct DiffView {
+    changes: Changeset,
+    /// Whether the changes of each file will be written
+    accepted: Vec<bool>,
+    selected: usize,
+    pub state: ScrollViewState,
+}
+
+impl DiffView {
+    /// Every file is accepted by default.
+    pub fn new(changes: Changeset) -> Self {
+        Self {
+            accepted: vec![true; changes.changes.len()],
+            changes,
+            selected: 0,
+            state: ScrollViewState::new(),
+        }
+    }
+    pub fn next_file(&mut self) {
+        self.selected = (self.selected + 1).min(self.changes.changes.len().saturating_sub(1));
+    }
+    pub fn previous_file(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+    pub fn set_selected_accepted(&mut self, accepted: bool) {
+        if let Some(a) = self.accepted.get_mut(self.selected) {
+            *a = accepted;
+        }
+    }
+    pub fn scroll_down(&mut self) {
+        self.state.scroll_down();
+    }
+    pub fn scroll_up(&mut self) {
+        self.state.scroll_up();
+    }
+    /// Returns the changes of the accepted files.
+    pub fn accepted_changes(&self) -> Changeset {
+        Changeset {
+            changes: self
+                .changes
+                .changes
+                .iter()
+                .zip(&self.accepted)
+                .filter(|(_, accepted)| **accepted)
+                .map(|(change, _)| change.clone())
+                .collect(),
+        }
+    }
+
+    /// Changed words are reversed.
+    fn segments_to_line(
+        prefix: &'static str,
+        segments: Vec<Segment>,
+        color: Color,
+    ) -> Line<'static> {
+        let mut spans = vec![Span::raw(prefix)];
+        spans.extend(segments.into_iter().map(|s| {
+            if s.changed {
+                Span::raw(s.text).reversed()
+            } else {
+                Span::raw(s.text)
+            }
+        }));
+        Line::from(spans).fg(color)
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        let mut lines = vec![];
+        for (i, (change, accepted)) in self.changes.changes.iter().zip(&self.accepted).enumerate() {
+            let header = Line::from(format!(
+                "[{}] {}",
+                if *accepted { 'x' } else { ' ' },
+                change.path.display()
+            ))
+            .bold();
+            lines.push(if i == self.selected {
+                header.reversed()
+            } else {
+                header
+            });
+            for hunk in change.hunks() {
+                lines.push(
+                    Line::from(format!(
+                        "@@ -{},{} +{},{} @@",
+                        hunk.old_start, hunk.old_len, hunk.new_start, hunk.new_len
+                    ))
+                    .cyan(),
+                );
+                for line in hunk.lines {
+                    lines.push(match line {
+                        DiffLine::Context(l) => Line::from(format!(" {l}")),
+                        DiffLine::Removed(s) => Self::segments_to_line("-", s, Color::Red),
+                        DiffLine::Added(s) => Self::segments_to_line("+", s, Color::Green),
+                    });
+                }
+            }
+            lines.push(Line::default());
+        }
+        lines
+    }
+}
+
+impl StatefulWidget for DiffView {
+    type State = ScrollViewState;
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State)
+    where
+        Self: Sized,
+    {
+        let block = Block::bordered().title("Pending changes").title_bottom(
+            Line::from("↑↓: select file, ←→: reject/accept, Enter: write, Esc: cancel")
+                .right_aligned(),
+        );
+        let inner = block.inner(area);
+        Widget::render(Clear, area, buf);
+        block.render(area, buf);
+
+        let lines = self.lines();
+        let width = lines
+            .iter()
+            .map(Line::width)
+            .max()
+            .unwrap_or_default()
+            .max(inner.width as usize);
+        let content_size = Size::new(width as u16, lines.len() as u16);
+        let mut scroll_view = ScrollView::new(content_size);
+        scroll_view.render_widget(Paragraph::new(lines), scroll_view.area());
+        scroll_view.render(inner, buf, state);
+    }
+}
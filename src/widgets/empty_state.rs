@@ -0,0 +1,84 @@
+use ratatui::{
+    prelude::*,
+    widgets::{Paragraph, Wrap},
+};
+
+use super::hint_bar::shortest_key;
+use crate::{action::Action, app::Mode, config::Config};
+
+/// Message shown in place of an empty view, telling why it is empty and which keys help.
+#[derive(Default, Clone)]
+pub struct EmptyState {
+    title: String,
+    reason: String,
+    /// Keys and what their action does
+    hints: Vec<(String, String)>,
+}
+
+impl EmptyState {
+    pub fn new(title: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            reason: reason.into(),
+            hints: vec![],
+        }
+    }
+
+    /// Adds a hint for `action`, skipped if no single key is bound to it in `mode`.
+    #[must_use]
+    pub fn hint(mut self, config: &Config, mode: Mode, action: &Action, label: &str) -> Self {
+        if let Some(key) = shortest_key(config, mode, action) {
+            self.hints.push((key, label.to_owned()));
+        }
+        self
+    }
+}
+
+impl Widget for EmptyState {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut lines = vec![
+            Line::from(self.title).bold(),
+            Line::default(),
+            Line::from(self.reason),
+        ];
+        if !self.hints.is_empty() {
+            lines.push(Line::default());
+        }
+        lines.extend(self.hints.into_iter().map(|(key, label)| {
+            Line::from(vec![format!("<{key}>").bold(), format!(" {label}").into()])
+        }));
+        // Roughly centered, the reason may be wrapped on a few lines
+        let top = area.height.saturating_sub(lines.len() as u16) / 2;
+        let area = Rect {
+            y: area.y + top,
+            height: area.height - top,
+            ..area
+        };
+        Paragraph::new(lines)
+            .centered()
+            .wrap(Wrap { trim: true })
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_snapshot;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    use super::EmptyState;
+    use crate::{action::Action, app::Mode, config::Config};
+
+    #[test]
+    fn test_render_empty_state() {
+        let config = Config::default();
+        let empty_state = EmptyState::new("No task matches the search", "Nothing matches `#work`")
+            .hint(&config, Mode::Filter, &Action::Search, "edit the search")
+            .hint(&config, Mode::Filter, &Action::GotoToday, "not bound");
+        let mut terminal = Terminal::new(TestBackend::new(40, 8)).unwrap();
+        terminal
+            .draw(|frame| frame.render_widget(empty_state, frame.area()))
+            .unwrap();
+        assert_snapshot!(terminal.backend());
+    }
+}
@@ -1,6 +1,5 @@
 use std::collections::HashSet;
 
-use crossterm::event::KeyModifiers;
 use layout::Flex;
 use ratatui::{
     prelude::*,
@@ -9,6 +8,7 @@ use ratatui::{
 use tracing::debug;
 use tui_scrollview::{ScrollView, ScrollViewState};
 
+use super::hint_bar::format_key;
 use crate::{action::Action, app::Mode, config::Config};
 
 #[derive(Default, Clone)]
@@ -27,12 +27,7 @@ impl HelpMenu<'_> {
             .iter()
             .filter_map(|(k, v)| {
                 if *v == *action {
-                    let key = k.first().unwrap();
-                    Some(if key.modifiers == KeyModifiers::NONE {
-                        format!("<{}>", key.code)
-                    } else {
-                        format!("<{}-{}>", key.modifiers, key.code)
-                    })
+                    Some(format!("<{}>", format_key(k.first().unwrap())))
                 } else {
                     None
                 }
@@ -0,0 +1,177 @@
+use crossterm::event::{KeyEvent, KeyModifiers};
+use ratatui::prelude::*;
+
+use crate::{
+    action::Action,
+    app::Mode,
+    config::{Config, FooterHint},
+};
+
+const SEPARATOR: &str = " | ";
+
+/// Formats a key the way it is displayed to the user.
+pub fn format_key(key: &KeyEvent) -> String {
+    if key.modifiers == KeyModifiers::NONE {
+        format!("{}", key.code)
+    } else {
+        format!("{}-{}", key.modifiers, key.code)
+    }
+}
+
+/// Returns the shortest single key bound to `action` in the keymap of `mode`.
+pub fn shortest_key(config: &Config, mode: Mode, action: &Action) -> Option<String> {
+    config
+        .keybindings
+        .get(&mode)?
+        .iter()
+        .filter(|(keys, bound)| *bound == action && keys.len() == 1)
+        .map(|(keys, _)| format_key(&keys[0]))
+        .min_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))
+}
+
+/// A single line listing the keys of the most important actions of a tab.
+///
+/// Hints are displayed in order and the last ones are dropped when the line does not fit.
+#[derive(Default, Clone)]
+pub struct HintBar {
+    /// Labels and their keys
+    hints: Vec<(String, Vec<String>)>,
+}
+
+impl HintBar {
+    /// Builds the hint bar of a mode from the `footer` section of the config.
+    pub fn new(config: &Config, mode: Mode) -> Self {
+        Self::from_hints(
+            config,
+            &[mode],
+            config
+                .footer
+                .hints
+                .get(&mode)
+                .map(Vec::as_slice)
+                .unwrap_or_default(),
+        )
+    }
+
+    /// Builds a hint bar from a list of hints, keys are searched in the keymaps of `modes` in order.
+    ///
+    /// Hints whose action is not bound to any key are skipped.
+    pub fn from_hints(config: &Config, modes: &[Mode], hints: &[FooterHint]) -> Self {
+        if config.footer.hidden {
+            return Self::default();
+        }
+        let mut res: Vec<(String, Vec<String>)> = vec![];
+        for hint in hints {
+            let Some(key) = modes
+                .iter()
+                .find_map(|mode| shortest_key(config, *mode, &hint.action))
+            else {
+                continue;
+            };
+            if let Some((_, keys)) = res.iter_mut().find(|(label, _)| *label == hint.label) {
+                if !keys.contains(&key) {
+                    keys.push(key);
+                }
+            } else {
+                res.push((hint.label.clone(), vec![key]));
+            }
+        }
+        Self { hints: res }
+    }
+
+    fn hint_to_string(label: &str, keys: &[String]) -> String {
+        format!("{label}: <{}>", keys.join("|"))
+    }
+}
+
+impl Widget for HintBar {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut content = String::new();
+        for (label, keys) in &self.hints {
+            let hint = Self::hint_to_string(label, keys);
+            if content.is_empty() {
+                // Always show the most important hint, even if it gets cut
+                content = hint;
+                continue;
+            }
+            let new_content = format!("{content}{SEPARATOR}{hint}");
+            if Line::raw(new_content.as_str()).width() > area.width as usize {
+                break;
+            }
+            content = new_content;
+        }
+        Line::raw(content).centered().render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+
+    use super::HintBar;
+    use crate::{
+        action::Action,
+        app::Mode,
+        config::{Config, FooterHint},
+    };
+
+    fn hints() -> Vec<FooterHint> {
+        vec![
+            FooterHint {
+                action: Action::Down,
+                label: String::from("Navigate"),
+            },
+            FooterHint {
+                action: Action::Up,
+                label: String::from("Navigate"),
+            },
+            FooterHint {
+                action: Action::Open,
+                label: String::from("Open"),
+            },
+            FooterHint {
+                action: Action::GotoToday,
+                label: String::from("Not bound"),
+            },
+            FooterHint {
+                action: Action::Search,
+                label: String::from("Filter"),
+            },
+        ]
+    }
+
+    fn render(bar: HintBar, width: u16) -> String {
+        let mut buf = Buffer::empty(Rect::new(0, 0, width, 1));
+        bar.render(buf.area, &mut buf);
+        buf.content.iter().map(|c| c.symbol()).collect::<String>()
+    }
+
+    #[test]
+    fn test_hint_bar() {
+        let config = Config::default();
+        let bar = HintBar::from_hints(&config, &[Mode::Explorer], &hints());
+        assert_eq!(
+            render(bar, 50),
+            format!("{:^50}", "Navigate: <j|k> | Open: <o> | Filter: <s>")
+        );
+    }
+
+    #[test]
+    fn test_hint_bar_truncated() {
+        let config = Config::default();
+        let bar = HintBar::from_hints(&config, &[Mode::Explorer], &hints());
+        assert_eq!(
+            render(bar, 30),
+            format!("{:^30}", "Navigate: <j|k> | Open: <o>")
+        );
+    }
+
+    #[test]
+    fn test_hint_bar_hidden() {
+        let mut config = Config::default();
+        config.footer.hidden = true;
+        let bar = HintBar::from_hints(&config, &[Mode::Explorer], &hints());
+        assert_eq!(render(bar, 20), " ".repeat(20));
+    }
+}
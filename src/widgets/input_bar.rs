@@ -1,23 +1,63 @@
+use std::collections::HashSet;
+
+use crossterm::event::{Event, KeyCode, KeyEvent};
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
-    style::Style,
+    style::{Style, Stylize},
+    text::Line,
     widgets::{Block, Clear, Paragraph, Widget},
 };
-use tui_input::Input;
+use tui_input::{backend::crossterm::EventHandler, Input};
+
+use super::completion::Completion;
 
 #[derive(Default, Clone)]
 pub struct InputBar<'a> {
     pub input: Input,
     pub is_focused: bool,
     pub block: Option<Block<'a>>,
+    pub completion: Completion,
+    /// Suggestion shown dimmed after the input, appended with `Tab`
+    pub ghost: Option<String>,
+}
+
+impl InputBar<'_> {
+    /// Handles a key typed in the bar, completing `tags`, date keywords and priorities.
+    ///
+    /// Without completion candidates, `Tab` accepts the ghost suggestion.
+    pub fn handle_key(&mut self, key: KeyEvent, tags: &HashSet<String>) {
+        if self.completion.handle_key(&mut self.input, key) {
+            return;
+        }
+        if key.code == KeyCode::Tab {
+            if let Some(ghost) = self.ghost.take() {
+                self.input = Input::new(format!("{} {ghost}", self.input.value().trim_end()));
+                return;
+            }
+        }
+        self.input.handle_event(&Event::Key(key));
+        self.completion.update(&self.input, tags);
+    }
+    /// Renders the completion popup under the bar at `area`, to call once everything else is drawn.
+    pub fn render_completion(&self, area: Rect, buf: &mut Buffer) {
+        if self.is_focused {
+            let width = area.width.max(3) - 3; // 2 for borders, 1 for cursor
+            self.completion
+                .render(area, self.input.visual_scroll(width as usize), buf);
+        }
+    }
 }
 
 impl Widget for InputBar<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
         let width = area.width.max(3) - 3; // 2 for borders, 1 for cursor
         let scroll = self.input.visual_scroll(width as usize);
-        let res = Paragraph::new(self.input.value())
+        let mut line = Line::from(self.input.value());
+        if let Some(ghost) = &self.ghost {
+            line.push_span(format!(" {ghost}").dim());
+        }
+        let res = Paragraph::new(line)
             .style(Style::reset())
             .scroll((0, scroll as u16));
 
@@ -39,6 +79,9 @@ mod tests {
         widgets::Block,
         Terminal,
     };
+    use std::collections::HashSet;
+
+    use crossterm::event::{KeyCode, KeyEvent};
     use tui_input::Input;
 
     use crate::widgets::input_bar::InputBar;
@@ -49,6 +92,7 @@ mod tests {
             input: Input::new("input".to_owned()),
             is_focused: true,
             block: Some(Block::bordered().title_top("test")),
+            ..Default::default()
         };
         let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
         terminal
@@ -63,6 +107,7 @@ mod tests {
             input,
             is_focused: true,
             block: Some(Block::bordered().title_top("test")),
+            ..Default::default()
         };
         let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
         terminal
@@ -85,4 +130,17 @@ mod tests {
             .unwrap();
         assert_snapshot!(terminal.backend());
     }
+
+    #[test]
+    fn test_accept_ghost() {
+        let mut bar = InputBar {
+            input: Input::new("mow the lawn ".to_owned()),
+            ghost: Some("saturday".to_owned()),
+            ..Default::default()
+        };
+        bar.handle_key(KeyEvent::from(KeyCode::Tab), &HashSet::new());
+        assert_eq!(bar.input.value(), "mow the lawn saturday");
+        assert_eq!(bar.input.cursor(), bar.input.value().len());
+        assert_eq!(bar.ghost, None);
+    }
 }
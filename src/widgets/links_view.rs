@@ -0,0 +1,148 @@
+use std::path::PathBuf;
+
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Clear, Paragraph},
+};
+
+use vault_tasks_core::details::TaskDetails;
+
+/// Where an entry of the links view leads.
+#[derive(Debug, Clone)]
+pub enum LinkTarget {
+    Note(PathBuf),
+    Task(Box<TaskDetails>),
+}
+
+/// A titled list of links, shown even if it is empty.
+#[derive(Debug, Clone)]
+pub struct LinkSection {
+    pub title: String,
+    /// Labels of the entries and where they lead
+    pub entries: Vec<(String, LinkTarget)>,
+}
+
+/// Popup listing the notes the selected task links to and the tasks linking to the selected note.
+#[derive(Debug, Default, Clone)]
+pub struct LinksView {
+    sections: Vec<LinkSection>,
+    /// Index of the selected entry among the entries of every section
+    selected: usize,
+}
+
+impl LinksView {
+    pub const fn new(sections: Vec<LinkSection>) -> Self {
+        Self {
+            sections,
+            selected: 0,
+        }
+    }
+    fn len(&self) -> usize {
+        self.sections.iter().map(|s| s.entries.len()).sum()
+    }
+    pub fn next(&mut self) {
+        self.selected = (self.selected + 1).min(self.len().saturating_sub(1));
+    }
+    pub fn previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+    pub fn selected(&self) -> Option<&LinkTarget> {
+        self.sections
+            .iter()
+            .flat_map(|s| &s.entries)
+            .nth(self.selected)
+            .map(|(_, target)| target)
+    }
+}
+
+impl Widget for &LinksView {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .title("Links")
+            .title_bottom(Line::from("↑↓: select, Enter: go to, Esc: close").right_aligned());
+        let inner = block.inner(area);
+        Clear.render(area, buf);
+        block.render(area, buf);
+
+        let mut lines = vec![];
+        let mut index = 0;
+        for section in &self.sections {
+            if !lines.is_empty() {
+                lines.push(Line::default());
+            }
+            lines.push(Line::from(section.title.clone()).bold());
+            if section.entries.is_empty() {
+                lines.push(Line::from("  None").dim());
+            }
+            for (label, _) in &section.entries {
+                let line = Line::from(format!("  {label}"));
+                lines.push(if index == self.selected {
+                    line.reversed()
+                } else {
+                    line
+                });
+                index += 1;
+            }
+        }
+        // Keeps the selected entry in view
+        let selected_line = self
+            .sections
+            .iter()
+            .scan((0, 0), |(entries, lines), section| {
+                let start = *lines + 1;
+                *lines += section.entries.len().max(1) + 2;
+                let res = (*entries, start, section.entries.len());
+                *entries += section.entries.len();
+                Some(res)
+            })
+            .find(|(entries, _, len)| self.selected < entries + len)
+            .map_or(0, |(entries, start, _)| start + self.selected - entries);
+        let offset = (selected_line + 1).saturating_sub(inner.height as usize);
+        Paragraph::new(lines)
+            .scroll((u16::try_from(offset).unwrap_or_default(), 0))
+            .render(inner, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use insta::assert_snapshot;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    use super::{LinkSection, LinkTarget, LinksView};
+
+    #[test]
+    fn test_render_links_view() {
+        let mut links_view = LinksView::new(vec![
+            LinkSection {
+                title: "Notes linked from `plan the release`".to_owned(),
+                entries: vec![
+                    (
+                        "Work/Project.md".to_owned(),
+                        LinkTarget::Note(PathBuf::from("Work/Project.md")),
+                    ),
+                    (
+                        "Release.md".to_owned(),
+                        LinkTarget::Note(PathBuf::from("Release.md")),
+                    ),
+                ],
+            },
+            LinkSection {
+                title: "Tasks linking to Tasks.md".to_owned(),
+                entries: vec![],
+            },
+        ]);
+        links_view.next();
+        assert!(
+            matches!(links_view.selected(), Some(LinkTarget::Note(p)) if p.ends_with("Release.md"))
+        );
+        links_view.next();
+        let mut terminal = Terminal::new(TestBackend::new(50, 10)).unwrap();
+        terminal
+            .draw(|frame| frame.render_widget(&links_view, frame.area()))
+            .unwrap();
+        assert_snapshot!(terminal.backend());
+    }
+}
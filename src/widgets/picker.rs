@@ -0,0 +1,238 @@
+use std::io::stderr;
+
+use color_eyre::Result;
+use crossterm::{
+    event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    buffer::Buffer,
+    layout::{Position, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::Widget,
+    Terminal,
+};
+use tui_input::{backend::crossterm::EventHandler, Input};
+
+/// Scores how well `candidate` matches `query`, a higher score being a better match.
+///
+/// The characters of every word of `query` have to appear in `candidate` in order, case insensitively, but not necessarily contiguously.
+/// Consecutive characters and characters starting a word score higher.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<usize> {
+    let candidate = candidate.to_lowercase().chars().collect::<Vec<char>>();
+    query
+        .to_lowercase()
+        .split_whitespace()
+        .try_fold(0, |score, word| {
+            let mut start = 0;
+            let mut previous = None;
+            let mut word_score = 0;
+            for c in word.chars() {
+                let i = start + candidate[start..].iter().position(|&x| x == c)?;
+                word_score += 1;
+                if previous.is_some_and(|p| p + 1 == i) {
+                    word_score += 2;
+                }
+                if i == 0 || !candidate[i - 1].is_alphanumeric() {
+                    word_score += 3;
+                }
+                previous = Some(i);
+                start = i + 1;
+            }
+            Some(score + word_score)
+        })
+}
+
+/// What the picker does after a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PickerState {
+    Picking,
+    /// Index of the picked item
+    Picked(usize),
+    Canceled,
+}
+
+/// Minimal fuzzy finder over lines of text, à la `fzf`.
+#[derive(Default, Clone)]
+pub struct Picker {
+    items: Vec<String>,
+    input: Input,
+    /// Indices of the items matching the input, best match first
+    matches: Vec<usize>,
+    selected: usize,
+}
+
+impl Picker {
+    #[must_use]
+    pub fn new(items: Vec<String>) -> Self {
+        let mut res = Self {
+            items,
+            ..Default::default()
+        };
+        res.update_matches();
+        res
+    }
+
+    fn update_matches(&mut self) {
+        let mut matches = self
+            .items
+            .iter()
+            .enumerate()
+            .filter_map(|(i, item)| Some((fuzzy_score(self.input.value(), item)?, i)))
+            .collect::<Vec<(usize, usize)>>();
+        // Stable, items with the same score keep their order
+        matches.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        self.matches = matches.into_iter().map(|(_, i)| i).collect();
+        self.selected = 0;
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> PickerState {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => return PickerState::Canceled,
+            KeyCode::Char('c') if ctrl => return PickerState::Canceled,
+            KeyCode::Enter => {
+                return self
+                    .matches
+                    .get(self.selected)
+                    .map_or(PickerState::Picking, |&i| PickerState::Picked(i))
+            }
+            KeyCode::Down => self.select_next(),
+            KeyCode::Char('n') if ctrl => self.select_next(),
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Char('p') if ctrl => self.selected = self.selected.saturating_sub(1),
+            _ => {
+                if self
+                    .input
+                    .handle_event(&Event::Key(key))
+                    .is_some_and(|changed| changed.value)
+                {
+                    self.update_matches();
+                }
+            }
+        }
+        PickerState::Picking
+    }
+
+    fn select_next(&mut self) {
+        self.selected = (self.selected + 1).min(self.matches.len().saturating_sub(1));
+    }
+
+    /// Lets the user pick an item on the alternate screen of STDERR, leaving STDOUT to the result.
+    ///
+    /// Returns the index of the picked item, `None` if the user canceled.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the terminal can't be set up or drawn.
+    pub fn run(mut self) -> Result<Option<usize>> {
+        crossterm::terminal::enable_raw_mode()?;
+        crossterm::execute!(stderr(), EnterAlternateScreen)?;
+        let res = self.run_loop();
+        crossterm::execute!(stderr(), LeaveAlternateScreen)?;
+        crossterm::terminal::disable_raw_mode()?;
+        res
+    }
+
+    fn run_loop(&mut self) -> Result<Option<usize>> {
+        let mut terminal = Terminal::new(CrosstermBackend::new(stderr()))?;
+        loop {
+            terminal.draw(|frame| {
+                let area = frame.area();
+                frame.render_widget(&*self, area);
+                let cursor = 2 + self.input.visual_cursor() as u16;
+                frame.set_cursor_position(Position::new(cursor.min(area.width), 0));
+            })?;
+            if let Event::Key(key) = crossterm::event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                match self.handle_key(key) {
+                    PickerState::Picking => (),
+                    PickerState::Picked(i) => return Ok(Some(i)),
+                    PickerState::Canceled => return Ok(None),
+                }
+            }
+        }
+    }
+}
+
+impl Widget for &Picker {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut lines = vec![
+            Line::from(format!("> {}", self.input.value())),
+            Line::from(format!("  {}/{}", self.matches.len(), self.items.len())).dim(),
+        ];
+        let height = (area.height as usize).saturating_sub(lines.len());
+        let offset = (self.selected + 1).saturating_sub(height);
+        lines.extend(
+            self.matches
+                .iter()
+                .enumerate()
+                .skip(offset)
+                .take(height)
+                .map(|(n, &i)| {
+                    if n == self.selected {
+                        Line::from(format!("> {}", self.items[i])).style(Style::new().reversed())
+                    } else {
+                        Line::from(format!("  {}", self.items[i]))
+                    }
+                }),
+        );
+        for (line, y) in lines.into_iter().zip(area.top()..area.bottom()) {
+            line.render(Rect::new(area.x, y, area.width, 1), buf);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use insta::assert_snapshot;
+    use pretty_assertions::assert_eq;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    use super::{fuzzy_score, Picker, PickerState};
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert!(fuzzy_score("", "anything").is_some());
+        assert!(fuzzy_score("wtr plnt", "Water plants").is_some());
+        assert!(fuzzy_score("plants water", "water plants").is_some());
+        assert!(fuzzy_score("xyz", "water plants").is_none());
+        assert!(fuzzy_score("wp", "water plants") > fuzzy_score("wp", "swamp"));
+        assert!(fuzzy_score("call", "call bank") > fuzzy_score("call", "recall"));
+    }
+
+    #[test]
+    fn test_picker() {
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        let mut picker = Picker::new(vec![
+            "chores.md:1: water plants".to_owned(),
+            "work.md:3: review PR #work".to_owned(),
+            "work.md:4: write report #work".to_owned(),
+        ]);
+        for c in "#work".chars() {
+            assert_eq!(
+                picker.handle_key(key(KeyCode::Char(c))),
+                PickerState::Picking
+            );
+        }
+        picker.handle_key(key(KeyCode::Down));
+        picker.handle_key(key(KeyCode::Down));
+
+        let mut terminal = Terminal::new(TestBackend::new(30, 5)).unwrap();
+        terminal
+            .draw(|frame| frame.render_widget(&picker, frame.area()))
+            .unwrap();
+        assert_snapshot!(terminal.backend());
+
+        assert_eq!(
+            picker.handle_key(key(KeyCode::Enter)),
+            PickerState::Picked(2)
+        );
+        assert_eq!(picker.handle_key(key(KeyCode::Esc)), PickerState::Canceled);
+    }
+}
@@ -1,26 +1,32 @@
-use crate::core::vault_data::VaultData;
+use std::rc::Rc;
+
 use ratatui::prelude::*;
 use tui_scrollview::{ScrollView, ScrollViewState};
+use vault_tasks_core::{filter::Filter, vault_data::VaultData};
 
 use crate::config::Config;
 
-use super::task_list_item::TaskListItem;
+use super::{task_list_item::TaskListItem, text_utils::ellipsis};
 
 #[derive(Default, Clone)]
 pub struct TaskList {
     content: Vec<TaskListItem>,
-    constraints: Vec<Constraint>,
-    height: u16,
+    /// Index of the highlighted item, kept visible when rendered
+    selected: Option<usize>,
 }
 
 impl TaskList {
     pub fn new(config: &Config, file_content: &[VaultData], display_filename: bool) -> Self {
+        let style_rules: Rc<[(Filter, Style)]> =
+            config.style_rules.compile(&config.tasks_config).into();
+        let tag_colors = Rc::new(config.tag_colors.clone());
+        let date_format = config.tasks_config.date_display_format();
         let content = file_content
             .iter()
             .map(|fc| {
                 TaskListItem::new(
                     fc.clone(),
-                    !config.tasks_config.use_american_format,
+                    date_format.clone(),
                     config.tasks_config.pretty_symbols.clone(),
                     display_filename,
                     config.tasks_config.show_relative_due_dates,
@@ -33,19 +39,39 @@ impl TaskList {
                         .get("preview_headers")
                         .unwrap(),
                 )
+                .wrap(config.tasks_config.preview_soft_wrap)
+                .ellipsis(ellipsis(config.tasks_config.ascii_mode))
+                .ascii_mode(config.tasks_config.ascii_mode)
+                .style_rules(style_rules.clone())
+                .tag_colors(tag_colors.clone())
+                .tag_pills(config.tasks_config.tag_pills)
             })
             .collect::<Vec<TaskListItem>>();
-        let mut height = 0;
-        let mut constraints = vec![];
-        for item in &content {
-            height += item.height;
-            constraints.push(Constraint::Length(item.height));
-        }
         Self {
             content,
-            constraints,
-            height,
+            selected: None,
+        }
+    }
+    /// Shows the names of the parent tasks of each item, `None` for top level tasks.
+    pub fn breadcrumbs(mut self, breadcrumbs: &[Option<String>]) -> Self {
+        self.content = self
+            .content
+            .into_iter()
+            .zip(breadcrumbs.iter().cloned().chain(std::iter::repeat(None)))
+            .map(|(item, breadcrumb)| item.breadcrumb(breadcrumb))
+            .collect();
+        self
+    }
+    /// Highlights the border of the item at `index` with `style`.
+    pub fn select(mut self, index: Option<usize>, style: Style) -> Self {
+        self.selected = index.filter(|i| *i < self.content.len());
+        if let Some(i) = self.selected {
+            self.content[i] = self.content[i].clone().selected_style(Some(style));
         }
+        self
+    }
+    fn height(&self, width: u16) -> u16 {
+        self.content.iter().map(|item| item.height(width)).sum()
     }
     // pub fn height_of(&mut self, i: usize) -> u16 {
     //     (0..i).map(|i| self.content[i].height).sum()
@@ -67,16 +93,37 @@ impl StatefulWidget for TaskList {
         // If we don't do this, the horizontal scrollbar
         // appears for only one character
         // It basically disables the horizontal scrollbar
-        let width = if self.height > area.height {
+        let width = if self.height(area.width) > area.height {
             area.width - 1
         } else {
             area.width
         };
+        // Wrapped items are taller when the scrollbar takes a column
+        let height = self.height(width);
 
-        let size = Size::new(width, self.height);
+        let size = Size::new(width, height);
         let mut scroll_view = ScrollView::new(size);
 
-        let layout = Layout::vertical(self.constraints).split(scroll_view.area());
+        let layout = Layout::vertical(
+            self.content
+                .iter()
+                .map(|item| Constraint::Length(item.height(width))),
+        )
+        .split(scroll_view.area());
+
+        // Scroll to the selected item if it is out of view
+        if let Some(selected) = self.selected {
+            let (top, bottom) = (layout[selected].top(), layout[selected].bottom());
+            let offset = state.offset();
+            if top < offset.y {
+                state.set_offset(Position::new(offset.x, top));
+            } else if bottom > offset.y + area.height {
+                state.set_offset(Position::new(
+                    offset.x,
+                    bottom.saturating_sub(area.height).min(top),
+                ));
+            }
+        }
 
         for (i, item) in self.content.into_iter().enumerate() {
             scroll_view.render_widget(item, layout[i]);
@@ -87,14 +134,18 @@ impl StatefulWidget for TaskList {
 
 #[cfg(test)]
 mod tests {
-    use crate::core::{
-        task::{DueDate, State, Task},
-        vault_data::VaultData,
-    };
     use chrono::NaiveDate;
     use insta::assert_snapshot;
-    use ratatui::{backend::TestBackend, Terminal};
+    use ratatui::{
+        backend::TestBackend,
+        style::{Style, Stylize},
+        Terminal,
+    };
     use tui_scrollview::ScrollViewState;
+    use vault_tasks_core::{
+        task::{DueDate, State, Task},
+        vault_data::VaultData,
+    };
 
     use crate::{config::Config, widgets::task_list::TaskList};
 
@@ -198,4 +249,72 @@ mod tests {
             .unwrap();
         assert_snapshot!(terminal.backend());
     }
+    #[test]
+    fn test_render_long_task() {
+        let test_vault = VaultData::Task(Task {
+            name: "a task with a name much longer than the list".to_string(),
+            description: Some("a long description that has to be wrapped\nshort".to_string()),
+            ..Default::default()
+        });
+        let mut config = Config::default();
+        config.tasks_config.preview_soft_wrap = true;
+
+        let task_list = TaskList::new(&config, &[test_vault], false);
+        let mut terminal = Terminal::new(TestBackend::new(24, 8)).unwrap();
+        terminal
+            .draw(|frame| {
+                frame.render_stateful_widget(task_list, frame.area(), &mut ScrollViewState::new());
+            })
+            .unwrap();
+        assert_snapshot!(terminal.backend());
+    }
+    #[test]
+    fn test_render_breadcrumbs_and_selection() {
+        let task = |name: &str| {
+            VaultData::Task(Task {
+                name: name.to_string(),
+                ..Default::default()
+            })
+        };
+        let config = Config::default();
+        let task_list = TaskList::new(&config, &[task("tests"), task("unit"), task("docs")], false)
+            .breadcrumbs(&[
+                Some("release".to_string()),
+                Some("release › tests".to_string()),
+            ])
+            .select(Some(2), Style::new().red());
+        let mut terminal = Terminal::new(TestBackend::new(30, 6)).unwrap();
+        let mut state = ScrollViewState::new();
+        terminal
+            .draw(|frame| {
+                frame.render_stateful_widget(task_list, frame.area(), &mut state);
+            })
+            .unwrap();
+        // The selected task is scrolled into view
+        assert_eq!(state.offset().y, 3);
+        assert_snapshot!(terminal.backend());
+    }
+    #[test]
+    fn test_render_subtask_count() {
+        let subtask = |name: &str, state| Task {
+            name: name.to_string(),
+            state,
+            ..Default::default()
+        };
+        let test_vault = VaultData::Task(Task {
+            name: "release".to_string(),
+            subtask_count: Some((1, 2)),
+            subtasks: vec![subtask("tests", State::Done), subtask("docs", State::ToDo)],
+            ..Default::default()
+        });
+        let config = Config::default();
+        let task_list = TaskList::new(&config, &[test_vault], false);
+        let mut terminal = Terminal::new(TestBackend::new(30, 10)).unwrap();
+        terminal
+            .draw(|frame| {
+                frame.render_stateful_widget(task_list, frame.area(), &mut ScrollViewState::new());
+            })
+            .unwrap();
+        assert_snapshot!(terminal.backend());
+    }
 }
@@ -6,21 +6,37 @@ use ratatui::{
 };
 use tracing::error;
 
-use crate::core::{
-    task::{DueDate, Task},
+use crate::config::{task_style, TagColors};
+use vault_tasks_core::{
+    filter::Filter,
+    task::{format_estimate, DateDisplayFormat, DueDate, Task},
     vault_data::VaultData,
     PrettySymbolsConfig,
 };
 
+use super::text_utils::{progress_bar, tag_spans, truncate, wrap, ELLIPSIS};
+
 #[derive(Clone)]
 pub struct TaskListItem {
     item: VaultData,
-    pub height: u16,
     symbols: PrettySymbolsConfig,
-    not_american_format: bool,
+    date_format: DateDisplayFormat,
     show_relative_due_dates: bool,
     display_filename: bool,
     header_style: Style,
+    /// Whether descriptions are wrapped instead of cut
+    wrap: bool,
+    ellipsis: &'static str,
+    ascii_mode: bool,
+    /// Filters and the styles they apply to task titles
+    style_rules: Rc<[(Filter, Style)]>,
+    tag_colors: Rc<TagColors>,
+    /// Whether tags are shown as colored pills
+    tag_pills: bool,
+    /// Names of the parent tasks, shown on the top border of a task
+    breadcrumb: Option<String>,
+    /// Style of the border of a selected task
+    selected_style: Option<Style>,
 }
 
 impl TaskListItem {
@@ -28,29 +44,102 @@ impl TaskListItem {
         self.header_style = style;
         self
     }
+    pub const fn wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+    pub const fn ellipsis(mut self, ellipsis: &'static str) -> Self {
+        self.ellipsis = ellipsis;
+        self
+    }
+    pub const fn ascii_mode(mut self, ascii_mode: bool) -> Self {
+        self.ascii_mode = ascii_mode;
+        self
+    }
+    pub fn style_rules(mut self, style_rules: Rc<[(Filter, Style)]>) -> Self {
+        self.style_rules = style_rules;
+        self
+    }
+    pub fn tag_colors(mut self, tag_colors: Rc<TagColors>) -> Self {
+        self.tag_colors = tag_colors;
+        self
+    }
+    pub const fn tag_pills(mut self, tag_pills: bool) -> Self {
+        self.tag_pills = tag_pills;
+        self
+    }
+    pub fn breadcrumb(mut self, breadcrumb: Option<String>) -> Self {
+        self.breadcrumb = breadcrumb;
+        self
+    }
+    pub const fn selected_style(mut self, selected_style: Option<Style>) -> Self {
+        self.selected_style = selected_style;
+        self
+    }
     pub fn new(
         item: VaultData,
-        not_american_format: bool,
+        date_format: DateDisplayFormat,
         symbols: PrettySymbolsConfig,
         display_filename: bool,
         show_relative_due_dates: bool,
     ) -> Self {
-        let height = Self::compute_height(&item);
         Self {
             item,
-            height,
-            not_american_format,
+            date_format,
             display_filename,
             symbols,
             header_style: Style::default(),
             show_relative_due_dates,
+            wrap: false,
+            ellipsis: ELLIPSIS,
+            ascii_mode: false,
+            style_rules: Rc::new([]),
+            tag_colors: Rc::default(),
+            tag_pills: false,
+            breadcrumb: None,
+            selected_style: None,
+        }
+    }
+    /// Height of the item when rendered with the given width.
+    pub fn height(&self, width: u16) -> u16 {
+        Self::compute_height(&self.item, width, self.wrap)
+    }
+    /// Width available to the children of a header.
+    fn header_content_width(width: u16) -> u16 {
+        Self::header_layout(Rect::new(0, 0, width, 1))[1].width
+    }
+    fn header_layout(area: Rect) -> Rc<[Rect]> {
+        Layout::new(
+            Direction::Horizontal,
+            vec![Constraint::Percentage(3), Constraint::Percentage(97)],
+        )
+        .split(area)
+    }
+    /// Wraps a line of description to `width` if needed.
+    fn wrap_line(line: &str, width: u16, wrap_lines: bool) -> Vec<String> {
+        if wrap_lines {
+            wrap(line, width as usize)
+        } else {
+            vec![line.to_owned()]
         }
     }
     fn task_to_paragraph(&self, area: Rect, task: &Task) -> (Rc<[Rect]>, Paragraph<'_>) {
         let mut lines = vec![];
+        let inner_width = area.width.saturating_sub(2);
         let state = task.state.display(self.symbols.clone());
-        let title = Span::styled(format!("{state} {}", task.name), Style::default());
-        let surrounding_block =
+        let title = Span::styled(
+            truncate(
+                &format!("{state} {}", task.name),
+                inner_width as usize,
+                self.ellipsis,
+            ),
+            if task.projected {
+                task_style(&self.style_rules, task).italic().dim()
+            } else {
+                task_style(&self.style_rules, task)
+            },
+        );
+        let mut surrounding_block =
             Block::default()
                 .borders(Borders::ALL)
                 .title_bottom(if self.display_filename {
@@ -58,16 +147,33 @@ impl TaskListItem {
                 } else {
                     Line::from("")
                 });
+        if let Some(breadcrumb) = &self.breadcrumb {
+            surrounding_block = surrounding_block.title_top(
+                Line::from(truncate(
+                    breadcrumb,
+                    (inner_width / 2) as usize,
+                    self.ellipsis,
+                ))
+                .right_aligned()
+                .dim(),
+            );
+        }
+        if let Some(style) = self.selected_style {
+            surrounding_block = surrounding_block.border_style(style);
+        }
 
         let mut data_line = vec![];
 
+        if task.projected {
+            data_line.push(Span::styled("(projected) ", Style::new().italic().dim()));
+        }
         if task.is_today {
             data_line.push(Span::raw(format!("{} ", self.symbols.today_tag)));
         }
 
         let due_date_str = task
             .due_date
-            .to_display_format(self.symbols.due_date.clone(), self.not_american_format);
+            .to_display_format(&self.symbols.due_date, &self.date_format);
 
         if !due_date_str.is_empty() {
             data_line.push(Span::from(format!("{due_date_str} ")));
@@ -86,36 +192,63 @@ impl TaskListItem {
                 self.symbols.priority, task.priority
             )));
         }
+        let reminder_str = task
+            .reminder
+            .to_display_format(&self.symbols.reminder, &self.date_format);
+        if !reminder_str.is_empty() {
+            data_line.push(Span::from(format!("{reminder_str} ")));
+        }
+        if let Some(estimate) = task.estimate {
+            data_line.push(Span::raw(format!("~{} ", format_estimate(estimate))));
+        }
+        if let Some((done, total)) = task.subtask_count {
+            data_line.push(Span::raw(format!("[{done}/{total}] ")));
+        }
+        if let Some(completion) = task.completion {
+            data_line.push(Span::raw(format!(
+                "{} ",
+                progress_bar(completion, self.ascii_mode)
+            )));
+        }
+        if let Some(recurrence) = task.recurrence {
+            data_line.push(Span::raw(format!(
+                "{}{recurrence} ",
+                self.symbols.recurrence
+            )));
+        }
+        let attachment_count = task.attachments().len();
+        if attachment_count > 0 {
+            data_line.push(Span::raw(format!(
+                "{}{attachment_count} ",
+                self.symbols.attachment
+            )));
+        }
         if !data_line.is_empty() {
             lines.push(Line::from(data_line));
         }
-        let mut tag_line = String::new();
-        if task.tags.is_some() {
-            tag_line.push_str(
-                &task
-                    .tags
-                    .clone()
-                    .unwrap()
-                    .iter()
-                    .map(|t| format!("#{t}"))
-                    .collect::<Vec<String>>()
-                    .join(" "),
-            );
-        }
-        if !tag_line.is_empty() {
-            lines.push(Line::from(Span::styled(tag_line, Color::DarkGray)));
+        if let Some(tags) = &task.tags {
+            lines.push(Line::from(tag_spans(
+                tags,
+                &self.tag_colors,
+                self.tag_pills,
+            )));
         }
         if let Some(description) = task.description.clone() {
-            for l in description.lines() {
-                lines.push(Line::from(Span::styled(l.to_string(), Color::Gray)));
+            for l in description
+                .lines()
+                .flat_map(|l| Self::wrap_line(l, inner_width, self.wrap))
+            {
+                lines.push(Line::from(Span::styled(l, Color::Gray)));
             }
         }
         let mut constraints = vec![Constraint::Length((lines.len()).try_into().unwrap())];
 
         for st in &task.subtasks {
-            constraints.push(Constraint::Length(Self::compute_height(&VaultData::Task(
-                st.clone(),
-            ))));
+            constraints.push(Constraint::Length(Self::compute_height(
+                &VaultData::Task(st.clone()),
+                inner_width,
+                self.wrap,
+            )));
         }
 
         let layout = Layout::default()
@@ -132,28 +265,48 @@ impl TaskListItem {
             },
         )
     }
-    fn compute_height(item: &VaultData) -> u16 {
+    fn compute_height(item: &VaultData, width: u16, wrap_lines: bool) -> u16 {
         match &item {
             VaultData::Directory(_, _) => 1,
             VaultData::Header(_, _, children) => {
-                children.iter().map(Self::compute_height).sum::<u16>() + 1 // name in block (border only on top)
+                let content_width = Self::header_content_width(width);
+                children
+                    .iter()
+                    .map(|c| Self::compute_height(c, content_width, wrap_lines))
+                    .sum::<u16>()
+                    + 1 // name in block (border only on top)
             }
             VaultData::Task(task) => {
+                let inner_width = width.saturating_sub(2);
                 let mut count: u16 = 2; // block
                 if let Some(d) = &task.description {
-                    count += u16::try_from(d.split('\n').count()).unwrap_or_else(|e| {
+                    let description_lines = d
+                        .split('\n')
+                        .map(|l| Self::wrap_line(l, inner_width, wrap_lines).len())
+                        .sum::<usize>();
+                    count += u16::try_from(description_lines).unwrap_or_else(|e| {
                         error!("Could not convert description length to u16 :{e}");
                         0
                     });
                 }
-                if task.due_date != DueDate::NoDate || task.priority > 0 || task.is_today {
+                if task.due_date != DueDate::NoDate
+                    || task.reminder != DueDate::NoDate
+                    || task.priority > 0
+                    || task.is_today
+                    || task.estimate.is_some()
+                    || task.completion.is_some()
+                    || task.subtask_count.is_some()
+                    || task.recurrence.is_some()
+                    || !task.attachments().is_empty()
+                {
                     count += 1;
                 }
                 if task.tags.is_some() {
                     count += 1;
                 }
                 for sb in &task.subtasks {
-                    count += Self::compute_height(&VaultData::Task(sb.clone()));
+                    count +=
+                        Self::compute_height(&VaultData::Task(sb.clone()), inner_width, wrap_lines);
                 }
                 count.max(3) // If count == 2 then we add task name will be in the block
                              // Else name goes in block title
@@ -169,19 +322,23 @@ impl Widget for TaskListItem {
         match &self.item {
             VaultData::Directory(name, _) => error!("TaskList widget received a directory: {name}"),
             VaultData::Header(_level, name, children) => {
-                let surrounding_block = Block::default()
-                    .borders(Borders::TOP)
-                    .title(Span::styled(name.to_string(), self.header_style));
+                let surrounding_block = Block::default().borders(Borders::TOP).title(Span::styled(
+                    self.item
+                        .estimate_summary()
+                        .describe(name)
+                        .unwrap_or_else(|| name.to_string()),
+                    self.header_style,
+                ));
 
-                let indent = Layout::new(
-                    Direction::Horizontal,
-                    vec![Constraint::Percentage(3), Constraint::Percentage(97)],
-                )
-                .split(area);
+                let indent = Self::header_layout(area);
 
                 let mut constraints = vec![];
                 for child in children {
-                    constraints.push(Constraint::Length(Self::compute_height(child)));
+                    constraints.push(Constraint::Length(Self::compute_height(
+                        child,
+                        indent[1].width,
+                        self.wrap,
+                    )));
                 }
                 let layout = Layout::default()
                     .direction(Direction::Vertical)
@@ -192,12 +349,17 @@ impl Widget for TaskListItem {
                 for (i, child) in children.iter().enumerate() {
                     let sb_widget = Self::new(
                         child.clone(),
-                        self.not_american_format,
+                        self.date_format.clone(),
                         self.symbols.clone(),
                         self.display_filename,
                         self.show_relative_due_dates,
                     )
-                    .header_style(self.header_style);
+                    .header_style(self.header_style)
+                    .wrap(self.wrap)
+                    .ellipsis(self.ellipsis)
+                    .style_rules(self.style_rules.clone())
+                    .tag_colors(self.tag_colors.clone())
+                    .tag_pills(self.tag_pills);
                     sb_widget.render(layout[i], buf);
                 }
             }
@@ -208,12 +370,17 @@ impl Widget for TaskListItem {
                 for (i, sb) in task.subtasks.iter().enumerate() {
                     let sb_widget = Self::new(
                         VaultData::Task(sb.clone()),
-                        self.not_american_format,
+                        self.date_format.clone(),
                         self.symbols.clone(),
                         false,
                         self.show_relative_due_dates,
                     )
-                    .header_style(self.header_style);
+                    .header_style(self.header_style)
+                    .wrap(self.wrap)
+                    .ellipsis(self.ellipsis)
+                    .style_rules(self.style_rules.clone())
+                    .tag_colors(self.tag_colors.clone())
+                    .tag_pills(self.tag_pills);
 
                     sb_widget.render(layout[i + 1], buf);
                 }
@@ -0,0 +1,173 @@
+use ratatui::{
+    style::{Color, Style},
+    text::Span,
+};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::config::TagColors;
+
+pub const ELLIPSIS: &str = "…";
+pub const ASCII_ELLIPSIS: &str = "...";
+
+/// Returns the ellipsis used to mark truncated text.
+pub const fn ellipsis(ascii_mode: bool) -> &'static str {
+    if ascii_mode {
+        ASCII_ELLIPSIS
+    } else {
+        ELLIPSIS
+    }
+}
+
+/// Returns a 10 cells wide bar filled up to `percent`, e.g. `███░░░░░░░ 30%`.
+pub fn progress_bar(percent: u8, ascii_mode: bool) -> String {
+    let (filled, empty) = if ascii_mode {
+        ('#', '-')
+    } else {
+        ('█', '░')
+    };
+    let cells = usize::from(percent.min(100)) / 10;
+    format!(
+        "{}{} {percent}%",
+        filled.to_string().repeat(cells),
+        empty.to_string().repeat(10 - cells)
+    )
+}
+
+/// Returns `tags` as colored pills (` work `), or as `#work` written in their color.
+pub fn tag_spans(tags: &[String], colors: &TagColors, pills: bool) -> Vec<Span<'static>> {
+    let mut res = vec![];
+    for tag in tags {
+        if !res.is_empty() {
+            res.push(Span::raw(" "));
+        }
+        let color = colors.color(tag);
+        res.push(if pills {
+            Span::styled(format!(" {tag} "), Style::new().fg(Color::Black).bg(color))
+        } else {
+            Span::styled(format!("#{tag}"), color)
+        });
+    }
+    res
+}
+
+/// Truncates `text` to fit in `max_width` columns, ending it with `ellipsis` if it was cut.
+///
+/// Text is cut between graphemes so wide characters and emojis are never split.
+pub fn truncate(text: &str, max_width: usize, ellipsis: &str) -> String {
+    if text.width() <= max_width {
+        return text.to_owned();
+    }
+    // Not even enough room for the ellipsis
+    let ellipsis = if ellipsis.width() > max_width {
+        ""
+    } else {
+        ellipsis
+    };
+    let mut res = String::new();
+    let mut width = 0;
+    for grapheme in text.graphemes(true) {
+        let grapheme_width = grapheme.width();
+        if width + grapheme_width + ellipsis.width() > max_width {
+            break;
+        }
+        res.push_str(grapheme);
+        width += grapheme_width;
+    }
+    res.push_str(ellipsis);
+    res
+}
+
+/// Splits `text` in lines of at most `max_width` columns.
+///
+/// Lines are broken at spaces when possible, words longer than a line are broken between graphemes.
+pub fn wrap(text: &str, max_width: usize) -> Vec<String> {
+    if max_width == 0 || text.width() <= max_width {
+        return vec![text.to_owned()];
+    }
+    let mut lines = vec![];
+    let mut line = String::new();
+    for word in text.split(' ') {
+        let separator = usize::from(!line.is_empty());
+        if line.width() + separator + word.width() <= max_width {
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+            continue;
+        }
+        if !line.is_empty() {
+            lines.push(line);
+            line = String::new();
+        }
+        // Break words that can't fit on a line
+        for grapheme in word.graphemes(true) {
+            if !line.is_empty() && line.width() + grapheme.width() > max_width {
+                lines.push(line);
+                line = String::new();
+            }
+            line.push_str(grapheme);
+        }
+    }
+    lines.push(line);
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use ratatui::{style::Color, text::Line};
+
+    use super::{progress_bar, tag_spans, truncate, wrap, ASCII_ELLIPSIS, ELLIPSIS};
+    use crate::config::TagColors;
+
+    #[test]
+    fn test_progress_bar() {
+        assert_eq!(progress_bar(0, false), "░░░░░░░░░░ 0%");
+        assert_eq!(progress_bar(35, false), "███░░░░░░░ 35%");
+        assert_eq!(progress_bar(100, true), "########## 100%");
+    }
+
+    #[test]
+    fn test_tag_spans() {
+        let mut colors = TagColors::default();
+        colors.insert("work".to_owned(), Color::Blue);
+        let tags = ["work".to_owned(), "home".to_owned()];
+        let pills = tag_spans(&tags, &colors, true);
+        assert_eq!(Line::from(pills.clone()).to_string(), " work   home ");
+        assert_eq!(pills[0].style.bg, Some(Color::Blue));
+        assert_eq!(pills[2].style.bg, Some(colors.color("home")));
+        let text = tag_spans(&tags, &colors, false);
+        assert_eq!(Line::from(text.clone()).to_string(), "#work #home");
+        assert_eq!(text[0].style.fg, Some(Color::Blue));
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("short", 10, ELLIPSIS), "short");
+        assert_eq!(truncate("a long task name", 10, ELLIPSIS), "a long ta…");
+        assert_eq!(
+            truncate("a long task name", 10, ASCII_ELLIPSIS),
+            "a long ..."
+        );
+        assert_eq!(truncate("abc", 2, ASCII_ELLIPSIS), "ab");
+    }
+
+    #[test]
+    fn test_truncate_wide_graphemes() {
+        // Emojis are two columns wide and must not be split
+        assert_eq!(truncate("✅✅✅✅", 6, ELLIPSIS), "✅✅…");
+        assert_eq!(truncate("e\u{301}te\u{301}", 2, ELLIPSIS), "e\u{301}…");
+    }
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(wrap("short", 10), vec!["short"]);
+        assert_eq!(
+            wrap("a long description to wrap", 10),
+            vec!["a long", "descriptio", "n to wrap"]
+        );
+        assert_eq!(wrap("one two three", 7), vec!["one two", "three"]);
+    }
+}
@@ -1,8 +1,13 @@
 use std::time::Duration;
 
 use chrono::{NaiveTime, TimeDelta};
+use ratatui::text::Line;
 use ratatui::widgets::{Block, Gauge, StatefulWidget, Widget};
-pub struct TimerWidget;
+#[derive(Default)]
+pub struct TimerWidget {
+    /// Shown under the gauge, e.g. a break suggestion
+    pub title: Option<String>,
+}
 
 #[derive(Default, Clone)]
 pub enum TimerState {
@@ -188,8 +193,12 @@ impl StatefulWidget for TimerWidget {
             }
             | TimerState::NotInitialized => 1.0,
         };
+        let mut block = Block::bordered();
+        if let Some(title) = self.title {
+            block = block.title_bottom(Line::from(title).centered());
+        }
         Gauge::default()
-            .block(Block::bordered())
+            .block(block)
             .ratio(ratio)
             .label(text)
             .render(area, buf);
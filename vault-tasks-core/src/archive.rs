@@ -0,0 +1,452 @@
+use std::{
+    fs,
+    ops::Range,
+    path::{Component, Path, PathBuf},
+};
+
+use color_eyre::{eyre::bail, Result};
+
+use super::{
+    changeset::Changeset,
+    daily_notes::task_block,
+    details::{filter_to_details, TaskDetails},
+    filter::Filter,
+    is_canvas_file, is_markdown_file,
+    vault_data::VaultData,
+    TasksConfig,
+};
+
+/// A task removed from its file, with its subtasks and description.
+struct RemovedTask {
+    details: TaskDetails,
+    /// Lines of the task, unindented
+    lines: Vec<String>,
+}
+
+/// Adds the changes removing `tasks` from their files, with their subtasks and descriptions, to `changes`.
+///
+/// Tasks whose parent is removed too are part of its lines. Returns the removed tasks, sorted by file and line.
+fn remove_tasks(tasks: &[TaskDetails], changes: &mut Changeset) -> Result<Vec<RemovedTask>> {
+    let mut tasks = tasks
+        .iter()
+        .filter(|details| !details.task.projected)
+        .collect::<Vec<&TaskDetails>>();
+    tasks.sort_by(|a, b| {
+        a.file
+            .cmp(&b.file)
+            .then(a.task.line_number.cmp(&b.task.line_number))
+    });
+
+    let mut res = vec![];
+    for file_tasks in tasks.chunk_by(|a, b| a.file == b.file) {
+        let path = &file_tasks[0].file;
+        if is_canvas_file(path) {
+            bail!("Canvas files are read-only: {path:?}");
+        }
+        let content = fs::read_to_string(path)?;
+        let lines = content.split('\n').collect::<Vec<&str>>();
+        let mut blocks: Vec<Range<usize>> = vec![];
+        for details in file_tasks {
+            let line_number = details.task.line_number;
+            if line_number == 0 || line_number > lines.len() {
+                bail!(
+                    "Line {line_number} of task {:?} not found in {path:?}",
+                    details.task.name
+                );
+            }
+            // Blocks are sorted, only the last one can hold the task
+            if blocks
+                .last()
+                .is_some_and(|b| b.contains(&(line_number - 1)))
+            {
+                continue;
+            }
+            let block = task_block(&lines, line_number);
+            let indent = lines[block.start]
+                .chars()
+                .take_while(|c| c.is_whitespace())
+                .count();
+            res.push(RemovedTask {
+                details: (*details).clone(),
+                lines: lines[block.clone()]
+                    .iter()
+                    .map(|l| l.chars().skip(indent).collect())
+                    .collect(),
+            });
+            blocks.push(block);
+        }
+        let new = lines
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !blocks.iter().any(|b| b.contains(i)))
+            .map(|(_, l)| *l)
+            .collect::<Vec<&str>>()
+            .join("\n");
+        changes.add(path, content.clone(), new);
+    }
+    Ok(res)
+}
+
+/// Returns the changes deleting `tasks` from their files, with their subtasks and descriptions, without writing them.
+///
+/// Returns the number of tasks deleted, subtasks of deleted tasks excluded.
+///
+/// # Errors
+///
+/// Will return an error if a file can't be read or if a task is not found in its file.
+pub fn plan_delete(tasks: &[TaskDetails]) -> Result<(usize, Changeset)> {
+    let mut changes = Changeset::default();
+    let removed = remove_tasks(tasks, &mut changes)?;
+    Ok((removed.len(), changes))
+}
+
+/// Returns the changes moving the done and canceled tasks among `tasks` to `archive_file`, without writing them.
+///
+/// Tasks are appended under a header linking to the note and headers they come from.
+/// Returns the number of tasks archived, subtasks of archived tasks excluded.
+///
+/// # Errors
+///
+/// Will return an error if `archive_file` is not set, if a file can't be read or if a task is not found in its file.
+pub fn plan_archive(config: &TasksConfig, tasks: &[TaskDetails]) -> Result<(usize, Changeset)> {
+    if config.archive_file.as_os_str().is_empty() {
+        bail!("`archive_file` is not set in the configuration");
+    }
+    if !config.vault_path.is_dir() {
+        bail!("Archiving requires the vault to be a directory");
+    }
+    let archive_path = config.vault_path.join(&config.archive_file);
+    let tasks = tasks
+        .iter()
+        .filter(|details| details.task.state.is_finished())
+        .filter(|details| details.file != archive_path)
+        .cloned()
+        .collect::<Vec<TaskDetails>>();
+
+    let mut changes = Changeset::default();
+    let removed = remove_tasks(&tasks, &mut changes)?;
+    if removed.is_empty() {
+        return Ok((0, changes));
+    }
+
+    let old = if archive_path.exists() {
+        fs::read_to_string(&archive_path)?
+    } else {
+        String::new()
+    };
+    let mut new = old.clone();
+    for group in removed.chunk_by(|a, b| a.details.breadcrumb == b.details.breadcrumb) {
+        if !new.is_empty() && !new.ends_with('\n') {
+            new.push('\n');
+        }
+        if !new.is_empty() {
+            new.push('\n');
+        }
+        let details = &group[0].details;
+        let note = details
+            .file
+            .strip_prefix(&config.vault_path)
+            .unwrap_or(&details.file)
+            .with_extension("");
+        let headers = details
+            .breadcrumb
+            .iter()
+            .skip(note.components().count())
+            .map(|h| format!(" > {h}"))
+            .collect::<String>();
+        new.push_str(&format!("## [[{}]]{headers}\n", note.display()));
+        for task in group {
+            for line in &task.lines {
+                new.push_str(line);
+                new.push('\n');
+            }
+        }
+    }
+    changes.add(&archive_path, old, new);
+    Ok((removed.len(), changes))
+}
+
+/// Returns the path of `target`, a note relative to the vault, `.md` being added if it has no extension.
+///
+/// # Errors
+///
+/// Will return an error if the vault is not a directory or if `target` is not a note of the vault.
+pub fn note_path(config: &TasksConfig, target: &Path) -> Result<PathBuf> {
+    if !config.vault_path.is_dir() {
+        bail!("Writing to {target:?} requires the vault to be a directory");
+    }
+    if target.is_absolute() || target.components().any(|c| c == Component::ParentDir) {
+        bail!("{target:?} is not relative to the vault");
+    }
+    let mut path = config.vault_path.join(target);
+    if path.extension().is_none() {
+        path.set_extension("md");
+    }
+    if !is_markdown_file(&path) {
+        bail!("{target:?} is not a note");
+    }
+    Ok(path)
+}
+
+/// Returns the changes moving `tasks` to the end of `target`, a note relative to the vault, without writing them.
+///
+/// Returns the number of tasks moved, subtasks of moved tasks excluded.
+///
+/// # Errors
+///
+/// Will return an error if `target` is not a note of the vault, if it holds one of the tasks, if a file can't be read or if a task is not found in its file.
+pub fn plan_move(
+    config: &TasksConfig,
+    tasks: &[TaskDetails],
+    target: &Path,
+) -> Result<(usize, Changeset)> {
+    let target_path = note_path(config, target)?;
+    if tasks.iter().any(|details| details.file == target_path) {
+        bail!("Tasks are already in {target:?}");
+    }
+
+    let mut changes = Changeset::default();
+    let removed = remove_tasks(tasks, &mut changes)?;
+    if removed.is_empty() {
+        return Ok((0, changes));
+    }
+    let old = if target_path.exists() {
+        fs::read_to_string(&target_path)?
+    } else {
+        String::new()
+    };
+    let mut new = old.clone();
+    if !new.is_empty() && !new.ends_with('\n') {
+        new.push('\n');
+    }
+    for task in &removed {
+        for line in &task.lines {
+            new.push_str(line);
+            new.push('\n');
+        }
+    }
+    changes.add(&target_path, old, new);
+    Ok((removed.len(), changes))
+}
+
+/// Returns the changes turning `tag` into a project, without writing them: the tasks carrying it are moved to
+/// `projects/<tag>.md`, under sub-headers following the notes and headers they come from, and the tag is removed from them.
+///
+/// Returns the number of tasks moved, subtasks of moved tasks excluded.
+///
+/// # Errors
+///
+/// Will return an error if the vault is not a directory, if the tag is invalid, if a file can't be read or if a task is not found in its file.
+pub fn plan_tag_to_project(
+    config: &TasksConfig,
+    vault_data: &VaultData,
+    tag: &str,
+) -> Result<(usize, Changeset)> {
+    let tag = tag.trim().trim_start_matches('#');
+    if tag.is_empty() || !tag.chars().all(|c| c == '_' || c.is_ascii_alphanumeric()) {
+        bail!("Invalid tag: {tag:?}");
+    }
+    let target_path = note_path(config, &Path::new("projects").join(tag))?;
+    let tasks = filter_to_details(vault_data, &Filter::default())
+        .into_iter()
+        .filter(|details| details.file != target_path)
+        .filter(|details| {
+            details
+                .task
+                .tags
+                .as_ref()
+                .is_some_and(|tags| tags.iter().any(|t| t == tag))
+        })
+        .collect::<Vec<TaskDetails>>();
+
+    let mut changes = Changeset::default();
+    let removed = remove_tasks(&tasks, &mut changes)?;
+    if removed.is_empty() {
+        return Ok((0, changes));
+    }
+    let old = if target_path.exists() {
+        fs::read_to_string(&target_path)?
+    } else {
+        String::new()
+    };
+    let mut new = if old.is_empty() {
+        format!("# {tag}\n")
+    } else {
+        old.clone()
+    };
+    let hashtag = format!("#{tag}");
+    // File and headers of the previous task, to only write the sub-headers that change
+    let mut previous: Option<(&PathBuf, &[String])> = None;
+    for task in &removed {
+        let details = &task.details;
+        let note = details
+            .file
+            .strip_prefix(&config.vault_path)
+            .unwrap_or(&details.file)
+            .with_extension("");
+        let headers = details
+            .breadcrumb
+            .get(note.components().count()..)
+            .unwrap_or_default();
+        if !new.ends_with('\n') {
+            new.push('\n');
+        }
+        let common = match previous {
+            Some((file, previous_headers)) if *file == details.file => headers
+                .iter()
+                .zip(previous_headers)
+                .take_while(|(a, b)| a == b)
+                .count(),
+            _ => {
+                new.push_str(&format!("\n## [[{}]]\n", note.display()));
+                0
+            }
+        };
+        for (level, header) in headers.iter().enumerate().skip(common) {
+            new.push_str(&format!("{} {header}\n", "#".repeat(level + 3)));
+        }
+        for line in &task.lines {
+            let line = line
+                .split(' ')
+                .filter(|word| *word != hashtag)
+                .collect::<Vec<&str>>()
+                .join(" ");
+            new.push_str(&line);
+            new.push('\n');
+        }
+        previous = Some((&details.file, headers));
+    }
+    changes.add(&target_path, old, new);
+    Ok((removed.len(), changes))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use pretty_assertions::assert_eq;
+
+    use super::{plan_archive, plan_delete, plan_move, plan_tag_to_project};
+    use crate::{details::find_tasks, TaskManager, TasksConfig};
+
+    #[test]
+    fn test_delete_and_archive() {
+        let vault_path = std::env::temp_dir().join("vault-tasks-archive");
+        let _ = fs::remove_dir_all(&vault_path);
+        fs::create_dir_all(vault_path.join("Work")).unwrap();
+        let note = vault_path.join("Work/project.md");
+        fs::write(
+            &note,
+            "# Backlog\n- [x] ship v1 #release\n  notes\n  - [x] tag #release\n- [ ] ship v2 #release\n  - [-] drop IE #release\n",
+        )
+        .unwrap();
+        let mut config = TasksConfig {
+            vault_path: vault_path.clone(),
+            indent_length: 2,
+            ..Default::default()
+        };
+        let task_mgr = TaskManager::load_read_only(&config).unwrap();
+        let tasks = find_tasks(&task_mgr.tasks, "#release", &config);
+        assert_eq!(tasks.len(), 4);
+
+        let (count, changes) = plan_delete(&tasks).unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(changes.changes[0].new, "# Backlog\n");
+
+        assert!(plan_archive(&config, &tasks).is_err());
+        config.archive_file = "Archive.md".into();
+        fs::write(vault_path.join("Archive.md"), "# Archive").unwrap();
+        let (count, changes) = plan_archive(&config, &tasks).unwrap();
+        assert_eq!(count, 2);
+        changes.apply().unwrap();
+        assert_eq!(
+            fs::read_to_string(&note).unwrap(),
+            "# Backlog\n- [ ] ship v2 #release\n"
+        );
+        assert_eq!(
+            fs::read_to_string(vault_path.join("Archive.md")).unwrap(),
+            "# Archive\n\n## [[Work/project]] > Backlog\n- [x] ship v1 #release\n  notes\n  - [x] tag #release\n- [-] drop IE #release\n"
+        );
+    }
+
+    #[test]
+    fn test_move() {
+        let vault_path = std::env::temp_dir().join("vault-tasks-move");
+        let _ = fs::remove_dir_all(&vault_path);
+        fs::create_dir_all(&vault_path).unwrap();
+        let inbox = vault_path.join("Inbox.md");
+        fs::write(
+            &inbox,
+            "- [ ] buy seeds #garden\n  - [ ] tomatoes\n- [ ] call bank\n",
+        )
+        .unwrap();
+        fs::write(vault_path.join("Garden.md"), "# Garden").unwrap();
+        let config = TasksConfig {
+            vault_path: vault_path.clone(),
+            indent_length: 2,
+            ..Default::default()
+        };
+        let task_mgr = TaskManager::load_read_only(&config).unwrap();
+        let tasks = find_tasks(&task_mgr.tasks, "#garden", &config);
+
+        assert!(plan_move(&config, &tasks, Path::new("../Garden.md")).is_err());
+        assert!(plan_move(&config, &tasks, Path::new("Inbox.md")).is_err());
+        let (count, changes) = plan_move(&config, &tasks, Path::new("Garden")).unwrap();
+        assert_eq!(count, 1);
+        changes.apply().unwrap();
+        assert_eq!(fs::read_to_string(&inbox).unwrap(), "- [ ] call bank\n");
+        assert_eq!(
+            fs::read_to_string(vault_path.join("Garden.md")).unwrap(),
+            "# Garden\n- [ ] buy seeds #garden\n  - [ ] tomatoes\n"
+        );
+    }
+
+    #[test]
+    fn test_tag_to_project() {
+        let vault_path = std::env::temp_dir().join("vault-tasks-tag-to-project");
+        let _ = fs::remove_dir_all(&vault_path);
+        fs::create_dir_all(vault_path.join("Work")).unwrap();
+        let note = vault_path.join("Work/notes.md");
+        fs::write(
+            &note,
+            "# Garden
+## Spring
+- [ ] buy seeds #garden
+  - [ ] tomatoes #garden
+- [ ] sow #garden #outdoor
+## Summer
+- [ ] water #garden
+# Other
+- [ ] call bank
+",
+        )
+        .unwrap();
+        let inbox = vault_path.join("Inbox.md");
+        fs::write(
+            &inbox,
+            "- [ ] fix fence #garden
+",
+        )
+        .unwrap();
+        let config = TasksConfig {
+            vault_path: vault_path.clone(),
+            indent_length: 2,
+            ..Default::default()
+        };
+        let task_mgr = TaskManager::load_read_only(&config).unwrap();
+
+        assert!(plan_tag_to_project(&config, &task_mgr.tasks, "#garden/../x").is_err());
+        let (count, changes) = plan_tag_to_project(&config, &task_mgr.tasks, "#garden").unwrap();
+        assert_eq!(count, 4);
+        changes.apply().unwrap();
+        assert_eq!(fs::read_to_string(&inbox).unwrap(), "");
+        assert_eq!(
+            fs::read_to_string(&note).unwrap(),
+            "# Garden\n## Spring\n## Summer\n# Other\n- [ ] call bank\n"
+        );
+        assert_eq!(
+            fs::read_to_string(vault_path.join("projects/garden.md")).unwrap(),
+            "# garden\n\n## [[Inbox]]\n- [ ] fix fence\n\n## [[Work/notes]]\n### Garden\n#### Spring\n- [ ] buy seeds\n  - [ ] tomatoes\n- [ ] sow #outdoor\n#### Summer\n- [ ] water\n"
+        );
+    }
+}
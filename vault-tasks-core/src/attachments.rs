@@ -0,0 +1,172 @@
+use std::path::{Path, PathBuf};
+
+use super::{task::Task, TasksConfig};
+
+/// Whether a link target points to a file that is not a note.
+pub fn is_attachment(target: &str) -> bool {
+    Path::new(target)
+        .extension()
+        .is_some_and(|ext| !ext.eq_ignore_ascii_case("md"))
+}
+
+/// Removes aliases (`[[file|alias]]`) and sections (`[[file#section]]`) from a wiki link target.
+pub fn clean_wiki_target(target: &str) -> &str {
+    target.split(['|', '#']).next().unwrap_or_default().trim()
+}
+
+/// Finds the attachments referenced in `text`.
+///
+/// Attachments are embeds (`![[image.png]]`) and links to local files that are not notes (`[[spec.pdf]]`, `[spec](docs/spec.pdf)`).
+/// They are returned in the order they appear.
+#[must_use]
+pub fn find_attachments(text: &str) -> Vec<String> {
+    let mut res = vec![];
+
+    // Wiki links
+    let mut offset = 0;
+    while let Some(start) = text[offset..].find("[[").map(|i| i + offset) {
+        let Some(end) = text[start + 2..].find("]]").map(|i| i + start + 2) else {
+            break;
+        };
+        let embedded = text[..start].ends_with('!');
+        let target = clean_wiki_target(&text[start + 2..end]);
+        if !target.is_empty() && (embedded || is_attachment(target)) {
+            res.push((start, target.to_owned()));
+        }
+        offset = end + 2;
+    }
+
+    // Markdown links
+    let mut offset = 0;
+    while let Some(start) = text[offset..].find("](").map(|i| i + offset) {
+        let Some(end) = text[start + 2..].find(')').map(|i| i + start + 2) else {
+            break;
+        };
+        let target = text[start + 2..end].trim();
+        if !target.contains("://") && !target.starts_with("mailto:") && is_attachment(target) {
+            res.push((start, target.replace("%20", " ")));
+        }
+        offset = end + 1;
+    }
+
+    res.sort_by_key(|(position, _)| *position);
+    res.into_iter().map(|(_, target)| target).collect()
+}
+
+impl Task {
+    /// Returns the attachments referenced in the name and description of the task.
+    #[must_use]
+    pub fn attachments(&self) -> Vec<String> {
+        let mut res = find_attachments(&self.name);
+        if let Some(description) = &self.description {
+            res.append(&mut find_attachments(description));
+        }
+        res
+    }
+}
+
+/// Finds the file an attachment target refers to.
+///
+/// Targets are looked up relative to `note_dir` (the directory of the note containing the link), then to the vault root and finally by file name anywhere in the vault, like Obsidian does.
+#[must_use]
+pub fn resolve_attachment(config: &TasksConfig, note_dir: &Path, target: &str) -> Option<PathBuf> {
+    fn find_by_name(config: &TasksConfig, dir: &Path, name: &str) -> Option<PathBuf> {
+        let mut sub_dirs = vec![];
+        for entry in dir.read_dir().ok()?.flatten() {
+            let path = entry.path();
+            if config.ignored.contains(&path) {
+                continue;
+            }
+            if path.is_dir() {
+                sub_dirs.push(path);
+            } else if path.file_name().is_some_and(|n| n == name) {
+                return Some(path);
+            }
+        }
+        sub_dirs
+            .iter()
+            .find_map(|sub_dir| find_by_name(config, sub_dir, name))
+    }
+
+    [note_dir.join(target), config.vault_path.join(target)]
+        .into_iter()
+        .find(|p| p.is_file())
+        .or_else(|| {
+            let name = Path::new(target).file_name()?.to_str()?;
+            if config.vault_path.is_dir() {
+                find_by_name(config, &config.vault_path, name)
+            } else {
+                None
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{find_attachments, resolve_attachment};
+    use crate::{task::Task, TasksConfig};
+
+    #[test]
+    fn test_find_attachments() {
+        assert_eq!(
+            find_attachments(
+                "see ![[diagram.png]] and [[spec.pdf|the spec]], not [[Other note]] nor [[note.md]]"
+            ),
+            vec!["diagram.png", "spec.pdf"]
+        );
+        assert_eq!(
+            find_attachments(
+                "[report](files/annual%20report.xlsx) ![](img.jpg) [site](https://example.com/a.html) [note](note.md)"
+            ),
+            vec!["files/annual report.xlsx", "img.jpg"]
+        );
+        assert_eq!(
+            find_attachments("![[Embedded note]]"),
+            vec!["Embedded note"]
+        );
+        assert!(find_attachments("[[unclosed.png").is_empty());
+    }
+
+    #[test]
+    fn test_task_attachments() {
+        let task = Task {
+            name: "review [[draft.pdf]]".to_owned(),
+            description: Some("comments\n![[screenshot.png]]".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(task.attachments(), vec!["draft.pdf", "screenshot.png"]);
+    }
+
+    #[test]
+    fn test_resolve_attachment() {
+        let vault_path = std::env::temp_dir().join("vault-tasks-resolve-attachment");
+        let _ = fs::remove_dir_all(&vault_path);
+        fs::create_dir_all(vault_path.join("notes")).unwrap();
+        fs::create_dir_all(vault_path.join("assets/images")).unwrap();
+        fs::write(vault_path.join("notes/local.pdf"), "").unwrap();
+        fs::write(vault_path.join("assets/images/diagram.png"), "").unwrap();
+        let config = TasksConfig {
+            vault_path: vault_path.clone(),
+            ..Default::default()
+        };
+        let note_dir = vault_path.join("notes");
+
+        assert_eq!(
+            resolve_attachment(&config, &note_dir, "local.pdf"),
+            Some(vault_path.join("notes/local.pdf"))
+        );
+        assert_eq!(
+            resolve_attachment(&config, &note_dir, "assets/images/diagram.png"),
+            Some(vault_path.join("assets/images/diagram.png"))
+        );
+        assert_eq!(
+            resolve_attachment(&config, &note_dir, "diagram.png"),
+            Some(vault_path.join("assets/images/diagram.png"))
+        );
+        assert_eq!(resolve_attachment(&config, &note_dir, "missing.png"), None);
+    }
+}
@@ -0,0 +1,477 @@
+use chrono::{Datelike, Months, NaiveDate, TimeDelta, Weekday};
+
+use super::{
+    filter::{filter_to_vec, Filter},
+    recurrence::projected_occurrences,
+    sorter::SortingMode,
+    task::{DueDate, State, Task},
+    vault_data::VaultData,
+    TasksConfig,
+};
+
+/// Width of a day in the plain text grid
+const TEXT_CELL_WIDTH: usize = 6;
+
+/// Returns the tasks shown in the Calendar tab: every task and the next occurrences of recurring tasks, sorted by due date.
+#[must_use]
+pub fn calendar_tasks(vault_data: &VaultData, config: &TasksConfig) -> Vec<Task> {
+    let mut tasks = filter_to_vec(vault_data, &Filter::default());
+    let projected = tasks
+        .iter()
+        .flat_map(|t| projected_occurrences(t, config.recurrence_preview))
+        .collect::<Vec<Task>>();
+    tasks.extend(projected);
+    tasks.sort_by(|t1, t2| SortingMode::cmp_due_date(t1, t2, config.untimed_tasks_last));
+    tasks
+}
+
+/// Parses a month like `2025-03`, returning its first day.
+#[must_use]
+pub fn parse_month(input: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(&format!("{}-01", input.trim()), "%Y-%m-%d").ok()
+}
+
+/// Parses an ISO week like `2025-W11`, returning its Monday.
+#[must_use]
+pub fn parse_week(input: &str) -> Option<NaiveDate> {
+    let (year, week) = input.trim().split_once("-W")?;
+    NaiveDate::from_isoywd_opt(year.parse().ok()?, week.parse().ok()?, Weekday::Mon)
+}
+
+/// Returns the days from `first_day` to `end`, excluded, along with their tasks.
+fn days_between(
+    tasks: &[Task],
+    first_day: NaiveDate,
+    end: NaiveDate,
+) -> Vec<(NaiveDate, Vec<&Task>)> {
+    first_day
+        .iter_days()
+        .take_while(|day| *day < end)
+        .map(|day| {
+            (
+                day,
+                tasks
+                    .iter()
+                    .filter(|t| t.due_date.to_naive_date_time().map(|d| d.date()) == Some(day))
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+/// Returns the days of the month of `first_day` along with their tasks.
+fn month_days(tasks: &[Task], first_day: NaiveDate) -> Vec<(NaiveDate, Vec<&Task>)> {
+    days_between(tasks, first_day, first_day + Months::new(1))
+}
+
+/// Splits the days of a month in weeks starting on Monday, days of other months are `None`.
+fn weeks<T>(days: &[(NaiveDate, T)]) -> Vec<Vec<Option<&(NaiveDate, T)>>> {
+    let Some((first_day, _)) = days.first() else {
+        return vec![];
+    };
+    let mut cells = vec![None; first_day.weekday().num_days_from_monday() as usize];
+    cells.extend(days.iter().map(Some));
+    cells.resize(cells.len().div_ceil(7) * 7, None);
+    cells.chunks(7).map(<[_]>::to_vec).collect()
+}
+
+/// Returns the due time and the name of a task, e.g. `09:30 Standup (projected)`.
+fn task_label(task: &Task) -> String {
+    let time = match task.due_date {
+        DueDate::DayTime(date) => date.format("%H:%M ").to_string(),
+        _ => String::new(),
+    };
+    let projected = if task.projected { " (projected)" } else { "" };
+    format!("{time}{}{projected}", task.name)
+}
+
+/// Writes the month of `first_day` as a Markdown table counting the tasks of every day, followed by the tasks of each day.
+#[must_use]
+pub fn month_to_markdown(tasks: &[Task], first_day: NaiveDate, config: &TasksConfig) -> String {
+    let days = month_days(tasks, first_day);
+    let mut res = format!("# {}\n\n", first_day.format("%B %Y"));
+    res.push_str("| Mon | Tue | Wed | Thu | Fri | Sat | Sun |\n");
+    res.push_str("| --- | --- | --- | --- | --- | --- | --- |\n");
+    for week in weeks(&days) {
+        let cells = week
+            .iter()
+            .map(|cell| match cell {
+                None => String::new(),
+                Some((day, tasks)) if tasks.is_empty() => day.day().to_string(),
+                Some((day, tasks)) => format!("{} ({})", day.day(), tasks.len()),
+            })
+            .collect::<Vec<String>>();
+        res.push_str(&format!("| {} |\n", cells.join(" | ")));
+    }
+    for (day, tasks) in days.iter().filter(|(_, tasks)| !tasks.is_empty()) {
+        res.push_str(&format!("\n## {}\n\n", day.format("%A %d")));
+        for task in tasks {
+            let marker = config.task_state_markers.marker(&task.state);
+            res.push_str(&format!("- [{marker}] {}\n", task_label(task)));
+        }
+    }
+    res
+}
+
+/// Writes the month of `first_day` as a plain text grid, days with tasks show their count, followed by the tasks of each day.
+#[must_use]
+pub fn month_to_text(tasks: &[Task], first_day: NaiveDate, config: &TasksConfig) -> String {
+    let days = month_days(tasks, first_day);
+    let title = first_day.format("%B %Y").to_string();
+    let mut res = format!("{title:^width$}\n", width = 7 * TEXT_CELL_WIDTH)
+        .trim_end()
+        .to_owned();
+    res.push('\n');
+    let header = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"]
+        .iter()
+        .map(|d| format!("{d:<TEXT_CELL_WIDTH$}"))
+        .collect::<String>();
+    res.push_str(header.trim_end());
+    res.push('\n');
+    for week in weeks(&days) {
+        let line = week
+            .iter()
+            .map(|cell| {
+                let cell = match cell {
+                    None => String::new(),
+                    Some((day, tasks)) if tasks.is_empty() => format!("{:>2}", day.day()),
+                    Some((day, tasks)) => format!("{:>2}({})", day.day(), tasks.len()),
+                };
+                format!("{cell:<TEXT_CELL_WIDTH$}")
+            })
+            .collect::<String>();
+        res.push_str(line.trim_end());
+        res.push('\n');
+    }
+    for (day, tasks) in days.iter().filter(|(_, tasks)| !tasks.is_empty()) {
+        res.push_str(&format!("\n{}\n", day.format("%A %d")));
+        for task in tasks {
+            res.push_str(&format!(
+                "  {} {}\n",
+                task.state.display(config.pretty_symbols.clone()),
+                task_label(task)
+            ));
+        }
+    }
+    res
+}
+
+/// Returns the unfinished tasks due before `day`, future occurrences of recurring tasks left out.
+fn overdue_tasks(tasks: &[Task], day: NaiveDate) -> Vec<&Task> {
+    tasks
+        .iter()
+        .filter(|t| {
+            !t.projected
+                && !t.state.is_finished()
+                && t.due_date
+                    .to_naive_date_time()
+                    .is_some_and(|d| d.date() < day)
+        })
+        .collect()
+}
+
+/// Returns the due date and the label of an overdue task, e.g. `Fri 28 Feb Pay rent`.
+fn overdue_label(task: &Task) -> String {
+    let day = task
+        .due_date
+        .to_naive_date_time()
+        .map(|d| d.format("%a %d %b ").to_string())
+        .unwrap_or_default();
+    format!("{day}{}", task_label(task))
+}
+
+/// Writes the tasks due from `today` to `end`, excluded, grouped by day, after the unfinished tasks that are overdue.
+///
+/// Days without tasks are left out.
+#[must_use]
+pub fn agenda_to_text(
+    tasks: &[Task],
+    today: NaiveDate,
+    end: NaiveDate,
+    config: &TasksConfig,
+) -> String {
+    let write_task = |res: &mut String, task: &Task, label: String| {
+        res.push_str(&format!(
+            "  {} {label}\n",
+            task.state.display(config.pretty_symbols.clone())
+        ));
+    };
+    let mut sections = vec![];
+    let overdue = overdue_tasks(tasks, today);
+    if !overdue.is_empty() {
+        let mut section = "Overdue\n".to_owned();
+        for task in overdue {
+            write_task(&mut section, task, overdue_label(task));
+        }
+        sections.push(section);
+    }
+    for (day, tasks) in days_between(tasks, today, end)
+        .iter()
+        .filter(|(_, tasks)| !tasks.is_empty())
+    {
+        let mut section = day.format("%A %d %B").to_string();
+        if *day == today {
+            section.push_str(" (today)");
+        }
+        section.push('\n');
+        for task in tasks {
+            write_task(&mut section, task, task_label(task));
+        }
+        sections.push(section);
+    }
+    if sections.is_empty() {
+        return "Nothing due\n".to_owned();
+    }
+    sections.join("\n")
+}
+
+/// Returns the title of the week starting on `monday`, e.g. `Week 11 of 2025, Mon 10 Mar to Sun 16 Mar`.
+fn week_title(monday: NaiveDate) -> String {
+    format!(
+        "Week {} of {}, {} to {}",
+        monday.iso_week().week(),
+        monday.iso_week().year(),
+        monday.format("%a %d %b"),
+        (monday + TimeDelta::days(6)).format("%a %d %b")
+    )
+}
+
+/// Writes the agenda of the week starting on `monday` to print it: every day with its tasks, after the unfinished
+/// tasks carried over from the weeks before.
+#[must_use]
+pub fn week_agenda_to_text(tasks: &[Task], monday: NaiveDate, config: &TasksConfig) -> String {
+    let write_task = |res: &mut String, task: &Task, label: String| {
+        res.push_str(&format!(
+            "  {} {label}\n",
+            task.state.display(config.pretty_symbols.clone())
+        ));
+    };
+    let title = week_title(monday);
+    let mut res = format!("{title}\n{}\n", "=".repeat(title.chars().count()));
+    let overdue = overdue_tasks(tasks, monday);
+    if !overdue.is_empty() {
+        res.push_str("\nCarried over\n");
+        for task in overdue {
+            write_task(&mut res, task, overdue_label(task));
+        }
+    }
+    for (day, tasks) in days_between(tasks, monday, monday + TimeDelta::days(7)) {
+        res.push_str(&format!("\n{}\n", day.format("%A %d %B")));
+        if tasks.is_empty() {
+            res.push_str("  Nothing due\n");
+        }
+        for task in tasks {
+            write_task(&mut res, task, task_label(task));
+        }
+    }
+    res
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Writes the agenda of `week_agenda_to_text` as a standalone HTML page, laid out to be printed on one sheet.
+#[must_use]
+pub fn week_agenda_to_html(tasks: &[Task], monday: NaiveDate) -> String {
+    let task_list = |tasks: &[&Task], label: &dyn Fn(&Task) -> String| {
+        if tasks.is_empty() {
+            return "<p class=\"empty\">Nothing due</p>\n".to_owned();
+        }
+        let mut res = "<ul>\n".to_owned();
+        for task in tasks {
+            let checked = if task.state == State::Done {
+                " checked"
+            } else {
+                ""
+            };
+            let mut label = html_escape(&label(task));
+            if task.state == State::Canceled {
+                label = format!("<s>{label}</s>");
+            }
+            res.push_str(&format!(
+                "<li><input type=\"checkbox\" disabled{checked}> {label}</li>\n"
+            ));
+        }
+        res.push_str("</ul>\n");
+        res
+    };
+    let title = html_escape(&week_title(monday));
+    let mut res = format!(
+        "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>{title}</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; }}
+h1 {{ font-size: 1.4em; }}
+section {{ break-inside: avoid; border-top: 1px solid #ccc; }}
+h2 {{ font-size: 1.1em; margin-bottom: 0.3em; }}
+ul {{ list-style: none; padding-left: 0.5em; }}
+.empty {{ color: #888; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+"
+    );
+    let overdue = overdue_tasks(tasks, monday);
+    if !overdue.is_empty() {
+        res.push_str("<section>\n<h2>Carried over</h2>\n");
+        res.push_str(&task_list(&overdue, &overdue_label));
+        res.push_str("</section>\n");
+    }
+    for (day, tasks) in days_between(tasks, monday, monday + TimeDelta::days(7)) {
+        res.push_str(&format!("<section>\n<h2>{}</h2>\n", day.format("%A %d %B")));
+        res.push_str(&task_list(&tasks, &task_label));
+        res.push_str("</section>\n");
+    }
+    res.push_str("</body>\n</html>\n");
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use pretty_assertions::assert_eq;
+
+    use super::{
+        agenda_to_text, month_to_markdown, month_to_text, parse_month, parse_week,
+        week_agenda_to_html, week_agenda_to_text,
+    };
+    use crate::{
+        task::{DueDate, State, Task},
+        TasksConfig,
+    };
+
+    fn tasks() -> Vec<Task> {
+        let day = |d| NaiveDate::from_ymd_opt(2025, 3, d).unwrap();
+        vec![
+            Task {
+                name: "Standup".to_owned(),
+                due_date: DueDate::DayTime(day(3).and_hms_opt(9, 30, 0).unwrap()),
+                ..Default::default()
+            },
+            Task {
+                name: "Report".to_owned(),
+                due_date: DueDate::Day(day(3)),
+                state: State::Done,
+                ..Default::default()
+            },
+            Task {
+                name: "Standup".to_owned(),
+                due_date: DueDate::DayTime(day(10).and_hms_opt(9, 30, 0).unwrap()),
+                projected: true,
+                ..Default::default()
+            },
+            Task {
+                name: "Next month".to_owned(),
+                due_date: DueDate::Day(NaiveDate::from_ymd_opt(2025, 4, 1).unwrap()),
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn test_parse_month() {
+        assert_eq!(parse_month("2025-03"), NaiveDate::from_ymd_opt(2025, 3, 1));
+        assert_eq!(parse_month("2025-13"), None);
+        assert_eq!(parse_month("March"), None);
+    }
+
+    #[test]
+    fn test_month_to_markdown() {
+        let config = TasksConfig::default();
+        let month = parse_month("2025-03").unwrap();
+        assert_eq!(
+            month_to_markdown(&tasks(), month, &config),
+            "# March 2025
+
+| Mon | Tue | Wed | Thu | Fri | Sat | Sun |
+| --- | --- | --- | --- | --- | --- | --- |
+|  |  |  |  |  | 1 | 2 |
+| 3 (2) | 4 | 5 | 6 | 7 | 8 | 9 |
+| 10 (1) | 11 | 12 | 13 | 14 | 15 | 16 |
+| 17 | 18 | 19 | 20 | 21 | 22 | 23 |
+| 24 | 25 | 26 | 27 | 28 | 29 | 30 |
+| 31 |  |  |  |  |  |  |
+
+## Monday 03
+
+- [ ] 09:30 Standup
+- [x] Report
+
+## Monday 10
+
+- [ ] 09:30 Standup (projected)
+"
+        );
+    }
+
+    #[test]
+    fn test_month_to_text() {
+        let config = TasksConfig::default();
+        let month = parse_month("2025-03").unwrap();
+        insta::assert_snapshot!(month_to_text(&tasks(), month, &config));
+    }
+
+    #[test]
+    fn test_agenda_to_text() {
+        let config = TasksConfig::default();
+        let mut tasks = tasks();
+        tasks.push(Task {
+            name: "Pay rent".to_owned(),
+            due_date: DueDate::Day(NaiveDate::from_ymd_opt(2025, 2, 28).unwrap()),
+            ..Default::default()
+        });
+        let day = |d| NaiveDate::from_ymd_opt(2025, 3, d).unwrap();
+        assert_eq!(
+            agenda_to_text(&tasks, day(3), day(10), &config),
+            "Overdue
+  ❌ Fri 28 Feb Pay rent
+
+Monday 03 March (today)
+  ❌ 09:30 Standup
+  ✅ Report
+"
+        );
+        assert_eq!(
+            agenda_to_text(&tasks[1..4], day(11), day(20), &config),
+            "Nothing due\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_week() {
+        assert_eq!(parse_week("2025-W11"), NaiveDate::from_ymd_opt(2025, 3, 10));
+        assert_eq!(
+            parse_week("2025-W01"),
+            NaiveDate::from_ymd_opt(2024, 12, 30)
+        );
+        assert_eq!(parse_week("2025-W54"), None);
+        assert_eq!(parse_week("2025-03"), None);
+    }
+
+    #[test]
+    fn test_week_agenda() {
+        let config = TasksConfig::default();
+        let mut tasks = tasks();
+        tasks.push(Task {
+            name: "Pay <rent>".to_owned(),
+            due_date: DueDate::Day(NaiveDate::from_ymd_opt(2025, 2, 28).unwrap()),
+            ..Default::default()
+        });
+        let monday = parse_week("2025-W10").unwrap();
+        insta::assert_snapshot!(week_agenda_to_text(&tasks, monday, &config));
+        let html = week_agenda_to_html(&tasks, monday);
+        assert!(html.contains("<h1>Week 10 of 2025, Mon 03 Mar to Sun 09 Mar</h1>"));
+        assert!(html
+            .contains("<li><input type=\"checkbox\" disabled> Fri 28 Feb Pay &lt;rent&gt;</li>"));
+        assert!(html.contains("<li><input type=\"checkbox\" disabled checked> Report</li>"));
+        assert_eq!(html.matches("<section>").count(), 8);
+    }
+}
@@ -0,0 +1,346 @@
+use std::{
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::Result;
+
+/// Number of unchanged lines shown around changes.
+const CONTEXT_LINES: usize = 3;
+
+/// A part of a changed line, `changed` is true for words that differ from the other version of the line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    pub text: String,
+    pub changed: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    Context(String),
+    Removed(Vec<Segment>),
+    Added(Vec<Segment>),
+}
+
+/// Changed lines and their context, line numbers start at 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk {
+    pub old_start: usize,
+    pub old_len: usize,
+    pub new_start: usize,
+    pub new_len: usize,
+    pub lines: Vec<DiffLine>,
+}
+
+/// The content of a file before and after an operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    pub path: PathBuf,
+    pub old: String,
+    pub new: String,
+}
+
+/// Changes of an operation touching several files, to preview them before they are written.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Changeset {
+    pub changes: Vec<FileChange>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Equal(usize, usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+/// Longest common subsequence diff of `a` and `b`.
+fn diff_ops<T: PartialEq>(a: &[T], b: &[T]) -> Vec<Op> {
+    // lcs[i][j] is the length of the LCS of a[i..] and b[j..]
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut ops = vec![];
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            ops.push(Op::Equal(i, j));
+            i += 1;
+            j += 1;
+        } else if j == b.len() || (i < a.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            ops.push(Op::Delete(i));
+            i += 1;
+        } else {
+            ops.push(Op::Insert(j));
+            j += 1;
+        }
+    }
+    ops
+}
+
+/// Splits a line into words and the whitespace between them.
+fn words(line: &str) -> Vec<&str> {
+    let mut res = vec![];
+    let mut start = 0;
+    let mut in_space = None;
+    for (i, c) in line.char_indices() {
+        let is_space = c.is_whitespace();
+        if in_space.is_some_and(|s| s != is_space) {
+            res.push(&line[start..i]);
+            start = i;
+        }
+        in_space = Some(is_space);
+    }
+    if start < line.len() {
+        res.push(&line[start..]);
+    }
+    res
+}
+
+/// Pushes `text` to `segments`, merging it with the last segment if they have the same state.
+fn push_segment(segments: &mut Vec<Segment>, text: &str, changed: bool) {
+    match segments.last_mut() {
+        Some(last) if last.changed == changed => last.text.push_str(text),
+        _ => segments.push(Segment {
+            text: text.to_owned(),
+            changed,
+        }),
+    }
+}
+
+/// Compares two versions of a line word by word.
+fn word_diff(old: &str, new: &str) -> (Vec<Segment>, Vec<Segment>) {
+    let (old_words, new_words) = (words(old), words(new));
+    let (mut removed, mut added) = (vec![], vec![]);
+    for op in diff_ops(&old_words, &new_words) {
+        match op {
+            Op::Equal(i, j) => {
+                push_segment(&mut removed, old_words[i], false);
+                push_segment(&mut added, new_words[j], false);
+            }
+            Op::Delete(i) => push_segment(&mut removed, old_words[i], true),
+            Op::Insert(j) => push_segment(&mut added, new_words[j], true),
+        }
+    }
+    (removed, added)
+}
+
+/// Turns a run of deleted and inserted lines into diff lines, pairing them to compare their words.
+fn flush_changes(removed: &mut Vec<&str>, added: &mut Vec<&str>, lines: &mut Vec<DiffLine>) {
+    let whole = |line: &str| {
+        vec![Segment {
+            text: line.to_owned(),
+            changed: true,
+        }]
+    };
+    let mut added_lines = vec![];
+    for (i, line) in removed.iter().enumerate() {
+        match added.get(i) {
+            Some(new) => {
+                let (r, a) = word_diff(line, new);
+                lines.push(DiffLine::Removed(r));
+                added_lines.push(DiffLine::Added(a));
+            }
+            None => lines.push(DiffLine::Removed(whole(line))),
+        }
+    }
+    for line in added.iter().skip(removed.len()) {
+        added_lines.push(DiffLine::Added(whole(line)));
+    }
+    lines.append(&mut added_lines);
+    removed.clear();
+    added.clear();
+}
+
+impl FileChange {
+    /// Returns the changes of the file, with `CONTEXT_LINES` lines of context.
+    #[must_use]
+    pub fn hunks(&self) -> Vec<Hunk> {
+        let old_lines = self.old.lines().collect::<Vec<&str>>();
+        let new_lines = self.new.lines().collect::<Vec<&str>>();
+        let ops = diff_ops(&old_lines, &new_lines);
+
+        // Diff lines in order, removed lines before added ones
+        let mut lines = vec![];
+        let (mut removed, mut added) = (vec![], vec![]);
+        for op in ops {
+            match op {
+                Op::Equal(i, _) => {
+                    flush_changes(&mut removed, &mut added, &mut lines);
+                    lines.push(DiffLine::Context(old_lines[i].to_owned()));
+                }
+                Op::Delete(i) => removed.push(old_lines[i]),
+                Op::Insert(j) => added.push(new_lines[j]),
+            }
+        }
+        flush_changes(&mut removed, &mut added, &mut lines);
+
+        // Group changes closer than twice the context
+        let changed = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| !matches!(line, DiffLine::Context(_)))
+            .map(|(i, _)| i)
+            .collect::<Vec<usize>>();
+        let mut ranges: Vec<(usize, usize)> = vec![];
+        for i in changed {
+            let start = i.saturating_sub(CONTEXT_LINES);
+            let end = (i + CONTEXT_LINES + 1).min(lines.len());
+            match ranges.last_mut() {
+                Some(last) if start <= last.1 => last.1 = end,
+                _ => ranges.push((start, end)),
+            }
+        }
+
+        ranges
+            .into_iter()
+            .map(|(start, end)| {
+                // Position of the hunk in both files
+                let (mut old_start, mut new_start) = (0, 0);
+                for line in &lines[..start] {
+                    match line {
+                        DiffLine::Context(_) => {
+                            old_start += 1;
+                            new_start += 1;
+                        }
+                        DiffLine::Removed(_) => old_start += 1,
+                        DiffLine::Added(_) => new_start += 1,
+                    }
+                }
+                let hunk_lines = &lines[start..end];
+                let old_len = hunk_lines
+                    .iter()
+                    .filter(|line| !matches!(line, DiffLine::Added(_)))
+                    .count();
+                let new_len = hunk_lines
+                    .iter()
+                    .filter(|line| !matches!(line, DiffLine::Removed(_)))
+                    .count();
+                Hunk {
+                    old_start: old_start + 1,
+                    old_len,
+                    new_start: new_start + 1,
+                    new_len,
+                    lines: hunk_lines.to_vec(),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the changes of the file as a unified diff.
+    ///
+    /// If `highlight` is true, words that changed in a modified line are highlighted with ANSI escape codes.
+    #[must_use]
+    pub fn to_unified_diff(&self, highlight: bool) -> String {
+        let render = |segments: &[Segment]| {
+            segments
+                .iter()
+                .map(|s| {
+                    if highlight && s.changed {
+                        format!("\x1b[7m{}\x1b[27m", s.text)
+                    } else {
+                        s.text.clone()
+                    }
+                })
+                .collect::<String>()
+        };
+        let mut res = format!("--- {}\n+++ {}\n", self.path.display(), self.path.display());
+        for hunk in self.hunks() {
+            let _ = writeln!(
+                res,
+                "@@ -{},{} +{},{} @@",
+                hunk.old_start, hunk.old_len, hunk.new_start, hunk.new_len
+            );
+            for line in hunk.lines {
+                let _ = match line {
+                    DiffLine::Context(l) => writeln!(res, " {l}"),
+                    DiffLine::Removed(s) => writeln!(res, "-{}", render(&s)),
+                    DiffLine::Added(s) => writeln!(res, "+{}", render(&s)),
+                };
+            }
+        }
+        res
+    }
+}
+
+impl Changeset {
+    /// Adds a change to `path`, ignored if the content is the same.
+    pub fn add(&mut self, path: &Path, old: String, new: String) {
+        if old != new {
+            self.changes.push(FileChange {
+                path: path.to_path_buf(),
+                old,
+                new,
+            });
+        }
+    }
+
+    /// Writes the changes, creating missing directories.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if a file can't be written.
+    pub fn apply(&self) -> Result<()> {
+        for change in &self.changes {
+            if let Some(parent) = change.path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&change.path, &change.new)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{word_diff, FileChange, Segment};
+
+    #[test]
+    fn test_word_diff() {
+        let segment = |text: &str, changed| Segment {
+            text: text.to_owned(),
+            changed,
+        };
+        assert_eq!(
+            word_diff("- [ ] task #old p1", "- [ ] task #new p1"),
+            (
+                vec![
+                    segment("- [ ] task ", false),
+                    segment("#old", true),
+                    segment(" p1", false)
+                ],
+                vec![
+                    segment("- [ ] task ", false),
+                    segment("#new", true),
+                    segment(" p1", false)
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn test_unified_diff() {
+        let change = FileChange {
+            path: PathBuf::from("note.md"),
+            old: "# Tasks\n- [ ] a\n- [ ] b 01/01/2020\n1\n2\n3\n4\n5\n6\n7\n- [ ] c\n".to_owned(),
+            new: "# Tasks\n- [ ] a\n- [ ] b 02/01/2020\n1\n2\n3\n4\n5\n6\n7\n".to_owned(),
+        };
+        assert_eq!(
+            change.to_unified_diff(false),
+            "--- note.md\n+++ note.md\n\
+             @@ -1,6 +1,6 @@\n # Tasks\n - [ ] a\n-- [ ] b 01/01/2020\n+- [ ] b 02/01/2020\n 1\n 2\n 3\n\
+             @@ -8,4 +8,3 @@\n 5\n 6\n 7\n-- [ ] c\n"
+        );
+    }
+}
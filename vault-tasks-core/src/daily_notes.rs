@@ -0,0 +1,563 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::{
+    format::{Item, StrftimeItems},
+    NaiveDate,
+};
+use color_eyre::{eyre::bail, Result};
+use serde::Deserialize;
+use tracing::{debug, info};
+
+use super::{
+    changeset::Changeset,
+    import::insert_tasks,
+    parser::{
+        parser_file_entry::{parse_frontmatter, ParserFileEntry},
+        task::parse_task,
+    },
+    task::{State, Task},
+    vault_data::VaultData,
+    TasksConfig,
+};
+
+/// What happens to unfinished tasks of a previous daily note when they are carried over.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CarryOverMode {
+    /// Tasks are copied to today's note with a link to the note they come from.
+    #[default]
+    Copy,
+    /// Tasks are removed from the previous note.
+    Move,
+}
+
+/// Ensures `daily_notes_pattern` is set and is a valid `strftime` pattern.
+///
+/// # Errors
+///
+/// Will return an error if the pattern is not set or invalid, or if the vault is not a directory.
+pub fn check_pattern(config: &TasksConfig) -> Result<()> {
+    if config.daily_notes_pattern.is_empty() {
+        bail!("`daily_notes_pattern` is not set in the configuration");
+    }
+    if StrftimeItems::new(&config.daily_notes_pattern).any(|item| matches!(item, Item::Error)) {
+        bail!(
+            "Invalid `daily_notes_pattern`: {}",
+            config.daily_notes_pattern
+        );
+    }
+    if !config.vault_path.is_dir() {
+        bail!("Daily notes require the vault to be a directory");
+    }
+    Ok(())
+}
+
+/// Returns the path of the daily note of `date`.
+#[must_use]
+pub fn daily_note_path(config: &TasksConfig, date: NaiveDate) -> PathBuf {
+    config
+        .vault_path
+        .join(date.format(&config.daily_notes_pattern).to_string())
+}
+
+/// Finds every daily note of the vault, sorted chronologically.
+///
+/// # Errors
+///
+/// Will return an error if `daily_notes_pattern` is invalid or if the vault can't be read.
+pub fn find_daily_notes(config: &TasksConfig) -> Result<Vec<(NaiveDate, PathBuf)>> {
+    fn aux(config: &TasksConfig, path: &Path, res: &mut Vec<(NaiveDate, PathBuf)>) -> Result<()> {
+        for entry in path.read_dir()?.flatten() {
+            let entry_path = entry.path();
+            if config.ignored.contains(&entry_path) {
+                continue;
+            }
+            if entry_path.is_dir() {
+                aux(config, &entry_path, res)?;
+                continue;
+            }
+            let Ok(relative_path) = entry_path.strip_prefix(&config.vault_path) else {
+                continue;
+            };
+            if let Ok(date) = NaiveDate::parse_from_str(
+                &relative_path.to_string_lossy(),
+                &config.daily_notes_pattern,
+            ) {
+                res.push((date, entry_path));
+            }
+        }
+        Ok(())
+    }
+    check_pattern(config)?;
+
+    let mut res = vec![];
+    aux(config, &config.vault_path, &mut res)?;
+    res.sort();
+    Ok(res)
+}
+
+/// Returns the top level tasks of a parsed file, ignoring subtasks.
+fn top_level_tasks(file_entry: &VaultData, res: &mut Vec<Task>) {
+    match file_entry {
+        VaultData::Directory(_, children) | VaultData::Header(_, _, children) => {
+            children.iter().for_each(|c| top_level_tasks(c, res));
+        }
+        VaultData::Task(task) => res.push(task.clone()),
+    }
+}
+
+fn parse_note_tasks(config: &TasksConfig, path: &Path, content: &str) -> Vec<Task> {
+    let mut parser = ParserFileEntry {
+        config,
+        filename: String::new(),
+    };
+    let filename = path
+        .file_name()
+        .map(|f| f.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut tasks = vec![];
+    if let Some(file_entry) = parser.parse_file(&filename, &content) {
+        top_level_tasks(&file_entry, &mut tasks);
+    }
+    tasks
+}
+
+/// Returns the indices of the lines of a task block: the task line followed by every line indented deeper (subtasks and descriptions).
+#[must_use]
+pub fn task_block(lines: &[&str], line_number: usize) -> std::ops::Range<usize> {
+    let indent = |line: &str| line.chars().take_while(|c| c.is_whitespace()).count();
+    let start = line_number - 1;
+    let task_indent = indent(lines[start]);
+    let len = lines[start + 1..]
+        .iter()
+        .take_while(|l| !l.trim().is_empty() && indent(l) > task_indent)
+        .count();
+    start..start + 1 + len
+}
+
+/// Carries unfinished tasks of the most recent daily note before `today` over to the daily note of `today`.
+///
+/// Tasks already present in today's note are skipped, so running it twice has no effect.
+/// Returns the number of tasks carried over.
+///
+/// # Errors
+///
+/// Will return an error if `daily_notes_pattern` is invalid, if the vault is read-only or if notes can't be read or written.
+pub fn carry_over(config: &TasksConfig, today: NaiveDate, mode: CarryOverMode) -> Result<usize> {
+    config.check_writable()?;
+    let (count, changes) = plan_carry_over(config, today, mode)?;
+    changes.apply()?;
+    if count > 0 {
+        info!(
+            "Carried over {count} tasks to {:?}",
+            daily_note_path(config, today)
+        );
+    }
+    Ok(count)
+}
+
+/// Computes the changes of `carry_over` without writing them.
+///
+/// Returns the number of tasks carried over and the changes to the notes.
+///
+/// # Errors
+///
+/// Will return an error if `daily_notes_pattern` is invalid or if notes can't be read.
+pub fn plan_carry_over(
+    config: &TasksConfig,
+    today: NaiveDate,
+    mode: CarryOverMode,
+) -> Result<(usize, Changeset)> {
+    let mut changes = Changeset::default();
+    let notes = find_daily_notes(config)?;
+    let Some((_, source_path)) = notes.iter().rev().find(|(date, _)| *date < today) else {
+        info!("No previous daily note found");
+        return Ok((0, changes));
+    };
+    let target_path = daily_note_path(config, today);
+    debug!("Carrying over tasks from {source_path:?} to {target_path:?}");
+
+    let source_content = fs::read_to_string(source_path)?;
+    let target_content = if target_path.exists() {
+        fs::read_to_string(&target_path)?
+    } else {
+        String::new()
+    };
+
+    let backlink = source_path
+        .file_stem()
+        .map(|s| format!("[[{}]]", s.to_string_lossy()))
+        .unwrap_or_default();
+    let existing_names = parse_note_tasks(config, &target_path, &target_content)
+        .into_iter()
+        .map(|t| t.name)
+        .collect::<Vec<String>>();
+
+    let source_lines = source_content.split('\n').collect::<Vec<&str>>();
+    let blocks = parse_note_tasks(config, source_path, &source_content)
+        .iter()
+        .filter(|t| !t.state.is_finished())
+        .filter(|t| {
+            !existing_names.contains(&t.name)
+                && !existing_names.contains(&format!("{} {backlink}", t.name))
+        })
+        .map(|t| task_block(&source_lines, t.line_number))
+        .collect::<Vec<std::ops::Range<usize>>>();
+
+    if blocks.is_empty() {
+        info!("Nothing to carry over");
+        return Ok((0, changes));
+    }
+
+    let mut carried = vec![];
+    for block in &blocks {
+        let task_indent = source_lines[block.start]
+            .chars()
+            .take_while(|c| c.is_whitespace())
+            .count();
+        for (i, line) in source_lines[block.clone()].iter().enumerate() {
+            let mut line = line.chars().skip(task_indent).collect::<String>();
+            if i == 0 && mode == CarryOverMode::Copy {
+                line.push(' ');
+                line.push_str(&backlink);
+            }
+            carried.push(line);
+        }
+    }
+
+    let mut new_target_content = target_content.clone();
+    if !new_target_content.is_empty() && !new_target_content.ends_with('\n') {
+        new_target_content.push('\n');
+    }
+    new_target_content.push_str(&carried.join("\n"));
+    new_target_content.push('\n');
+    changes.add(&target_path, target_content, new_target_content);
+
+    if mode == CarryOverMode::Move {
+        let new_source_content = source_lines
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !blocks.iter().any(|b| b.contains(i)))
+            .map(|(_, l)| *l)
+            .collect::<Vec<&str>>()
+            .join("\n");
+        changes.add(source_path, source_content.clone(), new_source_content);
+    }
+
+    Ok((blocks.len(), changes))
+}
+
+/// Returns the path of the file given by the `key` setting, `pattern` being relative to the vault and using strftime syntax, or today's daily note if it is empty.
+fn log_file_path(
+    config: &TasksConfig,
+    key: &str,
+    pattern: &str,
+    today: NaiveDate,
+) -> Result<PathBuf> {
+    if pattern.is_empty() {
+        check_pattern(config)?;
+        return Ok(daily_note_path(config, today));
+    }
+    if StrftimeItems::new(pattern).any(|item| matches!(item, Item::Error)) {
+        bail!("Invalid `{key}`: {pattern}");
+    }
+    let vault_dir = if config.vault_path.is_dir() {
+        config.vault_path.as_path()
+    } else {
+        config.vault_path.parent().unwrap_or(Path::new(""))
+    };
+    Ok(vault_dir.join(today.format(pattern).to_string()))
+}
+
+/// Returns the path of `drop_file` for `today`, or of today's daily note if it is not set.
+///
+/// # Errors
+///
+/// Will return an error if `drop_file` is invalid, or if it is not set and daily notes are not configured.
+pub fn drop_file_path(config: &TasksConfig, today: NaiveDate) -> Result<PathBuf> {
+    log_file_path(config, "drop_file", &config.drop_file, today)
+}
+
+/// Appends `tasks` to the file at `path`, creating it if needed.
+///
+/// Tasks are written under the `tasks-default-heading` of the frontmatter of the file if it has one.
+fn append_tasks(config: &TasksConfig, path: &Path, tasks: &[Task]) -> Result<()> {
+    let old = if path.exists() {
+        fs::read_to_string(path)?
+    } else {
+        String::new()
+    };
+    let content = insert_tasks(
+        &old,
+        parse_frontmatter(&old).default_heading.as_deref(),
+        tasks
+            .iter()
+            .map(|task| task.get_fixed_attributes(config, 0)),
+    );
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content)?;
+    Ok(())
+}
+
+/// Appends an already completed task, done on `today`, to the log file or to today's daily note.
+///
+/// `description` is parsed like any task, so it can contain tags, priorities, etc.
+/// Returns the path of the file the task was appended to.
+///
+/// # Errors
+///
+/// Will return an error if no log file nor daily note is configured, if the vault is read-only or if the file can't be written.
+pub fn log_done_task(config: &TasksConfig, description: &str, today: NaiveDate) -> Result<PathBuf> {
+    config.check_writable()?;
+    let path = log_file_path(config, "did_log_file", &config.did_log_file, today)?;
+
+    let input = format!(
+        "- [{}] {}",
+        config.task_state_markers.done,
+        description.trim()
+    );
+    let Ok(mut task) = parse_task(&mut input.as_str(), String::new(), config) else {
+        bail!("Failed to parse task: {description}");
+    };
+    if task.name.is_empty() {
+        bail!("Nothing to log");
+    }
+    task.state = State::Done;
+    task.completed = Some(today);
+
+    append_tasks(config, &path, std::slice::from_ref(&task))?;
+    info!("Logged {:?} to {path:?}", task.name);
+    Ok(path)
+}
+
+/// Parses the tasks to add, one task per line of `lines`.
+///
+/// Lines are parsed like any task, with or without a leading `- [ ]` or list marker. Blank lines are skipped.
+///
+/// # Errors
+///
+/// Will return an error if a line has no task name or if there is no task.
+pub fn parse_new_tasks(config: &TasksConfig, lines: &[&str]) -> Result<Vec<Task>> {
+    let mut tasks = vec![];
+    for line in lines.iter().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+        let description = line
+            .strip_prefix(['-', '*', '+'])
+            .filter(|l| l.starts_with(' '))
+            .map_or(line, str::trim_start);
+        let task = parse_task(
+            &mut format!("- {description}").as_str(),
+            String::new(),
+            config,
+        )
+        .or_else(|_| {
+            let input = format!("- [{}] {description}", config.task_state_markers.todo);
+            parse_task(&mut input.as_str(), String::new(), config)
+        });
+        match task {
+            Ok(task) if !task.name.is_empty() => tasks.push(task),
+            _ => bail!("Not a task: {line}"),
+        }
+    }
+    if tasks.is_empty() {
+        bail!("Nothing to add");
+    }
+    Ok(tasks)
+}
+
+/// Appends new tasks to `drop_file`, or to today's daily note, see `parse_new_tasks`.
+///
+/// With `creation_date`, tasks without a creation date are created `today`. Returns the path of the file the tasks were appended to.
+///
+/// # Errors
+///
+/// Will return an error if no drop file nor daily note is configured, if the vault is read-only or if the file can't be written.
+pub fn add_tasks(config: &TasksConfig, tasks: &[Task], today: NaiveDate) -> Result<PathBuf> {
+    config.check_writable()?;
+    let path = drop_file_path(config, today)?;
+    let tasks = tasks
+        .iter()
+        .cloned()
+        .map(|mut task| {
+            if config.creation_date {
+                task.created = task.created.or(Some(today));
+            }
+            task
+        })
+        .collect::<Vec<Task>>();
+    append_tasks(config, &path, &tasks)?;
+    info!("Added {} tasks to {path:?}", tasks.len());
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use chrono::NaiveDate;
+    use pretty_assertions::assert_eq;
+
+    use super::{
+        add_tasks, carry_over, find_daily_notes, log_done_task, parse_new_tasks, plan_carry_over,
+        CarryOverMode,
+    };
+    use crate::TasksConfig;
+
+    fn setup(name: &str) -> TasksConfig {
+        let vault_path = std::env::temp_dir().join(format!("vault-tasks-{name}"));
+        let _ = fs::remove_dir_all(&vault_path);
+        fs::create_dir_all(vault_path.join("Journal")).unwrap();
+        fs::write(
+            vault_path.join("Journal/2024-12-30.md"),
+            "- [ ] very old task\n",
+        )
+        .unwrap();
+        fs::write(
+            vault_path.join("Journal/2025-01-02.md"),
+            "# Tasks\n- [ ] unfinished\n  - [x] subtask\n  description\n- [x] finished\n- [/] started\n",
+        )
+        .unwrap();
+        fs::write(vault_path.join("notes.md"), "- [ ] not a daily note\n").unwrap();
+        TasksConfig {
+            vault_path,
+            indent_length: 2,
+            daily_notes_pattern: String::from("Journal/%Y-%m-%d.md"),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_find_daily_notes() {
+        let config = setup("find-daily-notes");
+        let notes = find_daily_notes(&config).unwrap();
+        assert_eq!(
+            notes.iter().map(|(d, _)| *d).collect::<Vec<NaiveDate>>(),
+            vec![
+                NaiveDate::from_ymd_opt(2024, 12, 30).unwrap(),
+                NaiveDate::from_ymd_opt(2025, 1, 2).unwrap()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_carry_over_copy() {
+        let config = setup("carry-over-copy");
+        let today = NaiveDate::from_ymd_opt(2025, 1, 3).unwrap();
+
+        assert_eq!(carry_over(&config, today, CarryOverMode::Copy).unwrap(), 2);
+        // Already carried over
+        assert_eq!(carry_over(&config, today, CarryOverMode::Copy).unwrap(), 0);
+
+        let today_note =
+            fs::read_to_string(config.vault_path.join("Journal/2025-01-03.md")).unwrap();
+        assert_eq!(
+            today_note,
+            "- [ ] unfinished [[2025-01-02]]\n  - [x] subtask\n  description\n- [/] started [[2025-01-02]]\n"
+        );
+        let source = fs::read_to_string(config.vault_path.join("Journal/2025-01-02.md")).unwrap();
+        assert!(source.contains("- [ ] unfinished"));
+    }
+
+    #[test]
+    fn test_plan_carry_over() {
+        let config = setup("plan-carry-over");
+        let today = NaiveDate::from_ymd_opt(2025, 1, 3).unwrap();
+
+        let (count, changes) = plan_carry_over(&config, today, CarryOverMode::Move).unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(
+            changes
+                .changes
+                .iter()
+                .map(|c| c.path.strip_prefix(&config.vault_path).unwrap())
+                .collect::<Vec<_>>(),
+            vec![
+                std::path::Path::new("Journal/2025-01-03.md"),
+                std::path::Path::new("Journal/2025-01-02.md")
+            ]
+        );
+        // Nothing is written until the changes are applied
+        assert!(!config.vault_path.join("Journal/2025-01-03.md").exists());
+        changes.apply().unwrap();
+        assert!(config.vault_path.join("Journal/2025-01-03.md").exists());
+    }
+
+    #[test]
+    fn test_carry_over_move() {
+        let config = setup("carry-over-move");
+        let today = NaiveDate::from_ymd_opt(2025, 1, 3).unwrap();
+
+        assert_eq!(carry_over(&config, today, CarryOverMode::Move).unwrap(), 2);
+
+        let today_note =
+            fs::read_to_string(config.vault_path.join("Journal/2025-01-03.md")).unwrap();
+        assert_eq!(
+            today_note,
+            "- [ ] unfinished\n  - [x] subtask\n  description\n- [/] started\n"
+        );
+        let source = fs::read_to_string(config.vault_path.join("Journal/2025-01-02.md")).unwrap();
+        assert_eq!(source, "# Tasks\n- [x] finished\n");
+    }
+
+    #[test]
+    fn test_log_done_task() {
+        let config = setup("log-done-task");
+        let today = NaiveDate::from_ymd_opt(2025, 1, 2).unwrap();
+
+        let path = log_done_task(&config, "reviewed PRs #work", today).unwrap();
+        assert_eq!(path, config.vault_path.join("Journal/2025-01-02.md"));
+        assert!(fs::read_to_string(&path).unwrap().ends_with(
+            "- [x] finished\n- [/] started\n- [x] reviewed PRs done:02/01/2025 #work\n"
+        ));
+
+        let config = crate::TasksConfig {
+            did_log_file: String::from("Log/%Y.md"),
+            ..config
+        };
+        let path = log_done_task(&config, "fixed the printer", today).unwrap();
+        assert_eq!(
+            fs::read_to_string(path).unwrap(),
+            "- [x] fixed the printer done:02/01/2025\n"
+        );
+        assert!(log_done_task(&config, "  ", today).is_err());
+    }
+
+    #[test]
+    fn test_add_tasks() {
+        let config = crate::TasksConfig {
+            drop_file: String::from("Inbox.md"),
+            ..setup("add-tasks")
+        };
+        let today = NaiveDate::from_ymd_opt(2025, 1, 2).unwrap();
+
+        let tasks = parse_new_tasks(
+            &config,
+            &["call bank #home", "", "- buy milk p1", "* [x] already done"],
+        )
+        .unwrap();
+        assert_eq!(tasks.len(), 3);
+        let path = add_tasks(&config, &tasks, today).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "- [ ] call bank #home\n- [ ] buy milk p1\n- [x] already done\n"
+        );
+        assert!(parse_new_tasks(&config, &["", " "]).is_err());
+        assert!(parse_new_tasks(&config, &["today"]).is_err());
+
+        let config = crate::TasksConfig {
+            creation_date: true,
+            ..config
+        };
+        let tasks =
+            parse_new_tasks(&config, &["pay rent", "renew passport created:01/12/2024"]).unwrap();
+        add_tasks(&config, &tasks, today).unwrap();
+        assert!(fs::read_to_string(&path).unwrap().ends_with(
+            "- [ ] pay rent created:02/01/2025\n- [ ] renew passport created:01/12/2024\n"
+        ));
+    }
+}
@@ -0,0 +1,20 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Deserializer, Serializer};
+
+/// Format of the dates written to the data directory, e.g. with `#[serde(with = "date_time")]`
+const DATE_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// # Errors
+///
+/// Will return an error if the serializer fails.
+pub fn serialize<S: Serializer>(date: &NaiveDateTime, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_str(&date.format(DATE_TIME_FORMAT).to_string())
+}
+
+/// # Errors
+///
+/// Will return an error if the date is not formatted as `YYYY-MM-DD HH:MM:SS`.
+pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<NaiveDateTime, D::Error> {
+    NaiveDateTime::parse_from_str(&String::deserialize(d)?, DATE_TIME_FORMAT)
+        .map_err(serde::de::Error::custom)
+}
@@ -0,0 +1,329 @@
+use std::path::PathBuf;
+
+use serde_json::{json, Value};
+
+use super::{
+    filter::{filter_to_vec_with_paths, parse_search_input, Filter},
+    task::{State, Task},
+    vault_data::VaultData,
+    TasksConfig,
+};
+
+/// A task and where it comes from in the vault.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskDetails {
+    /// Directories, file and headers leading to the task, without the vault root
+    pub breadcrumb: Vec<String>,
+    pub file: PathBuf,
+    pub task: Task,
+}
+
+/// Finds the tasks whose block identifier is `query` (with or without the leading `^`), or the tasks matching `query` as a search if none has it.
+#[must_use]
+pub fn find_tasks(vault_data: &VaultData, query: &str, config: &TasksConfig) -> Vec<TaskDetails> {
+    let id = query.trim().trim_start_matches('^');
+    let mut tasks = filter_to_vec_with_paths(vault_data, &Filter::default())
+        .into_iter()
+        .filter(|(_, task)| task.block_id.as_deref() == Some(id))
+        .collect::<Vec<(Vec<String>, Task)>>();
+    if tasks.is_empty() {
+        tasks = filter_to_vec_with_paths(vault_data, &parse_search_input(query, config));
+    }
+    to_details(tasks)
+}
+
+/// Share of words two task names must have in common to be considered duplicates.
+const SIMILARITY_THRESHOLD: f64 = 0.6;
+
+/// Lowercase words of a task name, without punctuation.
+fn name_words(name: &str) -> Vec<String> {
+    let mut words = name
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<String>>();
+    words.sort_unstable();
+    words.dedup();
+    words
+}
+
+/// Returns the unfinished tasks of `vault_data` whose name shares most of its words with `name`, whatever their
+/// order and case, to warn before adding a duplicate.
+#[must_use]
+pub fn similar_tasks(vault_data: &VaultData, name: &str) -> Vec<TaskDetails> {
+    let words = name_words(name);
+    if words.is_empty() {
+        return vec![];
+    }
+    filter_to_details(vault_data, &Filter::default())
+        .into_iter()
+        .filter(|details| !details.task.state.is_finished())
+        .filter(|details| {
+            let other = name_words(&details.task.name);
+            let common = words.iter().filter(|w| other.contains(w)).count();
+            let all = words.len() + other.len() - common;
+            #[allow(clippy::cast_precision_loss)]
+            let similarity = common as f64 / all as f64;
+            similarity >= SIMILARITY_THRESHOLD
+        })
+        .collect()
+}
+
+/// Returns the tasks matching `filter`, subtasks included, along with where they come from.
+#[must_use]
+pub fn filter_to_details(vault_data: &VaultData, filter: &Filter) -> Vec<TaskDetails> {
+    to_details(filter_to_vec_with_paths(vault_data, filter))
+}
+
+fn to_details(tasks: Vec<(Vec<String>, Task)>) -> Vec<TaskDetails> {
+    tasks
+        .into_iter()
+        .map(|(path, task)| {
+            // The path holds the vault root and directories, then the file and its headers
+            let file_index = path
+                .iter()
+                .rposition(|p| *p == task.filename)
+                .unwrap_or(path.len().saturating_sub(1));
+            TaskDetails {
+                file: path.iter().take(file_index + 1).collect(),
+                breadcrumb: path
+                    .iter()
+                    .skip(1)
+                    .map(|p| p.trim_end_matches(".md").to_owned())
+                    .collect(),
+                task,
+            }
+        })
+        .collect()
+}
+
+/// Name of `state` in the JSON output and reports.
+#[must_use]
+pub fn state_name(state: &State) -> String {
+    match state {
+        State::ToDo => "todo".to_owned(),
+        State::Done => "done".to_owned(),
+        State::Incomplete => "incomplete".to_owned(),
+        State::Canceled => "canceled".to_owned(),
+        State::Custom(custom) => custom.name.to_lowercase(),
+    }
+}
+
+/// Appends the task line, its description and its subtasks, indented by `depth`.
+fn tree_lines(task: &Task, config: &TasksConfig, depth: usize, res: &mut Vec<String>) {
+    let indent_length = depth * config.indent_length;
+    res.push(task.get_fixed_attributes(config, indent_length));
+    if let Some(description) = &task.description {
+        let indent = " ".repeat(indent_length + config.indent_length);
+        res.extend(description.lines().map(|l| format!("{indent}{l}")));
+    }
+    for subtask in &task.subtasks {
+        tree_lines(subtask, config, depth + 1, res);
+    }
+}
+
+fn task_to_json(task: &Task) -> Value {
+    let date = |d: Option<chrono::NaiveDate>| d.map(|d| d.to_string());
+    json!({
+        "name": task.name,
+        "state": state_name(&task.state),
+        "priority": task.priority,
+        "tags": task.tags.clone().unwrap_or_default(),
+        "due": task.due_date.to_naive_date_time().map(|_| task.due_date.to_string()),
+        "created": date(task.created),
+        "completed": date(task.completed),
+        "estimate": task.estimate,
+        "completion": task.completion,
+        "id": task.block_id,
+        "description": task.description,
+        "line": task.line_number,
+        "meta": task
+            .hidden_meta
+            .iter()
+            .map(|(key, value)| (key.clone(), json!(value)))
+            .collect::<serde_json::Map<String, Value>>(),
+        "subtasks": task.subtasks.iter().map(task_to_json).collect::<Vec<Value>>(),
+    })
+}
+
+impl TaskDetails {
+    /// Returns the task with its subtask tree, followed by its location and dates.
+    #[must_use]
+    pub fn to_pretty(&self, config: &TasksConfig) -> String {
+        let date_format = config.date_display_format();
+        let mut lines = vec![];
+        tree_lines(&self.task, config, 0, &mut lines);
+        lines.push(String::new());
+        lines.push(format!("Path:      {}", self.breadcrumb.join(" > ")));
+        lines.push(format!(
+            "File:      {}:{}",
+            self.file.display(),
+            self.task.line_number
+        ));
+        lines.push(format!("State:     {}", state_name(&self.task.state)));
+        let optional = [
+            (
+                "Due",
+                Some(self.task.due_date.to_display_string(&date_format)).filter(|d| !d.is_empty()),
+            ),
+            (
+                "Created",
+                self.task.created.map(|d| date_format.format_date(d)),
+            ),
+            (
+                "Completed",
+                self.task.completed.map(|d| date_format.format_date(d)),
+            ),
+            ("Id", self.task.block_id.clone()),
+        ];
+        for (name, value) in optional {
+            if let Some(value) = value {
+                lines.push(format!("{:<11}{value}", format!("{name}:")));
+            }
+        }
+        lines.join("\n")
+    }
+
+    /// Returns `file:line` followed by the task line, like `grep -n`.
+    #[must_use]
+    pub fn to_line(&self, config: &TasksConfig) -> String {
+        format!(
+            "{}:{}: {}",
+            self.file.display(),
+            self.task.line_number,
+            self.task.get_fixed_attributes(config, 0).trim_start()
+        )
+    }
+
+    #[must_use]
+    pub fn to_json(&self) -> Value {
+        let mut res = task_to_json(&self.task);
+        res["file"] = json!(self.file);
+        res["breadcrumb"] = json!(self.breadcrumb);
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use chrono::NaiveDate;
+    use pretty_assertions::assert_eq;
+
+    use super::{filter_to_details, find_tasks, similar_tasks};
+    use crate::{
+        filter::parse_search_input,
+        task::{DueDate, State, Task},
+        vault_data::VaultData,
+        TasksConfig,
+    };
+
+    fn vault() -> VaultData {
+        VaultData::Directory(
+            "./vault".to_owned(),
+            vec![VaultData::Directory(
+                "projects".to_owned(),
+                vec![VaultData::Directory(
+                    "work.md".to_owned(),
+                    vec![VaultData::Header(
+                        1,
+                        "Meeting".to_owned(),
+                        vec![VaultData::Task(Task {
+                            name: "send notes".to_owned(),
+                            filename: "work.md".to_owned(),
+                            line_number: 3,
+                            tags: Some(vec!["work".to_owned()]),
+                            description: Some("to everyone".to_owned()),
+                            due_date: DueDate::Day(NaiveDate::from_ymd_opt(2020, 1, 2).unwrap()),
+                            block_id: Some("notes".to_owned()),
+                            subtasks: vec![Task {
+                                name: "write notes".to_owned(),
+                                filename: "work.md".to_owned(),
+                                line_number: 5,
+                                state: State::Done,
+                                ..Default::default()
+                            }],
+                            ..Default::default()
+                        })],
+                    )],
+                )],
+            )],
+        )
+    }
+
+    fn config() -> TasksConfig {
+        TasksConfig {
+            indent_length: 2,
+            use_american_format: false,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_find_tasks() {
+        let res = find_tasks(&vault(), "^notes", &config());
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].task.name, "send notes");
+        assert_eq!(res[0].file, PathBuf::from("./vault/projects/work.md"));
+        assert_eq!(res[0].breadcrumb, vec!["projects", "work", "Meeting"]);
+
+        let res = find_tasks(&vault(), "write", &config());
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].task.line_number, 5);
+        assert!(find_tasks(&vault(), "nothing", &config()).is_empty());
+    }
+
+    #[test]
+    fn test_to_pretty() {
+        assert_eq!(
+            find_tasks(&vault(), "write", &config())[0].to_pretty(&config()),
+            "- [x] write notes\n\n\
+             Path:      projects > work > Meeting\n\
+             File:      ./vault/projects/work.md:5\n\
+             State:     done"
+        );
+        assert_eq!(
+            find_tasks(&vault(), "notes", &config())[0].to_pretty(&config()),
+            "- [ ] send notes 02/01/2020 #work ^notes\n  to everyone\n  - [x] write notes\n\n\
+             Path:      projects > work > Meeting\n\
+             File:      ./vault/projects/work.md:3\n\
+             State:     todo\n\
+             Due:       02/01/2020\n\
+             Id:        notes"
+        );
+    }
+
+    #[test]
+    fn test_to_json() {
+        let res = find_tasks(&vault(), "^notes", &config());
+        let json = res[0].to_json();
+        assert_eq!(json["due"], "2020-01-02");
+        assert_eq!(json["file"], "./vault/projects/work.md");
+        assert_eq!(json["subtasks"][0]["state"], "done");
+        assert_eq!(json["subtasks"][0]["line"], 5);
+    }
+
+    #[test]
+    fn test_filter_to_details() {
+        let res = filter_to_details(&vault(), &parse_search_input("#work", &config()));
+        assert_eq!(res.len(), 1);
+        assert_eq!(
+            res[0].to_line(&config()),
+            "./vault/projects/work.md:3: - [ ] send notes 02/01/2020 #work ^notes"
+        );
+        // Subtasks are listed on their own too
+        assert_eq!(filter_to_details(&vault(), &Default::default()).len(), 2);
+    }
+
+    #[test]
+    fn test_similar_tasks() {
+        let res = similar_tasks(&vault(), "Send the notes!");
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].task.line_number, 3);
+        assert!(similar_tasks(&vault(), "send invoices").is_empty());
+        // Finished tasks are not duplicates
+        assert!(similar_tasks(&vault(), "write notes").is_empty());
+        assert!(similar_tasks(&vault(), "").is_empty());
+    }
+}
@@ -0,0 +1,149 @@
+use color_eyre::{eyre::bail, Result};
+
+use super::{
+    parser::task::parse_task,
+    task::{DueDate, Task},
+    TasksConfig,
+};
+
+/// Changes of the attributes of tasks, as given to the `edit` command.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TaskEdit {
+    /// Attributes given as task tokens, replacing the ones of the tasks
+    set: Task,
+    add_tags: Vec<String>,
+    remove_tags: Vec<String>,
+    /// `0` removes the priority
+    priority: Option<usize>,
+}
+
+impl TaskEdit {
+    /// `set` holds task tokens, e.g. `tomorrow`, `est:1h` or `every week`. A due date can also be written `due:tomorrow`.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if `set` holds anything but attributes, or if nothing would change.
+    pub fn new(
+        config: &TasksConfig,
+        set: &[String],
+        add_tags: &[String],
+        remove_tags: &[String],
+        priority: Option<usize>,
+    ) -> Result<Self> {
+        let tokens = set
+            .iter()
+            .flat_map(|s| s.split_ascii_whitespace())
+            .map(|token| token.strip_prefix("due:").unwrap_or(token))
+            .collect::<Vec<&str>>()
+            .join(" ");
+        let input = format!("- [{}] {tokens}", config.task_state_markers.todo);
+        let Ok(set) = parse_task(&mut input.as_str(), String::new(), config) else {
+            bail!("Invalid attributes: {tokens}");
+        };
+        if !set.name.is_empty() {
+            bail!("Not an attribute: {}", set.name);
+        }
+        let tag = |t: &String| t.trim().trim_start_matches('#').to_owned();
+        let res = Self {
+            set,
+            add_tags: add_tags.iter().map(tag).collect(),
+            remove_tags: remove_tags.iter().map(tag).collect(),
+            priority,
+        };
+        if res == Self::default() {
+            bail!("Nothing to change, use `--set`, `--add-tag`, `--remove-tag` or `--priority`");
+        }
+        Ok(res)
+    }
+
+    /// Applies the changes to `task`, leaving its other attributes, subtasks and description alone.
+    pub fn apply(&self, task: &mut Task) {
+        let set = &self.set;
+        if set.due_date != DueDate::NoDate {
+            task.due_date = set.due_date.clone();
+        }
+        if set.reminder != DueDate::NoDate {
+            task.reminder = set.reminder.clone();
+        }
+        if set.priority > 0 {
+            task.priority = set.priority;
+            task.inherited_priority = false;
+        }
+        task.is_today |= set.is_today;
+        task.created = set.created.or(task.created);
+        task.completed = set.completed.or(task.completed);
+        task.estimate = set.estimate.or(task.estimate);
+        task.completion = set.completion.or(task.completion);
+        if set.recurrence.is_some() {
+            task.recurrence.clone_from(&set.recurrence);
+        }
+        if set.block_id.is_some() {
+            task.block_id.clone_from(&set.block_id);
+        }
+        if let Some(priority) = self.priority {
+            task.priority = priority;
+            task.inherited_priority = false;
+        }
+
+        let mut tags = task.tags.take().unwrap_or_default();
+        for tag in set.tags.iter().flatten().chain(&self.add_tags) {
+            if !tags.contains(tag) {
+                tags.push(tag.clone());
+            }
+        }
+        tags.retain(|t| !self.remove_tags.contains(t));
+        task.tags = (!tags.is_empty()).then_some(tags);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use pretty_assertions::assert_eq;
+
+    use super::TaskEdit;
+    use crate::{
+        task::{DueDate, Task},
+        TasksConfig,
+    };
+
+    #[test]
+    fn test_task_edit() {
+        let config = TasksConfig::default();
+        let mut task = Task {
+            name: "review PR".to_owned(),
+            tags: Some(vec!["work".to_owned(), "later".to_owned()]),
+            estimate: Some(30),
+            priority: 2,
+            inherited_priority: true,
+            ..Default::default()
+        };
+        let edit = TaskEdit::new(
+            &config,
+            &["due:10/03/2025".to_owned(), "est:1h #code".to_owned()],
+            &["#review".to_owned(), "work".to_owned()],
+            &["later".to_owned()],
+            Some(3),
+        )
+        .unwrap();
+        edit.apply(&mut task);
+        assert_eq!(
+            task,
+            Task {
+                name: "review PR".to_owned(),
+                tags: Some(vec![
+                    "work".to_owned(),
+                    "code".to_owned(),
+                    "review".to_owned()
+                ]),
+                due_date: DueDate::Day(NaiveDate::from_ymd_opt(2025, 3, 10).unwrap()),
+                estimate: Some(60),
+                priority: 3,
+                ..Default::default()
+            }
+        );
+
+        assert!(TaskEdit::new(&config, &["not a token".to_owned()], &[], &[], None).is_err());
+        assert!(TaskEdit::new(&config, &[], &[], &[], None).is_err());
+    }
+}
@@ -0,0 +1,630 @@
+use chrono::NaiveDateTime;
+
+use super::{
+    details::{filter_to_details, state_name},
+    filter::{filter, Filter},
+    recurrence::Recurrence,
+    sorter::SortKey,
+    task::{DueDate, State, Task},
+    vault_data::VaultData,
+    TasksConfig,
+};
+
+/// Appends the lines of a task, its description and its subtasks.
+fn task_lines(task: &Task, config: &TasksConfig, depth: usize, res: &mut Vec<String>) {
+    let indent_length = depth * config.indent_length;
+    res.push(task.get_fixed_attributes(config, indent_length));
+    if let Some(description) = &task.description {
+        let indent = " ".repeat(indent_length + config.indent_length);
+        res.extend(description.lines().map(|l| format!("{indent}{l}")));
+    }
+    for subtask in &task.subtasks {
+        task_lines(subtask, config, depth + 1, res);
+    }
+}
+
+/// Collects top level tasks along with the path of directories, files and headers leading to them.
+fn collect_tasks<'a>(
+    vault_data: &'a VaultData,
+    path: &mut Vec<String>,
+    res: &mut Vec<(Vec<String>, &'a Task)>,
+) {
+    match vault_data {
+        VaultData::Directory(name, children) | VaultData::Header(_, name, children) => {
+            path.push(name.trim_end_matches(".md").to_owned());
+            for child in children {
+                collect_tasks(child, path, res);
+            }
+            path.pop();
+        }
+        VaultData::Task(task) => res.push((path.clone(), task)),
+    }
+}
+
+/// Writes the tasks of `vault_data` matching `task_filter` as a Markdown checklist.
+///
+/// With `breadcrumbs`, tasks are grouped under headers showing the files and headers they come from.
+/// Top level tasks are sorted by the sort keys of the filter, if any.
+#[must_use]
+pub fn to_markdown(
+    vault_data: &VaultData,
+    task_filter: &Filter,
+    config: &TasksConfig,
+    breadcrumbs: bool,
+) -> String {
+    let Some(filtered) = filter(vault_data, task_filter) else {
+        return String::new();
+    };
+    let mut tasks = vec![];
+    // The name of the root is the path of the vault
+    match &filtered {
+        VaultData::Directory(_, children) => children
+            .iter()
+            .for_each(|c| collect_tasks(c, &mut vec![], &mut tasks)),
+        _ => collect_tasks(&filtered, &mut vec![], &mut tasks),
+    }
+    if !task_filter.sort.is_empty() {
+        tasks.sort_by(|(p1, t1), (p2, t2)| {
+            SortKey::cmp_tasks(
+                &task_filter.sort,
+                (p1, t1),
+                (p2, t2),
+                config.untimed_tasks_last,
+            )
+        });
+    }
+
+    // Consecutive tasks with the same path are grouped
+    let mut groups: Vec<(Vec<String>, Vec<String>)> = vec![];
+    for (path, task) in tasks {
+        let mut lines = vec![];
+        task_lines(task, config, 0, &mut lines);
+        match groups.last_mut() {
+            Some((group_path, group_lines)) if *group_path == path => {
+                group_lines.append(&mut lines);
+            }
+            _ => groups.push((path, lines)),
+        }
+    }
+
+    if !breadcrumbs {
+        return groups
+            .into_iter()
+            .flat_map(|(_, lines)| lines)
+            .map(|l| l + "\n")
+            .collect();
+    }
+    groups
+        .into_iter()
+        .map(|(path, lines)| {
+            let mut res = String::new();
+            if !path.is_empty() {
+                res.push_str(&format!("### {}\n\n", path.join(" > ")));
+            }
+            for line in lines {
+                res.push_str(&line);
+                res.push('\n');
+            }
+            res
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Column of the CSV export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsvColumn {
+    Name,
+    State,
+    Due,
+    Tags,
+    Priority,
+    /// File of the task, relative to the vault
+    Path,
+    Line,
+}
+
+impl CsvColumn {
+    const fn header(self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::State => "state",
+            Self::Due => "due",
+            Self::Tags => "tags",
+            Self::Priority => "priority",
+            Self::Path => "path",
+            Self::Line => "line",
+        }
+    }
+}
+
+/// Quotes `value` if it holds the separator, a quote or a line break.
+fn csv_field(value: &str, separator: char) -> String {
+    if value.contains([separator, '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+/// Writes the tasks of `vault_data` matching `task_filter`, subtasks included, as CSV with a header, or TSV if `separator` is a tab.
+///
+/// Due dates are written `2025-03-01` or `2025-03-01 09:30`, tags are separated by spaces and a priority of 0 is left empty.
+/// Tasks are in file order, or sorted by the sort keys of the filter, if any.
+#[must_use]
+pub fn to_csv(
+    vault_data: &VaultData,
+    task_filter: &Filter,
+    config: &TasksConfig,
+    columns: &[CsvColumn],
+    separator: char,
+) -> String {
+    let mut tasks = filter_to_details(vault_data, task_filter);
+    tasks.sort_by(|a, b| {
+        a.file
+            .cmp(&b.file)
+            .then(a.task.line_number.cmp(&b.task.line_number))
+    });
+    if !task_filter.sort.is_empty() {
+        tasks.sort_by(|d1, d2| {
+            SortKey::cmp_tasks(
+                &task_filter.sort,
+                (&d1.breadcrumb, &d1.task),
+                (&d2.breadcrumb, &d2.task),
+                config.untimed_tasks_last,
+            )
+        });
+    }
+    let separator_str = separator.to_string();
+    let mut res = columns
+        .iter()
+        .map(|c| c.header())
+        .collect::<Vec<&str>>()
+        .join(&separator_str);
+    res.push('\n');
+    for details in tasks {
+        let task = &details.task;
+        let row = columns
+            .iter()
+            .map(|column| {
+                let value = match column {
+                    CsvColumn::Name => task.name.clone(),
+                    CsvColumn::State => state_name(&task.state),
+                    CsvColumn::Due => match task.due_date {
+                        DueDate::NoDate => String::new(),
+                        DueDate::Day(date) => date.format("%Y-%m-%d").to_string(),
+                        DueDate::DayTime(date_time) => {
+                            date_time.format("%Y-%m-%d %H:%M").to_string()
+                        }
+                    },
+                    CsvColumn::Tags => task
+                        .tags
+                        .iter()
+                        .flatten()
+                        .chain(&task.inherited_tags)
+                        .cloned()
+                        .collect::<Vec<String>>()
+                        .join(" "),
+                    CsvColumn::Priority if task.priority == 0 => String::new(),
+                    CsvColumn::Priority => task.priority.to_string(),
+                    CsvColumn::Path => details
+                        .file
+                        .strip_prefix(&config.vault_path)
+                        .unwrap_or(&details.file)
+                        .display()
+                        .to_string(),
+                    CsvColumn::Line => task.line_number.to_string(),
+                };
+                csv_field(&value, separator)
+            })
+            .collect::<Vec<String>>();
+        res.push_str(&row.join(&separator_str));
+        res.push('\n');
+    }
+    res
+}
+
+/// Escapes `text` for an iCalendar text value.
+fn ical_escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Appends `line` folded at 75 octets, as iCalendar requires, with a CRLF line ending.
+fn push_ical_line(res: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            res.push_str("\r\n ");
+            // The leading space counts
+            width = 1;
+        }
+        res.push(c);
+        width += c.len_utf8();
+    }
+    res.push_str("\r\n");
+}
+
+/// Value of a date property: a date alone, or a floating local time.
+fn ical_date(date: &DueDate) -> Option<String> {
+    match date {
+        DueDate::NoDate => None,
+        DueDate::Day(date) => Some(format!(";VALUE=DATE:{}", date.format("%Y%m%d"))),
+        DueDate::DayTime(date_time) => Some(format!(
+            ";VALUE=DATE-TIME:{}",
+            date_time.format("%Y%m%dT%H%M%S")
+        )),
+    }
+}
+
+fn ical_rrule(recurrence: Recurrence) -> String {
+    let (freq, interval) = match recurrence {
+        Recurrence::Days(n) => ("DAILY", n),
+        Recurrence::Weeks(n) => ("WEEKLY", n),
+        // Due on that day of the week already
+        Recurrence::Weekday(_) => ("WEEKLY", 1),
+        Recurrence::Months(n) => ("MONTHLY", n),
+        Recurrence::Years(n) => ("YEARLY", n),
+    };
+    format!("RRULE:FREQ={freq};INTERVAL={interval}")
+}
+
+/// Writes the tasks of `vault_data` matching `task_filter` that have a due date as an iCalendar feed of `VTODO`s.
+///
+/// Recurring tasks repeat with a `RRULE` and reminders become alarms.
+/// `UID`s are the block identifiers of the tasks if any, their file and line otherwise. `now` is in UTC.
+#[must_use]
+pub fn to_ical(
+    vault_data: &VaultData,
+    task_filter: &Filter,
+    config: &TasksConfig,
+    now: NaiveDateTime,
+) -> String {
+    let mut res = String::new();
+    push_ical_line(&mut res, "BEGIN:VCALENDAR");
+    push_ical_line(&mut res, "VERSION:2.0");
+    push_ical_line(&mut res, "PRODID:-//vault-tasks//EN");
+    push_ical_line(&mut res, "CALSCALE:GREGORIAN");
+    for details in filter_to_details(vault_data, task_filter) {
+        let task = &details.task;
+        let Some(due) = ical_date(&task.due_date) else {
+            continue;
+        };
+        let file = details
+            .file
+            .strip_prefix(&config.vault_path)
+            .unwrap_or(&details.file);
+        let uid = task.block_id.clone().unwrap_or_else(|| {
+            format!("{}-{}", file.display(), task.line_number)
+                .chars()
+                .map(|c| if c.is_alphanumeric() { c } else { '-' })
+                .collect()
+        });
+        let mut lines = vec![
+            "BEGIN:VTODO".to_owned(),
+            format!("UID:{uid}@vault-tasks"),
+            format!("DTSTAMP:{}", now.format("%Y%m%dT%H%M%SZ")),
+            format!("SUMMARY:{}", ical_escape(&task.name)),
+            format!("DUE{due}"),
+            format!(
+                "STATUS:{}",
+                match &task.state {
+                    State::ToDo => "NEEDS-ACTION",
+                    State::Incomplete => "IN-PROCESS",
+                    State::Done => "COMPLETED",
+                    State::Canceled => "CANCELLED",
+                    State::Custom(custom) if custom.finished => "COMPLETED",
+                    State::Custom(_) => "NEEDS-ACTION",
+                }
+            ),
+        ];
+        if let Some(description) = &task.description {
+            lines.push(format!("DESCRIPTION:{}", ical_escape(description)));
+        }
+        if task.priority > 0 {
+            lines.push(format!("PRIORITY:{}", task.priority.min(9)));
+        }
+        let tags = task
+            .tags
+            .iter()
+            .flatten()
+            .chain(&task.inherited_tags)
+            .map(|t| ical_escape(t))
+            .collect::<Vec<String>>();
+        if !tags.is_empty() {
+            lines.push(format!("CATEGORIES:{}", tags.join(",")));
+        }
+        if let Some(completed) = task.completed {
+            lines.push(format!("COMPLETED:{}T000000", completed.format("%Y%m%d")));
+        }
+        if let Some(percent) = task.completion {
+            lines.push(format!("PERCENT-COMPLETE:{percent}"));
+        }
+        if let Some(recurrence) = task.recurrence {
+            lines.push(ical_rrule(recurrence));
+        }
+        lines.push(format!(
+            "X-VAULT-TASKS-LOCATION:{}:{}",
+            file.display(),
+            task.line_number
+        ));
+        if let Some(trigger) = ical_date(&task.reminder) {
+            lines.extend([
+                "BEGIN:VALARM".to_owned(),
+                "ACTION:DISPLAY".to_owned(),
+                format!("DESCRIPTION:{}", ical_escape(&task.name)),
+                format!("TRIGGER{trigger}"),
+                "END:VALARM".to_owned(),
+            ]);
+        }
+        lines.push("END:VTODO".to_owned());
+        for line in lines {
+            push_ical_line(&mut res, &line);
+        }
+    }
+    push_ical_line(&mut res, "END:VCALENDAR");
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::{push_ical_line, to_csv, to_ical, to_markdown, CsvColumn};
+    use chrono::NaiveDate;
+
+    use crate::{
+        filter::{parse_search_input, Filter},
+        recurrence::Recurrence,
+        task::{DueDate, State, Task},
+        vault_data::VaultData,
+        TasksConfig,
+    };
+
+    fn vault() -> VaultData {
+        VaultData::Directory(
+            "./vault".to_owned(),
+            vec![
+                VaultData::Directory(
+                    "work.md".to_owned(),
+                    vec![VaultData::Header(
+                        1,
+                        "Meeting".to_owned(),
+                        vec![
+                            VaultData::Task(Task {
+                                name: "send notes".to_owned(),
+                                tags: Some(vec!["work".to_owned()]),
+                                description: Some("to everyone".to_owned()),
+                                subtasks: vec![Task {
+                                    name: "write notes".to_owned(),
+                                    state: State::Done,
+                                    ..Default::default()
+                                }],
+                                ..Default::default()
+                            }),
+                            VaultData::Task(Task {
+                                name: "book room".to_owned(),
+                                priority: 2,
+                                ..Default::default()
+                            }),
+                        ],
+                    )],
+                ),
+                VaultData::Directory(
+                    "home.md".to_owned(),
+                    vec![VaultData::Task(Task {
+                        name: "water plants".to_owned(),
+                        ..Default::default()
+                    })],
+                ),
+            ],
+        )
+    }
+
+    fn config() -> TasksConfig {
+        TasksConfig {
+            indent_length: 2,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_to_markdown() {
+        assert_eq!(
+            to_markdown(&vault(), &Filter::default(), &config(), false),
+            "- [ ] send notes #work\n  to everyone\n  - [x] write notes\n- [ ] book room p2\n- [ ] water plants\n"
+        );
+    }
+
+    #[test]
+    fn test_to_markdown_breadcrumbs() {
+        assert_eq!(
+            to_markdown(&vault(), &Filter::default(), &config(), true),
+            "### work > Meeting\n\n- [ ] send notes #work\n  to everyone\n  - [x] write notes\n- [ ] book room p2\n\n### home\n\n- [ ] water plants\n"
+        );
+    }
+
+    #[test]
+    fn test_to_markdown_filtered() {
+        let filter = parse_search_input("plants", &config());
+        assert_eq!(
+            to_markdown(&vault(), &filter, &config(), true),
+            "### home\n\n- [ ] water plants\n"
+        );
+        let filter = parse_search_input("nothing matches", &config());
+        assert_eq!(to_markdown(&vault(), &filter, &config(), true), "");
+    }
+
+    #[test]
+    fn test_to_markdown_sorted() {
+        let filter = parse_search_input("sort:path,name", &config());
+        assert_eq!(
+            to_markdown(&vault(), &filter, &config(), true),
+            "### home\n\n- [ ] water plants\n\n### work > Meeting\n\n- [ ] book room p2\n- [ ] send notes #work\n  to everyone\n  - [x] write notes\n"
+        );
+    }
+
+    #[test]
+    fn test_push_ical_line() {
+        let mut res = String::new();
+        push_ical_line(&mut res, &format!("SUMMARY:{}", "é".repeat(40)));
+        let lines = res.split("\r\n").collect::<Vec<&str>>();
+        assert_eq!(lines.len(), 3);
+        assert!(lines.iter().all(|l| l.len() <= 75));
+        assert_eq!(
+            res.replace("\r\n ", ""),
+            format!("SUMMARY:{}\r\n", "é".repeat(40))
+        );
+    }
+
+    #[test]
+    fn test_to_ical() {
+        let vault = VaultData::Directory(
+            "./vault".to_owned(),
+            vec![VaultData::Directory(
+                "home.md".to_owned(),
+                vec![
+                    VaultData::Task(Task {
+                        name: "pay rent, on time".to_owned(),
+                        filename: "home.md".to_owned(),
+                        line_number: 3,
+                        due_date: DueDate::Day(NaiveDate::from_ymd_opt(2025, 3, 1).unwrap()),
+                        recurrence: Some(Recurrence::Months(1)),
+                        tags: Some(vec!["home".to_owned()]),
+                        priority: 1,
+                        ..Default::default()
+                    }),
+                    VaultData::Task(Task {
+                        name: "call mom".to_owned(),
+                        filename: "home.md".to_owned(),
+                        state: State::Done,
+                        due_date: DueDate::DayTime(
+                            NaiveDate::from_ymd_opt(2025, 3, 2)
+                                .unwrap()
+                                .and_hms_opt(18, 30, 0)
+                                .unwrap(),
+                        ),
+                        reminder: DueDate::DayTime(
+                            NaiveDate::from_ymd_opt(2025, 3, 2)
+                                .unwrap()
+                                .and_hms_opt(18, 0, 0)
+                                .unwrap(),
+                        ),
+                        block_id: Some("mom".to_owned()),
+                        ..Default::default()
+                    }),
+                    VaultData::Task(Task {
+                        name: "no date".to_owned(),
+                        filename: "home.md".to_owned(),
+                        ..Default::default()
+                    }),
+                ],
+            )],
+        );
+        let config = TasksConfig {
+            vault_path: "./vault".into(),
+            ..config()
+        };
+        let now = NaiveDate::from_ymd_opt(2025, 2, 1)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap();
+        assert_eq!(
+            to_ical(&vault, &Filter::default(), &config, now).replace("\r\n", "\n"),
+            "BEGIN:VCALENDAR
+VERSION:2.0
+PRODID:-//vault-tasks//EN
+CALSCALE:GREGORIAN
+BEGIN:VTODO
+UID:home-md-3@vault-tasks
+DTSTAMP:20250201T120000Z
+SUMMARY:pay rent\\, on time
+DUE;VALUE=DATE:20250301
+STATUS:NEEDS-ACTION
+PRIORITY:1
+CATEGORIES:home
+RRULE:FREQ=MONTHLY;INTERVAL=1
+X-VAULT-TASKS-LOCATION:home.md:3
+END:VTODO
+BEGIN:VTODO
+UID:mom@vault-tasks
+DTSTAMP:20250201T120000Z
+SUMMARY:call mom
+DUE;VALUE=DATE-TIME:20250302T183000
+STATUS:COMPLETED
+X-VAULT-TASKS-LOCATION:home.md:1
+BEGIN:VALARM
+ACTION:DISPLAY
+DESCRIPTION:call mom
+TRIGGER;VALUE=DATE-TIME:20250302T180000
+END:VALARM
+END:VTODO
+END:VCALENDAR
+"
+        );
+    }
+
+    #[test]
+    fn test_to_csv() {
+        let vault = VaultData::Directory(
+            "./vault".to_owned(),
+            vec![VaultData::Directory(
+                "work.md".to_owned(),
+                vec![VaultData::Task(Task {
+                    name: "review \"v2\", then ship".to_owned(),
+                    filename: "work.md".to_owned(),
+                    line_number: 2,
+                    due_date: DueDate::Day(NaiveDate::from_ymd_opt(2025, 3, 1).unwrap()),
+                    tags: Some(vec!["work".to_owned(), "release".to_owned()]),
+                    priority: 2,
+                    subtasks: vec![Task {
+                        name: "write notes".to_owned(),
+                        filename: "work.md".to_owned(),
+                        line_number: 3,
+                        state: State::Done,
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                })],
+            )],
+        );
+        let config = TasksConfig {
+            vault_path: "./vault".into(),
+            ..config()
+        };
+        assert_eq!(
+            to_csv(
+                &vault,
+                &Filter::default(),
+                &config,
+                &[
+                    CsvColumn::Name,
+                    CsvColumn::State,
+                    CsvColumn::Due,
+                    CsvColumn::Tags,
+                    CsvColumn::Priority,
+                    CsvColumn::Path,
+                    CsvColumn::Line
+                ],
+                ','
+            ),
+            "name,state,due,tags,priority,path,line
+\"review \"\"v2\"\", then ship\",todo,2025-03-01,work release,2,work.md,2
+write notes,done,,,,work.md,3
+"
+        );
+        let filter = parse_search_input("sort:name", &config);
+        assert_eq!(
+            to_csv(
+                &vault,
+                &filter,
+                &config,
+                &[CsvColumn::Name, CsvColumn::Line],
+                '\t'
+            ),
+            "name\tline\n\"review \"\"v2\"\", then ship\"\t2\nwrite notes\t3\n"
+        );
+    }
+}
@@ -0,0 +1,2158 @@
+use std::cmp::Ordering;
+
+use chrono::{NaiveDate, NaiveDateTime, TimeDelta};
+use serde::Deserialize;
+
+use crate::task::DueDate;
+use crate::{TaskMarkerConfig, TasksConfig};
+
+use super::{
+    parser::task::parse_task,
+    sorter::SortKey,
+    task::{State, Task},
+    vault_data::VaultData,
+};
+
+/// Matches tasks due sooner (`due<2d`, `before:2d`) or later (`due>2d`, `after:2d`) than a duration from now.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct DuePredicate {
+    pub before: bool,
+    /// Whether tasks due exactly at the end of the duration match, from `due<=` and `due>=`
+    pub inclusive: bool,
+    pub delta: TimeDelta,
+}
+
+impl DuePredicate {
+    /// Parses a predicate like `due<2d` or `before:2d`, units are `h`, `d` and `w`.
+    fn parse(word: &str) -> Option<Self> {
+        let (before, inclusive, rest) = if let Some(rest) = word.strip_prefix("before:") {
+            (true, false, rest)
+        } else if let Some(rest) = word.strip_prefix("after:") {
+            (false, false, rest)
+        } else {
+            match CompareOp::parse(word.strip_prefix("due")?)? {
+                (CompareOp::Lt, rest) => (true, false, rest),
+                (CompareOp::Le, rest) => (true, true, rest),
+                (CompareOp::Gt, rest) => (false, false, rest),
+                (CompareOp::Ge, rest) => (false, true, rest),
+                (CompareOp::Eq, _) => return None,
+            }
+        };
+        let unit = rest.chars().last()?;
+        let n = rest[..rest.len() - unit.len_utf8()].parse::<i64>().ok()?;
+        let delta = match unit {
+            'h' => TimeDelta::try_hours(n)?,
+            'd' => TimeDelta::try_days(n)?,
+            'w' => TimeDelta::try_weeks(n)?,
+            _ => return None,
+        };
+        Some(Self {
+            before,
+            inclusive,
+            delta,
+        })
+    }
+
+    fn matches(&self, task: &Task) -> bool {
+        let Some(due) = task.due_date.to_naive_date_time() else {
+            return false;
+        };
+        let remaining = due - chrono::Local::now().naive_local();
+        match (self.before, self.inclusive) {
+            (true, false) => remaining < self.delta,
+            (true, true) => remaining <= self.delta,
+            (false, false) => remaining > self.delta,
+            (false, true) => remaining >= self.delta,
+        }
+    }
+}
+
+/// Whether `task` is unfinished and past its due date, dates without a time are due at the end of the day.
+pub fn is_overdue(task: &Task, now: NaiveDateTime) -> bool {
+    !task.state.is_finished()
+        && match task.due_date {
+            DueDate::NoDate => false,
+            DueDate::Day(date) => date < now.date(),
+            DueDate::DayTime(date_time) => date_time < now,
+        }
+}
+
+/// Whether `task` can be started on `today`, tasks without a start date can.
+pub fn is_started(task: &Task, today: NaiveDate) -> bool {
+    task.start.is_none_or(|start| start <= today)
+}
+
+/// Parses `started` and `unstarted`, matching tasks whose start date has come or is still to come.
+fn parse_started_token(word: &str) -> Option<bool> {
+    match word {
+        "started" => Some(true),
+        "unstarted" => Some(false),
+        _ => None,
+    }
+}
+
+/// How the state of a search matches the state of tasks.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StateMatchMode {
+    /// To-Do matches Incomplete tasks and Done matches Canceled tasks
+    #[default]
+    Grouped,
+    /// Only tasks with the same state match
+    Exact,
+}
+
+/// Parses a state predicate like `is:canceled` or `state:forwarded`, it always matches the exact state.
+fn parse_state_predicate(word: &str, markers: &TaskMarkerConfig) -> Option<State> {
+    markers.state_named(
+        word.strip_prefix("is:")
+            .or_else(|| word.strip_prefix("state:"))?,
+    )
+}
+
+/// Comparison operator of a `Comparison`.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum CompareOp {
+    Lt,
+    Le,
+    Eq,
+    Ge,
+    Gt,
+}
+
+impl CompareOp {
+    /// Parses the operator at the start of `input`, returns it and the rest of the input.
+    fn parse(input: &str) -> Option<(Self, &str)> {
+        [
+            ("<=", Self::Le),
+            (">=", Self::Ge),
+            ("<", Self::Lt),
+            (">", Self::Gt),
+            ("=", Self::Eq),
+        ]
+        .into_iter()
+        .find_map(|(symbol, op)| input.strip_prefix(symbol).map(|rest| (op, rest)))
+    }
+
+    /// Whether `value.cmp(reference)` satisfies the operator.
+    fn holds(self, ordering: Ordering) -> bool {
+        match self {
+            Self::Lt => ordering.is_lt(),
+            Self::Le => ordering.is_le(),
+            Self::Eq => ordering.is_eq(),
+            Self::Ge => ordering.is_ge(),
+            Self::Gt => ordering.is_gt(),
+        }
+    }
+}
+
+/// Compares an attribute of tasks to a value, e.g. `due<2025-12-01` or `priority>=2`.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Comparison {
+    /// Tasks without a due date never match
+    Due(CompareOp, NaiveDate),
+    Priority(CompareOp, usize),
+}
+
+impl Comparison {
+    /// Parses a comparison like `due<=2025-12-01`, `before:2025-12-01`, `priority>1` or `p>1`.
+    fn parse(word: &str) -> Option<Self> {
+        let parse_date = |date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok();
+        if let Some(date) = word.strip_prefix("before:") {
+            return Some(Self::Due(CompareOp::Lt, parse_date(date)?));
+        }
+        if let Some(date) = word.strip_prefix("after:") {
+            return Some(Self::Due(CompareOp::Gt, parse_date(date)?));
+        }
+        if let Some(rest) = word.strip_prefix("due") {
+            let (op, value) = CompareOp::parse(rest)?;
+            return Some(Self::Due(op, parse_date(value)?));
+        }
+        let rest = word
+            .strip_prefix("priority")
+            .or_else(|| word.strip_prefix('p'))?;
+        let (op, value) = CompareOp::parse(rest)?;
+        Some(Self::Priority(op, value.parse().ok()?))
+    }
+
+    fn matches(&self, task: &Task) -> bool {
+        match self {
+            Self::Due(op, date) => task
+                .due_date
+                .to_naive_date_time()
+                .is_some_and(|due| op.holds(due.date().cmp(date))),
+            Self::Priority(op, priority) => op.holds(task.priority.cmp(priority)),
+        }
+    }
+}
+
+/// Boolean combination of searches, built when a search uses `AND`, `OR` or `NOT`.
+#[derive(PartialEq, Eq, Debug)]
+pub enum Query {
+    And(Vec<Query>),
+    Or(Vec<Query>),
+    Not(Box<Query>),
+    /// A plain search, e.g. `#work review p1`
+    Term(Box<Filter>),
+}
+
+impl Query {
+    /// Whether `task` matches, `location` is only checked if it is known.
+    fn matches(&self, task: &Task, location: Option<Location>) -> bool {
+        match self {
+            Self::And(queries) => queries.iter().all(|q| q.matches(task, location)),
+            Self::Or(queries) => queries.iter().any(|q| q.matches(task, location)),
+            Self::Not(query) => !query.matches(task, location),
+            Self::Term(filter) => filter.matches_at(task, location),
+        }
+    }
+}
+
+/// Token of a query, see `parse_query`.
+#[derive(PartialEq, Eq, Debug)]
+enum QueryToken {
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+    Word(String),
+}
+
+/// Whether a search uses boolean operators and must be parsed as a query.
+fn is_query(input: &str) -> bool {
+    tokenize_query(input)
+        .iter()
+        .any(|t| matches!(t, QueryToken::And | QueryToken::Or | QueryToken::Not))
+}
+
+/// Splits a query in tokens, quoted text (`in:"a b"`) stays in a single word.
+fn tokenize_query(input: &str) -> Vec<QueryToken> {
+    let mut words = vec![];
+    let mut word = String::new();
+    let mut quoted = false;
+    for c in input.chars() {
+        if c == '"' {
+            quoted = !quoted;
+        }
+        if c.is_whitespace() && !quoted {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+        } else {
+            word.push(c);
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+
+    let mut res = vec![];
+    for word in words {
+        let mut word = word.as_str();
+        while let Some(rest) = word.strip_prefix('(') {
+            res.push(QueryToken::Open);
+            word = rest;
+        }
+        let mut closing = 0;
+        while let Some(rest) = word.strip_suffix(')') {
+            closing += 1;
+            word = rest;
+        }
+        match word {
+            "" => (),
+            "AND" => res.push(QueryToken::And),
+            "OR" => res.push(QueryToken::Or),
+            "NOT" => res.push(QueryToken::Not),
+            _ => res.push(QueryToken::Word(word.to_owned())),
+        }
+        res.extend((0..closing).map(|_| QueryToken::Close));
+    }
+    res
+}
+
+/// Parses a query, `NOT` binds tighter than `AND` which binds tighter than `OR`.
+///
+/// Consecutive words form a single search and searches next to each other are combined with `AND`.
+/// Incomplete queries are accepted while they are being typed: missing parentheses are closed and dangling operators ignored.
+fn parse_query(input: &str, config: &TasksConfig) -> Option<Query> {
+    let tokens = tokenize_query(input);
+    let mut parser = QueryParser {
+        tokens: &tokens,
+        position: 0,
+        config,
+    };
+    let mut res = parser.parse_or();
+    // Skip unbalanced closing parentheses
+    while parser.position < tokens.len() {
+        parser.position += 1;
+        if let Some(rest) = parser.parse_or() {
+            res = Some(match res {
+                Some(query) => Query::And(vec![query, rest]),
+                None => rest,
+            });
+        }
+    }
+    res
+}
+
+struct QueryParser<'a> {
+    tokens: &'a [QueryToken],
+    position: usize,
+    config: &'a TasksConfig,
+}
+
+impl QueryParser<'_> {
+    fn peek(&self) -> Option<&QueryToken> {
+        self.tokens.get(self.position)
+    }
+    fn parse_or(&mut self) -> Option<Query> {
+        let mut queries = vec![];
+        queries.extend(self.parse_and());
+        while self.peek() == Some(&QueryToken::Or) {
+            self.position += 1;
+            queries.extend(self.parse_and());
+        }
+        match queries.len() {
+            0 => None,
+            1 => queries.pop(),
+            _ => Some(Query::Or(queries)),
+        }
+    }
+    fn parse_and(&mut self) -> Option<Query> {
+        let mut queries = vec![];
+        loop {
+            match self.peek() {
+                Some(QueryToken::And) => self.position += 1,
+                Some(QueryToken::Not | QueryToken::Open | QueryToken::Word(_)) => {
+                    queries.extend(self.parse_not());
+                }
+                _ => break,
+            }
+        }
+        match queries.len() {
+            0 => None,
+            1 => queries.pop(),
+            _ => Some(Query::And(queries)),
+        }
+    }
+    fn parse_not(&mut self) -> Option<Query> {
+        match self.peek()? {
+            QueryToken::Not => {
+                self.position += 1;
+                self.parse_not().map(|q| Query::Not(Box::new(q)))
+            }
+            QueryToken::Open => {
+                self.position += 1;
+                let res = self.parse_or();
+                if self.peek() == Some(&QueryToken::Close) {
+                    self.position += 1;
+                }
+                res
+            }
+            QueryToken::Word(_) => {
+                let mut words = vec![];
+                let tokens = self.tokens;
+                while let Some(QueryToken::Word(word)) = tokens.get(self.position) {
+                    words.push(word.as_str());
+                    self.position += 1;
+                }
+                Some(Query::Term(Box::new(parse_search_input(
+                    &words.join(" "),
+                    self.config,
+                ))))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Default, PartialEq, Eq, Debug)]
+pub struct Filter {
+    pub task: Task,
+    state: Option<State>,
+    /// Whether `state` only matches the same state, see `StateMatchMode`
+    exact_state: bool,
+    due: Vec<DuePredicate>,
+    comparisons: Vec<Comparison>,
+    /// Only unfinished tasks past their due date match, from the `overdue` token
+    overdue: bool,
+    /// Sort keys from the `sort:` token, empty if not specified
+    pub sort: Vec<SortKey>,
+    /// Only tasks under this path (directories, file and headers) match, from the `in:` token
+    path: Vec<String>,
+    /// Only tasks under these directories and file match, from the `path:` token
+    file_path: Vec<String>,
+    /// Only tasks under a header containing this match, from the `heading:` token
+    heading: Option<String>,
+    /// Whether dates must be equal, otherwise a date matches every time of the same day
+    exact_dates: bool,
+    /// Set when the search uses boolean operators, the fields above are then unused except `sort`
+    query: Option<Query>,
+    /// Searches negated with a `-` prefix (`-#tag`, `-p5`), tasks matching one of them don't match
+    excluded: Vec<Filter>,
+    /// Whether tasks must have started or not, see `is_started`, from the `started` and `unstarted` tokens or
+    /// `hide_not_started`
+    started: Option<bool>,
+}
+
+/// Names of the directories, file and headers leading to a task, the vault itself first.
+#[derive(Debug, Clone, Copy)]
+struct Location<'a> {
+    path: &'a [String],
+    /// Number of headers at the end of `path`
+    headers: usize,
+}
+
+impl Location<'_> {
+    fn directories(&self) -> &[String] {
+        &self.path[1.min(self.path.len())..self.path.len() - self.headers]
+    }
+    fn headers(&self) -> &[String] {
+        &self.path[self.path.len() - self.headers..]
+    }
+}
+
+/// Whether `name` is the directory or file `component` of a `path:` token, `.md` can be omitted.
+fn component_matches(name: &str, component: &str) -> bool {
+    name == component || name.strip_suffix(".md") == Some(component)
+}
+
+impl Filter {
+    /// Whether tasks found under `path` can match, the first element of `path` is the vault itself.
+    fn path_matches(&self, path: &[String]) -> bool {
+        self.path.is_empty() || path.get(1..).is_some_and(|p| p.starts_with(&self.path))
+    }
+    /// Whether the filter has `in:`, `path:` or `heading:` tokens.
+    fn has_location(&self) -> bool {
+        !self.path.is_empty() || !self.file_path.is_empty() || self.heading.is_some()
+    }
+    /// Whether tasks found at `location` match the `in:`, `path:` and `heading:` tokens.
+    fn location_matches(&self, location: Location) -> bool {
+        let directories = location.directories();
+        self.path_matches(location.path)
+            && directories.len() >= self.file_path.len()
+            && directories
+                .iter()
+                .zip(&self.file_path)
+                .all(|(name, component)| component_matches(name, component))
+            && self.heading.as_ref().is_none_or(|heading| {
+                let heading = heading.to_lowercase();
+                location
+                    .headers()
+                    .iter()
+                    .any(|name| name.to_lowercase().contains(&heading))
+            })
+    }
+    /// Whether tasks under the directory or header at `location` may match, other subtrees are skipped.
+    fn may_match_under(&self, location: Location) -> bool {
+        if self.query.is_some() {
+            return true;
+        }
+        let directories = location.directories();
+        // Headers are only found in files, so the whole `path:` must have been matched
+        (location.headers == 0 || directories.len() >= self.file_path.len())
+            && directories
+                .iter()
+                .zip(&self.file_path)
+                .all(|(name, component)| component_matches(name, component))
+            && location
+                .path
+                .get(1..)
+                .unwrap_or_default()
+                .iter()
+                .zip(&self.path)
+                .all(|(name, component)| name == component)
+    }
+    /// Whether `task` itself matches the filter, subtasks are not considered.
+    pub fn matches(&self, task: &Task) -> bool {
+        self.matches_at(task, None)
+    }
+    /// Same as `matches`, `location` of the task is checked if it is known.
+    fn matches_at(&self, task: &Task, location: Option<Location>) -> bool {
+        if self
+            .started
+            .is_some_and(|started| started != is_started(task, chrono::Local::now().date_naive()))
+        {
+            return false;
+        }
+        match &self.query {
+            Some(query) => query.matches(task, location),
+            None => {
+                filter_task(task, self)
+                    && location.is_none_or(|l| self.location_matches(l))
+                    // An excluded location can only be checked if the location is known
+                    && !self.excluded.iter().any(|excluded| {
+                        (location.is_some() || !excluded.has_location())
+                            && excluded.matches_at(task, location)
+                    })
+            }
+        }
+    }
+}
+
+/// Extracts a token like `in:Projects` or `in:"Projects/notes.md/Weekly review"` from a search.
+///
+/// Returns the value of the token and the rest of the search.
+fn extract_token(input: &str, key: &str) -> Option<(String, String)> {
+    let start = input
+        .match_indices(key)
+        .map(|(i, _)| i)
+        .find(|i| *i == 0 || input[..*i].ends_with(char::is_whitespace))?;
+    let value = &input[start + key.len()..];
+    let (value, end) = if let Some(quoted) = value.strip_prefix('"') {
+        let len = quoted.find('"').unwrap_or(quoted.len());
+        (
+            &quoted[..len],
+            start + key.len() + 1 + (len + 1).min(quoted.len()),
+        )
+    } else {
+        let len = value.find(char::is_whitespace).unwrap_or(value.len());
+        (&value[..len], start + key.len() + len)
+    };
+    Some((
+        value.to_owned(),
+        format!("{}{}", &input[..start], &input[end..]),
+    ))
+}
+
+/// Extracts a path token like `in:Projects/notes.md`, returns its components and the rest of the search.
+fn extract_path(input: &str, key: &str) -> (Vec<String>, String) {
+    let Some((value, rest)) = extract_token(input, key) else {
+        return (vec![], input.to_owned());
+    };
+    let path = value
+        .split('/')
+        .filter(|p| !p.is_empty())
+        .map(str::to_owned)
+        .collect();
+    (path, rest)
+}
+
+/// Extracts the words negated with a `-` prefix like `-#tag` or `-path:"My Archive"`, quotes are kept.
+///
+/// Returns the negated words without their prefix and the rest of the search.
+fn extract_negated(input: &str) -> (Vec<String>, String) {
+    let mut words = vec![];
+    let mut word = String::new();
+    let mut quoted = false;
+    for c in input.chars() {
+        if c.is_whitespace() && !quoted {
+            words.push(std::mem::take(&mut word));
+        } else {
+            if c == '"' {
+                quoted = !quoted;
+            }
+            word.push(c);
+        }
+    }
+    words.push(word);
+    let (negated, rest): (Vec<String>, Vec<String>) = words
+        .into_iter()
+        .filter(|w| !w.is_empty())
+        .partition(|w| w.len() > 1 && w.starts_with('-') && !w.starts_with("--"));
+    if negated.is_empty() {
+        return (negated, input.to_owned());
+    }
+    (
+        negated.into_iter().map(|w| w[1..].to_owned()).collect(),
+        rest.join(" "),
+    )
+}
+
+/// Returns the `in:` token matching `path`, see `extract_token`.
+#[must_use]
+pub fn path_search(path: &[String]) -> String {
+    format!("in:\"{}\"", path.join("/"))
+}
+
+/// Parses a [`Task`] from an input `&str`. Returns the `Task` and whether the input specify a task state (- [X] or - [ ]) or not.
+#[must_use]
+///
+/// Searches using `AND`, `OR` or `NOT` are parsed as a `Query` of such searches, see `parse_query`.
+pub fn parse_search_input(input: &str, config: &TasksConfig) -> Filter {
+    let input = &expand_named_filters(input, config);
+    let started = input.split_whitespace().find_map(parse_started_token);
+    let input = &if input
+        .split_whitespace()
+        .any(|w| parse_started_token(w).is_some())
+    {
+        input
+            .split_whitespace()
+            .filter(|w| parse_started_token(w).is_none())
+            .collect::<Vec<&str>>()
+            .join(" ")
+    } else {
+        input.clone()
+    };
+    if is_query(input) {
+        // Sort keys apply to the whole query
+        let sort = input
+            .split_whitespace()
+            .find_map(SortKey::parse_keys)
+            .unwrap_or_default();
+        let input = input
+            .split_whitespace()
+            .filter(|w| SortKey::parse_keys(w).is_none())
+            .collect::<Vec<&str>>()
+            .join(" ");
+        return Filter {
+            sort,
+            exact_dates: config.exact_date_search,
+            query: Some(parse_query(&input, config).unwrap_or(Query::And(vec![]))),
+            started,
+            ..Default::default()
+        };
+    }
+    let (negated, input) = extract_negated(input);
+    let excluded = negated
+        .iter()
+        .map(|word| parse_search_input(word, config))
+        .collect::<Vec<Filter>>();
+    let (path, input) = extract_path(&input, "in:");
+    let (file_path, input) = extract_path(&input, "path:");
+    let (heading, input) = match extract_token(&input, "heading:") {
+        Some((heading, rest)) => (Some(heading).filter(|h| !h.is_empty()), rest),
+        None => (None, input),
+    };
+    let input = input.as_str();
+    // Predicates, comparisons and sort keys are not task tokens, extract them first
+    let due = input
+        .split_whitespace()
+        .filter_map(DuePredicate::parse)
+        .collect::<Vec<DuePredicate>>();
+    let overdue = input.split_whitespace().any(|w| w == OVERDUE_TOKEN);
+    let state_predicate = input
+        .split_whitespace()
+        .find_map(|w| parse_state_predicate(w, &config.task_state_markers));
+    let comparisons = input
+        .split_whitespace()
+        .filter(|w| DuePredicate::parse(w).is_none())
+        .filter_map(Comparison::parse)
+        .collect::<Vec<Comparison>>();
+    let sort = input
+        .split_whitespace()
+        .find_map(SortKey::parse_keys)
+        .unwrap_or_default();
+    let has_tag_token = input
+        .split_whitespace()
+        .any(|w| parse_tag_token(w).is_some());
+    let input = if !due.is_empty()
+        || overdue
+        || state_predicate.is_some()
+        || !comparisons.is_empty()
+        || !sort.is_empty()
+        || has_tag_token
+    {
+        &input
+            .split_whitespace()
+            .filter(|w| {
+                DuePredicate::parse(w).is_none()
+                    && *w != OVERDUE_TOKEN
+                    && parse_state_predicate(w, &config.task_state_markers).is_none()
+                    && Comparison::parse(w).is_none()
+                    && SortKey::parse_keys(w).is_none()
+            })
+            // `tag:work` is the same as `#work`
+            .map(|w| parse_tag_token(w).map_or_else(|| w.to_owned(), |tag| format!("#{tag}")))
+            .collect::<Vec<String>>()
+            .join(" ")
+    } else {
+        input
+    };
+
+    // Are searching for a specific state ?
+    let has_state = input.starts_with("- [");
+
+    // Make the input parsable, add a task state if needed
+    let input_value = format!("{}{}", if has_state { "" } else { "- [ ]" }, input);
+
+    // Parse the input
+    let task = match parse_task(&mut input_value.as_str(), String::new(), config) {
+        // Searched dates are always resolved against today
+        Ok(t) => Task {
+            relative_dates: vec![],
+            ..t
+        },
+        Err(_e) => Task {
+            name: String::from("Uncomplete search prompt"),
+            ..Default::default()
+        },
+    };
+    Filter {
+        task: task.clone(),
+        exact_state: state_predicate.is_some() || config.state_match_mode == StateMatchMode::Exact,
+        state: state_predicate.or(if has_state { Some(task.state) } else { None }),
+        due,
+        comparisons,
+        overdue,
+        sort,
+        path,
+        file_path,
+        heading,
+        exact_dates: config.exact_date_search,
+        query: None,
+        excluded,
+        started,
+    }
+}
+
+/// Same as `parse_search_input` for the searches of the Explorer and Filter tabs, which hide the tasks not started
+/// yet with `hide_not_started` unless the search has `started` or `unstarted`.
+pub fn parse_tab_search(input: &str, config: &TasksConfig) -> Filter {
+    let mut res = parse_search_input(input, config);
+    if config.hide_not_started {
+        res.started = res.started.or(Some(true));
+    }
+    res
+}
+
+/// Replaced by the saved search of `filters` with the same name, e.g. `filter:inbox`
+pub const NAMED_FILTER_TOKEN: &str = "filter:";
+
+/// Replaces the `filter:name` tokens of `input` by their saved search, unknown names are kept.
+///
+/// Saved searches are grouped in parentheses when the result uses boolean operators.
+fn expand_named_filters(input: &str, config: &TasksConfig) -> String {
+    if config.filters.is_empty() || !input.contains(NAMED_FILTER_TOKEN) {
+        return input.to_owned();
+    }
+    let expand = |grouped: bool| {
+        input
+            .split_whitespace()
+            .map(|word| {
+                match word
+                    .strip_prefix(NAMED_FILTER_TOKEN)
+                    .and_then(|name| config.filters.get(name))
+                {
+                    Some(search) if grouped => format!("({search})"),
+                    Some(search) => search.clone(),
+                    None => word.to_owned(),
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(" ")
+    };
+    let res = expand(false);
+    if is_query(&res) {
+        expand(true)
+    } else {
+        res
+    }
+}
+
+/// Matches unfinished tasks past their due date
+const OVERDUE_TOKEN: &str = "overdue";
+
+/// Parses a tag token like `tag:work`, returning the tag.
+fn parse_tag_token(word: &str) -> Option<&str> {
+    word.strip_prefix("tag:").filter(|tag| !tag.is_empty())
+}
+
+fn filter_task(task: &Task, filter: &Filter) -> bool {
+    let state_match = filter.state.is_none()
+        || filter.state.clone().is_some_and(|state| {
+            // Searching for a custom state only matches it, other states group custom states by `finished`
+            if filter.exact_state || matches!(state, State::Custom(_)) {
+                return state == task.state;
+            }
+            state.is_finished() == task.state.is_finished()
+        });
+
+    let name_match = if filter.task.name.is_empty() {
+        true
+    } else {
+        // for each word of the filter_task, if at least one
+        // matches in the task, then validate
+        filter
+            .task
+            .name
+            .to_lowercase()
+            .split_whitespace()
+            .filter(|w| task.name.to_lowercase().contains(w))
+            .count()
+            > 0
+    };
+
+    let today_flag_match = if filter.task.is_today {
+        task.is_today
+    } else {
+        true
+    };
+
+    let date_match = match (&task.due_date, &filter.task.due_date) {
+        (_, DueDate::NoDate) => true,
+        (task_date, search_date) if filter.exact_dates => task_date == search_date,
+        // Same calendar day
+        (task_date, search_date) => task_date
+            .to_naive_date_time()
+            .zip(search_date.to_naive_date_time())
+            .is_some_and(|(t, s)| t.date() == s.date()),
+    };
+
+    let tags_match = filter
+        .task
+        .tags
+        .clone()
+        .unwrap_or_default()
+        .iter()
+        .all(|t| {
+            task.tags
+                .clone()
+                .unwrap_or_default()
+                .iter()
+                .any(|x| x.to_lowercase().contains(&t.to_lowercase()))
+        });
+
+    let priority_match = if filter.task.priority > 0 {
+        filter.task.priority == task.priority
+    } else {
+        true
+    };
+
+    let due_match = filter.due.iter().all(|due| due.matches(task));
+
+    let overdue_match = !filter.overdue || is_overdue(task, chrono::Local::now().naive_local());
+
+    let comparisons_match = filter.comparisons.iter().all(|c| c.matches(task));
+
+    state_match
+        && name_match
+        && today_flag_match
+        && date_match
+        && tags_match
+        && priority_match
+        && due_match
+        && overdue_match
+        && comparisons_match
+}
+
+fn filter_to_vec_layer(
+    vault_data: &VaultData,
+    task_filter: &Filter,
+    explore_children: bool,
+    path: &mut Vec<String>,
+    headers: usize,
+    res: &mut Vec<(Vec<String>, Task)>,
+) {
+    match vault_data {
+        VaultData::Directory(name, children) | VaultData::Header(_, name, children) => {
+            let headers = headers + usize::from(is_header(vault_data));
+            path.push(name.clone());
+            if task_filter.may_match_under(Location { path, headers }) {
+                for c in children {
+                    filter_to_vec_layer(c, task_filter, explore_children, path, headers, res);
+                }
+            }
+            path.pop();
+        }
+        VaultData::Task(task) => {
+            if explore_children {
+                task.subtasks.iter().for_each(|t| {
+                    filter_to_vec_layer(
+                        &VaultData::Task(t.clone()),
+                        task_filter,
+                        explore_children,
+                        path,
+                        headers,
+                        res,
+                    );
+                });
+            }
+
+            if task_filter.matches_at(task, Some(Location { path, headers })) {
+                res.push((path.clone(), task.clone()));
+            }
+        }
+    }
+}
+
+/// Whether `vault_data` is a header of a file, files parsed as level 0 headers are not.
+const fn is_header(vault_data: &VaultData) -> bool {
+    matches!(vault_data, VaultData::Header(level, _, _) if *level > 0)
+}
+
+/// Will return a `Vec<Task>` matching the given `Filter` from the `VaultData`
+pub fn filter_to_vec(vault_data: &VaultData, filter: &Filter) -> Vec<Task> {
+    filter_to_vec_with_paths(vault_data, filter)
+        .into_iter()
+        .map(|(_, task)| task)
+        .collect()
+}
+
+/// Same as `filter_to_vec`, tasks come with the names of the directories, file and headers leading to them.
+pub fn filter_to_vec_with_paths(
+    vault_data: &VaultData,
+    filter: &Filter,
+) -> Vec<(Vec<String>, Task)> {
+    let mut res = vec![];
+    filter_to_vec_layer(vault_data, filter, true, &mut vec![], 0, &mut res);
+    res
+}
+
+pub fn filter(vault_data: &VaultData, task_filter: &Filter) -> Option<VaultData> {
+    filter_layer(vault_data, task_filter, &mut vec![], 0)
+}
+
+fn filter_layer(
+    vault_data: &VaultData,
+    task_filter: &Filter,
+    path: &mut Vec<String>,
+    headers: usize,
+) -> Option<VaultData> {
+    match vault_data {
+        VaultData::Directory(name, children) | VaultData::Header(_, name, children) => {
+            let headers = headers + usize::from(is_header(vault_data));
+            path.push(name.clone());
+            let mut actual_children = vec![];
+            if task_filter.may_match_under(Location { path, headers }) {
+                for child in children {
+                    if let Some(child) = filter_layer(child, task_filter, path, headers) {
+                        actual_children.push(child);
+                    }
+                }
+            }
+            path.pop();
+            if actual_children.is_empty() {
+                None
+            } else if let VaultData::Header(level, _, _) = vault_data {
+                Some(VaultData::Header(*level, name.to_string(), actual_children))
+            } else {
+                Some(VaultData::Directory(name.to_string(), actual_children))
+            }
+        }
+        VaultData::Task(task) => {
+            if task_filter.matches_at(task, Some(Location { path, headers })) {
+                Some(vault_data.clone())
+            } else {
+                let mut actual_children = vec![];
+                for child in &task.subtasks {
+                    if let Some(VaultData::Task(child)) =
+                        filter_layer(&VaultData::Task(child.clone()), task_filter, path, headers)
+                    {
+                        actual_children.push(child);
+                    }
+                }
+                if actual_children.is_empty() {
+                    return None;
+                }
+                Some(VaultData::Task(Task {
+                    subtasks: actual_children,
+                    ..task.clone()
+                }))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, TimeDelta};
+
+    use crate::{
+        filter::{filter, Filter},
+        task::{DueDate, State, Task},
+        vault_data::VaultData,
+        CustomState, TasksConfig,
+    };
+
+    use super::{
+        filter_to_vec, filter_to_vec_with_paths, parse_search_input, parse_tab_search, path_search,
+        tokenize_query, CompareOp, Comparison, DuePredicate, Location, Query, QueryToken,
+        StateMatchMode,
+    };
+    use crate::sorter::SortKey;
+
+    #[test]
+    fn parse_search_input_test() {
+        let input = "- [ ] #tag today name p5";
+        let config = TasksConfig::default();
+        let res = parse_search_input(input, &config);
+        let expected = Filter {
+            task: Task {
+                due_date: DueDate::Day(chrono::Local::now().date_naive()),
+                name: String::from("name"),
+                priority: 5,
+                state: State::ToDo,
+                tags: Some(vec![String::from("tag")]),
+                ..Default::default()
+            },
+            state: Some(State::ToDo),
+            exact_state: false,
+            due: vec![],
+            comparisons: vec![],
+            overdue: false,
+            sort: vec![],
+            path: vec![],
+            file_path: vec![],
+            heading: None,
+            exact_dates: false,
+            query: None,
+            excluded: vec![],
+            started: None,
+        };
+        assert_eq!(expected, res);
+    }
+
+    #[test]
+    fn parse_search_input_no_state_test() {
+        let input = "#tag today name p5";
+        let config = TasksConfig::default();
+        let res = parse_search_input(input, &config);
+        let expected = Filter {
+            task: Task {
+                due_date: DueDate::Day(chrono::Local::now().date_naive()),
+                name: String::from("name"),
+                priority: 5,
+                state: State::ToDo,
+                tags: Some(vec![String::from("tag")]),
+                ..Default::default()
+            },
+            state: None,
+            exact_state: false,
+            due: vec![],
+            comparisons: vec![],
+            overdue: false,
+            sort: vec![],
+            path: vec![],
+            file_path: vec![],
+            heading: None,
+            exact_dates: false,
+            query: None,
+            excluded: vec![],
+            started: None,
+        };
+        assert_eq!(expected, res);
+    }
+
+    #[test]
+    fn filter_tags_test() {
+        let input = VaultData::Directory(
+            "test".to_owned(),
+            vec![
+                VaultData::Header(
+                    0,
+                    "Test".to_string(),
+                    vec![
+                        VaultData::Header(
+                            1,
+                            "1".to_string(),
+                            vec![VaultData::Header(
+                                2,
+                                "2".to_string(),
+                                vec![VaultData::Task(Task {
+                                    name: "test 1".to_string(),
+                                    line_number: 8,
+                                    description: Some("test\ndesc".to_string()),
+                                    ..Default::default()
+                                })],
+                            )],
+                        ),
+                        VaultData::Header(
+                            1,
+                            "1.2".to_string(),
+                            vec![
+                                VaultData::Header(3, "3".to_string(), vec![]),
+                                VaultData::Header(
+                                    2,
+                                    "4".to_string(),
+                                    vec![VaultData::Task(Task {
+                                        name: "test 2".to_string(),
+                                        line_number: 8,
+                                        tags: Some(vec!["test".to_string()]),
+                                        description: Some("test\ndesc".to_string()),
+                                        ..Default::default()
+                                    })],
+                                ),
+                            ],
+                        ),
+                    ],
+                ),
+                VaultData::Task(Task {
+                    name: "test 3".to_string(),
+                    line_number: 8,
+                    tags: Some(vec!["test".to_string()]),
+                    description: Some("test\ndesc".to_string()),
+                    ..Default::default()
+                }),
+            ],
+        );
+        let expected = vec![
+            Task {
+                name: "test 2".to_string(),
+                line_number: 8,
+                tags: Some(vec!["test".to_string()]),
+                description: Some("test\ndesc".to_string()),
+                ..Default::default()
+            },
+            Task {
+                name: "test 3".to_string(),
+                line_number: 8,
+                tags: Some(vec!["test".to_string()]),
+                description: Some("test\ndesc".to_string()),
+                ..Default::default()
+            },
+        ];
+        let res = filter_to_vec(
+            &input,
+            &Filter {
+                task: Task {
+                    name: String::new(),
+                    tags: Some(vec!["test".to_string()]),
+                    ..Default::default()
+                },
+                state: None,
+                exact_state: false,
+                due: vec![],
+                comparisons: vec![],
+                overdue: false,
+                sort: vec![],
+                path: vec![],
+                file_path: vec![],
+                heading: None,
+                exact_dates: false,
+                query: None,
+                excluded: vec![],
+                started: None,
+            },
+        );
+        assert_eq!(res, expected);
+    }
+    #[test]
+    fn filter_names_test() {
+        let input = VaultData::Directory(
+            "test".to_owned(),
+            vec![
+                VaultData::Header(
+                    0,
+                    "Test".to_string(),
+                    vec![
+                        VaultData::Header(
+                            1,
+                            "1".to_string(),
+                            vec![VaultData::Header(
+                                2,
+                                "2".to_string(),
+                                vec![VaultData::Task(Task {
+                                    name: "hfdgqskhjfg1".to_string(),
+                                    line_number: 8,
+                                    description: Some("test\ndesc".to_string()),
+                                    ..Default::default()
+                                })],
+                            )],
+                        ),
+                        VaultData::Header(
+                            1,
+                            "1.2".to_string(),
+                            vec![
+                                VaultData::Header(3, "3".to_string(), vec![]),
+                                VaultData::Header(
+                                    2,
+                                    "4".to_string(),
+                                    vec![VaultData::Task(Task {
+                                        name: "test 2".to_string(),
+                                        line_number: 8,
+                                        tags: Some(vec!["test".to_string()]),
+                                        description: Some("test\ndesc".to_string()),
+                                        ..Default::default()
+                                    })],
+                                ),
+                            ],
+                        ),
+                    ],
+                ),
+                VaultData::Task(Task {
+                    name: "test 3".to_string(),
+                    line_number: 8,
+                    tags: Some(vec!["test".to_string()]),
+                    description: Some("test\ndesc".to_string()),
+                    ..Default::default()
+                }),
+            ],
+        );
+        let expected = vec![
+            Task {
+                name: "test 2".to_string(),
+                line_number: 8,
+                tags: Some(vec!["test".to_string()]),
+                description: Some("test\ndesc".to_string()),
+                ..Default::default()
+            },
+            Task {
+                name: "test 3".to_string(),
+                line_number: 8,
+                tags: Some(vec!["test".to_string()]),
+                description: Some("test\ndesc".to_string()),
+                ..Default::default()
+            },
+        ];
+        let res = filter_to_vec(
+            &input,
+            &Filter {
+                task: Task {
+                    name: String::from("test"),
+                    ..Default::default()
+                },
+                state: None,
+                exact_state: false,
+                due: vec![],
+                comparisons: vec![],
+                overdue: false,
+                sort: vec![],
+                path: vec![],
+                file_path: vec![],
+                heading: None,
+                exact_dates: false,
+                query: None,
+                excluded: vec![],
+                started: None,
+            },
+        );
+        assert_eq!(res, expected);
+    }
+    #[test]
+    fn filter_due_date_test() {
+        let input = VaultData::Directory(
+            "test".to_owned(),
+            vec![
+                VaultData::Header(
+                    0,
+                    "Test".to_string(),
+                    vec![
+                        VaultData::Header(
+                            1,
+                            "1".to_string(),
+                            vec![VaultData::Header(
+                                2,
+                                "2".to_string(),
+                                vec![VaultData::Task(Task {
+                                    name: "hfdgqskhjfg1".to_string(),
+                                    line_number: 8,
+                                    due_date: DueDate::Day(
+                                        NaiveDate::from_ymd_opt(2020, 2, 2).unwrap(),
+                                    ),
+                                    description: Some("test\ndesc".to_string()),
+                                    ..Default::default()
+                                })],
+                            )],
+                        ),
+                        VaultData::Header(
+                            1,
+                            "1.2".to_string(),
+                            vec![
+                                VaultData::Header(3, "3".to_string(), vec![]),
+                                VaultData::Header(
+                                    2,
+                                    "4".to_string(),
+                                    vec![VaultData::Task(Task {
+                                        name: "test 2".to_string(),
+                                        line_number: 8,
+                                        tags: Some(vec!["test".to_string()]),
+                                        description: Some("test\ndesc".to_string()),
+                                        ..Default::default()
+                                    })],
+                                ),
+                            ],
+                        ),
+                    ],
+                ),
+                VaultData::Task(Task {
+                    name: "test 3".to_string(),
+                    line_number: 8,
+                    tags: Some(vec!["test".to_string()]),
+                    description: Some("test\ndesc".to_string()),
+                    ..Default::default()
+                }),
+            ],
+        );
+        let expected = vec![Task {
+            name: "hfdgqskhjfg1".to_string(),
+            line_number: 8,
+            due_date: DueDate::Day(NaiveDate::from_ymd_opt(2020, 2, 2).unwrap()),
+            description: Some("test\ndesc".to_string()),
+            ..Default::default()
+        }];
+        let res = filter_to_vec(
+            &input,
+            &Filter {
+                task: Task {
+                    due_date: DueDate::Day(NaiveDate::from_ymd_opt(2020, 2, 2).unwrap()),
+                    ..Default::default()
+                },
+                state: None,
+                exact_state: false,
+                due: vec![],
+                comparisons: vec![],
+                overdue: false,
+                sort: vec![],
+                path: vec![],
+                file_path: vec![],
+                heading: None,
+                exact_dates: false,
+                query: None,
+                excluded: vec![],
+                started: None,
+            },
+        );
+        assert_eq!(res, expected);
+    }
+    #[test]
+    fn filter_full_test() {
+        let input = VaultData::Directory(
+            "test".to_owned(),
+            vec![
+                VaultData::Header(
+                    0,
+                    "Test".to_string(),
+                    vec![
+                        VaultData::Header(
+                            1,
+                            "1".to_string(),
+                            vec![VaultData::Header(
+                                2,
+                                "2".to_string(),
+                                vec![VaultData::Task(Task {
+                                    name: "real target".to_string(),
+                                    line_number: 8,
+                                    tags: Some(vec!["test".to_string()]),
+                                    due_date: DueDate::Day(
+                                        NaiveDate::from_ymd_opt(2020, 2, 2).unwrap(),
+                                    ),
+                                    description: Some("test\ndesc".to_string()),
+                                    ..Default::default()
+                                })],
+                            )],
+                        ),
+                        VaultData::Header(
+                            1,
+                            "1.2".to_string(),
+                            vec![
+                                VaultData::Header(3, "3".to_string(), vec![]),
+                                VaultData::Header(
+                                    2,
+                                    "4".to_string(),
+                                    vec![VaultData::Task(Task {
+                                        name: "false target 2".to_string(),
+                                        line_number: 8,
+                                        tags: Some(vec!["test".to_string()]),
+                                        description: Some("test\ndesc".to_string()),
+                                        ..Default::default()
+                                    })],
+                                ),
+                            ],
+                        ),
+                    ],
+                ),
+                VaultData::Task(Task {
+                    name: "test 3".to_string(),
+                    line_number: 8,
+                    tags: Some(vec!["test".to_string()]),
+                    description: Some("test\ndesc".to_string()),
+                    ..Default::default()
+                }),
+            ],
+        );
+        let expected = vec![Task {
+            name: "real target".to_string(),
+            line_number: 8,
+            due_date: DueDate::Day(NaiveDate::from_ymd_opt(2020, 2, 2).unwrap()),
+            tags: Some(vec!["test".to_string()]),
+            description: Some("test\ndesc".to_string()),
+            ..Default::default()
+        }];
+        let res = filter_to_vec(
+            &input,
+            &Filter {
+                task: Task {
+                    name: String::from("target"),
+                    tags: Some(vec!["test".to_string()]),
+                    due_date: DueDate::Day(NaiveDate::from_ymd_opt(2020, 2, 2).unwrap()),
+                    ..Default::default()
+                },
+                state: None,
+                exact_state: false,
+                due: vec![],
+                comparisons: vec![],
+                overdue: false,
+                sort: vec![],
+                path: vec![],
+                file_path: vec![],
+                heading: None,
+                exact_dates: false,
+                query: None,
+                excluded: vec![],
+                started: None,
+            },
+        );
+        assert_eq!(res, expected);
+    }
+    #[test]
+    fn filter_subtasks_test() {
+        let input = VaultData::Directory(
+            "test".to_owned(),
+            vec![
+                VaultData::Header(
+                    0,
+                    "Test".to_string(),
+                    vec![
+                        VaultData::Header(
+                            1,
+                            "1".to_string(),
+                            vec![VaultData::Header(
+                                2,
+                                "2".to_string(),
+                                vec![VaultData::Task(Task {
+                                    name: "task".to_string(),
+                                    line_number: 8,
+                                    tags: Some(vec!["test".to_string()]),
+                                    description: Some("test\ndesc".to_string()),
+                                    subtasks: vec![Task {
+                                        name: "subtask".to_string(),
+                                        ..Default::default()
+                                    }],
+                                    ..Default::default()
+                                })],
+                            )],
+                        ),
+                        VaultData::Header(
+                            1,
+                            "1.2".to_string(),
+                            vec![
+                                VaultData::Header(3, "3".to_string(), vec![]),
+                                VaultData::Header(
+                                    2,
+                                    "4".to_string(),
+                                    vec![VaultData::Task(Task {
+                                        name: "false target 2".to_string(),
+                                        line_number: 8,
+                                        tags: Some(vec!["test".to_string()]),
+                                        description: Some("test\ndesc".to_string()),
+                                        ..Default::default()
+                                    })],
+                                ),
+                            ],
+                        ),
+                    ],
+                ),
+                VaultData::Task(Task {
+                    name: "test 3".to_string(),
+                    line_number: 8,
+                    tags: Some(vec!["test".to_string()]),
+                    description: Some("test\ndesc".to_string()),
+                    ..Default::default()
+                }),
+            ],
+        );
+        let expected = Some(VaultData::Directory(
+            "test".to_owned(),
+            vec![VaultData::Header(
+                0,
+                "Test".to_string(),
+                vec![VaultData::Header(
+                    1,
+                    "1".to_string(),
+                    vec![VaultData::Header(
+                        2,
+                        "2".to_string(),
+                        vec![VaultData::Task(Task {
+                            name: "task".to_string(),
+                            line_number: 8,
+                            tags: Some(vec!["test".to_string()]),
+                            description: Some("test\ndesc".to_string()),
+                            subtasks: vec![Task {
+                                name: "subtask".to_string(),
+                                ..Default::default()
+                            }],
+                            ..Default::default()
+                        })],
+                    )],
+                )],
+            )],
+        ));
+        let res = filter(
+            &input,
+            &Filter {
+                task: Task {
+                    name: String::from("subtask"),
+                    ..Default::default()
+                },
+                state: None,
+                exact_state: false,
+                due: vec![],
+                comparisons: vec![],
+                overdue: false,
+                sort: vec![],
+                path: vec![],
+                file_path: vec![],
+                heading: None,
+                exact_dates: false,
+                query: None,
+                excluded: vec![],
+                started: None,
+            },
+        );
+        assert_eq!(res, expected);
+    }
+
+    #[test]
+    fn test_parse_due_predicate() {
+        assert_eq!(
+            DuePredicate::parse("due<2d"),
+            Some(DuePredicate {
+                before: true,
+                inclusive: false,
+                delta: TimeDelta::days(2)
+            })
+        );
+        assert_eq!(
+            DuePredicate::parse("due>1w"),
+            Some(DuePredicate {
+                before: false,
+                inclusive: false,
+                delta: TimeDelta::weeks(1)
+            })
+        );
+        assert_eq!(DuePredicate::parse("due<2"), None);
+        assert_eq!(DuePredicate::parse("due"), None);
+        assert_eq!(DuePredicate::parse("undue<2d"), None);
+    }
+
+    #[test]
+    fn test_filter_due_predicate() {
+        let config = TasksConfig::default();
+        let filter = parse_search_input("- [ ] due<2d #work", &config);
+        let now = chrono::Local::now().naive_local();
+
+        let due_soon = Task {
+            due_date: DueDate::DayTime(now + TimeDelta::hours(5)),
+            tags: Some(vec![String::from("work")]),
+            ..Default::default()
+        };
+        let due_later = Task {
+            due_date: DueDate::DayTime(now + TimeDelta::days(5)),
+            ..due_soon.clone()
+        };
+        let no_due_date = Task {
+            due_date: DueDate::NoDate,
+            ..due_soon.clone()
+        };
+        assert!(filter.matches(&due_soon));
+        assert!(!filter.matches(&due_later));
+        assert!(!filter.matches(&no_due_date));
+        assert!(parse_search_input("due>2d", &config).matches(&due_later));
+    }
+
+    #[test]
+    fn test_filter_due_range() {
+        let config = TasksConfig::default();
+        assert_eq!(
+            DuePredicate::parse("due<=7d"),
+            Some(DuePredicate {
+                before: true,
+                inclusive: true,
+                delta: TimeDelta::days(7)
+            })
+        );
+        assert_eq!(
+            DuePredicate::parse("after:2d"),
+            Some(DuePredicate {
+                before: false,
+                inclusive: false,
+                delta: TimeDelta::days(2)
+            })
+        );
+        assert_eq!(DuePredicate::parse("due=2d"), None);
+
+        let today = chrono::Local::now().date_naive();
+        let task = |due_date, state| Task {
+            name: "task".to_owned(),
+            due_date,
+            state,
+            ..Default::default()
+        };
+        let yesterday = task(DueDate::Day(today - TimeDelta::days(1)), State::ToDo);
+        let in_three_days = task(DueDate::Day(today + TimeDelta::days(3)), State::ToDo);
+        let next_month = task(DueDate::Day(today + TimeDelta::days(30)), State::ToDo);
+
+        // Relative predicates can be combined
+        let this_week = parse_search_input("after:-1d due<=7d", &config);
+        assert!(!this_week.matches(&yesterday));
+        assert!(this_week.matches(&in_three_days));
+        assert!(!this_week.matches(&next_month));
+
+        let range = parse_search_input(
+            &format!(
+                "after:{} before:{}",
+                today.format("%Y-%m-%d"),
+                (today + TimeDelta::days(7)).format("%Y-%m-%d")
+            ),
+            &config,
+        );
+        assert!(range.task.name.is_empty());
+        assert!(!range.matches(&yesterday));
+        assert!(range.matches(&in_three_days));
+        assert!(!range.matches(&next_month));
+
+        let overdue = parse_search_input("overdue task", &config);
+        assert_eq!(overdue.task.name, "task");
+        assert!(overdue.matches(&yesterday));
+        assert!(!overdue.matches(&in_three_days));
+        assert!(!overdue.matches(&task(yesterday.due_date.clone(), State::Done)));
+        assert!(overdue.matches(&task(
+            DueDate::DayTime(chrono::Local::now().naive_local() - TimeDelta::hours(1)),
+            State::Incomplete
+        )));
+    }
+
+    #[test]
+    fn test_filter_started() {
+        let today = chrono::Local::now().date_naive();
+        let task = |start| Task {
+            name: "task".to_owned(),
+            tags: Some(vec!["work".to_owned()]),
+            start,
+            ..Default::default()
+        };
+        let deferred = task(Some(today + TimeDelta::days(2)));
+        let started = task(Some(today));
+        let undated = task(None);
+
+        let mut config = TasksConfig::default();
+        assert!(parse_search_input("task", &config).matches(&deferred));
+        let filter = parse_search_input("#work started", &config);
+        assert!(filter.task.name.is_empty());
+        assert!(!filter.matches(&deferred));
+        assert!(filter.matches(&started));
+        assert!(filter.matches(&undated));
+
+        // Commands still find tasks not started yet
+        config.hide_not_started = true;
+        assert!(parse_search_input("task", &config).matches(&deferred));
+        for search in ["", "task", "#work OR #home", "-#home"] {
+            let filter = parse_tab_search(search, &config);
+            assert!(!filter.matches(&deferred), "{search}");
+            assert!(filter.matches(&undated), "{search}");
+        }
+        let filter = parse_tab_search("unstarted #work", &config);
+        assert!(filter.matches(&deferred));
+        assert!(!filter.matches(&undated));
+    }
+
+    #[test]
+    fn test_filter_sort_keys() {
+        let config = TasksConfig::default();
+        let filter = parse_search_input("#work sort:path,due", &config);
+        assert_eq!(filter.sort, vec![SortKey::Path, SortKey::Due]);
+        assert_eq!(filter.task.name, "");
+        // Unknown keys are kept in the search
+        let filter = parse_search_input("sort:path,size", &config);
+        assert!(filter.sort.is_empty());
+        assert_eq!(filter.task.name, "sort:path,size");
+
+        let vault_data = VaultData::Directory(
+            "vault".to_owned(),
+            vec![
+                VaultData::Header(
+                    0,
+                    "b.md".to_owned(),
+                    vec![VaultData::Task(Task {
+                        name: "in b".to_owned(),
+                        ..Default::default()
+                    })],
+                ),
+                VaultData::Header(
+                    0,
+                    "a.md".to_owned(),
+                    vec![VaultData::Task(Task {
+                        name: "in a".to_owned(),
+                        ..Default::default()
+                    })],
+                ),
+            ],
+        );
+        let mut tasks = filter_to_vec_with_paths(&vault_data, &Filter::default());
+        assert_eq!(tasks[1].0, vec!["vault".to_owned(), "a.md".to_owned()]);
+        SortKey::sort(&mut tasks, &[SortKey::Path], false);
+        assert_eq!(tasks[0].1.name, "in a");
+    }
+
+    #[test]
+    fn test_filter_path() {
+        let config = TasksConfig::default();
+        let filter = parse_search_input(r#"#work in:"notes.md/Weekly review" p1"#, &config);
+        assert_eq!(filter.path, vec!["notes.md", "Weekly review"]);
+        assert_eq!(filter.task.tags, Some(vec!["work".to_owned()]));
+        assert_eq!(filter.task.priority, 1);
+        assert_eq!(filter.task.name, "");
+        assert_eq!(
+            parse_search_input("in:Projects", &config).path,
+            vec!["Projects"]
+        );
+        // Only a token at the start of a word
+        assert!(parse_search_input("within:x", &config).path.is_empty());
+
+        let task = |name: &str| {
+            VaultData::Task(Task {
+                name: name.to_owned(),
+                ..Default::default()
+            })
+        };
+        let vault_data = VaultData::Directory(
+            "vault".to_owned(),
+            vec![VaultData::Header(
+                0,
+                "notes.md".to_owned(),
+                vec![
+                    VaultData::Header(1, "Weekly review".to_owned(), vec![task("in section")]),
+                    VaultData::Header(1, "Other".to_owned(), vec![task("elsewhere")]),
+                ],
+            )],
+        );
+        let filter = parse_search_input(
+            &path_search(&["notes.md".to_owned(), "Weekly review".to_owned()]),
+            &config,
+        );
+        assert_eq!(
+            filter_to_vec(&vault_data, &filter)
+                .iter()
+                .map(|t| t.name.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["in section"]
+        );
+        assert_eq!(
+            super::filter(&vault_data, &filter),
+            Some(VaultData::Directory(
+                "vault".to_owned(),
+                vec![VaultData::Header(
+                    0,
+                    "notes.md".to_owned(),
+                    vec![VaultData::Header(
+                        1,
+                        "Weekly review".to_owned(),
+                        vec![task("in section")]
+                    )],
+                )],
+            ))
+        );
+    }
+
+    #[test]
+    fn test_filter_file_path_heading() {
+        let config = TasksConfig::default();
+        let filter =
+            parse_search_input(r#"review path:Work/Projects heading:"Sprint 12""#, &config);
+        assert_eq!(filter.file_path, vec!["Work", "Projects"]);
+        assert_eq!(filter.heading, Some("Sprint 12".to_owned()));
+        assert_eq!(filter.task.name, "review");
+
+        let task = |name: &str| {
+            VaultData::Task(Task {
+                name: name.to_owned(),
+                ..Default::default()
+            })
+        };
+        let vault_data = VaultData::Directory(
+            "vault".to_owned(),
+            vec![
+                VaultData::Directory(
+                    "Work".to_owned(),
+                    vec![
+                        VaultData::Directory(
+                            "Projects".to_owned(),
+                            vec![VaultData::Directory(
+                                "acme.md".to_owned(),
+                                vec![
+                                    VaultData::Header(
+                                        1,
+                                        "Sprint 12".to_owned(),
+                                        vec![VaultData::Header(
+                                            2,
+                                            "Backend".to_owned(),
+                                            vec![task("api")],
+                                        )],
+                                    ),
+                                    VaultData::Header(1, "Sprint 11".to_owned(), vec![task("old")]),
+                                ],
+                            )],
+                        ),
+                        VaultData::Directory("Projects.md".to_owned(), vec![task("not a dir")]),
+                    ],
+                ),
+                VaultData::Directory(
+                    "Home".to_owned(),
+                    vec![VaultData::Directory(
+                        "chores.md".to_owned(),
+                        vec![VaultData::Header(
+                            1,
+                            "Sprint 12".to_owned(),
+                            vec![task("dishes")],
+                        )],
+                    )],
+                ),
+            ],
+        );
+        let names = |search: &str| {
+            filter_to_vec(&vault_data, &parse_search_input(search, &config))
+                .iter()
+                .map(|t| t.name.clone())
+                .collect::<Vec<String>>()
+        };
+        // `.md` can be omitted
+        assert_eq!(names("path:Work/Projects"), vec!["api", "old", "not a dir"]);
+        assert_eq!(names("path:Work/Projects/acme"), vec!["api", "old"]);
+        assert_eq!(names("path:Work"), vec!["api", "old", "not a dir"]);
+        assert_eq!(names(r#"heading:"sprint 12""#), vec!["api", "dishes"]);
+        assert_eq!(names("path:Work heading:Backend"), vec!["api"]);
+        assert_eq!(names("NOT path:Work"), vec!["dishes"]);
+        assert_eq!(
+            super::filter(&vault_data, &parse_search_input("heading:Backend", &config)),
+            Some(VaultData::Directory(
+                "vault".to_owned(),
+                vec![VaultData::Directory(
+                    "Work".to_owned(),
+                    vec![VaultData::Directory(
+                        "Projects".to_owned(),
+                        vec![VaultData::Directory(
+                            "acme.md".to_owned(),
+                            vec![VaultData::Header(
+                                1,
+                                "Sprint 12".to_owned(),
+                                vec![VaultData::Header(
+                                    2,
+                                    "Backend".to_owned(),
+                                    vec![task("api")]
+                                )],
+                            )],
+                        )],
+                    )],
+                )],
+            ))
+        );
+
+        // Other subtrees are not traversed
+        let filter = parse_search_input("path:Work/Projects", &config);
+        let path = ["vault", "Home"].map(str::to_owned);
+        assert!(!filter.may_match_under(Location {
+            path: &path,
+            headers: 0
+        }));
+        let path = ["vault", "Work"].map(str::to_owned);
+        assert!(filter.may_match_under(Location {
+            path: &path,
+            headers: 0
+        }));
+    }
+
+    #[test]
+    fn test_filter_same_day() {
+        let mut config = TasksConfig::default();
+        let date = NaiveDate::from_ymd_opt(2020, 1, 1).unwrap();
+        let task_at = |h| Task {
+            due_date: DueDate::DayTime(date.and_hms_opt(h, 0, 0).unwrap()),
+            ..Default::default()
+        };
+        let task_on_day = Task {
+            due_date: DueDate::Day(date),
+            ..Default::default()
+        };
+
+        let filter = parse_search_input("01/01/2020", &config);
+        assert!(filter.matches(&task_at(10)));
+        assert!(filter.matches(&task_on_day));
+        let filter = parse_search_input("01/01/2020 10:00", &config);
+        assert!(filter.matches(&task_at(10)));
+        assert!(filter.matches(&task_at(14)));
+        assert!(filter.matches(&task_on_day));
+        assert!(!parse_search_input("02/01/2020", &config).matches(&task_at(10)));
+
+        config.exact_date_search = true;
+        let filter = parse_search_input("01/01/2020", &config);
+        assert!(!filter.matches(&task_at(10)));
+        assert!(filter.matches(&task_on_day));
+        let filter = parse_search_input("01/01/2020 10:00", &config);
+        assert!(filter.matches(&task_at(10)));
+        assert!(!filter.matches(&task_at(14)));
+    }
+
+    #[test]
+    fn test_filter_state_match_mode() {
+        let mut config = TasksConfig::default();
+        let task = |state| Task {
+            name: "task".to_owned(),
+            state,
+            ..Default::default()
+        };
+
+        let filter = parse_search_input("- [x] task", &config);
+        assert!(filter.matches(&task(State::Done)));
+        assert!(filter.matches(&task(State::Canceled)));
+        let filter = parse_search_input("task is:canceled", &config);
+        assert_eq!(filter.task.name, "task");
+        assert!(!filter.matches(&task(State::Done)));
+        assert!(filter.matches(&task(State::Canceled)));
+        // Unknown states are kept in the search
+        assert_eq!(
+            parse_search_input("is:blocked", &config).task.name,
+            "is:blocked"
+        );
+
+        config.state_match_mode = StateMatchMode::Exact;
+        let filter = parse_search_input("- [ ] task", &config);
+        assert!(filter.matches(&task(State::ToDo)));
+        assert!(!filter.matches(&task(State::Incomplete)));
+    }
+
+    #[test]
+    fn test_filter_custom_states() {
+        let mut config = TasksConfig::default();
+        let custom = |marker, name: &str, finished| CustomState {
+            marker,
+            name: name.to_owned(),
+            symbol: String::new(),
+            finished,
+        };
+        let forwarded = custom('>', "Forwarded", true);
+        let question = custom('?', "Question", false);
+        config.task_state_markers.custom = vec![forwarded.clone(), question.clone()];
+        let task = |state| Task {
+            name: "task".to_owned(),
+            state,
+            ..Default::default()
+        };
+
+        let filter = parse_search_input("- [>] task", &config);
+        assert!(filter.matches(&task(State::Custom(forwarded.clone()))));
+        assert!(!filter.matches(&task(State::Done)));
+        let filter = parse_search_input("is:question", &config);
+        assert!(filter.task.name.is_empty());
+        assert!(filter.matches(&task(State::Custom(question.clone()))));
+        assert!(!filter.matches(&task(State::ToDo)));
+        // Other states group custom states by whether they are finished
+        let filter = parse_search_input("- [ ] task", &config);
+        assert!(filter.matches(&task(State::Custom(question))));
+        assert!(!filter.matches(&task(State::Custom(forwarded.clone()))));
+        assert!(parse_search_input("- [x] task", &config).matches(&task(State::Custom(forwarded))));
+    }
+
+    #[test]
+    fn test_parse_comparison() {
+        let config = TasksConfig::default();
+        let date = NaiveDate::from_ymd_opt(2025, 12, 1).unwrap();
+        let filter = parse_search_input("review due<2025-12-01 priority>=2", &config);
+        assert_eq!(
+            filter.comparisons,
+            vec![
+                Comparison::Due(CompareOp::Lt, date),
+                Comparison::Priority(CompareOp::Ge, 2)
+            ]
+        );
+        assert_eq!(filter.task.name, "review");
+        assert_eq!(
+            parse_search_input("p>1", &config).comparisons,
+            vec![Comparison::Priority(CompareOp::Gt, 1)]
+        );
+        // Relative durations are due predicates
+        assert!(parse_search_input("due<2d", &config).comparisons.is_empty());
+
+        let task = |due_date, priority| Task {
+            name: "review".to_owned(),
+            due_date,
+            priority,
+            ..Default::default()
+        };
+        assert!(filter.matches(&task(DueDate::Day(date.pred_opt().unwrap()), 2)));
+        assert!(!filter.matches(&task(DueDate::Day(date), 2)));
+        assert!(!filter.matches(&task(DueDate::Day(date.pred_opt().unwrap()), 1)));
+        assert!(!filter.matches(&task(DueDate::NoDate, 3)));
+    }
+
+    #[test]
+    fn test_tokenize_query() {
+        assert_eq!(
+            tokenize_query(r#"(tag:work OR NOT in:"a b") AND x"#),
+            vec![
+                QueryToken::Open,
+                QueryToken::Word("tag:work".to_owned()),
+                QueryToken::Or,
+                QueryToken::Not,
+                QueryToken::Word(r#"in:"a b""#.to_owned()),
+                QueryToken::Close,
+                QueryToken::And,
+                QueryToken::Word("x".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_query() {
+        let config = TasksConfig::default();
+        let term = |input| Query::Term(Box::new(parse_search_input(input, &config)));
+
+        let filter = parse_search_input(
+            "(tag:work OR tag:urgent) AND due<2025-12-01 AND NOT state:done sort:due",
+            &config,
+        );
+        assert_eq!(filter.sort, vec![SortKey::Due]);
+        assert_eq!(
+            filter.query,
+            Some(Query::And(vec![
+                Query::Or(vec![term("#work"), term("#urgent")]),
+                term("due<2025-12-01"),
+                Query::Not(Box::new(term("is:done"))),
+            ]))
+        );
+        // NOT binds tighter than AND, AND tighter than OR, words next to each other form one search
+        assert_eq!(
+            parse_search_input("a b OR NOT c AND d", &config).query,
+            Some(Query::Or(vec![
+                term("a b"),
+                Query::And(vec![Query::Not(Box::new(term("c"))), term("d")]),
+            ]))
+        );
+        // Incomplete queries
+        assert_eq!(parse_search_input("(a OR", &config).query, Some(term("a")));
+        assert_eq!(
+            parse_search_input("a) AND b", &config).query,
+            Some(Query::And(vec![term("a"), term("b")]))
+        );
+        // Lower case operators are searched
+        assert!(parse_search_input("bread and butter", &config)
+            .query
+            .is_none());
+    }
+
+    #[test]
+    fn test_filter_query() {
+        let config = TasksConfig::default();
+        let task = |name: &str, tags: &[&str], state| {
+            VaultData::Task(Task {
+                name: name.to_owned(),
+                tags: Some(tags.iter().map(|t| (*t).to_owned()).collect()),
+                state,
+                ..Default::default()
+            })
+        };
+        let vault_data = VaultData::Directory(
+            "vault".to_owned(),
+            vec![
+                VaultData::Header(
+                    0,
+                    "work.md".to_owned(),
+                    vec![
+                        task("report", &["work"], State::ToDo),
+                        task("deploy", &["urgent"], State::Done),
+                        task("call", &["urgent"], State::ToDo),
+                    ],
+                ),
+                VaultData::Header(
+                    0,
+                    "home.md".to_owned(),
+                    vec![task("plants", &[], State::ToDo)],
+                ),
+            ],
+        );
+        let names = |search: &str| {
+            filter_to_vec(&vault_data, &parse_search_input(search, &config))
+                .iter()
+                .map(|t| t.name.clone())
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(
+            names("(tag:work OR tag:urgent) AND NOT state:done"),
+            vec!["report", "call"]
+        );
+        assert_eq!(names("NOT in:work.md"), vec!["plants"]);
+        assert_eq!(names("plants OR in:work.md call"), vec!["call", "plants"]);
+        assert_eq!(names("NOT"), vec!["report", "deploy", "call", "plants"]);
+    }
+
+    #[test]
+    fn test_named_filters() {
+        let mut config = TasksConfig::default();
+        config
+            .filters
+            .insert("inbox".to_owned(), "path:Inbox is:todo".to_owned());
+        config
+            .filters
+            .insert("urgent".to_owned(), "p1 OR #urgent".to_owned());
+
+        let filter = parse_search_input("filter:inbox #work", &config);
+        assert_eq!(filter.file_path, vec!["Inbox"]);
+        assert_eq!(filter.state, Some(State::ToDo));
+        assert_eq!(filter.task.tags, Some(vec!["work".to_owned()]));
+        // Unknown names are searched as text
+        assert_eq!(
+            parse_search_input("filter:missing", &config).task.name,
+            "filter:missing"
+        );
+
+        let task = |priority, tags: &[&str]| Task {
+            priority,
+            tags: Some(tags.iter().map(|t| (*t).to_owned()).collect()),
+            ..Default::default()
+        };
+        let filter = parse_search_input("filter:urgent #work", &config);
+        assert!(filter.query.is_some());
+        assert!(filter.matches(&task(1, &["work"])));
+        assert!(filter.matches(&task(0, &["urgent", "work"])));
+        assert!(!filter.matches(&task(1, &[])));
+        assert!(!filter.matches(&task(2, &["work"])));
+    }
+
+    #[test]
+    fn test_filter_negated_tokens() {
+        let config = TasksConfig::default();
+        let filter = parse_search_input(r#"#work -p5 -#waiting -path:"My Archive""#, &config);
+        assert_eq!(filter.task.tags, Some(vec!["work".to_owned()]));
+        assert_eq!(filter.task.priority, 0);
+        assert_eq!(filter.excluded.len(), 3);
+        assert_eq!(filter.excluded[2].file_path, vec!["My Archive"]);
+        // A lone dash is the start of a task state
+        assert_eq!(
+            parse_search_input("- [x] -p1", &config).state,
+            Some(State::Done)
+        );
+
+        let task = |name: &str, priority, tags: &[&str]| {
+            VaultData::Task(Task {
+                name: name.to_owned(),
+                priority,
+                tags: Some(tags.iter().map(|t| (*t).to_owned()).collect()),
+                ..Default::default()
+            })
+        };
+        let vault_data = VaultData::Directory(
+            "vault".to_owned(),
+            vec![
+                VaultData::Directory(
+                    "work.md".to_owned(),
+                    vec![
+                        task("deploy", 5, &["work"]),
+                        task("review", 2, &["work"]),
+                        task("call", 1, &["work", "waiting"]),
+                    ],
+                ),
+                VaultData::Directory(
+                    "My Archive".to_owned(),
+                    vec![VaultData::Directory(
+                        "old.md".to_owned(),
+                        vec![task("migrate", 3, &["work"])],
+                    )],
+                ),
+            ],
+        );
+        let names = |search: &str| {
+            filter_to_vec(&vault_data, &parse_search_input(search, &config))
+                .iter()
+                .map(|t| t.name.clone())
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(names("#work -p5"), vec!["review", "call", "migrate"]);
+        assert_eq!(
+            names(r#"#work -p5 -#waiting -path:"My Archive""#),
+            vec!["review"]
+        );
+        assert_eq!(names("-call"), vec!["deploy", "review", "migrate"]);
+        assert_eq!(names("#work AND -p5 AND -p3"), vec!["review", "call"]);
+    }
+}
@@ -0,0 +1,326 @@
+use std::{
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+use chrono::{NaiveDateTime, TimeDelta};
+use color_eyre::Result;
+
+use super::{
+    is_markdown_file,
+    overload::{overloaded_days, OverloadedDay},
+    parser::task::parse_task,
+    task::{DueDate, Task},
+    vault_data::VaultData,
+    RelativeDates, TaskManager, TasksConfig,
+};
+
+/// A problem found on a line of a note.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub path: PathBuf,
+    pub line_number: usize,
+    pub message: String,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}: {}",
+            self.path.display(),
+            self.line_number,
+            self.message
+        )
+    }
+}
+
+/// Diagnostics and hygiene statistics of a vault.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct HealthReport {
+    pub diagnostics: Vec<Diagnostic>,
+    pub files: usize,
+    pub tasks: usize,
+    pub unfinished: usize,
+    /// Unfinished tasks without tags
+    pub untagged: usize,
+    /// Unfinished tasks without due date
+    pub undated: usize,
+    /// Unfinished tasks past their due date
+    pub overdue: usize,
+    /// Files with unfinished tasks that were not modified for `stale_days` days
+    pub stale_files: Vec<PathBuf>,
+    /// Days from today with more work due than the configured capacity
+    pub overloaded_days: Vec<OverloadedDay>,
+    /// Time taken to parse the vault, in milliseconds
+    pub parse_time: u128,
+}
+
+impl HealthReport {
+    /// Parses the vault and checks it, files are never written.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the vault can't be parsed.
+    pub fn check(config: &TasksConfig, now: NaiveDateTime) -> Result<Self> {
+        let start = Instant::now();
+        let task_mgr = TaskManager::load_read_only(config)?;
+        let parse_time = start.elapsed().as_millis();
+        Ok(Self {
+            parse_time,
+            ..Self::from_task_manager(config, &task_mgr, now)
+        })
+    }
+
+    /// Checks an already loaded vault.
+    #[must_use]
+    pub fn from_task_manager(
+        config: &TasksConfig,
+        task_mgr: &TaskManager,
+        now: NaiveDateTime,
+    ) -> Self {
+        let mut res = Self {
+            files: task_mgr.modification_times.len(),
+            ..Default::default()
+        };
+        res.add_tasks(&task_mgr.tasks, now);
+
+        let mut paths = task_mgr
+            .modification_times
+            .keys()
+            .filter(|p| is_markdown_file(p))
+            .collect::<Vec<&PathBuf>>();
+        paths.sort();
+        for path in paths {
+            if let Ok(content) = fs::read_to_string(path) {
+                res.diagnostics
+                    .append(&mut check_file(config, path, &content));
+            }
+        }
+
+        let stale_since = now - TimeDelta::days(config.stale_days.into());
+        res.stale_files = task_mgr
+            .modification_times
+            .iter()
+            .filter(|(_, modified)| **modified < stale_since)
+            .filter(|(path, _)| {
+                task_mgr
+                    .get_file_entry(&config.vault_path, path)
+                    .is_some_and(has_unfinished_tasks)
+            })
+            .map(|(path, _)| path.clone())
+            .collect();
+        res.stale_files.sort();
+        res.overloaded_days = overloaded_days(&task_mgr.tasks, config, now.date());
+        res
+    }
+
+    fn add_tasks(&mut self, vault_data: &VaultData, now: NaiveDateTime) {
+        match vault_data {
+            VaultData::Directory(_, children) | VaultData::Header(_, _, children) => {
+                children.iter().for_each(|c| self.add_tasks(c, now));
+            }
+            VaultData::Task(task) => self.add_task(task, now),
+        }
+    }
+
+    fn add_task(&mut self, task: &Task, now: NaiveDateTime) {
+        self.tasks += 1;
+        if !task.state.is_finished() {
+            self.unfinished += 1;
+            if task.tags.is_none() {
+                self.untagged += 1;
+            }
+            match task.due_date {
+                DueDate::NoDate => self.undated += 1,
+                DueDate::Day(date) if date < now.date() => self.overdue += 1,
+                DueDate::DayTime(date_time) if date_time < now => self.overdue += 1,
+                _ => (),
+            }
+        }
+        task.subtasks.iter().for_each(|t| self.add_task(t, now));
+    }
+
+    /// Lines summarizing the report.
+    #[must_use]
+    pub fn summary(&self) -> Vec<String> {
+        vec![
+            format!(
+                "Diagnostics: {} problem{} found",
+                self.diagnostics.len(),
+                if self.diagnostics.len() == 1 { "" } else { "s" }
+            ),
+            format!(
+                "Tasks: {} in {} files, {} unfinished",
+                self.tasks, self.files, self.unfinished
+            ),
+            format!("Untagged unfinished tasks: {}", self.untagged),
+            format!("Unfinished tasks without due date: {}", self.undated),
+            format!("Overdue tasks: {}", self.overdue),
+            format!("Stale files: {}", self.stale_files.len()),
+            format!("Overloaded days: {}", self.overloaded_days.len()),
+            format!(
+                "Index: none, the vault was parsed in {} ms",
+                self.parse_time
+            ),
+        ]
+    }
+}
+
+fn has_unfinished_tasks(vault_data: &VaultData) -> bool {
+    fn unfinished(task: &Task) -> bool {
+        !task.state.is_finished() || task.subtasks.iter().any(unfinished)
+    }
+    match vault_data {
+        VaultData::Directory(_, children) | VaultData::Header(_, _, children) => {
+            children.iter().any(has_unfinished_tasks)
+        }
+        VaultData::Task(task) => unfinished(task),
+    }
+}
+
+/// Finds task lines with unknown state markers or inconsistent indentation, and with `relative_dates = "warn"` the
+/// relative dates left as written.
+fn check_file(config: &TasksConfig, path: &Path, content: &str) -> Vec<Diagnostic> {
+    let markers = &config.task_state_markers;
+    let mut res = vec![];
+    let mut in_code_block = false;
+    for (i, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") {
+            in_code_block = !in_code_block;
+        }
+        let mut chars = trimmed.chars();
+        let (Some('-'), Some(' '), Some('['), Some(marker), Some(']')) = (
+            chars.next(),
+            chars.next(),
+            chars.next(),
+            chars.next(),
+            chars.next(),
+        ) else {
+            continue;
+        };
+        if in_code_block {
+            continue;
+        }
+        let diagnostic = |message: String| Diagnostic {
+            path: path.to_path_buf(),
+            line_number: i + 1,
+            message,
+        };
+        if ![
+            markers.todo,
+            markers.done,
+            markers.incomplete,
+            markers.canceled,
+        ]
+        .contains(&marker)
+        {
+            res.push(diagnostic(format!(
+                "Unknown task state marker `{marker}`, read as done"
+            )));
+        }
+        let indent = line.len() - trimmed.len();
+        if config.indent_length > 0 && indent % config.indent_length != 0 {
+            res.push(diagnostic(format!(
+                "Indentation of {indent} is not a multiple of `indent_length` ({})",
+                config.indent_length
+            )));
+        }
+        if config.relative_dates == RelativeDates::Warn {
+            if let Ok(task) = parse_task(&mut trimmed.trim_end(), String::new(), config) {
+                for word in task.relative_dates {
+                    res.push(diagnostic(format!(
+                        "Relative date `{word}` is resolved again each time the vault is loaded"
+                    )));
+                }
+            }
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use chrono::{NaiveDate, TimeDelta};
+    use pretty_assertions::assert_eq;
+
+    use super::{check_file, HealthReport};
+    use crate::{RelativeDates, TasksConfig};
+
+    fn config() -> TasksConfig {
+        TasksConfig {
+            indent_length: 2,
+            stale_days: 30,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_check_file() {
+        let path = std::path::Path::new("note.md");
+        let content = "- [ ] fine\n   - [ ] odd indent\n- [q] unknown\n```\n- [q] in code\n```\n";
+        let res = check_file(&config(), path, content)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<String>>();
+        assert_eq!(
+            res,
+            vec![
+                "note.md:2: Indentation of 3 is not a multiple of `indent_length` (2)",
+                "note.md:3: Unknown task state marker `q`, read as done"
+            ]
+        );
+
+        let config = TasksConfig {
+            relative_dates: RelativeDates::Warn,
+            ..config()
+        };
+        assert_eq!(
+            check_file(&config, path, "- [ ] call bank tmr remind:mon\n- [ ] cook 2025/03/02\n")
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<String>>(),
+            vec![
+                "note.md:1: Relative date `tmr` is resolved again each time the vault is loaded",
+                "note.md:1: Relative date `remind:mon` is resolved again each time the vault is loaded"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_health_report() {
+        let vault_path = std::env::temp_dir().join("vault-tasks-health");
+        let _ = fs::remove_dir_all(&vault_path);
+        fs::create_dir_all(&vault_path).unwrap();
+        fs::write(
+            vault_path.join("note.md"),
+            "- [ ] untagged\n- [ ] tagged #work 01/01/2020\n- [x] done\n",
+        )
+        .unwrap();
+        fs::write(vault_path.join("finished.md"), "- [x] done\n").unwrap();
+        let config = TasksConfig {
+            vault_path: vault_path.clone(),
+            ..config()
+        };
+        let now = NaiveDate::from_ymd_opt(2025, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+
+        let report = HealthReport::check(&config, now).unwrap();
+        assert!(report.diagnostics.is_empty());
+        assert_eq!((report.files, report.tasks, report.unfinished), (2, 4, 2));
+        assert_eq!((report.untagged, report.undated, report.overdue), (1, 1, 1));
+        assert!(report.stale_files.is_empty());
+        assert!(report.overloaded_days.is_empty());
+
+        // Files are stale once they were not modified for `stale_days` days
+        let report = HealthReport::check(&config, now + TimeDelta::days(365 * 100)).unwrap();
+        assert_eq!(report.stale_files, vec![vault_path.join("note.md")]);
+    }
+}
@@ -0,0 +1,284 @@
+use std::{fs, path::Path};
+
+use chrono::NaiveDate;
+use color_eyre::{eyre::bail, Result};
+
+use super::{
+    archive::note_path,
+    changeset::Changeset,
+    parser::parser_file_entry::parse_frontmatter,
+    task::{DueDate, State, Task},
+    TasksConfig,
+};
+
+fn parse_date(word: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(word, "%Y-%m-%d").ok()
+}
+
+/// Parses a todo.txt priority letter, `A` being priority 1.
+fn parse_priority(letter: &str) -> Option<usize> {
+    match letter.as_bytes() {
+        [c @ b'A'..=b'Z'] => Some(usize::from(c - b'A') + 1),
+        _ => None,
+    }
+}
+
+/// Turns a todo.txt project or context into a tag, characters not allowed in tags becoming `_`.
+fn to_tag(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// Parses a line of a todo.txt file.
+fn todotxt_task(line: &str) -> Task {
+    let mut task = Task::default();
+    let mut words = line.split_whitespace().peekable();
+    if words.next_if_eq(&"x").is_some() {
+        task.state = State::Done;
+        task.completed = words
+            .next_if(|w| parse_date(w).is_some())
+            .and_then(parse_date);
+    }
+    if let Some(priority) = words
+        .next_if(|w| {
+            w.strip_prefix('(')
+                .and_then(|w| w.strip_suffix(')'))
+                .and_then(parse_priority)
+                .is_some()
+        })
+        .and_then(|w| parse_priority(&w[1..w.len() - 1]))
+    {
+        task.priority = priority;
+    }
+    task.created = words
+        .next_if(|w| parse_date(w).is_some())
+        .and_then(parse_date);
+
+    let mut name = vec![];
+    let mut tags: Vec<String> = vec![];
+    for word in words {
+        if let Some(tag) = word
+            .strip_prefix(['+', '@'])
+            .filter(|t| !t.is_empty())
+            .map(to_tag)
+        {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+            continue;
+        }
+        match word.split_once(':') {
+            Some(("due", value)) if parse_date(value).is_some() => {
+                task.due_date = DueDate::Day(parse_date(value).unwrap_or_default());
+            }
+            // Done tasks lose their priority, some clients keep it this way
+            Some(("pri", value)) if parse_priority(value).is_some() => {
+                task.priority = parse_priority(value).unwrap_or_default();
+            }
+            // Not times nor URLs
+            Some((key, value))
+                if key.chars().all(|c| c.is_ascii_alphabetic())
+                    && !key.is_empty()
+                    && !value.is_empty()
+                    && !value.contains(':')
+                    && !value.starts_with('/') =>
+            {
+                task.hidden_meta.push((key.to_owned(), value.to_owned()));
+            }
+            _ => name.push(word),
+        }
+    }
+    task.name = name.join(" ");
+    task.tags = (!tags.is_empty()).then_some(tags);
+    task
+}
+
+/// Parses the tasks of a todo.txt file, one per line, blank lines being skipped.
+///
+/// `x` marks done tasks, `(A)` to `(Z)` become priorities 1 to 26, completion and creation dates become `done:` and `created:`,
+/// `+projects` and `@contexts` become tags and `due:` becomes the due date.
+/// Other `key:value` extensions are kept as hidden metadata.
+#[must_use]
+pub fn todotxt(content: &str) -> Vec<Task> {
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(todotxt_task)
+        .collect()
+}
+
+/// Returns the level of `line` if it is a Markdown header.
+fn header_level(line: &str) -> Option<usize> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    (level > 0 && line[level..].starts_with(' ')).then_some(level)
+}
+
+/// Returns the changes appending `tasks` to `target`, a note relative to the vault, without writing them.
+///
+/// Tasks are written under `heading`, or under the `tasks-default-heading` of the frontmatter of the note, see `insert_tasks`.
+///
+/// # Errors
+///
+/// Will return an error if there is no task, if `target` is not a note of the vault or if it can't be read.
+pub fn plan_import(
+    config: &TasksConfig,
+    tasks: &[Task],
+    target: &Path,
+    heading: Option<&str>,
+) -> Result<Changeset> {
+    if tasks.is_empty() {
+        bail!("Nothing to import");
+    }
+    let path = note_path(config, target)?;
+    let old = if path.exists() {
+        fs::read_to_string(&path)?
+    } else {
+        String::new()
+    };
+    let default_heading = parse_frontmatter(&old).default_heading;
+    let new = insert_tasks(
+        &old,
+        heading.or(default_heading.as_deref()),
+        tasks
+            .iter()
+            .map(|task| task.get_fixed_attributes(config, 0)),
+    );
+
+    let mut changes = Changeset::default();
+    changes.add(&path, old, new);
+    Ok(changes)
+}
+
+/// Appends `task_lines` to `content`.
+///
+/// With a `heading`, tasks are added at the end of its section, the header being appended to the note if it is missing.
+pub fn insert_tasks(
+    content: &str,
+    heading: Option<&str>,
+    task_lines: impl Iterator<Item = String>,
+) -> String {
+    let mut lines = content.lines().map(str::to_owned).collect::<Vec<String>>();
+
+    let section = heading.and_then(|heading| {
+        lines.iter().position(|line| {
+            header_level(line).is_some_and(|level| line[level..].trim() == heading)
+        })
+    });
+    match (heading, section) {
+        (_, Some(start)) => {
+            let level = header_level(&lines[start]).unwrap_or_default();
+            let mut end = lines[start + 1..]
+                .iter()
+                .position(|line| header_level(line).is_some_and(|l| l <= level))
+                .map_or(lines.len(), |i| start + 1 + i);
+            while end > start + 1 && lines[end - 1].trim().is_empty() {
+                end -= 1;
+            }
+            lines.splice(end..end, task_lines);
+        }
+        (Some(heading), None) => {
+            if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+                lines.push(String::new());
+            }
+            lines.push(format!("## {heading}"));
+            lines.extend(task_lines);
+        }
+        (None, None) => lines.extend(task_lines),
+    }
+    let mut new = lines.join("\n");
+    new.push('\n');
+    new
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use chrono::NaiveDate;
+    use pretty_assertions::assert_eq;
+
+    use super::{plan_import, todotxt};
+    use crate::{
+        task::{DueDate, State, Task},
+        TasksConfig,
+    };
+
+    #[test]
+    fn test_todotxt() {
+        let date = |d| NaiveDate::from_ymd_opt(2025, 3, d).unwrap();
+        let tasks = todotxt(
+            "(A) 2025-03-01 Call Mom +Family @phone due:2025-03-05 t:2025-03-04\n\nx 2025-03-02 2025-03-01 Pay rent +home-admin pri:B\nsee http://example.com at 10:30\n",
+        );
+        assert_eq!(
+            tasks,
+            vec![
+                Task {
+                    name: "Call Mom".to_owned(),
+                    priority: 1,
+                    created: Some(date(1)),
+                    tags: Some(vec!["Family".to_owned(), "phone".to_owned()]),
+                    due_date: DueDate::Day(date(5)),
+                    hidden_meta: vec![("t".to_owned(), "2025-03-04".to_owned())],
+                    ..Default::default()
+                },
+                Task {
+                    name: "Pay rent".to_owned(),
+                    state: State::Done,
+                    priority: 2,
+                    completed: Some(date(2)),
+                    created: Some(date(1)),
+                    tags: Some(vec!["home_admin".to_owned()]),
+                    ..Default::default()
+                },
+                Task {
+                    name: "see http://example.com at 10:30".to_owned(),
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_import() {
+        let vault_path = std::env::temp_dir().join("vault-tasks-import");
+        let _ = fs::remove_dir_all(&vault_path);
+        fs::create_dir_all(&vault_path).unwrap();
+        fs::write(
+            vault_path.join("Tasks.md"),
+            "# Tasks\n## Imported\n- [ ] old\n\n## Later\n- [ ] later\n",
+        )
+        .unwrap();
+        let config = TasksConfig {
+            vault_path: vault_path.clone(),
+            indent_length: 2,
+            ..Default::default()
+        };
+        let tasks = todotxt("(B) water plants +garden\n");
+
+        assert!(plan_import(&config, &[], Path::new("Tasks"), None).is_err());
+        let changes = plan_import(&config, &tasks, Path::new("Tasks"), Some("Imported")).unwrap();
+        assert_eq!(
+            changes.changes[0].new,
+            "# Tasks\n## Imported\n- [ ] old\n- [ ] water plants p2 #garden\n\n## Later\n- [ ] later\n"
+        );
+        let changes = plan_import(&config, &tasks, Path::new("Tasks"), Some("todo.txt")).unwrap();
+        assert!(changes.changes[0]
+            .new
+            .ends_with("- [ ] later\n\n## todo.txt\n- [ ] water plants p2 #garden\n"));
+        let changes = plan_import(&config, &tasks, Path::new("New"), None).unwrap();
+        assert_eq!(changes.changes[0].new, "- [ ] water plants p2 #garden\n");
+
+        // The heading of the frontmatter is used without `heading`
+        fs::write(
+            vault_path.join("Inbox.md"),
+            "---\ntasks-default-heading: Later\n---\n## Later\n- [ ] later\n## Done\n",
+        )
+        .unwrap();
+        let changes = plan_import(&config, &tasks, Path::new("Inbox"), None).unwrap();
+        assert_eq!(
+            changes.changes[0].new,
+            "---\ntasks-default-heading: Later\n---\n## Later\n- [ ] later\n- [ ] water plants p2 #garden\n## Done\n"
+        );
+    }
+}
@@ -0,0 +1,72 @@
+use std::path::Path;
+
+use super::{
+    details::{filter_to_details, TaskDetails},
+    filter::Filter,
+    task::Task,
+    vault_data::VaultData,
+};
+
+/// Returns the unfinished top level tasks of the file at `path`, e.g. the drop file, in the order of the file.
+///
+/// Subtasks are left out since they are triaged along with their parent.
+#[must_use]
+pub fn inbox_tasks(vault_data: &VaultData, path: &Path) -> Vec<TaskDetails> {
+    fn subtask_lines(task: &Task, res: &mut Vec<usize>) {
+        for subtask in &task.subtasks {
+            res.push(subtask.line_number);
+            subtask_lines(subtask, res);
+        }
+    }
+    let tasks = filter_to_details(vault_data, &Filter::default())
+        .into_iter()
+        .filter(|details| details.file == path)
+        .collect::<Vec<TaskDetails>>();
+    let mut subtasks = vec![];
+    for details in &tasks {
+        subtask_lines(&details.task, &mut subtasks);
+    }
+    let mut res = tasks
+        .into_iter()
+        .filter(|details| !subtasks.contains(&details.task.line_number))
+        .filter(|details| !details.task.state.is_finished())
+        .collect::<Vec<TaskDetails>>();
+    res.sort_by_key(|details| details.task.line_number);
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use pretty_assertions::assert_eq;
+
+    use super::inbox_tasks;
+    use crate::{TaskManager, TasksConfig};
+
+    #[test]
+    fn test_inbox_tasks() {
+        let vault_path = std::env::temp_dir().join("vault-tasks-inbox");
+        let _ = fs::remove_dir_all(&vault_path);
+        fs::create_dir_all(&vault_path).unwrap();
+        fs::write(
+            vault_path.join("Inbox.md"),
+            "- [ ] buy seeds\n  - [ ] tomatoes\n- [x] call bank\n# Later\n- [/] read book\n",
+        )
+        .unwrap();
+        fs::write(vault_path.join("Other.md"), "- [ ] elsewhere\n").unwrap();
+        let config = TasksConfig {
+            vault_path: vault_path.clone(),
+            indent_length: 2,
+            ..Default::default()
+        };
+        let task_mgr = TaskManager::load_read_only(&config).unwrap();
+        assert_eq!(
+            inbox_tasks(&task_mgr.tasks, &vault_path.join("Inbox.md"))
+                .iter()
+                .map(|details| details.task.name.as_str())
+                .collect::<Vec<&str>>(),
+            ["buy seeds", "read book"]
+        );
+    }
+}
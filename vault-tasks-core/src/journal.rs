@@ -0,0 +1,169 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::{eyre::bail, Result};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use super::{parser::task::parse_task, TasksConfig};
+
+/// An operation that was started but not applied to the vault yet.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JournalEntry {
+    /// A task being edited in the quick edit bar
+    EditTask {
+        path: PathBuf,
+        line_number: usize,
+        /// The line of the task when editing started
+        original: String,
+        input: String,
+    },
+}
+
+impl JournalEntry {
+    /// Applies the operation, unless the file changed since it was started.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the file changed or can't be written, or if the input is not a valid task.
+    pub fn replay(&self, config: &TasksConfig) -> Result<()> {
+        match self {
+            Self::EditTask {
+                path,
+                line_number,
+                original,
+                input,
+            } => {
+                let content = fs::read_to_string(path)?;
+                if content.split('\n').nth(line_number - 1) != Some(original.as_str()) {
+                    bail!("{path:?} changed since the edit was started");
+                }
+                let Ok(mut task) = parse_task(
+                    &mut input.as_str(),
+                    path.to_string_lossy().to_string(),
+                    config,
+                ) else {
+                    bail!("Invalid task: {input}");
+                };
+                task.line_number = *line_number;
+                task.fix_task_attributes(config, path)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for JournalEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::EditTask {
+                path,
+                line_number,
+                input,
+                ..
+            } => write!(f, "Edit of {}:{line_number}: {input}", path.display()),
+        }
+    }
+}
+
+/// Keeps the operation in progress on disk so it can be recovered if the app crashes.
+#[derive(Debug, Clone, Default)]
+pub struct Journal {
+    path: PathBuf,
+}
+
+impl Journal {
+    #[must_use]
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+        }
+    }
+
+    /// Saves the operation in progress, replacing the previous one.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the journal can't be written.
+    pub fn record(&self, entry: &JournalEntry) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Write then rename, so a crash never leaves a partial journal
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, serde_json::to_string(entry)?)?;
+        fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+
+    /// Forgets the operation in progress, once it was applied or canceled.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the journal exists but can't be removed.
+    pub fn clear(&self) -> Result<()> {
+        if self.path.exists() {
+            fs::remove_file(&self.path)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the operation left in progress by a previous session, if any.
+    #[must_use]
+    pub fn load(&self) -> Option<JournalEntry> {
+        let content = fs::read_to_string(&self.path).ok()?;
+        match serde_json::from_str(&content) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                debug!("Ignoring invalid journal {:?}: {e}", self.path);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{Journal, JournalEntry};
+    use crate::TasksConfig;
+
+    #[test]
+    fn test_journal() {
+        let dir = std::env::temp_dir().join("vault-tasks-journal");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let note = dir.join("note.md");
+        fs::write(&note, "# Tasks\n- [ ] old name\n").unwrap();
+        let config = TasksConfig {
+            vault_path: dir.clone(),
+            ..Default::default()
+        };
+
+        let journal = Journal::new(&dir.join("data/journal.json"));
+        assert_eq!(journal.load(), None);
+        let entry = JournalEntry::EditTask {
+            path: note.clone(),
+            line_number: 2,
+            original: "- [ ] old name".to_owned(),
+            input: "- [ ] new name p1".to_owned(),
+        };
+        journal.record(&entry).unwrap();
+        assert_eq!(journal.load(), Some(entry.clone()));
+
+        entry.replay(&config).unwrap();
+        assert_eq!(
+            fs::read_to_string(&note).unwrap(),
+            "# Tasks\n- [ ] new name p1\n"
+        );
+        // The line changed, replaying again would overwrite it
+        assert!(entry.replay(&config).is_err());
+
+        journal.clear().unwrap();
+        assert_eq!(journal.load(), None);
+    }
+}
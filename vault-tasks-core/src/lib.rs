@@ -0,0 +1,1746 @@
+//! Parsing, filtering and editing of the Markdown tasks of a vault, the library behind `vault-tasks`.
+//!
+//! [`TaskManager`] loads a vault and [`TasksConfig`] holds its settings. Optional pieces are behind the `watcher` and `sync` features.
+
+use chrono::{format::StrftimeItems, NaiveDateTime, NaiveTime};
+use color_eyre::{eyre::bail, Result};
+use serde::Deserialize;
+
+use daily_notes::CarryOverMode;
+use maintenance::MaintenanceConfig;
+use sorter::{ExplorerSortingMode, OutputSort};
+use stats::StateCounts;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+#[cfg(feature = "watcher")]
+use std::{
+    sync::mpsc::{channel, Receiver},
+    time::Duration,
+};
+use urgency::UrgencyConfig;
+use vault_data::VaultData;
+
+use filter::{filter, filter_to_vec, parse_search_input, Filter, StateMatchMode};
+use parser::task::parse_duration;
+use recurrence::next_occurrence;
+use task::{DateDisplayFormat, DueDate, State, Task};
+use tracing::{error, warn};
+use vault_parser::VaultParser;
+pub use vault_parser::{is_canvas_file, is_markdown_file};
+#[cfg(feature = "watcher")]
+use watcher::VaultWatcher;
+
+pub mod archive;
+pub mod attachments;
+pub mod calendar;
+pub mod changeset;
+pub mod daily_notes;
+pub mod date_time;
+pub mod details;
+pub mod edit;
+pub mod export;
+pub mod filter;
+pub mod health;
+pub mod import;
+pub mod inbox;
+pub mod journal;
+pub mod links;
+pub mod lock;
+pub mod maintenance;
+pub mod mark;
+pub mod obsidian;
+pub mod overload;
+pub mod parser;
+pub mod recurrence;
+pub mod rename;
+pub mod schedule;
+pub mod sorter;
+pub mod stats;
+#[cfg(feature = "sync")]
+pub mod sync_map;
+pub mod task;
+pub mod task_notes;
+pub mod urgency;
+pub mod vault_data;
+pub mod vault_diff;
+mod vault_parser;
+#[cfg(feature = "watcher")]
+pub mod watcher;
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct TaskMarkerConfig {
+    pub done: char,
+    pub todo: char,
+    pub incomplete: char,
+    pub canceled: char,
+    /// Extra states, like `[>]` for forwarded tasks
+    #[serde(default)]
+    pub custom: Vec<CustomState>,
+    /// Markers of the states `CycleState` goes through, in order, every state if empty
+    #[serde(default)]
+    pub cycle: Vec<char>,
+}
+
+impl TaskMarkerConfig {
+    /// Returns the character written between the brackets of a task in `state`.
+    #[must_use]
+    pub const fn marker(&self, state: &State) -> char {
+        match state {
+            State::Done => self.done,
+            State::ToDo => self.todo,
+            State::Incomplete => self.incomplete,
+            State::Canceled => self.canceled,
+            State::Custom(custom) => custom.marker,
+        }
+    }
+    /// Returns the state written with `marker`, if any.
+    #[must_use]
+    pub fn state(&self, marker: char) -> Option<State> {
+        if marker == self.todo {
+            Some(State::ToDo)
+        } else if marker == self.done {
+            Some(State::Done)
+        } else if marker == self.incomplete {
+            Some(State::Incomplete)
+        } else if marker == self.canceled {
+            Some(State::Canceled)
+        } else {
+            self.custom
+                .iter()
+                .find(|custom| custom.marker == marker)
+                .cloned()
+                .map(State::Custom)
+        }
+    }
+    /// Returns the state called `name` (`todo`, `done`, `incomplete`, `canceled` or a custom state), ignoring case.
+    #[must_use]
+    pub fn state_named(&self, name: &str) -> Option<State> {
+        match name.to_lowercase().as_str() {
+            "todo" => Some(State::ToDo),
+            "done" => Some(State::Done),
+            "incomplete" => Some(State::Incomplete),
+            "canceled" | "cancelled" => Some(State::Canceled),
+            name => self
+                .custom
+                .iter()
+                .find(|custom| custom.name.eq_ignore_ascii_case(name))
+                .cloned()
+                .map(State::Custom),
+        }
+    }
+}
+
+/// A task state declared in the config, written with its own marker.
+#[derive(Clone, Debug, Deserialize, Hash, PartialEq, Eq)]
+pub struct CustomState {
+    pub marker: char,
+    pub name: String,
+    /// Shown instead of the state, `[marker]` if empty
+    #[serde(default)]
+    pub symbol: String,
+    /// Counts the task as over, like Done and Canceled tasks
+    #[serde(default)]
+    pub finished: bool,
+}
+
+// Mostly for tests
+impl Default for TaskMarkerConfig {
+    fn default() -> Self {
+        Self {
+            done: 'x',
+            todo: ' ',
+            incomplete: '/',
+            canceled: '-',
+            custom: vec![],
+            cycle: vec![],
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct PrettySymbolsConfig {
+    pub task_done: String,
+    pub task_todo: String,
+    pub task_incomplete: String,
+    pub task_canceled: String,
+    pub due_date: String,
+    pub priority: String,
+    pub today_tag: String,
+    pub reminder: String,
+    #[serde(default = "default_attachment_symbol")]
+    pub attachment: String,
+    #[serde(default = "default_recurrence_symbol")]
+    pub recurrence: String,
+}
+fn default_attachment_symbol() -> String {
+    String::from("📎")
+}
+fn default_recurrence_symbol() -> String {
+    String::from("🔁")
+}
+impl Default for PrettySymbolsConfig {
+    fn default() -> Self {
+        Self {
+            task_done: String::from("✅"),
+            task_todo: String::from("❌"),
+            task_incomplete: String::from("⏳"),
+            task_canceled: String::from("🚫"),
+            due_date: String::from("📅"),
+            priority: String::from("❗"),
+            today_tag: String::from("☀️"),
+            reminder: String::from("⏰"),
+            attachment: default_attachment_symbol(),
+            recurrence: default_recurrence_symbol(),
+        }
+    }
+}
+impl PrettySymbolsConfig {
+    /// Symbols used in ASCII mode, for terminals without emoji fonts.
+    #[must_use]
+    pub fn ascii() -> Self {
+        Self {
+            task_done: String::from("[x]"),
+            task_todo: String::from("[ ]"),
+            task_incomplete: String::from("[/]"),
+            task_canceled: String::from("[-]"),
+            due_date: String::from("@"),
+            priority: String::from("!"),
+            today_tag: String::from("+"),
+            reminder: String::from("~"),
+            attachment: String::from("&"),
+            recurrence: String::from("*"),
+        }
+    }
+}
+/// What happens when a task is marked done.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CompletionFeedback {
+    #[default]
+    None,
+    /// Rings the terminal bell
+    Bell,
+    /// Shows a celebration line in the status bar
+    Confetti,
+}
+/// Whether and how the completion date is written when a task is marked done, both formats are always read.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CompletionDate {
+    #[default]
+    None,
+    /// `done:<date>`, in the date format of the vault
+    Token,
+    /// `✅ YYYY-MM-DD`, like the Obsidian Tasks plugin
+    Emoji,
+}
+/// What happens to relative dates (`monday`, `3d`, `remind:tmr`) when the vault is loaded.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RelativeDates {
+    /// They are replaced by literal dates
+    #[default]
+    Fix,
+    /// Lines are left as written, dates are resolved against the day they were first read, recorded in a `<!-- vt: -->` comment
+    Keep,
+    /// Like `Keep`, and they are logged and reported by `check`
+    Warn,
+}
+/// What happens to a task with subtasks when the vault is loaded.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ParentCompletion {
+    /// Nothing
+    #[default]
+    Off,
+    /// It is marked done once every subtask is done or canceled
+    Done,
+    /// Its completion is the share of its subtasks that are done, canceled ones excluded
+    Percentage,
+}
+/// How the progress of tasks with subtasks is shown, it is never written to notes.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SubtaskProgress {
+    #[default]
+    Off,
+    /// Done subtasks out of the subtasks, e.g. `[3/5]`
+    Count,
+    /// Share of done subtasks, as a progress bar
+    Percentage,
+}
+/// How the attributes of tasks are written to notes, both syntaxes are always read.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum TaskSyntax {
+    #[default]
+    VaultTasks,
+    /// Dates, priorities and recurrences use the emoji signifiers of the Obsidian Tasks plugin
+    ObsidianTasks,
+}
+/// A vault of `vaults`, to switch to from the TUI.
+#[derive(Clone, Debug, Deserialize, Default, PartialEq, Eq)]
+pub struct VaultProfile {
+    pub path: PathBuf,
+    /// Color of the selected tab and of the name of the vault while it is open, e.g. "blue" or "#89b4fa"
+    #[serde(default)]
+    pub accent: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Default)]
+pub struct TasksConfig {
+    #[serde(default)]
+    pub parse_dot_files: bool,
+    #[serde(default)]
+    pub parse_canvas_files: bool,
+    #[serde(default)]
+    pub file_tags_propagation: bool,
+    #[serde(default)]
+    pub ignored: Vec<PathBuf>,
+    #[serde(default)]
+    pub indent_length: usize,
+    #[serde(default)]
+    pub use_american_format: bool,
+    #[serde(default)]
+    pub show_relative_due_dates: bool,
+    #[serde(default)]
+    pub vault_path: PathBuf,
+    /// Only this file or directory of the vault is parsed, set with `--scope`
+    #[serde(skip)]
+    pub scope: PathBuf,
+    #[serde(default)]
+    pub explorer_default_search_string: String,
+    #[serde(default)]
+    pub filter_default_search_string: String,
+    /// Time of due dates and reminders without one when a time is needed, e.g. `"17:00"`, midnight if empty
+    #[serde(default)]
+    pub default_due_time: String,
+    /// `strftime` format of the dates shown in the TUI and printed by commands, e.g. `"%a %d %b"`, the format of the notes if empty
+    #[serde(default)]
+    pub date_display_format: String,
+    /// `strftime` format of the times shown after dates, e.g. `"%H:%M"`, `"%T"` if empty
+    #[serde(default)]
+    pub time_display_format: String,
+    /// Tasks due on a day without a time are sorted after the timed tasks of that day
+    #[serde(default)]
+    pub untimed_tasks_last: bool,
+    /// Order of the tasks printed by `vault-tasks stdout` and `vault-tasks list`, unless `--sort` is given
+    #[serde(default)]
+    pub output_sort: OutputSort,
+    /// Reverses `output_sort`, unless `--sort` is given
+    #[serde(default)]
+    pub output_sort_reverse: bool,
+    /// Weights of the urgency used by `--sort urgency` and `vault-tasks next`
+    #[serde(default)]
+    pub urgency: UrgencyConfig,
+    #[serde(default)]
+    pub state_match_mode: StateMatchMode,
+    /// Dates of searches only match the same date and time instead of the same day
+    #[serde(default)]
+    pub exact_date_search: bool,
+    #[serde(default)]
+    pub explorer_sorting_mode: ExplorerSortingMode,
+    #[serde(default)]
+    pub explorer_natural_sort: bool,
+    #[serde(default)]
+    pub explorer_case_insensitive_sort: bool,
+    /// Relative widths of the previous, current and preview columns of the explorer, 0 hides the previous or preview column
+    #[serde(default)]
+    pub explorer_column_ratios: [u16; 3],
+    #[serde(default)]
+    pub preview_soft_wrap: bool,
+    /// Show tags as colored pills rather than as `#tag` written in their color
+    #[serde(default)]
+    pub tag_pills: bool,
+    #[serde(default)]
+    pub task_state_markers: TaskMarkerConfig,
+    /// Syntax of the attributes written to notes, `"obsidian-tasks"` to share the vault with the Obsidian Tasks plugin
+    #[serde(default)]
+    pub syntax: TaskSyntax,
+    #[serde(default)]
+    pub pretty_symbols: PrettySymbolsConfig,
+    #[serde(default)]
+    pub ascii_mode: bool,
+    #[serde(default)]
+    pub recent_days: u32,
+    #[serde(default)]
+    pub daily_notes_pattern: String,
+    #[serde(default)]
+    pub carry_over_mode: CarryOverMode,
+    /// Where `did` logs tasks relative to the vault, using strftime syntax (today's daily note if empty)
+    #[serde(default)]
+    pub did_log_file: String,
+    /// Where `add` appends new tasks relative to the vault, using strftime syntax (today's daily note if empty)
+    #[serde(default)]
+    pub drop_file: String,
+    /// File where `archive` moves finished tasks, relative to the vault
+    #[serde(default)]
+    pub archive_file: PathBuf,
+    /// Policies applied by `maintain`
+    #[serde(default)]
+    pub maintenance: MaintenanceConfig,
+    #[serde(default)]
+    pub task_notes_folder: PathBuf,
+    #[serde(default)]
+    pub task_note_template: PathBuf,
+    #[serde(default)]
+    pub completion_feedback: CompletionFeedback,
+    /// Completion date written when a task is marked done, removed when it is marked to-do again
+    #[serde(default)]
+    pub completion_date: CompletionDate,
+    /// Write today's date as the creation date of the tasks added with `add`
+    #[serde(default)]
+    pub creation_date: bool,
+    /// Whether relative dates are replaced by literal dates when the vault is loaded
+    #[serde(default)]
+    pub relative_dates: RelativeDates,
+    /// Hide tasks whose start date is still to come, unless the search has `unstarted`
+    #[serde(default)]
+    pub hide_not_started: bool,
+    /// Whether tasks are completed from their subtasks when the vault is loaded
+    #[serde(default)]
+    pub complete_parents: ParentCompletion,
+    /// Mark done tasks to-do again when the vault is loaded if one of their subtasks is unfinished
+    #[serde(default)]
+    pub reopen_parents: bool,
+    /// Show the progress of tasks with subtasks, canceled subtasks excluded
+    #[serde(default)]
+    pub subtask_progress: SubtaskProgress,
+    /// Show how many tasks were completed today in the status bar
+    #[serde(default)]
+    pub show_completion_counter: bool,
+    /// Show a countdown to the next timed due task of the Filter tab in the status bar
+    #[serde(default)]
+    pub show_next_due: bool,
+    /// Files with unfinished tasks not modified for this many days are reported as stale
+    #[serde(default)]
+    pub stale_days: u32,
+    /// Number of future occurrences of recurring tasks shown in the Calendar tab
+    #[serde(default)]
+    pub recurrence_preview: usize,
+    /// Days with more unfinished tasks due are overloaded, 0 to disable
+    #[serde(default)]
+    pub daily_task_capacity: usize,
+    /// Days whose unfinished tasks are estimated longer are overloaded, e.g. `6h`, empty to disable
+    #[serde(default)]
+    pub daily_estimate_capacity: String,
+    /// Number of weeks shown in the Stats tab
+    #[serde(default)]
+    pub stats_weeks: usize,
+    /// Reload the files of the vault modified outside of vault-tasks
+    #[serde(default)]
+    pub watch_vault: bool,
+    /// Reload every file of the vault this often, in seconds, when `watch_vault` misses changes (e.g. on network filesystems), 0 to disable
+    #[serde(default)]
+    pub auto_reload_seconds: u64,
+    /// Activities suggested during breaks of the Time Management tab
+    #[serde(default)]
+    pub break_suggestions: Vec<String>,
+    /// Note whose list items replace `break_suggestions`, relative to the vault
+    #[serde(default)]
+    pub break_suggestions_note: PathBuf,
+    /// Rewrite the wiki-links pointing at a file or header renamed from the Explorer tab
+    #[serde(default)]
+    pub update_links_on_rename: bool,
+    /// Saved searches by name, used with the `filter:name` token and `--filter-name`
+    #[serde(default)]
+    pub filters: BTreeMap<String, String>,
+    /// Vaults the TUI can switch between by name, the first one being opened if `vault_path` is not set
+    #[serde(default)]
+    pub vaults: BTreeMap<String, VaultProfile>,
+    /// Set when another instance holds the lock of the vault, files are never written
+    #[serde(skip)]
+    pub read_only: bool,
+}
+
+impl TasksConfig {
+    /// Returns `default_due_time`, midnight if it is not set or invalid.
+    #[must_use]
+    pub fn default_due_time(&self) -> NaiveTime {
+        if self.default_due_time.is_empty() {
+            return NaiveTime::default();
+        }
+        NaiveTime::parse_from_str(&self.default_due_time, "%H:%M").unwrap_or_else(|e| {
+            error!("Invalid default_due_time `{}`: {e}", self.default_due_time);
+            NaiveTime::default()
+        })
+    }
+    /// Returns `date_display_format` and `time_display_format`, the format of the notes and `"%T"` replacing them if they are empty or invalid.
+    #[must_use]
+    pub fn date_display_format(&self) -> DateDisplayFormat {
+        let valid = |name: &str, format: &str| {
+            if format.is_empty() {
+                return false;
+            }
+            let res = StrftimeItems::new(format).parse().is_ok();
+            if !res {
+                error!("Invalid {name} `{format}`");
+            }
+            res
+        };
+        let date = if valid("date_display_format", &self.date_display_format) {
+            self.date_display_format.clone()
+        } else if self.use_american_format {
+            String::from("%Y/%m/%d")
+        } else {
+            String::from("%d/%m/%Y")
+        };
+        let time = if valid("time_display_format", &self.time_display_format) {
+            self.time_display_format.as_str()
+        } else {
+            "%T"
+        };
+        DateDisplayFormat {
+            date_time: format!("{date} {time}"),
+            date,
+        }
+    }
+    /// Returns `daily_estimate_capacity` in minutes, `None` if it is not set or invalid.
+    #[must_use]
+    pub fn daily_estimate_capacity(&self) -> Option<u32> {
+        if self.daily_estimate_capacity.is_empty() {
+            return None;
+        }
+        let res = parse_duration(&self.daily_estimate_capacity);
+        if res.is_none() {
+            error!(
+                "Invalid daily_estimate_capacity `{}`",
+                self.daily_estimate_capacity
+            );
+        }
+        res
+    }
+    /// Returns the name and profile of the entry of `vaults` that is open, if any.
+    #[must_use]
+    pub fn current_vault(&self) -> Option<(&String, &VaultProfile)> {
+        self.vaults
+            .iter()
+            .find(|(_, vault)| vault.path == self.vault_path)
+    }
+    /// Fails if the vault must not be written to, see `lock::VaultLock`.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error in read-only mode.
+    pub fn check_writable(&self) -> Result<()> {
+        if self.read_only {
+            bail!("Vault is read-only, another vault-tasks instance is using it");
+        }
+        Ok(())
+    }
+}
+
+/// Number of unfinished tasks that are overdue or due today.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DueCount {
+    pub overdue: usize,
+    pub today: usize,
+}
+
+pub struct TaskManager {
+    pub tasks: VaultData,
+    pub tags: HashSet<String>,
+    pub current_filter: Option<Filter>,
+    /// Last modification time of every parsed file
+    pub modification_times: HashMap<PathBuf, NaiveDateTime>,
+}
+impl Default for TaskManager {
+    fn default() -> Self {
+        Self {
+            tasks: VaultData::Directory("Empty Vault".to_owned(), vec![]),
+            tags: HashSet::new(),
+            current_filter: None,
+            modification_times: HashMap::new(),
+        }
+    }
+}
+impl TaskManager {
+    /// Loads a vault from a `Config` and returns a `TaskManager`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the vault can't be loaded.
+    pub fn load_from_config(config: &TasksConfig) -> Result<Self> {
+        let mut res = Self::default();
+        res.reload(config)?;
+        Ok(res)
+    }
+
+    /// Reloads the `VaultData` from file system.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the vault can't be parsed, or if tasks can't be fixed (relative dates are replaced by fixed dates for example).
+    pub fn reload(&mut self, config: &TasksConfig) -> Result<()> {
+        let mut loaded = Self::load_read_only(config)?;
+
+        if !config.read_only {
+            Self::update_parents(config, &mut loaded.tasks);
+            let inserted = Self::rewrite_vault_tasks(config, &loaded.tasks).unwrap_or_else(|e| {
+                error!("Failed to fix tasks: {e}");
+                false
+            });
+            // Lines inserted for recurring tasks shift the tasks below them
+            if inserted {
+                loaded = Self::load_read_only(config)?;
+            }
+        }
+        let Self {
+            mut tasks,
+            tags,
+            modification_times,
+            ..
+        } = loaded;
+        Self::show_subtask_progress(config, &mut tasks);
+
+        self.tasks = tasks;
+        self.tags = tags;
+        self.modification_times = modification_times;
+        Ok(())
+    }
+
+    /// Reparses only `path`, a file of the vault that was created, modified or removed, and splices it into the vault.
+    ///
+    /// The whole vault is reloaded when it is a single file or a scope is set.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the vault can't be reloaded.
+    pub fn reload_file(&mut self, config: &TasksConfig, path: &Path) -> Result<()> {
+        if config.vault_path.is_file() || !config.scope.as_os_str().is_empty() {
+            return self.reload(config);
+        }
+        let Ok(relative_path) = path.strip_prefix(&config.vault_path) else {
+            bail!("{path:?} is not part of the vault");
+        };
+        let Some(file_name) = relative_path.file_name().and_then(|f| f.to_str()) else {
+            bail!("{path:?} is not a file");
+        };
+        let dirs = relative_path
+            .parent()
+            .unwrap_or(Path::new(""))
+            .iter()
+            .map(|d| d.to_string_lossy().to_string())
+            .collect::<Vec<String>>();
+
+        let mut vault_parser = VaultParser::new(config.clone());
+        let mut file_entry = if path.is_file() {
+            vault_parser.parse_path(path)
+        } else {
+            None
+        };
+        if let (Some(entry), false) = (&mut file_entry, config.read_only) {
+            Self::update_parents(config, entry);
+            let parent = path.parent().unwrap_or(Path::new("")).to_string_lossy();
+            let inserted = Self::rewrite_vault_tasks(
+                config,
+                &VaultData::Directory(parent.to_string(), vec![entry.clone()]),
+            )
+            .unwrap_or_else(|e| {
+                error!("Failed to fix tasks: {e}");
+                false
+            });
+            // Lines inserted for recurring tasks shift the tasks below them
+            if inserted {
+                file_entry = vault_parser.parse_path(path);
+            }
+        }
+        if let Some(file_entry) = &mut file_entry {
+            Self::show_subtask_progress(config, file_entry);
+        }
+        Self::replace_file_entry(&mut self.tasks, &dirs, file_name, file_entry);
+
+        self.modification_times.remove(path);
+        self.modification_times
+            .extend(vault_parser.modification_times);
+        self.tags.clear();
+        Self::collect_tags(&self.tasks, &mut self.tags);
+        Ok(())
+    }
+
+    /// Reparses only `paths`, see `reload_file`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the vault can't be reloaded.
+    pub fn reload_files(&mut self, config: &TasksConfig, paths: &[PathBuf]) -> Result<()> {
+        if config.vault_path.is_file() || !config.scope.as_os_str().is_empty() {
+            return self.reload(config);
+        }
+        for path in paths {
+            self.reload_file(config, path)
+                .unwrap_or_else(|e| error!("Failed to reload {path:?}: {e}"));
+        }
+        Ok(())
+    }
+
+    /// Replaces the entry of the file `file_name` in the directories `dirs`, or removes it if `file_entry` is `None`.
+    ///
+    /// Missing directories are created, and directories left empty are removed.
+    fn replace_file_entry(
+        tasks: &mut VaultData,
+        dirs: &[String],
+        file_name: &str,
+        file_entry: Option<VaultData>,
+    ) {
+        let VaultData::Directory(_, children) = tasks else {
+            return;
+        };
+        let position = |children: &Vec<VaultData>, name: &str| {
+            children
+                .iter()
+                .position(|c| matches!(c, VaultData::Directory(n, _) if n == name))
+        };
+        let Some((dir, dirs)) = dirs.split_first() else {
+            match (position(children, file_name), file_entry) {
+                (Some(i), Some(file_entry)) => children[i] = file_entry,
+                (Some(i), None) => {
+                    children.remove(i);
+                }
+                (None, Some(file_entry)) => children.push(file_entry),
+                (None, None) => (),
+            }
+            return;
+        };
+        let i = if let Some(i) = position(children, dir) {
+            i
+        } else {
+            if file_entry.is_none() {
+                return;
+            }
+            children.push(VaultData::Directory(dir.clone(), vec![]));
+            children.len() - 1
+        };
+        Self::replace_file_entry(&mut children[i], dirs, file_name, file_entry);
+        if matches!(&children[i], VaultData::Directory(_, c) if c.is_empty()) {
+            children.remove(i);
+        }
+    }
+
+    /// Watches the vault from a background thread, with the notifications of the filesystem.
+    ///
+    /// The files created, modified or removed are sent to the returned receiver at most every `interval`, to be given to `reload_files`. The thread stops when the receiver is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the filesystem can't notify changes to the vault, `auto_reload` then polls it instead.
+    #[cfg(feature = "watcher")]
+    pub fn watch(config: &TasksConfig, interval: Duration) -> Result<Receiver<Vec<PathBuf>>> {
+        Ok(watcher::watch_vault(config, interval)?)
+    }
+
+    /// Sends every file of the vault to the returned receiver every `interval`, for filesystems that can't notify changes.
+    ///
+    /// The thread stops when the receiver is dropped.
+    #[cfg(feature = "watcher")]
+    #[must_use]
+    pub fn auto_reload(config: &TasksConfig, interval: Duration) -> Receiver<Vec<PathBuf>> {
+        let (tx, rx) = channel();
+        let mut watcher = VaultWatcher::new(config.clone());
+        std::thread::spawn(move || loop {
+            std::thread::sleep(interval);
+            // Sent even if empty, to stop as soon as the receiver is dropped
+            if tx.send(watcher.all_files()).is_err() {
+                break;
+            }
+        });
+        rx
+    }
+
+    /// Loads a vault without fixing its tasks, files are never written.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the vault can't be parsed.
+    pub fn load_read_only(config: &TasksConfig) -> Result<Self> {
+        let mut vault_parser = VaultParser::new(config.clone());
+        let tasks = vault_parser.scan_vault()?;
+
+        let mut tags = HashSet::new();
+        Self::collect_tags(&tasks, &mut tags);
+
+        Ok(Self {
+            tasks,
+            tags,
+            current_filter: None,
+            modification_times: vault_parser.modification_times,
+        })
+    }
+
+    /// Sets `current_filter` to the saved search `name` of `filters`.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if there is no saved search with this name.
+    pub fn apply_named_filter(&mut self, config: &TasksConfig, name: &str) -> Result<()> {
+        let Some(search) = config.filters.get(name) else {
+            bail!(
+                "Unknown filter `{name}`, saved filters are: {}",
+                config
+                    .filters
+                    .keys()
+                    .map(String::as_str)
+                    .collect::<Vec<&str>>()
+                    .join(", ")
+            );
+        };
+        self.current_filter = Some(parse_search_input(search, config));
+        Ok(())
+    }
+
+    /// Counts unfinished tasks matching `filter` that are overdue or due today.
+    ///
+    /// Tasks marked with `@today` are counted as due today.
+    #[must_use]
+    pub fn count_due(&self, filter: &Filter, now: NaiveDateTime) -> DueCount {
+        let today = now.date();
+        let mut res = DueCount::default();
+        for task in filter_to_vec(&self.tasks, filter)
+            .iter()
+            .filter(|t| !t.state.is_finished())
+        {
+            match task.due_date {
+                DueDate::Day(date) if date < today => res.overdue += 1,
+                DueDate::DayTime(date_time) if date_time < now => res.overdue += 1,
+                DueDate::Day(date) if date == today => res.today += 1,
+                DueDate::DayTime(date_time) if date_time.date() == today => res.today += 1,
+                _ if task.is_today => res.today += 1,
+                _ => (),
+            }
+        }
+        res
+    }
+
+    /// Returns the reminders of unfinished tasks set after `since` with their task, sorted chronologically.
+    ///
+    /// Reminders without a time are set at `default_time`.
+    #[must_use]
+    pub fn get_reminders(
+        &self,
+        since: NaiveDateTime,
+        default_time: NaiveTime,
+    ) -> Vec<(NaiveDateTime, Task)> {
+        let mut res = filter_to_vec(&self.tasks, &Filter::default())
+            .into_iter()
+            .filter(|t| !t.state.is_finished())
+            .filter_map(|t| {
+                t.reminder
+                    .to_naive_date_time_at(default_time)
+                    .filter(|reminder| *reminder > since)
+                    .map(|reminder| (reminder, t))
+            })
+            .collect::<Vec<(NaiveDateTime, Task)>>();
+        res.sort_by_key(|a| a.0);
+        res
+    }
+
+    /// Returns the files modified since `since`, most recent first.
+    #[must_use]
+    pub fn recent_files(&self, since: NaiveDateTime) -> Vec<(PathBuf, NaiveDateTime)> {
+        let mut res = self
+            .modification_times
+            .iter()
+            .filter(|(_, modified)| **modified >= since)
+            .map(|(path, modified)| (path.clone(), *modified))
+            .collect::<Vec<(PathBuf, NaiveDateTime)>>();
+        res.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        res
+    }
+
+    /// Returns the `VaultData` of a file of the vault.
+    #[must_use]
+    pub fn get_file_entry(&self, vault_path: &Path, path: &Path) -> Option<&VaultData> {
+        let relative_path = if vault_path.is_file() {
+            Path::new(path.file_name()?)
+        } else {
+            path.strip_prefix(vault_path).ok()?
+        };
+        let mut current = &self.tasks;
+        for component in relative_path {
+            let VaultData::Directory(_, children) = current else {
+                return None;
+            };
+            current = children.iter().find(
+                |c| matches!(c, VaultData::Directory(name, _) if component == name.as_str()),
+            )?;
+        }
+        Some(current)
+    }
+
+    /// Counts the tasks using each of the `tags`, only under `path` if set.
+    ///
+    /// Returns `None` if `path`, relative to the vault, is not a file or a directory of the vault.
+    #[must_use]
+    pub fn tag_counts(
+        &self,
+        config: &TasksConfig,
+        path: Option<&Path>,
+    ) -> Option<BTreeMap<String, StateCounts>> {
+        let entry = match path {
+            Some(path) => self.get_file_entry(&config.vault_path, &config.vault_path.join(path))?,
+            None => &self.tasks,
+        };
+        Some(stats::tag_counts(entry))
+    }
+
+    /// Explores the vault and fills a `&mut HashSet<String>` with every tags found.
+    pub fn collect_tags(tasks: &VaultData, tags: &mut HashSet<String>) {
+        match tasks {
+            VaultData::Directory(_, children) | VaultData::Header(_, _, children) => {
+                children.iter().for_each(|c| Self::collect_tags(c, tags));
+            }
+            VaultData::Task(task) => {
+                task.tags.clone().unwrap_or_default().iter().for_each(|t| {
+                    tags.insert(t.clone());
+                });
+                task.subtasks
+                    .iter()
+                    .for_each(|task| Self::collect_tags(&VaultData::Task(task.clone()), tags));
+            }
+        }
+    }
+    /// Follows a path and returns every `VaultData` that are on the target layer, discarding every children.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the path can't be resolved.
+    pub fn get_path_layer_entries(&self, path: &[String]) -> Result<Vec<VaultData>> {
+        Ok(self
+            .get_explorer_entries(path)?
+            .iter()
+            .map(|vd| match vd {
+                VaultData::Directory(name, _) => VaultData::Directory(name.clone(), vec![]),
+                VaultData::Header(level, name, _) => {
+                    VaultData::Header(*level, name.clone(), vec![])
+                }
+                VaultData::Task(t) => {
+                    let mut t = t.clone();
+                    t.subtasks = vec![];
+                    VaultData::Task(t)
+                }
+            })
+            .collect::<Vec<VaultData>>())
+    }
+
+    /// Calls `update` on the tasks of `vault_data`, canvas files excluded.
+    fn update_tasks(vault_data: &mut VaultData, update: &impl Fn(&mut Task)) {
+        match vault_data {
+            VaultData::Directory(name, _) if is_canvas_file(Path::new(name)) => (),
+            VaultData::Directory(_, children) | VaultData::Header(_, _, children) => children
+                .iter_mut()
+                .for_each(|c| Self::update_tasks(c, update)),
+            VaultData::Task(task) => update(task),
+        }
+    }
+
+    /// Updates the tasks of the vault from their subtasks before they are written, see `Task::update_from_subtasks`.
+    fn update_parents(config: &TasksConfig, vault_data: &mut VaultData) {
+        if config.complete_parents != ParentCompletion::Off || config.reopen_parents {
+            let today = chrono::Local::now().date_naive();
+            Self::update_tasks(vault_data, &|task| task.update_from_subtasks(config, today));
+        }
+    }
+
+    /// Computes the progress shown on tasks with subtasks once they are written, see `Task::compute_subtask_progress`.
+    fn show_subtask_progress(config: &TasksConfig, vault_data: &mut VaultData) {
+        if config.subtask_progress != SubtaskProgress::Off {
+            Self::update_tasks(vault_data, &|task| {
+                task.compute_subtask_progress(config.subtask_progress);
+            });
+        }
+    }
+
+    /// Recursively calls `Task.fix_task_attributes` on every task from the vault.
+    ///
+    /// Tasks of a file are fixed from the bottom up, so that lines inserted for recurring tasks don't move the tasks left to fix.
+    /// Tasks with relative dates are left alone unless `relative_dates` is `fix`.
+    /// Returns whether lines were inserted, in which case the line numbers of `tasks` are outdated.
+    fn rewrite_vault_tasks(config: &TasksConfig, tasks: &VaultData) -> Result<bool> {
+        fn collect_tasks_rec<'a>(
+            filename: &PathBuf,
+            file_entry: &'a VaultData,
+            res: &mut Vec<(PathBuf, &'a Task)>,
+        ) {
+            match file_entry {
+                VaultData::Header(_, _, children) => {
+                    children
+                        .iter()
+                        .for_each(|c| collect_tasks_rec(filename, c, res));
+                }
+                VaultData::Task(task) => {
+                    fn add_task<'a>(
+                        filename: &PathBuf,
+                        task: &'a Task,
+                        res: &mut Vec<(PathBuf, &'a Task)>,
+                    ) {
+                        res.push((filename.clone(), task));
+                        task.subtasks
+                            .iter()
+                            .for_each(|t| add_task(filename, t, res));
+                    }
+                    add_task(filename, task, res);
+                }
+                VaultData::Directory(dir_name, children) => {
+                    let mut filename = filename.clone();
+                    filename.push(dir_name);
+                    if is_canvas_file(&filename) {
+                        return;
+                    }
+                    children
+                        .iter()
+                        .for_each(|c| collect_tasks_rec(&filename, c, res));
+                }
+            }
+        }
+        let mut res = vec![];
+        collect_tasks_rec(&PathBuf::new(), tasks, &mut res);
+        res.sort_by(|(p1, t1), (p2, t2)| p1.cmp(p2).then(t2.line_number.cmp(&t1.line_number)));
+        let today = chrono::Local::now().date_naive();
+        let mut inserted = false;
+        for (path, task) in &res {
+            if task.relative_dates.is_empty() || config.relative_dates == RelativeDates::Fix {
+                task.fix_task_attributes(config, path)?;
+                inserted |= next_occurrence(task).is_some();
+                continue;
+            }
+            if config.relative_dates == RelativeDates::Warn {
+                warn!(
+                    "{path:?}:{}: relative date `{}` left as written",
+                    task.line_number,
+                    task.relative_dates.join(" ")
+                );
+            }
+            if task.relative_dates_resolved_on().is_none() {
+                task.record_relative_dates(config, path, today)?;
+            }
+        }
+        Ok(inserted)
+    }
+
+    /// Follows the `selected_header_path` to retrieve the correct `VaultData`.
+    /// Then returns every `VaultData` objects on the same layer.
+    ///
+    /// # Errors
+    /// Will return an error if the vault is empty or the first layer is not a `VaultData::Directory`
+    pub fn get_explorer_entries(&self, selected_header_path: &[String]) -> Result<Vec<VaultData>> {
+        fn aux(
+            file_entry: Vec<VaultData>,
+            selected_header_path: &[String],
+            path_index: usize,
+        ) -> Result<Vec<VaultData>> {
+            if path_index == selected_header_path.len() {
+                Ok(file_entry)
+            } else {
+                for entry in file_entry {
+                    match entry {
+                        VaultData::Directory(name, children)
+                        | VaultData::Header(_, name, children) => {
+                            if name == selected_header_path[path_index] {
+                                return aux(children, selected_header_path, path_index + 1);
+                            }
+                        }
+                        VaultData::Task(task) => {
+                            if task.name == selected_header_path[path_index] {
+                                return aux(
+                                    task.subtasks
+                                        .iter()
+                                        .map(|t| VaultData::Task(t.clone()))
+                                        .collect(),
+                                    selected_header_path,
+                                    path_index + 1,
+                                );
+                            }
+                        }
+                    }
+                }
+                bail!("Couldn't find corresponding entry");
+            }
+        }
+
+        let filtered_tasks = if let Some(task_filter) = &self.current_filter {
+            filter(&self.tasks, task_filter)
+        } else {
+            Some(self.tasks.clone())
+        };
+
+        match filtered_tasks {
+            Some(VaultData::Directory(_, entries)) => aux(entries, selected_header_path, 0),
+            None => bail!("Empty Vault"),
+            _ => {
+                error!("First layer of VaultData was not a Directory");
+                bail!("First layer of VaultData was not a Directory")
+            }
+        }
+    }
+
+    /// Follows the `selected_header_path` to retrieve the correct `VaultData`.
+    /// Returns a vector of `VaultData` with the items to display in TUI, preserving the recursive nature.
+    /// `task_preview_offset`: add offset to return a task instead of one of its subtasks
+    ///
+    /// # Errors
+    /// Will return an error if
+    /// - vault is empty or the first layer is not a `VaultData::Directory`
+    /// - the path can't be resolved in the vault data
+    pub fn get_vault_data_from_path(
+        &self,
+        selected_header_path: &[String],
+        task_preview_offset: usize,
+    ) -> Result<Vec<VaultData>> {
+        fn aux(
+            file_entry: VaultData,
+            selected_header_path: &[String],
+            path_index: usize,
+            task_preview_offset: usize,
+        ) -> Result<Vec<VaultData>> {
+            if path_index == selected_header_path.len() {
+                Ok(vec![file_entry])
+            } else {
+                match file_entry {
+                    VaultData::Directory(name, children) | VaultData::Header(_, name, children) => {
+                        if name == selected_header_path[path_index] {
+                            let mut res = vec![];
+                            for child in children {
+                                if let Ok(mut found) = aux(
+                                    child,
+                                    selected_header_path,
+                                    path_index + 1,
+                                    task_preview_offset,
+                                ) {
+                                    res.append(&mut found);
+                                }
+                            }
+                            Ok(res)
+                        } else {
+                            bail!("Couldn't find corresponding entry");
+                        }
+                    }
+                    VaultData::Task(task) => {
+                        if task.name == selected_header_path[path_index] {
+                            let mut res = vec![];
+
+                            if path_index + task_preview_offset == selected_header_path.len() {
+                                res.push(VaultData::Task(task));
+                            } else {
+                                for child in task.subtasks {
+                                    if let Ok(mut found) = aux(
+                                        VaultData::Task(child),
+                                        selected_header_path,
+                                        path_index + 1,
+                                        task_preview_offset,
+                                    ) {
+                                        res.append(&mut found);
+                                    }
+                                }
+                            }
+                            Ok(res)
+                        } else {
+                            bail!("Couldn't find corresponding entry");
+                        }
+                    }
+                }
+            }
+        }
+
+        let filtered_tasks = if let Some(task_filter) = &self.current_filter {
+            filter(&self.tasks, task_filter)
+        } else {
+            Some(self.tasks.clone())
+        };
+        match filtered_tasks {
+            Some(VaultData::Directory(_, entries)) => {
+                for entry in entries {
+                    if let Ok(res) = aux(entry, selected_header_path, 0, task_preview_offset) {
+                        return Ok(res);
+                    }
+                }
+                error!("Vault was not empty but the entry was not found");
+                bail!("Vault was not empty but the entry was not found");
+            }
+            None => bail!("Empty Vault"),
+            _ => {
+                error!("First layer of VaultData was not a Directory");
+                bail!("Empty Vault")
+            }
+        }
+    }
+
+    /// Whether the path resolves to something that can be entered or not.
+    /// Directories, Headers and Tasks with subtasks can be entered.
+    #[must_use]
+    pub fn can_enter(&self, selected_header_path: &[String]) -> bool {
+        fn aux(file_entry: VaultData, selected_header_path: &[String], path_index: usize) -> bool {
+            if path_index == selected_header_path.len() {
+                true
+            } else {
+                match file_entry {
+                    VaultData::Directory(name, children) | VaultData::Header(_, name, children) => {
+                        if name == selected_header_path[path_index] {
+                            return children
+                                .iter()
+                                .any(|c| aux(c.clone(), selected_header_path, path_index + 1));
+                        }
+                        false
+                    }
+                    VaultData::Task(task) => {
+                        if task.name == selected_header_path[path_index] {
+                            return task.subtasks.iter().any(|t| {
+                                aux(
+                                    VaultData::Task(t.clone()),
+                                    selected_header_path,
+                                    path_index + 1,
+                                )
+                            });
+                        }
+                        false
+                    }
+                }
+            }
+        }
+
+        let filtered_tasks = if let Some(task_filter) = &self.current_filter {
+            filter(&self.tasks, task_filter)
+        } else {
+            return false;
+        };
+        let Some(VaultData::Directory(_, entries)) = filtered_tasks else {
+            return false;
+        };
+        entries
+            .iter()
+            .any(|e| aux(e.clone(), selected_header_path, 0))
+    }
+}
+impl Display for TaskManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.tasks)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{HashMap, HashSet},
+        path::{Path, PathBuf},
+    };
+
+    use chrono::NaiveDate;
+
+    use super::{
+        CustomState, DueCount, ParentCompletion, RelativeDates, SubtaskProgress, TaskManager,
+        TaskMarkerConfig, TasksConfig,
+    };
+
+    use crate::{
+        filter::{filter_to_vec, parse_search_input, Filter},
+        task::{DueDate, State, Task},
+        vault_data::VaultData,
+    };
+
+    #[test]
+    fn test_state_named() {
+        let forwarded = CustomState {
+            marker: '>',
+            name: "Forwarded".to_owned(),
+            symbol: String::new(),
+            finished: true,
+        };
+        let markers = TaskMarkerConfig {
+            custom: vec![forwarded.clone()],
+            ..Default::default()
+        };
+        assert_eq!(markers.state_named("done"), Some(State::Done));
+        assert_eq!(markers.state_named("Cancelled"), Some(State::Canceled));
+        assert_eq!(
+            markers.state_named("forwarded"),
+            Some(State::Custom(forwarded))
+        );
+        assert_eq!(markers.state_named("blocked"), None);
+    }
+
+    #[test]
+    fn test_get_vault_data() {
+        let expected_tasks = vec![
+            VaultData::Task(Task {
+                name: "test".to_string(),
+                line_number: 8,
+                description: Some("test\ndesc".to_string()),
+                ..Default::default()
+            }),
+            VaultData::Task(Task {
+                name: "test".to_string(),
+                line_number: 8,
+                description: Some("test\ndesc".to_string()),
+                ..Default::default()
+            }),
+            VaultData::Task(Task {
+                name: "test".to_string(),
+                line_number: 8,
+                description: Some("test\ndesc".to_string()),
+                ..Default::default()
+            }),
+        ];
+        let expected_header = VaultData::Header(3, "3".to_string(), expected_tasks.clone());
+        let input = VaultData::Directory(
+            "test".to_owned(),
+            vec![VaultData::Header(
+                0,
+                "Test".to_string(),
+                vec![
+                    VaultData::Header(
+                        1,
+                        "1".to_string(),
+                        vec![VaultData::Header(
+                            2,
+                            "2".to_string(),
+                            vec![expected_header.clone()],
+                        )],
+                    ),
+                    VaultData::Header(
+                        1,
+                        "1.2".to_string(),
+                        vec![
+                            VaultData::Header(3, "3".to_string(), vec![]),
+                            VaultData::Header(
+                                2,
+                                "4".to_string(),
+                                vec![VaultData::Task(Task {
+                                    name: "test".to_string(),
+                                    line_number: 8,
+                                    description: Some("test\ndesc".to_string()),
+                                    ..Default::default()
+                                })],
+                            ),
+                        ],
+                    ),
+                ],
+            )],
+        );
+
+        let task_mgr = TaskManager {
+            tasks: input,
+            tags: HashSet::new(),
+            ..Default::default()
+        };
+
+        let path = vec![String::from("Test"), String::from("1"), String::from("2")];
+        let res = task_mgr.get_vault_data_from_path(&path, 0).unwrap();
+        assert_eq!(vec![expected_header], res);
+
+        let path = vec![
+            String::from("Test"),
+            String::from("1"),
+            String::from("2"),
+            String::from("3"),
+        ];
+        let res = task_mgr.get_vault_data_from_path(&path, 0).unwrap();
+        assert_eq!(expected_tasks, res);
+    }
+
+    #[test]
+    fn test_recent_files() {
+        let date = |day| {
+            NaiveDate::from_ymd_opt(2025, 1, day)
+                .unwrap()
+                .and_hms_opt(12, 0, 0)
+                .unwrap()
+        };
+        let task_mgr = TaskManager {
+            tasks: VaultData::Directory(
+                "vault".to_owned(),
+                vec![VaultData::Directory(
+                    "dir".to_owned(),
+                    vec![VaultData::Directory(
+                        "b.md".to_owned(),
+                        vec![VaultData::Task(Task::default())],
+                    )],
+                )],
+            ),
+            modification_times: HashMap::from([
+                (PathBuf::from("vault/a.md"), date(1)),
+                (PathBuf::from("vault/dir/b.md"), date(5)),
+                (PathBuf::from("vault/c.md"), date(3)),
+            ]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            task_mgr.recent_files(date(2)),
+            vec![
+                (PathBuf::from("vault/dir/b.md"), date(5)),
+                (PathBuf::from("vault/c.md"), date(3))
+            ]
+        );
+        assert_eq!(
+            task_mgr
+                .get_file_entry(Path::new("vault"), Path::new("vault/dir/b.md"))
+                .map(VaultData::count_tasks),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_count_due() {
+        let now = NaiveDate::from_ymd_opt(2025, 1, 10)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap();
+        let task = |due_date, state| {
+            VaultData::Task(Task {
+                due_date,
+                state,
+                ..Default::default()
+            })
+        };
+        let day = |d| NaiveDate::from_ymd_opt(2025, 1, d).unwrap();
+        let task_mgr = TaskManager {
+            tasks: VaultData::Directory(
+                "vault".to_owned(),
+                vec![
+                    task(DueDate::Day(day(9)), State::ToDo),
+                    task(
+                        DueDate::DayTime(day(10).and_hms_opt(8, 0, 0).unwrap()),
+                        State::Incomplete,
+                    ),
+                    task(DueDate::Day(day(10)), State::ToDo),
+                    task(
+                        DueDate::DayTime(day(10).and_hms_opt(18, 0, 0).unwrap()),
+                        State::ToDo,
+                    ),
+                    task(DueDate::Day(day(9)), State::Done),
+                    task(DueDate::Day(day(11)), State::ToDo),
+                    task(DueDate::NoDate, State::ToDo),
+                    VaultData::Task(Task {
+                        is_today: true,
+                        ..Default::default()
+                    }),
+                ],
+            ),
+            ..Default::default()
+        };
+        assert_eq!(
+            task_mgr.count_due(&Filter::default(), now),
+            DueCount {
+                overdue: 2,
+                today: 3
+            }
+        );
+    }
+
+    #[test]
+    fn test_apply_named_filter() {
+        let mut config = TasksConfig::default();
+        config
+            .filters
+            .insert("work".to_owned(), "#work sort:due".to_owned());
+        let mut task_mgr = TaskManager::default();
+        assert!(task_mgr.apply_named_filter(&config, "home").is_err());
+        assert!(task_mgr.current_filter.is_none());
+        task_mgr.apply_named_filter(&config, "work").unwrap();
+        assert_eq!(
+            task_mgr.current_filter,
+            Some(parse_search_input("#work sort:due", &config))
+        );
+    }
+
+    #[test]
+    fn test_rewrite_recurring_task() {
+        let vault_path = std::env::temp_dir().join("vault-tasks-recurrence");
+        let _ = std::fs::remove_dir_all(&vault_path);
+        std::fs::create_dir_all(&vault_path).unwrap();
+        let note = vault_path.join("chores.md");
+        std::fs::write(
+            &note,
+            "# Chores\n- [x] water plants 2020/01/01 every week\n- [x] sweep 2020/01/03 every 2 days\n- [ ] cook\n",
+        )
+        .unwrap();
+        let config = TasksConfig {
+            vault_path: vault_path.clone(),
+            indent_length: 2,
+            use_american_format: true,
+            ..Default::default()
+        };
+        TaskManager::load_from_config(&config).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&note).unwrap(),
+            "# Chores\n- [ ] water plants 2020/01/08 every week\n- [x] water plants 2020/01/01\n- [ ] sweep 2020/01/05 every 2 days\n- [x] sweep 2020/01/03\n- [ ] cook\n"
+        );
+        // The next occurrences are only created once
+        TaskManager::load_from_config(&config).unwrap();
+        assert_eq!(std::fs::read_to_string(&note).unwrap().lines().count(), 6);
+    }
+
+    #[test]
+    fn test_relative_dates_policy() {
+        let vault_path = std::env::temp_dir().join("vault-tasks-relative-dates");
+        let _ = std::fs::remove_dir_all(&vault_path);
+        std::fs::create_dir_all(&vault_path).unwrap();
+        let note = vault_path.join("chores.md");
+        let content = "- [ ] call bank monday\n- [ ] cook  p1\n";
+        std::fs::write(&note, content).unwrap();
+        let mut config = TasksConfig {
+            vault_path: vault_path.clone(),
+            indent_length: 2,
+            relative_dates: RelativeDates::Keep,
+            ..Default::default()
+        };
+        let first_task = |task_mgr: &TaskManager| {
+            let VaultData::Directory(_, files) = &task_mgr.tasks else {
+                panic!()
+            };
+            let VaultData::Directory(_, tasks) = &files[0] else {
+                panic!()
+            };
+            let VaultData::Task(task) = &tasks[0] else {
+                panic!()
+            };
+            task.clone()
+        };
+        let task_mgr = TaskManager::load_from_config(&config).unwrap();
+        // The day it was first read is recorded, other tasks are still fixed
+        let today = chrono::Local::now().date_naive();
+        assert_eq!(
+            std::fs::read_to_string(&note).unwrap(),
+            format!(
+                "- [ ] call bank monday <!-- vt: relative=monday resolved={} -->\n- [ ] cook p1\n",
+                today.format("%Y-%m-%d")
+            )
+        );
+        let task = first_task(&task_mgr);
+        assert_eq!(task.relative_dates, vec!["monday"]);
+        assert_ne!(task.due_date, DueDate::NoDate);
+        assert_eq!(
+            first_task(&TaskManager::load_from_config(&config).unwrap()).due_date,
+            task.due_date
+        );
+
+        // A line first read on Tuesday 2025-03-04 is due the Monday after it, whatever day it is loaded on
+        let content = "- [ ] call bank monday <!-- vt: relative=monday resolved=2025-03-04 -->\n";
+        std::fs::write(&note, content).unwrap();
+        for _ in 0..2 {
+            let task = first_task(&TaskManager::load_from_config(&config).unwrap());
+            assert_eq!(
+                task.due_date,
+                DueDate::Day(NaiveDate::from_ymd_opt(2025, 3, 10).unwrap())
+            );
+            assert_eq!(std::fs::read_to_string(&note).unwrap(), content);
+        }
+
+        config.relative_dates = RelativeDates::Fix;
+        TaskManager::load_from_config(&config).unwrap();
+        assert!(!std::fs::read_to_string(&note).unwrap().contains("monday"));
+    }
+
+    #[test]
+    fn test_complete_parents() {
+        let vault_path = std::env::temp_dir().join("vault-tasks-complete-parents");
+        let _ = std::fs::remove_dir_all(&vault_path);
+        std::fs::create_dir_all(&vault_path).unwrap();
+        let note = vault_path.join("project.md");
+        std::fs::write(
+            &note,
+            "- [ ] release\n  - [x] build\n  - [-] docs\n- [x] plan\n  - [ ] budget\n- [ ] move\n  - [ ] pack\n    - [x] books\n  - [ ] clean\n",
+        )
+        .unwrap();
+        let mut config = TasksConfig {
+            vault_path: vault_path.clone(),
+            indent_length: 2,
+            complete_parents: ParentCompletion::Done,
+            reopen_parents: true,
+            ..Default::default()
+        };
+        let task_mgr = TaskManager::load_from_config(&config).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&note).unwrap(),
+            "- [x] release\n  - [x] build\n  - [-] docs\n- [ ] plan\n  - [ ] budget\n- [ ] move\n  - [x] pack\n    - [x] books\n  - [ ] clean\n"
+        );
+        assert_eq!(
+            filter_to_vec(&task_mgr.tasks, &Filter::default())
+                .iter()
+                .filter(|t| t.state == State::Done)
+                .count(),
+            4
+        );
+
+        config.complete_parents = ParentCompletion::Percentage;
+        config.reopen_parents = false;
+        std::fs::write(
+            &note,
+            "- [ ] move\n  - [x] pack\n  - [ ] clean\n  - [-] paint\n",
+        )
+        .unwrap();
+        TaskManager::load_from_config(&config).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&note).unwrap(),
+            "- [ ] move [50%]\n  - [x] pack\n  - [ ] clean\n  - [-] paint\n"
+        );
+    }
+
+    #[test]
+    fn test_subtask_progress() {
+        let vault_path = std::env::temp_dir().join("vault-tasks-subtask-progress");
+        let _ = std::fs::remove_dir_all(&vault_path);
+        std::fs::create_dir_all(&vault_path).unwrap();
+        let note = vault_path.join("project.md");
+        let content = "- [ ] release\n  - [x] build\n  - [-] docs\n  - [ ] tag\n- [ ] plan [20%]\n  - [x] budget\n";
+        std::fs::write(&note, content).unwrap();
+        let mut config = TasksConfig {
+            vault_path: vault_path.clone(),
+            indent_length: 2,
+            subtask_progress: SubtaskProgress::Count,
+            ..Default::default()
+        };
+        let progress = |config: &TasksConfig| {
+            filter_to_vec(
+                &TaskManager::load_from_config(config).unwrap().tasks,
+                &Filter::default(),
+            )
+            .into_iter()
+            .filter(|t| !t.subtasks.is_empty())
+            .map(|t| (t.subtask_count, t.completion))
+            .collect::<Vec<(Option<(usize, usize)>, Option<u8>)>>()
+        };
+        // Written completions are kept
+        assert_eq!(
+            progress(&config),
+            vec![(Some((1, 2)), None), (None, Some(20))]
+        );
+        config.subtask_progress = SubtaskProgress::Percentage;
+        assert_eq!(progress(&config), vec![(None, Some(50)), (None, Some(20))]);
+        // Nothing is written
+        assert_eq!(std::fs::read_to_string(&note).unwrap(), content);
+    }
+
+    #[test]
+    fn test_edit_below_recurring_task() {
+        let vault_path = std::env::temp_dir().join("vault-tasks-edit-below-recurring");
+        let _ = std::fs::remove_dir_all(&vault_path);
+        std::fs::create_dir_all(&vault_path).unwrap();
+        let note = vault_path.join("chores.md");
+        std::fs::write(
+            &note,
+            "# H\n- [x] water plants 01/01/2025 every week\n- [ ] second task\n- [ ] third task\n",
+        )
+        .unwrap();
+        let config = TasksConfig {
+            vault_path: vault_path.clone(),
+            indent_length: 2,
+            ..Default::default()
+        };
+        let mark_done = |task_mgr: &TaskManager, name: &str| {
+            let mut task = filter_to_vec(&task_mgr.tasks, &Filter::default())
+                .into_iter()
+                .find(|t| t.name == name)
+                .unwrap();
+            task.state = State::Done;
+            task.fix_task_attributes(&config, &note).unwrap();
+        };
+
+        let mut task_mgr = TaskManager::load_from_config(&config).unwrap();
+        mark_done(&task_mgr, "second task");
+        let content = std::fs::read_to_string(&note).unwrap();
+        let lines = content.lines().collect::<Vec<&str>>();
+        assert_eq!(lines.len(), 5);
+        assert!(lines[1].starts_with("- [ ] water plants"));
+        assert_eq!(lines[2], "- [x] water plants 01/01/2025");
+        assert_eq!(lines[3], "- [x] second task");
+        assert_eq!(lines[4], "- [ ] third task");
+
+        // Same once the file is reloaded alone
+        std::fs::write(
+            &note,
+            "# H\n- [x] water plants 01/01/2025 every week\n- [ ] second task\n- [ ] third task\n",
+        )
+        .unwrap();
+        task_mgr.reload_file(&config, &note).unwrap();
+        mark_done(&task_mgr, "third task");
+        let content = std::fs::read_to_string(&note).unwrap();
+        let lines = content.lines().collect::<Vec<&str>>();
+        assert_eq!(lines[2], "- [x] water plants 01/01/2025");
+        assert_eq!(lines[3], "- [ ] second task");
+        assert_eq!(lines[4], "- [x] third task");
+    }
+
+    #[test]
+    fn test_reload_file() {
+        let vault_path = std::env::temp_dir().join("vault-tasks-reload-file");
+        let _ = std::fs::remove_dir_all(&vault_path);
+        std::fs::create_dir_all(&vault_path).unwrap();
+        std::fs::write(vault_path.join("a.md"), "- [ ] a\n").unwrap();
+        std::fs::write(vault_path.join("b.md"), "- [ ] b\n").unwrap();
+        let config = TasksConfig {
+            vault_path: vault_path.clone(),
+            indent_length: 2,
+            ..Default::default()
+        };
+        let mut task_mgr = TaskManager::load_from_config(&config).unwrap();
+
+        std::fs::write(vault_path.join("a.md"), "- [ ] a #new\n").unwrap();
+        // Not reloaded
+        std::fs::write(vault_path.join("b.md"), "- [x] b\n").unwrap();
+        task_mgr
+            .reload_file(&config, &vault_path.join("a.md"))
+            .unwrap();
+        let tasks = filter_to_vec(&task_mgr.tasks, &Filter::default());
+        assert_eq!(tasks.len(), 2);
+        assert!(tasks.iter().all(|t| t.state == State::ToDo));
+        assert_eq!(task_mgr.tags, HashSet::from(["new".to_owned()]));
+        assert!(task_mgr
+            .reload_file(&config, Path::new("/elsewhere/c.md"))
+            .is_err());
+    }
+
+    #[test]
+    fn test_reload_files() {
+        let vault_path = std::env::temp_dir().join("vault-tasks-reload-files");
+        let _ = std::fs::remove_dir_all(&vault_path);
+        std::fs::create_dir_all(vault_path.join("old")).unwrap();
+        std::fs::write(vault_path.join("note.md"), "- [ ] task #a\n").unwrap();
+        std::fs::write(vault_path.join("old/gone.md"), "- [ ] gone #b\n").unwrap();
+        let config = TasksConfig {
+            vault_path: vault_path.clone(),
+            indent_length: 2,
+            ..Default::default()
+        };
+        let mut task_mgr = TaskManager::load_from_config(&config).unwrap();
+
+        std::fs::write(vault_path.join("note.md"), "- [x] task #c\n").unwrap();
+        std::fs::remove_dir_all(vault_path.join("old")).unwrap();
+        std::fs::create_dir_all(vault_path.join("new")).unwrap();
+        std::fs::write(vault_path.join("new/added.md"), "- [ ] added\n").unwrap();
+        task_mgr
+            .reload_files(
+                &config,
+                &[
+                    vault_path.join("new/added.md"),
+                    vault_path.join("note.md"),
+                    vault_path.join("old/gone.md"),
+                ],
+            )
+            .unwrap();
+
+        let names = |task_mgr: &TaskManager| {
+            let mut res = filter_to_vec(&task_mgr.tasks, &Filter::default())
+                .into_iter()
+                .map(|t| format!("{} {:?}", t.name, t.state))
+                .collect::<Vec<String>>();
+            res.sort();
+            res
+        };
+        assert_eq!(names(&task_mgr), vec!["added ToDo", "task Done"]);
+        assert_eq!(task_mgr.tags, HashSet::from(["c".to_owned()]));
+        assert!(!task_mgr
+            .modification_times
+            .contains_key(&vault_path.join("old/gone.md")));
+        // Same tasks as a full reload, empty directories are removed
+        let reloaded = TaskManager::load_from_config(&config).unwrap();
+        assert_eq!(names(&task_mgr), names(&reloaded));
+        assert_eq!(
+            task_mgr.get_path_layer_entries(&[]).unwrap().len(),
+            reloaded.get_path_layer_entries(&[]).unwrap().len()
+        );
+    }
+}
@@ -0,0 +1,232 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::Result;
+
+use super::{
+    attachments::{clean_wiki_target, is_attachment},
+    details::{filter_to_details, TaskDetails},
+    filter::Filter,
+    parser::parser_file_entry::parse_frontmatter,
+    rename::vault_notes,
+    task::Task,
+    vault_data::VaultData,
+    TasksConfig,
+};
+
+/// Finds the targets of the wiki-links to notes in `text`, embeds included, in the order they appear.
+///
+/// Links to attachments and to headers of the same note (`[[#Header]]`) are left out.
+#[must_use]
+pub fn note_links(text: &str) -> Vec<String> {
+    let mut res = vec![];
+    let mut offset = 0;
+    while let Some(start) = text[offset..].find("[[").map(|i| i + offset + 2) {
+        let Some(end) = text[start..].find("]]").map(|i| i + start) else {
+            break;
+        };
+        let target = clean_wiki_target(&text[start..end]);
+        if !target.is_empty() && !is_attachment(target) {
+            res.push(target.to_owned());
+        }
+        offset = end + 2;
+    }
+    res
+}
+
+/// Returns the `aliases` of the frontmatter of `notes`, along with the note they name.
+#[must_use]
+pub fn note_aliases(notes: &[PathBuf]) -> Vec<(String, PathBuf)> {
+    notes
+        .iter()
+        .flat_map(|note| {
+            let content = fs::read_to_string(note).unwrap_or_default();
+            parse_frontmatter(&content)
+                .aliases
+                .into_iter()
+                .map(|alias| (alias, note.clone()))
+        })
+        .collect()
+}
+
+/// Finds the note a wiki-link target refers to among `notes`.
+///
+/// Targets are looked up by path relative to the vault, by name anywhere in the vault, like Obsidian does, then among `aliases`.
+#[must_use]
+pub fn resolve_note(
+    config: &TasksConfig,
+    notes: &[PathBuf],
+    aliases: &[(String, PathBuf)],
+    target: &str,
+) -> Option<PathBuf> {
+    let target_path = Path::new(target.trim_end_matches(".md"));
+    notes
+        .iter()
+        .find(|note| {
+            note.strip_prefix(&config.vault_path)
+                .is_ok_and(|relative| relative.with_extension("") == target_path)
+        })
+        .or_else(|| {
+            let name = target_path.file_name()?;
+            notes.iter().find(|note| note.file_stem() == Some(name))
+        })
+        .or_else(|| {
+            aliases
+                .iter()
+                .find(|(alias, _)| alias.eq_ignore_ascii_case(target))
+                .map(|(_, note)| note)
+        })
+        .cloned()
+}
+
+impl Task {
+    /// Returns the targets of the wiki-links to notes in the name and description of the task.
+    #[must_use]
+    pub fn note_links(&self) -> Vec<String> {
+        let mut res = note_links(&self.name);
+        if let Some(description) = &self.description {
+            res.append(&mut note_links(description));
+        }
+        res
+    }
+}
+
+/// Links between tasks and the notes they reference with wiki-links, to browse them in both directions.
+#[derive(Debug, Default, Clone)]
+pub struct LinkGraph {
+    /// Tasks linking to at least one existing note, subtasks included, with the notes in the order of the links
+    links: Vec<(TaskDetails, Vec<PathBuf>)>,
+}
+
+impl LinkGraph {
+    /// Resolves the wiki-links of every task of `vault_data`, links to missing notes being left out.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if a directory of the vault can't be read.
+    pub fn new(config: &TasksConfig, vault_data: &VaultData) -> Result<Self> {
+        let notes = vault_notes(config)?;
+        let aliases = note_aliases(&notes);
+        let links = filter_to_details(vault_data, &Filter::default())
+            .into_iter()
+            .filter_map(|details| {
+                let mut linked = vec![];
+                for target in details.task.note_links() {
+                    if let Some(note) = resolve_note(config, &notes, &aliases, &target) {
+                        if !linked.contains(&note) {
+                            linked.push(note);
+                        }
+                    }
+                }
+                (!linked.is_empty()).then_some((details, linked))
+            })
+            .collect();
+        Ok(Self { links })
+    }
+
+    /// Returns the tasks linking to `note`, in the order of the files.
+    #[must_use]
+    pub fn referencing(&self, note: &Path) -> Vec<&TaskDetails> {
+        let mut res = self
+            .links
+            .iter()
+            .filter(|(_, notes)| notes.iter().any(|n| n == note))
+            .map(|(details, _)| details)
+            .collect::<Vec<&TaskDetails>>();
+        res.sort_by(|a, b| {
+            a.file
+                .cmp(&b.file)
+                .then(a.task.line_number.cmp(&b.task.line_number))
+        });
+        res
+    }
+
+    /// Returns the notes the task at `line_number` of `file` links to.
+    #[must_use]
+    pub fn referenced_by(&self, file: &Path, line_number: usize) -> &[PathBuf] {
+        self.links
+            .iter()
+            .find(|(details, _)| details.file == file && details.task.line_number == line_number)
+            .map(|(_, notes)| notes.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{note_aliases, note_links, resolve_note, LinkGraph};
+    use crate::{rename::vault_notes, TaskManager, TasksConfig};
+
+    #[test]
+    fn test_note_links() {
+        assert_eq!(
+            note_links("see [[Project|the project]], ![[Work/Spec#Goals]] and [[#Notes]], not [[spec.pdf]] nor [[unclosed"),
+            vec!["Project", "Work/Spec"]
+        );
+    }
+
+    #[test]
+    fn test_link_graph() {
+        let vault_path = std::env::temp_dir().join("vault-tasks-links");
+        let _ = fs::remove_dir_all(&vault_path);
+        fs::create_dir_all(vault_path.join("Work")).unwrap();
+        fs::write(
+            vault_path.join("Work/Project.md"),
+            "---\naliases: [Roadmap]\n---\n# Project\n",
+        )
+        .unwrap();
+        fs::write(vault_path.join("Project.md"), "- [ ] top level note\n").unwrap();
+        fs::write(
+            vault_path.join("Tasks.md"),
+            "- [ ] plan [[Work/Project]] and [[Missing]]\n  - [ ] ask [[Project]]\n    see [[Work/Project.md|it]]\n- [ ] unrelated\n",
+        )
+        .unwrap();
+        let config = TasksConfig {
+            vault_path: vault_path.clone(),
+            indent_length: 2,
+            ..Default::default()
+        };
+
+        let notes = vault_notes(&config).unwrap();
+        let aliases = note_aliases(&notes);
+        assert_eq!(
+            resolve_note(&config, &notes, &aliases, "Work/Project.md"),
+            Some(vault_path.join("Work/Project.md"))
+        );
+        assert_eq!(
+            resolve_note(&config, &notes, &aliases, "Project"),
+            Some(vault_path.join("Project.md"))
+        );
+        assert_eq!(
+            resolve_note(&config, &notes, &aliases, "roadmap"),
+            Some(vault_path.join("Work/Project.md"))
+        );
+        assert_eq!(resolve_note(&config, &notes, &aliases, "Missing"), None);
+
+        let task_mgr = TaskManager::load_read_only(&config).unwrap();
+        let graph = LinkGraph::new(&config, &task_mgr.tasks).unwrap();
+        let tasks = vault_path.join("Tasks.md");
+        assert_eq!(
+            graph
+                .referencing(&vault_path.join("Work/Project.md"))
+                .iter()
+                .map(|d| d.task.name.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["plan [[Work/Project]] and [[Missing]]", "ask [[Project]]"]
+        );
+        assert_eq!(
+            graph.referenced_by(&tasks, 2),
+            [
+                vault_path.join("Project.md"),
+                vault_path.join("Work/Project.md")
+            ]
+        );
+        assert!(graph.referenced_by(&tasks, 4).is_empty());
+    }
+}
@@ -0,0 +1,232 @@
+use std::{
+    fs::{self, File},
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use color_eyre::{eyre::bail, Result};
+use tracing::{debug, warn};
+
+use super::TasksConfig;
+
+/// Name of the lock file, at the root of the vault.
+pub const LOCK_FILE_NAME: &str = ".vault-tasks.lock";
+/// Age after which a lock file without a PID is considered stale
+const UNREADABLE_LOCK_AGE: Duration = Duration::from_secs(10);
+
+/// Advisory lock held by the instance allowed to write to a vault, the lock file is removed when dropped.
+///
+/// The lock file contains the PID of its owner. Locks of processes that are no longer running are taken over.
+#[derive(Debug)]
+pub struct VaultLock {
+    path: PathBuf,
+}
+
+impl VaultLock {
+    /// Path of the lock file of a vault, next to the vault if it is a single file.
+    #[must_use]
+    pub fn lock_path(vault_path: &Path) -> PathBuf {
+        if vault_path.is_file() {
+            vault_path
+                .parent()
+                .unwrap_or(Path::new(""))
+                .join(LOCK_FILE_NAME)
+        } else {
+            vault_path.join(LOCK_FILE_NAME)
+        }
+    }
+
+    /// Takes the lock of the vault.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if another running instance holds the lock or if the lock file can't be written.
+    pub fn acquire(config: &TasksConfig) -> Result<Self> {
+        let path = Self::lock_path(&config.vault_path);
+        // The PID is written to a file of this process first, the lock file then appears with its content at once
+        let pid = std::process::id();
+        let pid_path = path.with_file_name(format!("{LOCK_FILE_NAME}.{pid}"));
+        let mut pid_file = File::create(&pid_path)?;
+        let res = write!(pid_file, "{pid}")
+            .map_err(Into::into)
+            .and_then(|()| Self::link(&pid_path, &path));
+        fs::remove_file(&pid_path)?;
+        res
+    }
+
+    /// Links `pid_path` as the lock file `path`, a second attempt is made after removing a stale lock.
+    fn link(pid_path: &Path, path: &Path) -> Result<Self> {
+        for _ in 0..2 {
+            match fs::hard_link(pid_path, path) {
+                Ok(()) => {
+                    debug!("Acquired lock {path:?}");
+                    return Ok(Self {
+                        path: path.to_path_buf(),
+                    });
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    let content = fs::read_to_string(path)?;
+                    match content.trim().parse::<u32>() {
+                        Ok(pid) if is_running(pid) => bail!(
+                            "Vault is used by another vault-tasks instance (pid {pid}), remove {path:?} if it is not running"
+                        ),
+                        // Written by another program or a crashed instance, unless it is being written right now
+                        Err(_) if !is_old(path) => bail!(
+                            "Vault is being locked by another vault-tasks instance, remove {path:?} if it is not running"
+                        ),
+                        _ => Self::remove_stale(path, &content)?,
+                    }
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        bail!("Failed to acquire lock {path:?}")
+    }
+
+    /// Removes the stale lock file `path` read as `content`, unless another instance took it over in the meantime.
+    fn remove_stale(path: &Path, content: &str) -> Result<()> {
+        // Moved aside first, so only one of the instances finding the same stale lock removes it
+        let stale_path =
+            path.with_file_name(format!("{LOCK_FILE_NAME}.stale.{}", std::process::id()));
+        match fs::rename(path, &stale_path) {
+            Ok(()) => (),
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e.into()),
+        }
+        if fs::read_to_string(&stale_path)? != content {
+            // The lock of another instance was moved, it is put back
+            let res = fs::hard_link(&stale_path, path);
+            fs::remove_file(&stale_path)?;
+            res?;
+            bail!(
+                "Vault is being locked by another vault-tasks instance, remove {path:?} if it is not running"
+            );
+        }
+        warn!("Removing stale lock {path:?}");
+        fs::remove_file(&stale_path)?;
+        Ok(())
+    }
+}
+
+impl Drop for VaultLock {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_file(&self.path) {
+            warn!("Failed to remove lock {:?}: {e}", self.path);
+        }
+    }
+}
+
+/// Whether the lock file was last modified more than `UNREADABLE_LOCK_AGE` ago.
+fn is_old(path: &Path) -> bool {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .is_ok_and(|modified| {
+            SystemTime::now()
+                .duration_since(modified)
+                .is_ok_and(|age| age > UNREADABLE_LOCK_AGE)
+        })
+}
+
+/// Whether a process is running, assumed to be true when it can't be checked.
+fn is_running(pid: u32) -> bool {
+    if pid == std::process::id() {
+        return true;
+    }
+    #[cfg(unix)]
+    {
+        // 0 and negative PIDs would signal process groups
+        let Some(pid) = libc::pid_t::try_from(pid).ok().filter(|pid| *pid > 0) else {
+            return false;
+        };
+        // SAFETY: signal 0 sends nothing, it only checks that the process exists
+        if unsafe { libc::kill(pid, 0) } == 0 {
+            return true;
+        }
+        // A process of another user can't be signaled (EPERM) but is running
+        std::io::Error::last_os_error().raw_os_error() != Some(libc::ESRCH)
+    }
+    #[cfg(not(unix))]
+    {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        time::{Duration, SystemTime},
+    };
+
+    use super::VaultLock;
+    use crate::TasksConfig;
+
+    #[test]
+    fn test_vault_lock() {
+        let vault_path = std::env::temp_dir().join("vault-tasks-lock");
+        let _ = fs::remove_dir_all(&vault_path);
+        fs::create_dir_all(&vault_path).unwrap();
+        let config = TasksConfig {
+            vault_path: vault_path.clone(),
+            ..Default::default()
+        };
+        let lock_path = VaultLock::lock_path(&vault_path);
+
+        let lock = VaultLock::acquire(&config).unwrap();
+        assert!(lock_path.exists());
+        // Held by this process
+        assert!(VaultLock::acquire(&config).is_err());
+        drop(lock);
+        assert!(!lock_path.exists());
+
+        // Possibly being written by another instance
+        fs::write(&lock_path, "").unwrap();
+        assert!(VaultLock::acquire(&config).is_err());
+        assert!(lock_path.exists());
+
+        // Not a running process
+        fs::write(&lock_path, "not a pid").unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&lock_path)
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(60))
+            .unwrap();
+        let _lock = VaultLock::acquire(&config).unwrap();
+        assert_eq!(
+            fs::read_to_string(&lock_path).unwrap(),
+            std::process::id().to_string()
+        );
+    }
+
+    #[test]
+    fn test_remove_stale() {
+        let vault_path = std::env::temp_dir().join("vault-tasks-lock-stale");
+        let _ = fs::remove_dir_all(&vault_path);
+        fs::create_dir_all(&vault_path).unwrap();
+        let lock_path = VaultLock::lock_path(&vault_path);
+
+        // Taken over by another instance since it was read
+        fs::write(&lock_path, "12").unwrap();
+        assert!(VaultLock::remove_stale(&lock_path, "not a pid").is_err());
+        assert_eq!(fs::read_to_string(&lock_path).unwrap(), "12");
+
+        VaultLock::remove_stale(&lock_path, "12").unwrap();
+        assert_eq!(fs::read_dir(&vault_path).unwrap().count(), 0);
+        // Already removed by another instance
+        VaultLock::remove_stale(&lock_path, "12").unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_is_running() {
+        assert!(!super::is_running(0));
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        child.wait().unwrap();
+        assert!(!super::is_running(child.id()));
+        assert!(super::is_running(std::process::id()));
+        // Belongs to root, it can't be signaled by other users
+        assert!(super::is_running(1));
+    }
+}
@@ -0,0 +1,252 @@
+use std::{fs, path::PathBuf};
+
+use chrono::{NaiveDate, NaiveDateTime};
+use color_eyre::Result;
+use serde::Deserialize;
+
+use super::{
+    archive::plan_archive,
+    changeset::Changeset,
+    details::{filter_to_details, TaskDetails},
+    filter::Filter,
+    is_markdown_file,
+    mark::write_changes,
+    TaskManager, TasksConfig,
+};
+
+/// Policies applied by `vault-tasks maintain`, a policy is disabled when its setting is 0 or empty.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct MaintenanceConfig {
+    /// Done and canceled tasks completed this many days ago are moved to `archive_file`
+    pub archive_after_days: u32,
+    /// Unfinished tasks created this many days ago are tagged with `stale_tag`
+    pub stale_after_days: u32,
+    pub stale_tag: String,
+    /// Remove `@today` from the tasks finished before today
+    pub clear_expired_today: bool,
+    /// Remove the headers holding neither tasks nor text
+    pub prune_empty_headers: bool,
+}
+
+/// What `maintain` changed, per policy.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MaintenanceReport {
+    pub tagged_stale: usize,
+    pub cleared_today: usize,
+    pub archived: usize,
+    pub pruned_headers: usize,
+}
+
+impl MaintenanceReport {
+    /// Lines summarizing the report.
+    #[must_use]
+    pub fn summary(&self) -> Vec<String> {
+        vec![
+            format!("Tasks tagged as stale: {}", self.tagged_stale),
+            format!("Expired @today flags cleared: {}", self.cleared_today),
+            format!("Tasks archived: {}", self.archived),
+            format!("Empty headers pruned: {}", self.pruned_headers),
+        ]
+    }
+}
+
+/// Days since `date`, or else since the file of the task was last modified.
+fn age(
+    date: Option<NaiveDate>,
+    details: &TaskDetails,
+    task_mgr: &TaskManager,
+    today: NaiveDate,
+) -> Option<i64> {
+    let date = date.or_else(|| {
+        task_mgr
+            .modification_times
+            .get(&details.file)
+            .map(NaiveDateTime::date)
+    })?;
+    Some((today - date).num_days())
+}
+
+/// Returns `content` without the headers holding nothing but blank lines and empty subheaders, and the number of headers removed.
+fn prune_empty_headers(content: &str) -> (String, usize) {
+    fn header_level(line: &str) -> Option<usize> {
+        let level = line.chars().take_while(|&c| c == '#').count();
+        (level > 0 && line[level..].starts_with([' ', '\t'])).then_some(level)
+    }
+    let mut lines = content.split('\n').collect::<Vec<&str>>();
+    let mut pruned = 0;
+    // Last headers first, so that headers only holding empty subheaders are empty once they are reached
+    for i in (0..lines.len()).rev() {
+        let Some(level) = header_level(lines[i]) else {
+            continue;
+        };
+        let end = lines[i + 1..]
+            .iter()
+            .position(|l| header_level(l).is_some_and(|l| l <= level))
+            .map_or(lines.len(), |n| i + 1 + n);
+        if lines[i + 1..end].iter().all(|l| l.trim().is_empty()) {
+            lines.drain(i..end);
+            pruned += 1;
+        }
+    }
+    let mut res = lines.join("\n");
+    if content.ends_with('\n') && !res.is_empty() && !res.ends_with('\n') {
+        res.push('\n');
+    }
+    (res, pruned)
+}
+
+/// Applies the policies of `maintenance` to the vault in one pass, see [`MaintenanceConfig`].
+///
+/// The changes of each policy are written before the next one runs, empty headers are pruned once tasks are archived.
+///
+/// # Errors
+///
+/// Will return an error if the vault is read-only, if it can't be parsed or if a file can't be written.
+pub fn maintain(config: &TasksConfig, today: NaiveDate) -> Result<MaintenanceReport> {
+    config.check_writable()?;
+    let policies = &config.maintenance;
+    let mut res = MaintenanceReport::default();
+
+    let tag = policies.stale_tag.trim().trim_start_matches('#').to_owned();
+    if policies.stale_after_days > 0 && !tag.is_empty() {
+        let task_mgr = TaskManager::load_read_only(config)?;
+        let stale = filter_to_details(&task_mgr.tasks, &Filter::default())
+            .into_iter()
+            .filter(|details| {
+                let task = &details.task;
+                !task.state.is_finished()
+                    && !task.tags.iter().flatten().any(|t| *t == tag)
+                    && age(task.created, details, &task_mgr, today)
+                        .is_some_and(|age| age >= i64::from(policies.stale_after_days))
+            })
+            .collect::<Vec<TaskDetails>>();
+        write_changes(config, &stale, &|task| {
+            task.tags.get_or_insert_default().push(tag.clone());
+        })?;
+        res.tagged_stale = stale.len();
+    }
+
+    if policies.clear_expired_today {
+        let task_mgr = TaskManager::load_read_only(config)?;
+        let expired = filter_to_details(&task_mgr.tasks, &Filter::default())
+            .into_iter()
+            .filter(|details| {
+                let task = &details.task;
+                task.is_today
+                    && task.state.is_finished()
+                    && task.completed.is_none_or(|date| date < today)
+            })
+            .collect::<Vec<TaskDetails>>();
+        write_changes(config, &expired, &|task| task.is_today = false)?;
+        res.cleared_today = expired.len();
+    }
+
+    if policies.archive_after_days > 0 {
+        let task_mgr = TaskManager::load_read_only(config)?;
+        let old = filter_to_details(&task_mgr.tasks, &Filter::default())
+            .into_iter()
+            .filter(|details| {
+                age(details.task.completed, details, &task_mgr, today)
+                    .is_some_and(|age| age >= i64::from(policies.archive_after_days))
+            })
+            .collect::<Vec<TaskDetails>>();
+        let (count, changes) = plan_archive(config, &old)?;
+        changes.apply()?;
+        res.archived = count;
+    }
+
+    if policies.prune_empty_headers {
+        let task_mgr = TaskManager::load_read_only(config)?;
+        let mut paths = task_mgr
+            .modification_times
+            .keys()
+            .filter(|p| is_markdown_file(p))
+            .collect::<Vec<&PathBuf>>();
+        paths.sort();
+        let mut changes = Changeset::default();
+        for path in paths {
+            let old = fs::read_to_string(path)?;
+            let (new, count) = prune_empty_headers(&old);
+            if count > 0 {
+                changes.add(path, old, new);
+                res.pruned_headers += count;
+            }
+        }
+        changes.apply()?;
+    }
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use chrono::NaiveDate;
+    use pretty_assertions::assert_eq;
+
+    use super::{maintain, prune_empty_headers, MaintenanceConfig, MaintenanceReport};
+    use crate::TasksConfig;
+
+    #[test]
+    fn test_prune_empty_headers() {
+        assert_eq!(
+            prune_empty_headers("# Keep\n- [ ] task\n\n## Empty\n\n### Empty too\n## Text\ntext\n"),
+            ("# Keep\n- [ ] task\n\n## Text\ntext\n".to_owned(), 2)
+        );
+        assert_eq!(
+            prune_empty_headers("#tag\n# Empty\n"),
+            ("#tag\n".to_owned(), 1)
+        );
+    }
+
+    #[test]
+    fn test_maintain() {
+        let vault_path = std::env::temp_dir().join("vault-tasks-maintain");
+        let _ = fs::remove_dir_all(&vault_path);
+        fs::create_dir_all(&vault_path).unwrap();
+        let note = vault_path.join("note.md");
+        fs::write(
+            &note,
+            "# Old\n- [x] shipped done:01/01/2025\n# Current\n- [ ] review created:01/01/2025\n- [ ] plan created:01/03/2025\n- [x] call @today done:30/03/2025\n- [x] email @today done:31/03/2025\n",
+        )
+        .unwrap();
+        let config = TasksConfig {
+            vault_path: vault_path.clone(),
+            indent_length: 2,
+            archive_file: "Archive.md".into(),
+            maintenance: MaintenanceConfig {
+                archive_after_days: 30,
+                stale_after_days: 60,
+                stale_tag: "#stale".to_owned(),
+                clear_expired_today: true,
+                prune_empty_headers: true,
+            },
+            ..Default::default()
+        };
+        let today = NaiveDate::from_ymd_opt(2025, 3, 31).unwrap();
+
+        assert_eq!(
+            maintain(&config, today).unwrap(),
+            MaintenanceReport {
+                tagged_stale: 1,
+                cleared_today: 1,
+                archived: 1,
+                pruned_headers: 1,
+            }
+        );
+        assert_eq!(
+            fs::read_to_string(&note).unwrap(),
+            "# Current\n- [ ] review created:01/01/2025 #stale\n- [ ] plan created:01/03/2025\n- [x] call done:30/03/2025\n- [x] email @today done:31/03/2025\n"
+        );
+        assert_eq!(
+            fs::read_to_string(vault_path.join("Archive.md")).unwrap(),
+            "## [[note]] > Old\n- [x] shipped done:01/01/2025\n"
+        );
+        // Nothing left to do
+        assert_eq!(
+            maintain(&config, today).unwrap(),
+            MaintenanceReport::default()
+        );
+    }
+}
@@ -0,0 +1,142 @@
+use std::fs;
+
+use color_eyre::{eyre::bail, Result};
+
+use super::{
+    changeset::Changeset,
+    details::TaskDetails,
+    task::{State, Task},
+    TasksConfig,
+};
+
+/// Returns `tasks` once changed by `change`, grouped by file, last lines first.
+///
+/// Lines inserted for the next occurrence of recurring tasks then don't shift the tasks left to write.
+fn changed_tasks(tasks: &[TaskDetails], change: &impl Fn(&mut Task)) -> Vec<TaskDetails> {
+    let mut res = tasks
+        .iter()
+        .filter(|details| !details.task.projected)
+        .map(|details| {
+            let mut details = details.clone();
+            change(&mut details.task);
+            details
+        })
+        .collect::<Vec<TaskDetails>>();
+    res.sort_by(|a, b| {
+        a.file
+            .cmp(&b.file)
+            .then(b.task.line_number.cmp(&a.task.line_number))
+    });
+    res
+}
+
+/// Returns the changes rewriting the lines of `tasks` once changed by `change`, without writing them.
+///
+/// # Errors
+///
+/// Will return an error if a file can't be read or if a task is not found in its file.
+pub fn plan_changes(
+    config: &TasksConfig,
+    tasks: &[TaskDetails],
+    change: &impl Fn(&mut Task),
+) -> Result<Changeset> {
+    let mut changes = Changeset::default();
+    let changed = changed_tasks(tasks, change);
+    for file_tasks in changed.chunk_by(|a, b| a.file == b.file) {
+        let path = &file_tasks[0].file;
+        let old = fs::read_to_string(path)?;
+        let mut new = old.clone();
+        for details in file_tasks {
+            let Some(content) = details.task.fix_attributes_in(config, &new) else {
+                bail!(
+                    "Line {} of task {:?} not found in {path:?}",
+                    details.task.line_number,
+                    details.task.name
+                );
+            };
+            new = content;
+        }
+        changes.add(path, old, new);
+    }
+    Ok(changes)
+}
+
+/// Changes `tasks` with `change` and writes their lines to their files.
+///
+/// # Errors
+///
+/// Will return an error if the vault is read-only or if a task can't be written.
+pub fn write_changes(
+    config: &TasksConfig,
+    tasks: &[TaskDetails],
+    change: &impl Fn(&mut Task),
+) -> Result<()> {
+    for details in changed_tasks(tasks, change) {
+        details.task.fix_task_attributes(config, &details.file)?;
+    }
+    Ok(())
+}
+
+/// Returns the changes setting the state of `tasks` to `state`, without writing them.
+///
+/// # Errors
+///
+/// Will return an error if a file can't be read or if a task is not found in its file.
+pub fn plan_mark(config: &TasksConfig, tasks: &[TaskDetails], state: &State) -> Result<Changeset> {
+    let today = chrono::Local::now().date_naive();
+    plan_changes(config, tasks, &|task| {
+        task.set_state(state.clone(), config, today);
+    })
+}
+
+/// Sets the state of `tasks` to `state` and writes them to their files.
+///
+/// # Errors
+///
+/// Will return an error if the vault is read-only or if a task can't be written.
+pub fn mark(config: &TasksConfig, tasks: &[TaskDetails], state: &State) -> Result<()> {
+    let today = chrono::Local::now().date_naive();
+    write_changes(config, tasks, &|task| {
+        task.set_state(state.clone(), config, today);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{mark, plan_mark};
+    use crate::{details::find_tasks, task::State, TaskManager, TasksConfig};
+
+    #[test]
+    fn test_mark() {
+        let vault_path = std::env::temp_dir().join("vault-tasks-mark");
+        let _ = fs::remove_dir_all(&vault_path);
+        fs::create_dir_all(&vault_path).unwrap();
+        let note = vault_path.join("chores.md");
+        let content = "# Chores\n- [ ] water plants #home\n- [ ] cook #home\n- [ ] call bank\n";
+        fs::write(&note, content).unwrap();
+        let config = TasksConfig {
+            vault_path: vault_path.clone(),
+            indent_length: 2,
+            ..Default::default()
+        };
+        let task_mgr = TaskManager::load_read_only(&config).unwrap();
+        let tasks = find_tasks(&task_mgr.tasks, "#home", &config);
+        assert_eq!(tasks.len(), 2);
+
+        let changes = plan_mark(&config, &tasks, &State::Done).unwrap();
+        assert_eq!(changes.changes.len(), 1);
+        assert_eq!(
+            changes.changes[0].new,
+            "# Chores\n- [x] water plants #home\n- [x] cook #home\n- [ ] call bank\n"
+        );
+        // Planning doesn't write anything
+        assert_eq!(fs::read_to_string(&note).unwrap(), content);
+
+        mark(&config, &tasks, &State::Done).unwrap();
+        assert_eq!(fs::read_to_string(&note).unwrap(), changes.changes[0].new);
+    }
+}
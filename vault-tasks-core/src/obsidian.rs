@@ -0,0 +1,73 @@
+use std::path::Path;
+
+use super::task::Task;
+
+/// Percent-encodes a component of an URI, only unreserved characters are kept.
+fn encode_uri_component(input: &str) -> String {
+    let mut res = String::new();
+    for byte in input.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-_.~".contains(&byte) {
+            res.push(byte as char);
+        } else {
+            res.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    res
+}
+
+/// Builds an `obsidian://open` link to the note at `file_path`.
+///
+/// The vault is named after the directory at `vault_path`. If `task` has a block identifier, the link points to the task itself (`#^id`).
+/// Returns `None` if `file_path` is not inside the vault.
+#[must_use]
+pub fn obsidian_uri(vault_path: &Path, file_path: &Path, task: Option<&Task>) -> Option<String> {
+    let vault_name = vault_path.file_name()?.to_string_lossy();
+    let relative_path = file_path.strip_prefix(vault_path).ok()?;
+    let mut file = relative_path
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    if let Some(id) = task.and_then(|t| t.block_id.as_ref()) {
+        file.push_str(&format!("#^{id}"));
+    }
+    Some(format!(
+        "obsidian://open?vault={}&file={}",
+        encode_uri_component(&vault_name),
+        encode_uri_component(&file)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+
+    use super::obsidian_uri;
+    use crate::task::Task;
+
+    #[test]
+    fn test_obsidian_uri() {
+        let vault_path = Path::new("/home/me/My Vault");
+        let file_path = vault_path.join("projects/note.md");
+        let task = Task {
+            block_id: Some("abc-123".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(
+            obsidian_uri(vault_path, &file_path, None),
+            Some("obsidian://open?vault=My%20Vault&file=projects%2Fnote.md".to_owned())
+        );
+        assert_eq!(
+            obsidian_uri(vault_path, &file_path, Some(&task)),
+            Some(
+                "obsidian://open?vault=My%20Vault&file=projects%2Fnote.md%23%5Eabc-123".to_owned()
+            )
+        );
+        assert_eq!(
+            obsidian_uri(vault_path, Path::new("/elsewhere/note.md"), None),
+            None
+        );
+    }
+}
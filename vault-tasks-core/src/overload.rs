@@ -0,0 +1,134 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use chrono::NaiveDate;
+
+use super::{
+    filter::{filter_to_vec, Filter},
+    task::format_estimate,
+    vault_data::VaultData,
+    TasksConfig,
+};
+
+/// A day with more work due than `daily_task_capacity` or `daily_estimate_capacity`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OverloadedDay {
+    pub date: NaiveDate,
+    /// Number of unfinished tasks due that day
+    pub tasks: usize,
+    /// Sum of their estimates, in minutes
+    pub estimate: u32,
+}
+
+impl Display for OverloadedDay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} task{}",
+            self.date.format("%Y-%m-%d %a"),
+            self.tasks,
+            if self.tasks == 1 { "" } else { "s" }
+        )?;
+        if self.estimate > 0 {
+            write!(f, ", {} estimated", format_estimate(self.estimate))?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the overloaded days from `from` onwards, sorted by date.
+///
+/// Only unfinished tasks count, subtasks included. Nothing is overloaded if no capacity is set.
+#[must_use]
+pub fn overloaded_days(
+    vault_data: &VaultData,
+    config: &TasksConfig,
+    from: NaiveDate,
+) -> Vec<OverloadedDay> {
+    let estimate_capacity = config.daily_estimate_capacity();
+    if config.daily_task_capacity == 0 && estimate_capacity.is_none() {
+        return vec![];
+    }
+    let mut days = BTreeMap::<NaiveDate, (usize, u32)>::new();
+    for task in filter_to_vec(vault_data, &Filter::default())
+        .iter()
+        .filter(|t| !t.state.is_finished())
+    {
+        let Some(date) = task.due_date.to_naive_date_time().map(|d| d.date()) else {
+            continue;
+        };
+        if date < from {
+            continue;
+        }
+        let day = days.entry(date).or_default();
+        day.0 += 1;
+        day.1 += task.estimate.unwrap_or_default();
+    }
+    days.into_iter()
+        .filter(|(_, (tasks, estimate))| {
+            (config.daily_task_capacity > 0 && *tasks > config.daily_task_capacity)
+                || estimate_capacity.is_some_and(|capacity| *estimate > capacity)
+        })
+        .map(|(date, (tasks, estimate))| OverloadedDay {
+            date,
+            tasks,
+            estimate,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use pretty_assertions::assert_eq;
+
+    use super::{overloaded_days, OverloadedDay};
+    use crate::{
+        task::{DueDate, State, Task},
+        vault_data::VaultData,
+        TasksConfig,
+    };
+
+    #[test]
+    fn test_overloaded_days() {
+        let day = |d| NaiveDate::from_ymd_opt(2025, 3, d).unwrap();
+        let task = |d, estimate, state| {
+            VaultData::Task(Task {
+                name: "task".to_owned(),
+                due_date: DueDate::Day(day(d)),
+                estimate,
+                state,
+                ..Default::default()
+            })
+        };
+        let vault_data = VaultData::Directory(
+            "vault".to_owned(),
+            vec![
+                task(2, Some(600), State::ToDo),
+                task(3, Some(120), State::ToDo),
+                task(3, Some(300), State::ToDo),
+                task(3, None, State::Done),
+                task(4, Some(60), State::ToDo),
+                task(4, None, State::ToDo),
+                task(4, None, State::Incomplete),
+            ],
+        );
+        let mut config = TasksConfig::default();
+        assert!(overloaded_days(&vault_data, &config, day(3)).is_empty());
+
+        config.daily_task_capacity = 2;
+        assert_eq!(
+            overloaded_days(&vault_data, &config, day(3)),
+            vec![OverloadedDay {
+                date: day(4),
+                tasks: 3,
+                estimate: 60
+            }]
+        );
+
+        config.daily_task_capacity = 0;
+        config.daily_estimate_capacity = "6h".to_owned();
+        let res = overloaded_days(&vault_data, &config, day(3));
+        assert_eq!(res.iter().map(|d| d.date).collect::<Vec<_>>(), vec![day(3)]);
+        assert_eq!(res[0].to_string(), "2025-03-03 Mon: 2 tasks, 7h estimated");
+    }
+}
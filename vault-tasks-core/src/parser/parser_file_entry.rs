@@ -9,7 +9,7 @@ use winnow::{
     PResult, Parser,
 };
 
-use crate::{core::task::Task, core::vault_data::VaultData, core::TasksConfig};
+use crate::{task::Task, vault_data::VaultData, TasksConfig};
 
 use super::task::parse_task;
 
@@ -19,7 +19,7 @@ enum FileToken {
     /// Content, Indent length
     Description(String, usize),
     /// Task, Indent length
-    Task(Task, usize),
+    Task(Box<Task>, usize),
     /// A tag found outside a task in the file
     FileTag(String),
 }
@@ -41,7 +41,7 @@ impl ParserFileEntry<'_> {
         let mut task_parser =
             |input: &mut &str| parse_task(input, self.filename.clone(), self.config);
         let task_res = task_parser.parse_next(input)?;
-        Ok(FileToken::Task(task_res, indent_length))
+        Ok(FileToken::Task(Box::new(task_res), indent_length))
     }
     fn parse_header(input: &mut &str) -> PResult<FileToken> {
         let header_depth: String = repeat(1.., "#").parse_next(input)?;
@@ -359,7 +359,7 @@ impl ParserFileEntry<'_> {
                 task.line_number = line_number + 1; // line 1 was element 0 of iterator
                 if Self::insert_task_at(
                     file_entry,
-                    task,
+                    *task,
                     header_depth,
                     indent_length / self.config.indent_length,
                 )
@@ -424,6 +424,11 @@ impl ParserFileEntry<'_> {
     }
 
     pub fn parse_file(&mut self, filename: &str, input: &&str) -> Option<VaultData> {
+        let frontmatter = parse_frontmatter(input);
+        if frontmatter.ignore {
+            debug!("Ignoring {filename}, as set in its frontmatter");
+            return None;
+        }
         let lines = input.split('\n');
 
         let mut res = VaultData::Header(0, filename.to_owned(), vec![]);
@@ -434,6 +439,11 @@ impl ParserFileEntry<'_> {
         if self.config.file_tags_propagation {
             file_tags.iter().for_each(|t| add_global_tag(&mut res, t));
         }
+        let mut tags = frontmatter.default_tags;
+        if self.config.file_tags_propagation {
+            tags.extend(frontmatter.tags);
+        }
+        apply_file_defaults(&mut res, &tags, frontmatter.default_priority);
 
         // Filename is changed from Header to Directory variant at the end
         if let Some(VaultData::Header(_, name, children)) = Self::clean_file_entry(&mut res) {
@@ -444,6 +454,118 @@ impl ParserFileEntry<'_> {
     }
 }
 
+/// Keys of the YAML frontmatter of a file read by vault-tasks.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Frontmatter {
+    /// `tags`, given to every task of the file with `file_tags_propagation`
+    pub tags: Vec<String>,
+    /// `aliases`, other names of the note that wiki-links can use
+    pub aliases: Vec<String>,
+    /// `default_tags`, given to every task of the file
+    pub default_tags: Vec<String>,
+    /// `default_priority`, given to the tasks of the file without a priority
+    pub default_priority: usize,
+    /// `tasks-default-heading`, header of the section where `add` and `import` write new tasks
+    pub default_heading: Option<String>,
+    /// `vault-tasks: ignore`, the file is not parsed
+    pub ignore: bool,
+}
+
+/// Reads the `Frontmatter` of a file, files without one get the default.
+///
+/// Lists can be given as a flow list (`[work, home]`), a block list or a single value.
+#[must_use]
+pub fn parse_frontmatter(input: &str) -> Frontmatter {
+    fn parse_item(item: &str) -> Option<String> {
+        let item = item.trim().trim_matches(['"', '\'']);
+        (!item.is_empty()).then(|| item.to_owned())
+    }
+    fn list<'a>(res: &'a mut Frontmatter, key: &str) -> Option<&'a mut Vec<String>> {
+        match key {
+            "tags" => Some(&mut res.tags),
+            "aliases" => Some(&mut res.aliases),
+            "default_tags" => Some(&mut res.default_tags),
+            _ => None,
+        }
+    }
+    let mut res = Frontmatter::default();
+    let mut lines = input.lines();
+    if lines.next().map(str::trim_end) != Some("---") {
+        return res;
+    }
+    // Key of the block list being read
+    let mut list_key = None;
+    for line in lines.take_while(|l| l.trim_end() != "---") {
+        if let Some(key) = list_key {
+            if let Some(item) = line.trim_start().strip_prefix("- ") {
+                if let Some(list) = list(&mut res, key) {
+                    list.extend(parse_item(item));
+                }
+                continue;
+            }
+            list_key = None;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let (key, value) = (key.trim(), value.trim());
+        if let Some(list) = list(&mut res, key) {
+            if value.is_empty() {
+                list_key = Some(key);
+            } else {
+                list.extend(
+                    value
+                        .trim_start_matches('[')
+                        .trim_end_matches(']')
+                        .split(',')
+                        .filter_map(parse_item),
+                );
+            }
+            continue;
+        }
+        match key {
+            "default_priority" => res.default_priority = value.parse().unwrap_or_default(),
+            "tasks-default-heading" => res.default_heading = parse_item(value),
+            "vault-tasks" => res.ignore = value == "ignore",
+            _ => (),
+        }
+    }
+    for tag in res.tags.iter_mut().chain(&mut res.default_tags) {
+        *tag = tag.trim_start_matches('#').to_owned();
+    }
+    res
+}
+
+/// Gives `tags` and `priority` to every task of a file, tasks keep their own priority.
+///
+/// They are inherited, so they are not written into each line.
+fn apply_file_defaults(file_entry: &mut VaultData, tags: &[String], priority: usize) {
+    fn apply_task(task: &mut Task, tags: &[String], priority: usize) {
+        for tag in tags {
+            let task_tags = task.tags.get_or_insert_with(Vec::new);
+            if !task_tags.contains(tag) {
+                task_tags.push(tag.clone());
+                task.inherited_tags.push(tag.clone());
+            }
+        }
+        if task.priority == 0 && priority > 0 {
+            task.priority = priority;
+            task.inherited_priority = true;
+        }
+        task.subtasks
+            .iter_mut()
+            .for_each(|t| apply_task(t, tags, priority));
+    }
+    match file_entry {
+        VaultData::Header(_, _, children) | VaultData::Directory(_, children) => {
+            children
+                .iter_mut()
+                .for_each(|c| apply_file_defaults(c, tags, priority));
+        }
+        VaultData::Task(task) => apply_task(task, tags, priority),
+    }
+}
+
 fn add_global_tag(file_entry: &mut VaultData, tag: &String) {
     fn add_tag_aux(file_entry: &mut VaultData, tag: &String) {
         match file_entry {
@@ -480,8 +602,11 @@ mod tests {
 
     use super::ParserFileEntry;
 
-    use crate::core::{
-        parser::parser_file_entry::add_global_tag, task::Task, vault_data::VaultData, TasksConfig,
+    use crate::{
+        parser::parser_file_entry::{add_global_tag, parse_frontmatter, Frontmatter},
+        task::Task,
+        vault_data::VaultData,
+        TasksConfig,
     };
     #[test]
     fn test_with_useless_headers() {
@@ -801,4 +926,94 @@ mod tests {
         parser.parse_file_aux(input, &mut res, &mut vec![], 0);
         assert_snapshot!(res);
     }
+    #[test]
+    fn test_file_defaults() {
+        let input = r"---
+title: Work
+default_tags: [work, '#acme']
+default_priority: 2
+---
+- [ ] t1 #work
+  - [ ] t2 p1
+";
+        let config = TasksConfig {
+            indent_length: 2,
+            ..Default::default()
+        };
+        let mut parser = ParserFileEntry {
+            config: &config,
+            filename: String::new(),
+        };
+        let Some(VaultData::Directory(_, children)) = parser.parse_file("Test", &input) else {
+            panic!("File should contain tasks")
+        };
+        let VaultData::Task(t1) = &children[0] else {
+            panic!("Expected a task")
+        };
+        let t2 = &t1.subtasks[0];
+        assert_eq!(t1.tags, Some(vec!["work".to_owned(), "acme".to_owned()]));
+        assert_eq!((t1.priority, t2.priority), (2, 1));
+        // Defaults are not written into each line
+        assert_eq!(t1.get_fixed_attributes(&config, 0), "- [ ] t1 #work");
+        assert_eq!(t2.get_fixed_attributes(&config, 2), "  - [ ] t2 p1");
+    }
+
+    #[test]
+    fn test_frontmatter() {
+        let mut config = TasksConfig {
+            indent_length: 2,
+            file_tags_propagation: true,
+            ..Default::default()
+        };
+        let mut parser = ParserFileEntry {
+            config: &config,
+            filename: String::new(),
+        };
+        let input = "---\ntags: [project]\n---\n- [ ] t1\n";
+        let Some(VaultData::Directory(_, children)) = parser.parse_file("Test", &input) else {
+            panic!("File should contain tasks")
+        };
+        let VaultData::Task(t1) = &children[0] else {
+            panic!("Expected a task")
+        };
+        assert_eq!(t1.tags, Some(vec!["project".to_owned()]));
+        assert_eq!(t1.get_fixed_attributes(&config, 0), "- [ ] t1");
+
+        let ignored = "---\nvault-tasks: ignore\n---\n- [ ] t1\n";
+        assert!(parser.parse_file("Test", &ignored).is_none());
+
+        // Frontmatter tags are only propagated with `file_tags_propagation`
+        config.file_tags_propagation = false;
+        let mut parser = ParserFileEntry {
+            config: &config,
+            filename: String::new(),
+        };
+        let Some(VaultData::Directory(_, children)) = parser.parse_file("Test", &input) else {
+            panic!("File should contain tasks")
+        };
+        assert!(matches!(&children[0], VaultData::Task(t) if t.tags.is_none()));
+    }
+
+    #[test]
+    fn test_parse_frontmatter() {
+        let input = "---\ndefault_tags:\n  - work\n  - home\nother: 1\n---\ndefault_priority: 3\n";
+        assert_eq!(
+            parse_frontmatter(input),
+            Frontmatter {
+                default_tags: vec!["work".to_owned(), "home".to_owned()],
+                ..Default::default()
+            }
+        );
+        let input = "---\ntags: [project, '#acme']\naliases:\n  - Roadmap\n  - \"Q3 plan\"\ntasks-default-heading: Next actions\nvault-tasks: ignore\n---\n";
+        assert_eq!(
+            parse_frontmatter(input),
+            Frontmatter {
+                tags: vec!["project".to_owned(), "acme".to_owned()],
+                aliases: vec!["Roadmap".to_owned(), "Q3 plan".to_owned()],
+                default_heading: Some("Next actions".to_owned()),
+                ignore: true,
+                ..Default::default()
+            }
+        );
+    }
 }
@@ -0,0 +1,586 @@
+mod parse_today;
+mod parser_block_id;
+mod parser_completion;
+mod parser_due_date;
+mod parser_estimate;
+mod parser_hidden_meta;
+mod parser_history;
+mod parser_obsidian_tasks;
+mod parser_priorities;
+mod parser_recurrence;
+mod parser_reminder;
+mod parser_state;
+mod parser_tags;
+mod parser_time;
+mod token;
+
+use chrono::NaiveDateTime;
+use parse_today::parse_today;
+use parser_block_id::parse_block_id;
+use parser_completion::parse_completion;
+use parser_due_date::{is_relative_date, parse_naive_date, resolve_relative_date};
+use parser_estimate::parse_estimate;
+pub use parser_hidden_meta::{
+    extract_hidden_meta, HIDDEN_META_PREFIX, RELATIVE_META, RESOLVED_META,
+};
+use parser_history::{parse_completed, parse_created, parse_start};
+use parser_obsidian_tasks::{join_signifier_words, parse_signifier_date, parse_signifier_priority};
+pub use parser_obsidian_tasks::{
+    CREATED_SIGNIFIER, DONE_SIGNIFIER, DUE_SIGNIFIER, PRIORITY_SIGNIFIERS, RECURRENCE_SIGNIFIER,
+    SCHEDULED_SIGNIFIER, START_SIGNIFIER,
+};
+use parser_priorities::parse_priority;
+use parser_recurrence::{join_recurrence_words, parse_recurrence};
+use parser_reminder::parse_reminder;
+use parser_state::parse_task_state;
+use parser_tags::parse_tag;
+use parser_time::parse_naive_time;
+use token::Token;
+use tracing::error;
+use winnow::{
+    combinator::{alt, fail, repeat},
+    token::any,
+    PResult, Parser,
+};
+
+use crate::{
+    task::{DueDate, Task},
+    TasksConfig,
+};
+
+/// Parses a `Token` from an input string.FileEntry
+fn parse_token(input: &mut &str, config: &TasksConfig) -> PResult<Token> {
+    alt((
+        |input: &mut &str| parse_naive_date(input, config.use_american_format),
+        parse_naive_time,
+        parse_tag,
+        |input: &mut &str| parse_task_state(input, &config.task_state_markers),
+        parse_priority,
+        parse_today,
+        |input: &mut &str| parse_reminder(input, config.use_american_format),
+        |input: &mut &str| parse_created(input, config.use_american_format),
+        |input: &mut &str| parse_completed(input, config.use_american_format),
+        |input: &mut &str| parse_start(input, config.use_american_format),
+        parse_estimate,
+        parse_completion,
+        parse_recurrence,
+        parse_block_id,
+        parse_signifier_date,
+        parse_signifier_priority,
+        |input: &mut &str| {
+            let res = repeat(0.., any)
+                .fold(String::new, |mut string, c| {
+                    string.push(c);
+                    string
+                })
+                .parse_next(input)?;
+            Ok(Token::Name(res))
+        },
+    ))
+    .parse_next(input)
+}
+
+/// Parses a duration written like an estimate without `est:`, e.g. `6h` or `1h30`, in minutes.
+#[must_use]
+pub fn parse_duration(input: &str) -> Option<u32> {
+    match parse_estimate(&mut format!("est:{input}").as_str()) {
+        Ok(Token::Estimate(minutes)) => Some(minutes),
+        _ => None,
+    }
+}
+
+/// Parses a `Task` from an input string. Filename must be specified to be added to the task.
+///
+/// # Errors
+///
+/// Will return an error if the task can't be parsed.
+#[allow(clippy::module_name_repetitions)]
+pub fn parse_task(input: &mut &str, filename: String, config: &TasksConfig) -> PResult<Task> {
+    let task_state = match parse_task_state(input, &config.task_state_markers)? {
+        Token::State(state) => Ok(state),
+        _ => fail(input),
+    }?;
+
+    let mut token_parser = |input: &mut &str| parse_token(input, config);
+
+    let (line, hidden_meta) = extract_hidden_meta(input);
+    let words = join_signifier_words(&line.split_ascii_whitespace().collect::<Vec<&str>>());
+    let words = join_recurrence_words(&words.iter().map(String::as_str).collect::<Vec<&str>>());
+    let tokens = words
+        .iter()
+        .map(|token| (token, token_parser.parse(token)))
+        .collect::<Vec<_>>();
+
+    let mut task = Task {
+        state: task_state,
+        filename,
+        hidden_meta,
+        relative_dates: tokens
+            .iter()
+            .filter(|(word, token_res)| {
+                matches!(
+                    token_res,
+                    Ok(Token::DueDate(_)
+                        | Token::ReminderDate(_)
+                        | Token::CreatedDate(_)
+                        | Token::CompletedDate(_)
+                        | Token::StartDate(_))
+                ) && is_relative_date(word)
+            })
+            .map(|(word, _)| (*word).clone())
+            .collect(),
+        ..Default::default()
+    };
+    // Relative dates kept as written are resolved against the day they were first read
+    let resolved_on = task.relative_dates_resolved_on();
+
+    // Placeholders for a date and a time
+    let mut due_date_opt = None;
+    let mut due_time_opt = None;
+    // Placeholders for a reminder date and time
+    let mut reminder_date_opt = None;
+    let mut reminder_time_opt = None;
+    let mut name_vec = vec![]; // collects words that aren't tokens from the input string
+
+    let mut previous_was_reminder = false;
+    for (word, token_res) in tokens {
+        let is_reminder = matches!(token_res, Ok(Token::ReminderDate(_)));
+        let token_res = match (
+            resolved_on.and_then(|day| resolve_relative_date(word, day)),
+            token_res,
+        ) {
+            (Some(date), Ok(Token::DueDate(_))) => Ok(Token::DueDate(date)),
+            (Some(date), Ok(Token::ReminderDate(_))) => Ok(Token::ReminderDate(date)),
+            (Some(date), Ok(Token::CreatedDate(_))) => Ok(Token::CreatedDate(date)),
+            (Some(date), Ok(Token::CompletedDate(_))) => Ok(Token::CompletedDate(date)),
+            (Some(date), Ok(Token::StartDate(_))) => Ok(Token::StartDate(date)),
+            (_, token_res) => token_res,
+        };
+        match token_res {
+            Ok(Token::DueDate(date)) => due_date_opt = Some(date),
+            // A time right after a reminder date is the reminder time
+            Ok(Token::DueTime(time)) if previous_was_reminder => reminder_time_opt = Some(time),
+            Ok(Token::DueTime(time)) => due_time_opt = Some(time),
+            Ok(Token::ReminderDate(date)) => reminder_date_opt = Some(date),
+            Ok(Token::Name(name)) => name_vec.push(name),
+            Ok(Token::Priority(p)) => task.priority = p,
+            Ok(Token::State(state)) => task.state = state,
+            Ok(Token::Tag(tag)) => {
+                if let Some(ref mut tags) = task.tags {
+                    tags.push(tag);
+                } else {
+                    task.tags = Some(vec![tag]);
+                }
+            }
+            Ok(Token::TodayFlag) => task.is_today = true,
+            Ok(Token::CreatedDate(date)) => task.created = Some(date),
+            Ok(Token::CompletedDate(date)) => task.completed = Some(date),
+            Ok(Token::ScheduledDate(date)) => task.scheduled = Some(date),
+            Ok(Token::StartDate(date)) => task.start = Some(date),
+            Ok(Token::Estimate(minutes)) => task.estimate = Some(minutes),
+            Ok(Token::Completion(percent)) => task.completion = Some(percent),
+            Ok(Token::Recurrence(recurrence)) => task.recurrence = Some(recurrence),
+            Ok(Token::BlockId(id)) => task.block_id = Some(id),
+            Err(error) => error!("Error: {error:?}"),
+        }
+        previous_was_reminder = is_reminder;
+    }
+
+    if !name_vec.is_empty() {
+        task.name = name_vec.join(" ");
+    }
+
+    let now = chrono::Local::now();
+    let (due_date, has_date) = (
+        due_date_opt.unwrap_or_else(|| now.date_naive()),
+        due_date_opt.is_some(),
+    );
+    let (due_time, has_time) = (
+        due_time_opt.unwrap_or_else(|| now.time()),
+        due_time_opt.is_some(),
+    );
+    let due_date_time = if has_date {
+        if has_time {
+            DueDate::DayTime(NaiveDateTime::new(due_date, due_time))
+        } else {
+            DueDate::Day(due_date)
+        }
+    } else if has_time {
+        DueDate::DayTime(NaiveDateTime::new(now.date_naive(), due_time))
+    } else {
+        DueDate::NoDate
+    };
+    task.due_date = due_date_time;
+    task.reminder = match (reminder_date_opt, reminder_time_opt) {
+        (Some(date), Some(time)) => DueDate::DayTime(NaiveDateTime::new(date, time)),
+        (Some(date), None) => DueDate::Day(date),
+        _ => DueDate::NoDate,
+    };
+    Ok(task)
+}
+#[cfg(test)]
+mod test {
+
+    use chrono::{Datelike, Days, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+
+    use crate::{
+        parser::task::parse_task,
+        recurrence::Recurrence,
+        task::{DueDate, State, Task},
+        TaskSyntax, TasksConfig,
+    };
+    #[test]
+    fn test_parse_task_no_description() {
+        let mut input = "- [x] 10/15 task_name #done";
+        let config = TasksConfig {
+            use_american_format: true,
+            ..Default::default()
+        };
+        let res = parse_task(&mut input, String::new(), &config);
+        assert!(res.is_ok());
+        let res = res.unwrap();
+        let year = chrono::Local::now().year();
+        let expected = Task {
+            name: "task_name".to_string(),
+            description: None,
+            tags: Some(vec!["done".to_string()]),
+            due_date: DueDate::Day(NaiveDate::from_ymd_opt(year, 10, 15).unwrap()),
+            priority: 0,
+            state: State::Done,
+            line_number: 1,
+            ..Default::default()
+        };
+        assert_eq!(res, expected);
+    }
+
+    #[test]
+    fn test_parse_task_only_state() {
+        let mut input = "- [ ]";
+        let config = TasksConfig::default();
+        let res = parse_task(&mut input, String::new(), &config);
+        assert!(res.is_ok());
+        let res = res.unwrap();
+        let expected = Task {
+            subtasks: vec![],
+            name: String::new(),
+            description: None,
+            tags: None,
+            due_date: DueDate::NoDate,
+            priority: 0,
+            state: State::ToDo,
+            line_number: 1,
+            filename: String::new(),
+            is_today: false,
+            reminder: DueDate::NoDate,
+            created: None,
+            completed: None,
+            scheduled: None,
+            start: None,
+            estimate: None,
+            completion: None,
+            computed_completion: false,
+            subtask_count: None,
+            recurrence: None,
+            block_id: None,
+            hidden_meta: vec![],
+            inherited_tags: vec![],
+            inherited_priority: false,
+            projected: false,
+            relative_dates: vec![],
+        };
+        assert_eq!(res, expected);
+    }
+    #[test]
+    fn test_parse_task_with_due_date_words() {
+        let mut input = "- [ ] today 15:30 task_name";
+        let config = TasksConfig::default();
+        let res = parse_task(&mut input, String::new(), &config);
+        assert!(res.is_ok());
+        let res = res.unwrap();
+        let expected_date = chrono::Local::now().date_naive();
+        let expected_time = NaiveTime::from_hms_opt(15, 30, 0).unwrap();
+        let expected_due_date = DueDate::DayTime(NaiveDateTime::new(expected_date, expected_time));
+        assert_eq!(res.due_date, expected_due_date);
+    }
+
+    #[test]
+    fn test_parse_task_with_weekday() {
+        let mut input = "- [ ] monday 15:30 task_name";
+        let config = TasksConfig::default();
+        let res = parse_task(&mut input, String::new(), &config);
+        assert!(res.is_ok());
+        let res = res.unwrap();
+
+        let now = chrono::Local::now();
+        let expected_date = now
+            .date_naive()
+            .checked_add_days(Days::new(
+                8 - u64::from(now.date_naive().weekday().number_from_monday()),
+            ))
+            .unwrap();
+        let expected_time = NaiveTime::from_hms_opt(15, 30, 0).unwrap();
+        let expected_due_date = DueDate::DayTime(NaiveDateTime::new(expected_date, expected_time));
+        assert_eq!(res.due_date, expected_due_date);
+    }
+
+    #[test]
+    fn test_parse_task_with_weekday_this() {
+        let mut input = "- [ ] this monday 15:30 task_name";
+        let config = TasksConfig::default();
+        let res = parse_task(&mut input, String::new(), &config);
+        assert!(res.is_ok());
+        let res = res.unwrap();
+        let now = chrono::Local::now();
+        let expected_date = now
+            .date_naive()
+            .checked_add_days(Days::new(
+                8 - u64::from(now.date_naive().weekday().number_from_monday()),
+            ))
+            .unwrap();
+        let expected_time = NaiveTime::from_hms_opt(15, 30, 0).unwrap();
+        let expected_due_date = DueDate::DayTime(NaiveDateTime::new(expected_date, expected_time));
+        assert_eq!(res.due_date, expected_due_date);
+    }
+
+    #[test]
+    fn test_parse_task_with_weekday_next() {
+        let mut input = "- [ ] next monday 15:30 task_name";
+        let config = TasksConfig::default();
+        let res = parse_task(&mut input, String::new(), &config);
+        assert!(res.is_ok());
+        let res = res.unwrap();
+        let now = chrono::Local::now();
+        let expected_date = now
+            .date_naive()
+            .checked_add_days(Days::new(
+                8 - u64::from(now.date_naive().weekday().number_from_monday()),
+            ))
+            .unwrap();
+        let expected_time = NaiveTime::from_hms_opt(15, 30, 0).unwrap();
+        let expected_due_date = DueDate::DayTime(NaiveDateTime::new(expected_date, expected_time));
+        assert_eq!(res.due_date, expected_due_date);
+    }
+
+    #[test]
+    fn test_parse_task_without_due_date() {
+        let mut input = "- [ ] task_name";
+        let config = TasksConfig::default();
+        let res = parse_task(&mut input, String::new(), &config);
+        assert!(res.is_ok());
+        let res = res.unwrap();
+        let expected_due_date = DueDate::NoDate;
+        assert_eq!(res.due_date, expected_due_date);
+    }
+
+    #[test]
+    fn test_parse_task_with_invalid_state() {
+        let mut input = "- [invalid] task_name";
+        let config = TasksConfig::default();
+        let res = parse_task(&mut input, String::new(), &config);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_parse_task_without_state() {
+        let mut input = "task_name";
+        let config = TasksConfig::default();
+        let res = parse_task(&mut input, String::new(), &config);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_parse_task_with_invalid_priority() {
+        let mut input = "- [ ] task_name p-9";
+        let config = TasksConfig::default();
+        let res = parse_task(&mut input, String::new(), &config);
+        assert!(res.is_ok());
+        let res = res.unwrap();
+        assert_eq!(res.priority, 0);
+    }
+
+    #[test]
+    fn test_parse_task_without_name() {
+        let mut input = "- [ ]";
+        let config = TasksConfig::default();
+        let res = parse_task(&mut input, String::new(), &config);
+        assert!(res.is_ok());
+        let res = res.unwrap();
+        assert_eq!(res.name, ""); // Default name is used when no name is provided
+    }
+    #[test]
+    fn test_parse_task_with_today_flag() {
+        let mut input = "- [ ] @t";
+        let config = TasksConfig::default();
+        let res = parse_task(&mut input, String::new(), &config);
+        assert!(res.is_ok());
+        let res = res.unwrap();
+        assert!(res.is_today);
+    }
+    #[test]
+    fn test_parse_task_with_reminder() {
+        let mut input = "- [ ] task_name 2025/03/01 10:00 remind:2025/03/02 09:00";
+        let config = TasksConfig {
+            use_american_format: true,
+            ..Default::default()
+        };
+        let res = parse_task(&mut input, String::new(), &config).unwrap();
+        assert_eq!(res.name, "task_name");
+        assert_eq!(
+            res.due_date,
+            DueDate::DayTime(
+                NaiveDate::from_ymd_opt(2025, 3, 1)
+                    .unwrap()
+                    .and_hms_opt(10, 0, 0)
+                    .unwrap()
+            )
+        );
+        assert_eq!(
+            res.reminder,
+            DueDate::DayTime(
+                NaiveDate::from_ymd_opt(2025, 3, 2)
+                    .unwrap()
+                    .and_hms_opt(9, 0, 0)
+                    .unwrap()
+            )
+        );
+    }
+    #[test]
+    fn test_parse_task_with_history() {
+        let mut input = "- [x] task_name created:2025/03/01 done:2025/03/04 p1";
+        let config = TasksConfig {
+            use_american_format: true,
+            ..Default::default()
+        };
+        let res = parse_task(&mut input, String::new(), &config).unwrap();
+        assert_eq!(res.name, "task_name");
+        assert_eq!(res.created, NaiveDate::from_ymd_opt(2025, 3, 1));
+        assert_eq!(res.completed, NaiveDate::from_ymd_opt(2025, 3, 4));
+        assert_eq!(
+            res.get_fixed_attributes(&config, 0),
+            "- [x] task_name created:2025/03/01 done:2025/03/04 p1"
+        );
+    }
+    #[test]
+    fn test_parse_task_with_completion() {
+        let config = TasksConfig::default();
+        let mut input = "- [/] write report [40%] p1";
+        let res = parse_task(&mut input, String::new(), &config).unwrap();
+        assert_eq!(res.completion, Some(40));
+        assert_eq!(res.name, "write report");
+    }
+    #[test]
+    fn test_parse_task_with_block_id() {
+        let mut input = "- [ ] task_name #tag ^abc-123";
+        let config = TasksConfig::default();
+        let res = parse_task(&mut input, String::new(), &config).unwrap();
+        assert_eq!(res.name, "task_name");
+        assert_eq!(res.block_id, Some("abc-123".to_owned()));
+        assert_eq!(
+            res.get_fixed_attributes(&config, 0),
+            "- [ ] task_name #tag ^abc-123"
+        );
+    }
+    #[test]
+    fn test_parse_task_with_hidden_meta() {
+        let mut input = "- [ ] call <!--vt: id=42 ref=\"JIRA 7\"--> Bob #work ^abc";
+        let config = TasksConfig::default();
+        let res = parse_task(&mut input, String::new(), &config).unwrap();
+        assert_eq!(res.name, "call Bob");
+        assert_eq!(res.tags, Some(vec!["work".to_owned()]));
+        assert_eq!(
+            res.hidden_meta,
+            vec![
+                ("id".to_owned(), "42".to_owned()),
+                ("ref".to_owned(), "JIRA 7".to_owned())
+            ]
+        );
+        assert_eq!(
+            res.get_fixed_attributes(&config, 0),
+            "- [ ] call Bob #work <!-- vt: id=42 ref=\"JIRA 7\" --> ^abc"
+        );
+    }
+    #[test]
+    fn test_parse_task_with_resolved_relative_dates() {
+        let config = TasksConfig::default();
+        let mut input =
+            "- [ ] call bank monday remind:tmr <!-- vt: relative=\"monday remind:tmr\" resolved=2025-03-04 -->";
+        let res = parse_task(&mut input, String::new(), &config).unwrap();
+        assert_eq!(
+            res.due_date,
+            DueDate::Day(NaiveDate::from_ymd_opt(2025, 3, 10).unwrap())
+        );
+        assert_eq!(
+            res.reminder,
+            DueDate::Day(NaiveDate::from_ymd_opt(2025, 3, 5).unwrap())
+        );
+        assert!(!res.get_fixed_attributes(&config, 0).contains("vt:"));
+
+        // Once the relative dates are edited, they are resolved against today
+        let mut input = "- [ ] call bank tdy <!-- vt: relative=monday resolved=2025-03-04 -->";
+        let res = parse_task(&mut input, String::new(), &config).unwrap();
+        assert_eq!(res.relative_dates_resolved_on(), None);
+        assert_eq!(
+            res.due_date,
+            DueDate::Day(chrono::Local::now().date_naive())
+        );
+    }
+    #[test]
+    fn test_parse_task_with_recurrence() {
+        let config = TasksConfig::default();
+        for mut input in [
+            "- [ ] water plants every:2w #home",
+            "- [ ] water plants every 2 weeks #home",
+        ] {
+            let res = parse_task(&mut input, String::new(), &config).unwrap();
+            assert_eq!(res.name, "water plants");
+            assert_eq!(res.recurrence, Some(Recurrence::Weeks(2)));
+            assert_eq!(
+                res.get_fixed_attributes(&config, 0),
+                "- [ ] water plants every 2 weeks #home"
+            );
+        }
+    }
+    #[test]
+    fn test_parse_task_with_obsidian_tasks_signifiers() {
+        let mut config = TasksConfig {
+            use_american_format: true,
+            ..Default::default()
+        };
+        let mut input =
+            "- [x] water plants #home ⏫ 🔁 every Monday ➕ 2025-03-01 ⏳ 2025-03-02 📅 2025-03-03 ✅ 2025-03-04 ^abc";
+        let res = parse_task(&mut input, String::new(), &config).unwrap();
+        let date = |day| NaiveDate::from_ymd_opt(2025, 3, day).unwrap();
+        assert_eq!(res.name, "water plants");
+        assert_eq!(res.priority, 2);
+        assert_eq!(res.recurrence, Some(Recurrence::Weekday(Weekday::Mon)));
+        assert_eq!(res.created, Some(date(1)));
+        assert_eq!(res.scheduled, Some(date(2)));
+        assert_eq!(res.due_date, DueDate::Day(date(3)));
+        assert_eq!(res.completed, Some(date(4)));
+        assert_eq!(res.block_id, Some("abc".to_owned()));
+
+        // Scheduled and start dates are kept with the syntax of vault-tasks
+        assert_eq!(
+            res.get_fixed_attributes(&config, 0),
+            "- [x] water plants 2025/03/03 created:2025/03/01 done:2025/03/04 p2 every monday #home ⏳ 2025-03-02 ^abc"
+        );
+        config.syntax = TaskSyntax::ObsidianTasks;
+        assert_eq!(
+            res.get_fixed_attributes(&config, 0),
+            "- [x] water plants #home ⏫ 🔁 every monday ➕ 2025-03-01 ⏳ 2025-03-02 📅 2025-03-03 ✅ 2025-03-04 ^abc"
+        );
+
+        // Due times are written before the signifiers
+        let mut input = "- [ ] standup 09:30 🔽 🛫 2025-03-01 📅 2025-03-03";
+        let res = parse_task(&mut input, String::new(), &config).unwrap();
+        assert_eq!(
+            res.due_date,
+            DueDate::DayTime(date(3).and_hms_opt(9, 30, 0).unwrap())
+        );
+        assert_eq!(res.start, Some(date(1)));
+        assert_eq!(
+            res.get_fixed_attributes(&config, 0),
+            "- [ ] standup 09:30:00 🔽 🛫 2025-03-01 📅 2025-03-03"
+        );
+    }
+}
@@ -0,0 +1,39 @@
+use winnow::{
+    combinator::{eof, preceded},
+    token::take_while,
+    PResult, Parser,
+};
+
+use super::token::Token;
+
+/// Parses an Obsidian block identifier of the form `"^<id>"`, ids are made of letters, digits and dashes.
+pub fn parse_block_id(input: &mut &str) -> PResult<Token> {
+    let id = preceded(
+        '^',
+        take_while(1.., |c: char| c.is_ascii_alphanumeric() || c == '-'),
+    )
+    .parse_next(input)?;
+    eof.parse_next(input)?;
+    Ok(Token::BlockId(id.to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::task::{parser_block_id::parse_block_id, token::Token};
+
+    #[test]
+    fn test_parse_block_id_success() {
+        for (mut input, id) in [("^abc123", "abc123"), ("^my-task", "my-task")] {
+            assert_eq!(
+                parse_block_id(&mut input),
+                Ok(Token::BlockId(id.to_owned()))
+            );
+        }
+    }
+    #[test]
+    fn test_parse_block_id_fail() {
+        for mut input in ["^", "abc", "^a_b", "^^a"] {
+            assert!(parse_block_id(&mut input).is_err());
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use winnow::{
+    combinator::{alt, delimited, eof, preceded},
+    token::take_while,
+    PResult, Parser,
+};
+
+use super::token::Token;
+
+fn parse_percent(input: &mut &str) -> PResult<u8> {
+    take_while(1..=3, '0'..='9')
+        .parse_to()
+        .verify(|p: &u8| *p <= 100)
+        .parse_next(input)
+}
+
+/// Parses a completion percentage of the form `"[<percent>%]"` or `"%<percent>"`, e.g. `[50%]` or `%50`.
+pub fn parse_completion(input: &mut &str) -> PResult<Token> {
+    let res = alt((
+        delimited('[', parse_percent, "%]"),
+        preceded('%', parse_percent),
+    ))
+    .parse_next(input)?;
+    eof.parse_next(input)?;
+    Ok(Token::Completion(res))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::task::{parser_completion::parse_completion, token::Token};
+
+    #[test]
+    fn test_parse_completion_success() {
+        for (mut input, percent) in [("[50%]", 50), ("%50", 50), ("[0%]", 0), ("%100", 100)] {
+            assert_eq!(parse_completion(&mut input), Ok(Token::Completion(percent)));
+        }
+    }
+    #[test]
+    fn test_parse_completion_fail() {
+        for mut input in ["50%", "[50]", "%", "[101%]", "%50%", "[x]"] {
+            assert!(parse_completion(&mut input).is_err());
+        }
+    }
+}
@@ -12,7 +12,7 @@ use winnow::{
 use super::token::Token;
 
 /// Parses a literal day name (or its abbreviation) from an input string.
-fn parse_literal_day<'a>(input: &mut &'a str) -> PResult<&'a str> {
+pub fn parse_literal_day<'a>(input: &mut &'a str) -> PResult<&'a str> {
     let days = (
         "monday",
         "tuesday",
@@ -32,18 +32,10 @@ fn parse_literal_day<'a>(input: &mut &'a str) -> PResult<&'a str> {
     alt(days).parse_next(input)
 }
 
-/// Adds `n` days to today's date and returns it as a `NaiveDate`.
-fn calculate_in_n_days(n: u32) -> NaiveDate {
-    let now = chrono::Local::now();
-    now.checked_add_days(Days::new(u64::from(n)))
-        .unwrap()
-        .date_naive()
-}
-
 /// Parses a `NaiveDate` from a literal day name
 /// Day names can be abreviated.
-/// If sucessful, returns a `NaiveDate` representing the next occurence of that day in the future (not including today).
-fn parse_naive_date_from_literal_day(input: &mut &str) -> PResult<Token> {
+/// If sucessful, returns a `NaiveDate` representing the next occurence of that day after `today`.
+fn parse_naive_date_from_literal_day(input: &mut &str, today: NaiveDate) -> PResult<Token> {
     let output = parse_literal_day.parse_next(input)?;
     let day: u32 = match &output[0..3] {
         "mon" => 1,
@@ -58,13 +50,14 @@ fn parse_naive_date_from_literal_day(input: &mut &str) -> PResult<Token> {
             1
         }
     };
-    let now = chrono::Local::now();
-    let day_name_equals_today = now.weekday().number_from_monday() == day;
+    let day_name_equals_today = today.weekday().number_from_monday() == day;
 
-    let res = calculate_in_n_days(
-        (if day_name_equals_today { 7 } else { 0 })
-            + ((7 - now.weekday().num_days_from_sunday()) + day) % 7,
-    );
+    let res = today
+        .checked_add_days(Days::new(u64::from(
+            (if day_name_equals_today { 7 } else { 0 })
+                + ((7 - today.weekday().num_days_from_sunday()) + day) % 7,
+        )))
+        .unwrap();
 
     Ok(Token::DueDate(res))
 }
@@ -78,33 +71,31 @@ fn parse_literal_generic<'a>(input: &mut &'a str) -> PResult<&'a str> {
 }
 
 /// Parses a `NaiveDate` from an integer + a generic duration in `("day", "week", "month", "year", "weekend", "we")`
-/// If sucessful, returns a `NaiveDate` representing the start of the next generic duration found. "Next week" -> "Next Monday"
-fn parse_naive_date_from_generic_name(input: &mut &str) -> PResult<Token> {
+/// If sucessful, returns a `NaiveDate` representing the start of the next generic duration found after `today`. "Next week" -> "Next Monday"
+fn parse_naive_date_from_generic_name(input: &mut &str, today: NaiveDate) -> PResult<Token> {
     let number: u64 = digit1.parse_to().parse_next(input)?;
     let duration = parse_literal_generic.parse_next(input)?;
 
-    let now = chrono::Local::now();
-    let today_date = now.date_naive();
     match duration {
         "d" | "day" | "days" => Ok(Token::DueDate(
-            today_date.checked_add_days(Days::new(number)).unwrap(),
+            today.checked_add_days(Days::new(number)).unwrap(),
         )),
         "w" | "week" | "weeks" => Ok(Token::DueDate(
-            today_date
+            today
                 .checked_add_days(Days::new(
-                    7 * (number - 1) + 8 - u64::from(now.weekday().number_from_monday()),
+                    7 * (number - 1) + 8 - u64::from(today.weekday().number_from_monday()),
                 ))
                 .unwrap(),
         )),
         "m" | "month" | "months" => Ok(Token::DueDate(
-            today_date
+            today
                 .checked_add_months(Months::new(number.try_into().unwrap()))
                 .unwrap()
-                .checked_sub_days(Days::new(u64::from(today_date.day())))
+                .checked_sub_days(Days::new(u64::from(today.day())))
                 .unwrap(),
         )),
         "y" | "year" | "years" => Ok(Token::DueDate(
-            today_date
+            today
                 .checked_add_months(Months::new((12 * number).try_into().unwrap()))
                 .unwrap()
                 .with_month(1)
@@ -112,7 +103,7 @@ fn parse_naive_date_from_generic_name(input: &mut &str) -> PResult<Token> {
                 .with_day(1)
                 .unwrap(),
         )),
-        _ => Ok(Token::DueDate(today_date)),
+        _ => Ok(Token::DueDate(today)),
     }
 }
 
@@ -122,15 +113,14 @@ fn parse_adverb<'a>(input: &mut &'a str) -> PResult<&'a str> {
 }
 
 /// Parses a `NaiveDate` from an adverb in  `("tmr", "tomorrow", "today", "tdy", "tod")`
-/// If sucessful, returns a `NaiveDate` representing today's or tomorrow's date
-fn parse_naive_date_from_adverb(input: &mut &str) -> PResult<Token> {
+/// If sucessful, returns a `NaiveDate` representing `today` or the day after
+fn parse_naive_date_from_adverb(input: &mut &str, today: NaiveDate) -> PResult<Token> {
     let output = parse_adverb.parse_next(input)?;
-    let now = chrono::Local::now();
     match output {
         #[allow(clippy::match_same_arms)]
-        "tdy" | "tod" | "today" => Ok(Token::DueDate(now.date_naive())),
+        "tdy" | "tod" | "today" => Ok(Token::DueDate(today)),
         "tmr" | "tomorrow" => Ok(Token::DueDate(
-            now.date_naive().checked_add_days(Days::new(1)).unwrap(),
+            today.checked_add_days(Days::new(1)).unwrap(),
         )),
         _ => Err(ErrMode::from_error_kind(input, ErrorKind::Assert)),
     }
@@ -172,19 +162,52 @@ fn parse_naive_date_from_numeric_format(input: &mut &str, american_format: bool)
 pub fn parse_naive_date(input: &mut &str, american_format: bool) -> PResult<Token> {
     alt((
         (|input: &mut &str| parse_naive_date_from_numeric_format(input, american_format)),
-        parse_naive_date_from_literal_day,
-        parse_naive_date_from_adverb,
-        parse_naive_date_from_generic_name,
+        |input: &mut &str| parse_relative_date(input, chrono::Local::now().date_naive()),
     ))
     .parse_next(input)
 }
 
+/// Parses a `NaiveDate` depending on the day it is read, e.g. `monday`, `3d` or `tmr`, as if read on `today`.
+fn parse_relative_date(input: &mut &str, today: NaiveDate) -> PResult<Token> {
+    alt((
+        |input: &mut &str| parse_naive_date_from_literal_day(input, today),
+        |input: &mut &str| parse_naive_date_from_adverb(input, today),
+        |input: &mut &str| parse_naive_date_from_generic_name(input, today),
+    ))
+    .parse_next(input)
+}
+
+/// Resolves `word`, a relative date token like `monday` or `remind:tmr`, as if it was read on `today`.
+#[must_use]
+pub fn resolve_relative_date(word: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let date = word.rsplit_once(':').map_or(word, |(_, date)| date);
+    match (|input: &mut &str| parse_relative_date(input, today)).parse(date) {
+        Ok(Token::DueDate(date)) => Some(date),
+        _ => None,
+    }
+}
+
+/// Whether `word`, a date token, depends on the day it is read, e.g. `monday`, `3d` or `remind:tmr`.
+#[must_use]
+pub fn is_relative_date(word: &str) -> bool {
+    resolve_relative_date(word, chrono::Local::now().date_naive()).is_some()
+}
+
 /// For each functions that returns a `NaiveDate`, the complete parser `parse_due_date` is also tested to return the same result.
 #[cfg(test)]
 mod tests {
     use chrono::Datelike;
 
-    use crate::core::parser::task::parser_due_date::*;
+    use crate::parser::task::parser_due_date::*;
+
+    fn today() -> NaiveDate {
+        chrono::Local::now().date_naive()
+    }
+
+    /// Adds `n` days to today's date and returns it as a `NaiveDate`.
+    fn calculate_in_n_days(n: u32) -> NaiveDate {
+        today().checked_add_days(Days::new(u64::from(n))).unwrap()
+    }
 
     #[test]
     fn test_parse_literal_day() {
@@ -215,7 +238,7 @@ mod tests {
         let mut input = input.as_str();
         let mut copy = input;
         assert_eq!(
-            parse_naive_date_from_literal_day(&mut input),
+            parse_naive_date_from_literal_day(&mut input, today()),
             Ok(Token::DueDate(calculate_in_n_days(7)))
         );
 
@@ -230,7 +253,7 @@ mod tests {
         let mut copy = input;
         let expected = calculate_in_n_days(1);
         assert_eq!(
-            parse_naive_date_from_literal_day(&mut input),
+            parse_naive_date_from_literal_day(&mut input, today()),
             Ok(Token::DueDate(expected))
         );
         assert_eq!(
@@ -244,7 +267,7 @@ mod tests {
         let mut input = input.as_str();
         let mut copy = input;
         assert_eq!(
-            parse_naive_date_from_literal_day(&mut input),
+            parse_naive_date_from_literal_day(&mut input, today()),
             Ok(Token::DueDate(calculate_in_n_days(7)))
         );
         assert_eq!(
@@ -269,7 +292,7 @@ mod tests {
         let mut input = "2day";
         let mut copy = input;
         assert_eq!(
-            parse_naive_date_from_generic_name(&mut input),
+            parse_naive_date_from_generic_name(&mut input, today()),
             Ok(Token::DueDate(calculate_in_n_days(2)))
         );
         assert_eq!(
@@ -287,7 +310,7 @@ mod tests {
             ))
             .unwrap();
         assert_eq!(
-            parse_naive_date_from_generic_name(&mut input),
+            parse_naive_date_from_generic_name(&mut input, today()),
             Ok(Token::DueDate(expected))
         );
         assert_eq!(
@@ -313,7 +336,7 @@ mod tests {
         let mut copy = input;
         let now = chrono::Local::now();
         assert_eq!(
-            parse_naive_date_from_adverb(&mut input),
+            parse_naive_date_from_adverb(&mut input, today()),
             Ok(Token::DueDate(now.date_naive()))
         );
         assert_eq!(
@@ -325,7 +348,7 @@ mod tests {
         let mut copy = input;
         let expected = now.date_naive().checked_add_days(Days::new(1)).unwrap();
         assert_eq!(
-            parse_naive_date_from_adverb(&mut input),
+            parse_naive_date_from_adverb(&mut input, today()),
             Ok(Token::DueDate(expected))
         );
         assert_eq!(
@@ -377,4 +400,20 @@ mod tests {
         let yyyy_mm_dd = "2024/63/17".to_string();
         assert!(parse_naive_date_from_numeric_format(&mut yyyy_mm_dd.as_str(), true).is_err());
     }
+
+    #[test]
+    fn test_is_relative_date() {
+        for word in ["monday", "tmr", "3d", "remind:mon", "created:today"] {
+            assert!(is_relative_date(word), "{word}");
+        }
+        for word in [
+            "2025/03/02",
+            "23/10",
+            "remind:2025/03/02",
+            "📅2025-03-02",
+            "mondays",
+        ] {
+            assert!(!is_relative_date(word), "{word}");
+        }
+    }
 }
@@ -0,0 +1,54 @@
+use winnow::{
+    combinator::{eof, opt, preceded, terminated},
+    token::take_while,
+    PResult, Parser,
+};
+
+use super::token::Token;
+
+fn parse_number(input: &mut &str) -> PResult<u32> {
+    take_while(1.., '0'..='9').parse_to().parse_next(input)
+}
+
+/// Parses an estimate of the form `"est:<hours>h<minutes>"`, e.g. `est:2h`, `est:45m` or `est:1h30`.
+///
+/// The estimate is given in minutes.
+pub fn parse_estimate(input: &mut &str) -> PResult<Token> {
+    let (hours, minutes) = preceded(
+        "est:",
+        (
+            opt(terminated(parse_number, 'h')),
+            opt(terminated(parse_number, opt('m'))),
+        ),
+    )
+    .verify(|(h, m): &(Option<u32>, Option<u32>)| h.is_some() || m.is_some())
+    .parse_next(input)?;
+    eof.parse_next(input)?;
+    Ok(Token::Estimate(
+        hours.unwrap_or_default() * 60 + minutes.unwrap_or_default(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::task::{parser_estimate::parse_estimate, token::Token};
+
+    #[test]
+    fn test_parse_estimate_success() {
+        for (mut input, minutes) in [
+            ("est:2h", 120),
+            ("est:45m", 45),
+            ("est:45", 45),
+            ("est:1h30", 90),
+            ("est:1h30m", 90),
+        ] {
+            assert_eq!(parse_estimate(&mut input), Ok(Token::Estimate(minutes)));
+        }
+    }
+    #[test]
+    fn test_parse_estimate_fail() {
+        for mut input in ["est:", "est:soon", "2h", "est:2d"] {
+            assert!(parse_estimate(&mut input).is_err());
+        }
+    }
+}
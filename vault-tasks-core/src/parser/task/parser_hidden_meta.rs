@@ -0,0 +1,86 @@
+/// Prefix of the comments holding hidden metadata, e.g. `<!-- vt: id=123 -->`
+pub const HIDDEN_META_PREFIX: &str = "vt:";
+/// Key of the relative dates of a line left as written, see `RelativeDates::Keep`
+pub const RELATIVE_META: &str = "relative";
+/// Key of the day the relative dates of a line were first read on, they are resolved against it
+pub const RESOLVED_META: &str = "resolved";
+
+/// Parses the `key=value` pairs of a metadata comment. Values holding spaces are quoted, keys without a value get an empty one.
+fn parse_pairs(input: &str) -> Vec<(String, String)> {
+    let mut res = vec![];
+    let mut chars = input.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if chars.peek().is_none() {
+            break;
+        }
+        let mut key = String::new();
+        while let Some(c) = chars.next_if(|c| !c.is_whitespace() && *c != '=') {
+            key.push(c);
+        }
+        let mut value = String::new();
+        if chars.next_if_eq(&'=').is_some() {
+            if chars.next_if_eq(&'"').is_some() {
+                value = chars.by_ref().take_while(|c| *c != '"').collect();
+            } else {
+                while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                    value.push(c);
+                }
+            }
+        }
+        if !key.is_empty() {
+            res.push((key, value));
+        }
+    }
+    res
+}
+
+/// Removes the `<!-- vt: key=value -->` comments of a task line, returning the rest of the line and their pairs in order.
+///
+/// Other comments are left untouched.
+pub fn extract_hidden_meta(input: &str) -> (String, Vec<(String, String)>) {
+    let mut rest = String::new();
+    let mut meta = vec![];
+    let mut input = input;
+    while let Some(start) = input.find("<!--") {
+        let after = &input[start + 4..];
+        let Some(end) = after.find("-->") else {
+            break;
+        };
+        if let Some(pairs) = after[..end].trim().strip_prefix(HIDDEN_META_PREFIX) {
+            rest.push_str(&input[..start]);
+            meta.extend(parse_pairs(pairs));
+        } else {
+            rest.push_str(&input[..start + 4 + end + 3]);
+        }
+        input = &after[end + 3..];
+    }
+    rest.push_str(input);
+    (rest, meta)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::extract_hidden_meta;
+
+    #[test]
+    fn test_extract_hidden_meta() {
+        let (rest, meta) = extract_hidden_meta(
+            "call Bob <!-- vt: id=42 url=\"https://x.org/a b\" synced --> #work <!-- note -->",
+        );
+        assert_eq!(rest, "call Bob  #work <!-- note -->");
+        assert_eq!(
+            meta,
+            vec![
+                ("id".to_owned(), "42".to_owned()),
+                ("url".to_owned(), "https://x.org/a b".to_owned()),
+                ("synced".to_owned(), String::new()),
+            ]
+        );
+        let (rest, meta) = extract_hidden_meta("unclosed <!--vt: id=1");
+        assert_eq!(rest, "unclosed <!--vt: id=1");
+        assert!(meta.is_empty());
+    }
+}
@@ -0,0 +1,84 @@
+use winnow::{
+    combinator::{fail, preceded},
+    PResult, Parser,
+};
+
+use super::{parser_due_date::parse_naive_date, token::Token};
+
+/// Parses a creation date of the form `"created:<date>"`, see `parse_naive_date` for supported dates.
+pub fn parse_created(input: &mut &str, american_format: bool) -> PResult<Token> {
+    match preceded("created:", |input: &mut &str| {
+        parse_naive_date(input, american_format)
+    })
+    .parse_next(input)?
+    {
+        Token::DueDate(date) => Ok(Token::CreatedDate(date)),
+        _ => fail(input),
+    }
+}
+
+/// Parses a completion date of the form `"done:<date>"`, see `parse_naive_date` for supported dates.
+pub fn parse_completed(input: &mut &str, american_format: bool) -> PResult<Token> {
+    match preceded("done:", |input: &mut &str| {
+        parse_naive_date(input, american_format)
+    })
+    .parse_next(input)?
+    {
+        Token::DueDate(date) => Ok(Token::CompletedDate(date)),
+        _ => fail(input),
+    }
+}
+
+/// Parses a start date of the form `"start:<date>"`, before which the task can't be started.
+pub fn parse_start(input: &mut &str, american_format: bool) -> PResult<Token> {
+    match preceded("start:", |input: &mut &str| {
+        parse_naive_date(input, american_format)
+    })
+    .parse_next(input)?
+    {
+        Token::DueDate(date) => Ok(Token::StartDate(date)),
+        _ => fail(input),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use crate::parser::task::{
+        parser_history::{parse_completed, parse_created, parse_start},
+        token::Token,
+    };
+
+    #[test]
+    fn test_parse_history_success() {
+        let mut created = "created:2025/03/02";
+        assert_eq!(
+            parse_created(&mut created, true),
+            Ok(Token::CreatedDate(
+                NaiveDate::from_ymd_opt(2025, 3, 2).unwrap()
+            ))
+        );
+        let mut completed = "done:2025/03/04";
+        assert_eq!(
+            parse_completed(&mut completed, true),
+            Ok(Token::CompletedDate(
+                NaiveDate::from_ymd_opt(2025, 3, 4).unwrap()
+            ))
+        );
+        let mut start = "start:2025/03/05";
+        assert_eq!(
+            parse_start(&mut start, true),
+            Ok(Token::StartDate(
+                NaiveDate::from_ymd_opt(2025, 3, 5).unwrap()
+            ))
+        );
+    }
+    #[test]
+    fn test_parse_history_fail() {
+        let mut without_date = "created:";
+        assert!(parse_created(&mut without_date, true).is_err());
+        let mut wrong_prefix = "created:2025/03/02";
+        assert!(parse_completed(&mut wrong_prefix, true).is_err());
+    }
+}
@@ -0,0 +1,201 @@
+use chrono::NaiveDate;
+use winnow::{
+    combinator::{alt, eof},
+    token::{take, take_while},
+    PResult, Parser,
+};
+
+use super::token::Token;
+
+/// Signifiers of the dates of the Obsidian Tasks plugin, followed by a `YYYY-MM-DD` date.
+pub const DUE_SIGNIFIER: &str = "📅";
+pub const SCHEDULED_SIGNIFIER: &str = "⏳";
+pub const START_SIGNIFIER: &str = "🛫";
+pub const CREATED_SIGNIFIER: &str = "➕";
+pub const DONE_SIGNIFIER: &str = "✅";
+/// Signifier of the recurrence of the Obsidian Tasks plugin, followed by its rule, e.g. `every week`.
+pub const RECURRENCE_SIGNIFIER: &str = "🔁";
+/// Priorities of the Obsidian Tasks plugin from highest to lowest, read as `p1` to `p5`.
+pub const PRIORITY_SIGNIFIERS: [&str; 5] = ["🔺", "⏫", "🔼", "🔽", "⏬"];
+
+const DATE_SIGNIFIERS: [&str; 5] = [
+    DUE_SIGNIFIER,
+    SCHEDULED_SIGNIFIER,
+    START_SIGNIFIER,
+    CREATED_SIGNIFIER,
+    DONE_SIGNIFIER,
+];
+
+/// Dates of the Dataview format of Obsidian Tasks (`[created:: 2025-03-02]`) and their signifiers.
+const DATAVIEW_FIELDS: [(&str, &str); 5] = [
+    ("due", DUE_SIGNIFIER),
+    ("scheduled", SCHEDULED_SIGNIFIER),
+    ("start", START_SIGNIFIER),
+    ("created", CREATED_SIGNIFIER),
+    ("completion", DONE_SIGNIFIER),
+];
+
+/// Reads a date field of the Dataview format starting at `words[i]`, written in one or two words, as a signifier date.
+///
+/// Returns the signifier date and the number of words read.
+fn dataview_field(words: &[&str], i: usize) -> Option<(String, usize)> {
+    let field = words[i]
+        .strip_prefix('[')
+        .or_else(|| words[i].strip_prefix('('))?;
+    let (key, value) = field.split_once("::")?;
+    let (value, len) = if value.is_empty() {
+        (*words.get(i + 1)?, 2)
+    } else {
+        (value, 1)
+    };
+    let date = value
+        .strip_suffix(']')
+        .or_else(|| value.strip_suffix(')'))?;
+    let (_, signifier) = DATAVIEW_FIELDS.iter().find(|(k, _)| *k == key)?;
+    Some((format!("{signifier}{date}"), len))
+}
+
+/// Joins the signifiers of Obsidian Tasks dates to their date (`📅 2025-03-02` becomes `📅2025-03-02`), reads
+/// Dataview date fields as signifiers, and replaces the recurrence signifier by `every` so that
+/// `join_recurrence_words` reads the rule, other words are kept.
+pub fn join_signifier_words(words: &[&str]) -> Vec<String> {
+    let mut res = vec![];
+    let mut i = 0;
+    while i < words.len() {
+        if let Some((date, len)) = dataview_field(words, i) {
+            res.push(date);
+            i += len;
+            continue;
+        }
+        // Emoji presentation selectors are not part of the signifiers
+        let word = words[i].trim_end_matches('\u{fe0f}');
+        if DATE_SIGNIFIERS.contains(&word) {
+            if let Some(date) = words.get(i + 1) {
+                res.push(format!("{word}{date}"));
+                i += 2;
+                continue;
+            }
+        }
+        if word == RECURRENCE_SIGNIFIER && words.get(i + 1) == Some(&"every") {
+            res.push(String::from("every"));
+            // Obsidian Tasks capitalizes week days
+            if let Some(rule) = words.get(i + 2) {
+                res.push(rule.to_lowercase());
+            }
+            i += 3;
+            continue;
+        }
+        res.push(word.to_owned());
+        i += 1;
+    }
+    res
+}
+
+fn parse_iso_date(input: &mut &str) -> PResult<NaiveDate> {
+    take_while(10, ('0'..='9', '-'))
+        .try_map(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d"))
+        .parse_next(input)
+}
+
+/// Parses a date of the Obsidian Tasks plugin of the form `"<signifier><YYYY-MM-DD>"`, e.g. `📅2025-03-02`.
+pub fn parse_signifier_date(input: &mut &str) -> PResult<Token> {
+    let signifier = alt(DATE_SIGNIFIERS).parse_next(input)?;
+    let date = parse_iso_date.parse_next(input)?;
+    eof.parse_next(input)?;
+    Ok(match signifier {
+        DUE_SIGNIFIER => Token::DueDate(date),
+        SCHEDULED_SIGNIFIER => Token::ScheduledDate(date),
+        START_SIGNIFIER => Token::StartDate(date),
+        CREATED_SIGNIFIER => Token::CreatedDate(date),
+        _ => Token::CompletedDate(date),
+    })
+}
+
+/// Parses a priority signifier of the Obsidian Tasks plugin, see `PRIORITY_SIGNIFIERS`.
+pub fn parse_signifier_priority(input: &mut &str) -> PResult<Token> {
+    let signifier = take(1usize)
+        .verify(|s: &str| PRIORITY_SIGNIFIERS.contains(&s))
+        .parse_next(input)?;
+    eof.parse_next(input)?;
+    let priority = PRIORITY_SIGNIFIERS
+        .iter()
+        .position(|s| *s == signifier)
+        .unwrap_or_default();
+    Ok(Token::Priority(priority + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use pretty_assertions::assert_eq;
+
+    use crate::parser::task::{
+        parser_obsidian_tasks::{
+            join_signifier_words, parse_signifier_date, parse_signifier_priority,
+        },
+        token::Token,
+    };
+
+    #[test]
+    fn test_join_signifier_words() {
+        let join =
+            |input: &str| join_signifier_words(&input.split_whitespace().collect::<Vec<&str>>());
+        assert_eq!(
+            join("call ⏫ 🔁 every Monday ⏳ 2025-03-02 📅 2025-03-03"),
+            vec![
+                "call",
+                "⏫",
+                "every",
+                "monday",
+                "⏳2025-03-02",
+                "📅2025-03-03"
+            ]
+        );
+        assert_eq!(
+            join("mow 🔁 every 2 weeks"),
+            vec!["mow", "every", "2", "weeks"]
+        );
+        // Not followed by a date
+        assert_eq!(join("read 📅"), vec!["read", "📅"]);
+        assert_eq!(
+            join("read [created:: 2025-03-02] (due::2025-03-03) [author:: me]"),
+            vec!["read", "➕2025-03-02", "📅2025-03-03", "[author::", "me]"]
+        );
+    }
+
+    #[test]
+    fn test_parse_signifier_date() {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 2).unwrap();
+        for (mut input, token) in [
+            ("📅2025-03-02", Token::DueDate(date)),
+            ("⏳2025-03-02", Token::ScheduledDate(date)),
+            ("🛫2025-03-02", Token::StartDate(date)),
+            ("➕2025-03-02", Token::CreatedDate(date)),
+            ("✅2025-03-02", Token::CompletedDate(date)),
+        ] {
+            assert_eq!(parse_signifier_date(&mut input), Ok(token));
+        }
+        for mut input in [
+            "📅",
+            "📅2025/03/02",
+            "📅2025-13-02",
+            "📅2025-03-02x",
+            "2025-03-02",
+        ] {
+            assert!(parse_signifier_date(&mut input).is_err());
+        }
+    }
+
+    #[test]
+    fn test_parse_signifier_priority() {
+        let mut highest = "🔺";
+        assert_eq!(
+            parse_signifier_priority(&mut highest),
+            Ok(Token::Priority(1))
+        );
+        let mut low = "🔽";
+        assert_eq!(parse_signifier_priority(&mut low), Ok(Token::Priority(4)));
+        let mut other = "🔽🔽";
+        assert!(parse_signifier_priority(&mut other).is_err());
+    }
+}
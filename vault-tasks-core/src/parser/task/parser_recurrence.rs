@@ -0,0 +1,142 @@
+use chrono::Weekday;
+use winnow::{
+    combinator::{alt, eof, preceded},
+    token::{one_of, take_while},
+    PResult, Parser,
+};
+
+use super::{parser_due_date::parse_literal_day, token::Token};
+use crate::recurrence::Recurrence;
+
+fn parse_interval(input: &mut &str) -> PResult<Recurrence> {
+    let (n, unit) = (
+        take_while(1..=4, '0'..='9')
+            .parse_to()
+            .verify(|n: &u32| *n > 0),
+        one_of(['d', 'w', 'm', 'y']),
+    )
+        .parse_next(input)?;
+    Ok(match unit {
+        'd' => Recurrence::Days(n),
+        'w' => Recurrence::Weeks(n),
+        'm' => Recurrence::Months(n),
+        _ => Recurrence::Years(n),
+    })
+}
+
+fn parse_weekday(input: &mut &str) -> PResult<Recurrence> {
+    parse_literal_day
+        .try_map(str::parse::<Weekday>)
+        .map(Recurrence::Weekday)
+        .parse_next(input)
+}
+
+/// Joins the words of spaced recurrences (`every monday`, `every 2 weeks`) into `every:` tokens, other words are kept.
+pub fn join_recurrence_words(words: &[&str]) -> Vec<String> {
+    let is_recurrence = |token: &str| parse_recurrence.parse(token).is_ok();
+    let mut res = vec![];
+    let mut i = 0;
+    while i < words.len() {
+        if words[i] == "every" {
+            // `every 2 weeks`
+            if let (Some(n), Some(unit)) = (words.get(i + 1), words.get(i + 2)) {
+                let token = format!("every:{n}{}", unit.chars().next().unwrap_or_default());
+                if [
+                    "day", "days", "week", "weeks", "month", "months", "year", "years",
+                ]
+                .contains(unit)
+                    && is_recurrence(&token)
+                {
+                    res.push(token);
+                    i += 3;
+                    continue;
+                }
+            }
+            // `every monday`
+            if let Some(rule) = words.get(i + 1) {
+                let token = format!("every:{rule}");
+                if is_recurrence(&token) {
+                    res.push(token);
+                    i += 2;
+                    continue;
+                }
+            }
+        }
+        res.push(words[i].to_owned());
+        i += 1;
+    }
+    res
+}
+
+/// Parses a recurrence of the form `"every:<rule>"`, e.g. `every:day`, `every:2w` or `every:monday`.
+pub fn parse_recurrence(input: &mut &str) -> PResult<Token> {
+    let res = preceded(
+        "every:",
+        alt((
+            "day".value(Recurrence::Days(1)),
+            "week".value(Recurrence::Weeks(1)),
+            "month".value(Recurrence::Months(1)),
+            "year".value(Recurrence::Years(1)),
+            parse_interval,
+            parse_weekday,
+        )),
+    )
+    .parse_next(input)?;
+    eof.parse_next(input)?;
+    Ok(Token::Recurrence(res))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Weekday;
+
+    use crate::{
+        parser::task::{
+            parser_recurrence::{join_recurrence_words, parse_recurrence},
+            token::Token,
+        },
+        recurrence::Recurrence,
+    };
+
+    #[test]
+    fn test_parse_recurrence_success() {
+        for (mut input, recurrence) in [
+            ("every:day", Recurrence::Days(1)),
+            ("every:week", Recurrence::Weeks(1)),
+            ("every:month", Recurrence::Months(1)),
+            ("every:3d", Recurrence::Days(3)),
+            ("every:2w", Recurrence::Weeks(2)),
+            ("every:1y", Recurrence::Years(1)),
+            ("every:monday", Recurrence::Weekday(Weekday::Mon)),
+            ("every:fri", Recurrence::Weekday(Weekday::Fri)),
+        ] {
+            assert_eq!(
+                parse_recurrence(&mut input),
+                Ok(Token::Recurrence(recurrence))
+            );
+        }
+    }
+    #[test]
+    fn test_parse_recurrence_fail() {
+        for mut input in ["every:", "every:0d", "every:days", "every:2", "every", "2w"] {
+            assert!(parse_recurrence(&mut input).is_err());
+        }
+    }
+    #[test]
+    fn test_join_recurrence_words() {
+        let join =
+            |input: &str| join_recurrence_words(&input.split_whitespace().collect::<Vec<&str>>());
+        assert_eq!(
+            join("water plants every 2 weeks #home"),
+            vec!["water", "plants", "every:2w", "#home"]
+        );
+        assert_eq!(
+            join("standup every monday"),
+            vec!["standup", "every:monday"]
+        );
+        assert_eq!(join("call every week p1"), vec!["call", "every:week", "p1"]);
+        // Not a recurrence
+        assert_eq!(join("every 2 apples"), vec!["every", "2", "apples"]);
+        assert_eq!(join("read every book"), vec!["read", "every", "book"]);
+    }
+}
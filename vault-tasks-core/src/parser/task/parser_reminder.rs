@@ -0,0 +1,44 @@
+use winnow::{
+    combinator::{fail, preceded},
+    PResult, Parser,
+};
+
+use super::{parser_due_date::parse_naive_date, token::Token};
+
+/// Parses a reminder date of the form `"remind:<date>"`, see `parse_naive_date` for supported dates.
+/// The reminder time is given by the time token following it, if any.
+pub fn parse_reminder(input: &mut &str, american_format: bool) -> PResult<Token> {
+    match preceded("remind:", |input: &mut &str| {
+        parse_naive_date(input, american_format)
+    })
+    .parse_next(input)?
+    {
+        Token::DueDate(date) => Ok(Token::ReminderDate(date)),
+        _ => fail(input),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use crate::parser::task::{parser_reminder::parse_reminder, token::Token};
+
+    #[test]
+    fn test_parse_reminder_success() {
+        let mut with_reminder = "remind:2025/03/02";
+        assert_eq!(
+            parse_reminder(&mut with_reminder, true),
+            Ok(Token::ReminderDate(
+                NaiveDate::from_ymd_opt(2025, 3, 2).unwrap()
+            ))
+        );
+    }
+    #[test]
+    fn test_parse_reminder_fail() {
+        let mut without_date = "remind:";
+        assert!(parse_reminder(&mut without_date, true).is_err());
+        let mut without_prefix = "2025/03/02";
+        assert!(parse_reminder(&mut without_prefix, true).is_err());
+    }
+}
@@ -4,24 +4,17 @@ use winnow::{
     PResult, Parser,
 };
 
-use crate::core::{task::State, TaskMarkerConfig};
+use crate::{task::State, TaskMarkerConfig};
 
 use super::token::Token;
 
 /// Parses a `TaskState` from an input string.
 pub fn parse_task_state(input: &mut &str, task_marker_config: &TaskMarkerConfig) -> PResult<Token> {
     match preceded("- ", delimited("[", any, "]")).parse_next(input) {
-        Ok(c) => {
-            if c == task_marker_config.todo {
-                Ok(Token::State(State::ToDo))
-            } else if c == task_marker_config.incomplete {
-                Ok(Token::State(State::Incomplete))
-            } else if c == task_marker_config.canceled {
-                Ok(Token::State(State::Canceled))
-            } else {
-                Ok(Token::State(State::Done))
-            }
-        }
+        // Unknown markers mark tasks done
+        Ok(c) => Ok(Token::State(
+            task_marker_config.state(c).unwrap_or(State::Done),
+        )),
 
         Err(error) => Err(error),
     }
@@ -35,10 +28,10 @@ pub fn parse_task_state(input: &mut &str, task_marker_config: &TaskMarkerConfig)
 }
 #[cfg(test)]
 mod test {
-    use crate::core::{
+    use crate::{
         parser::task::{parser_state::parse_task_state, token::Token},
         task::State,
-        TaskMarkerConfig,
+        CustomState, TaskMarkerConfig,
     };
     fn config() -> TaskMarkerConfig {
         TaskMarkerConfig {
@@ -46,6 +39,13 @@ mod test {
             todo: ' ',
             incomplete: '/',
             canceled: '-',
+            custom: vec![CustomState {
+                marker: '>',
+                name: "Forwarded".to_owned(),
+                symbol: String::new(),
+                finished: true,
+            }],
+            cycle: vec![],
         }
     }
 
@@ -78,6 +78,13 @@ mod test {
         assert_eq!(parse_task_state(&mut input, config), expected);
     }
     #[test]
+    fn test_parse_task_state_custom() {
+        let mut input = "- [>]";
+        let config = &config();
+        let expected = Ok(Token::State(State::Custom(config.custom[0].clone())));
+        assert_eq!(parse_task_state(&mut input, config), expected);
+    }
+    #[test]
     fn test_parse_task_state_fail() {
         let mut input = "- o]";
         let config = &config();
@@ -14,7 +14,7 @@ pub fn parse_tag(input: &mut &str) -> PResult<Token> {
 
 #[cfg(test)]
 mod tests {
-    use crate::core::parser::task::{parser_tags::parse_tag, token::Token};
+    use crate::parser::task::{parser_tags::parse_tag, token::Token};
 
     #[test]
     fn test_parse_tag_sucess() {
@@ -0,0 +1,28 @@
+use chrono::{NaiveDate, NaiveTime};
+
+use crate::{recurrence::Recurrence, task::State};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Token {
+    /// Obsidian block identifier, without the `^`
+    BlockId(String),
+    CompletedDate(NaiveDate),
+    /// Percentage
+    Completion(u8),
+    CreatedDate(NaiveDate),
+    DueDate(NaiveDate),
+    DueTime(NaiveTime),
+    /// In minutes
+    Estimate(u32),
+    Name(String),
+    Priority(usize),
+    Recurrence(Recurrence),
+    ReminderDate(NaiveDate),
+    /// From the `⏳` signifier of Obsidian Tasks
+    ScheduledDate(NaiveDate),
+    /// From the `start:` token or the `🛫` signifier of Obsidian Tasks
+    StartDate(NaiveDate),
+    Tag(String),
+    State(State),
+    TodayFlag,
+}
@@ -0,0 +1,227 @@
+use std::fmt::Display;
+
+use chrono::{Datelike, Months, NaiveDate, TimeDelta, Weekday};
+
+use super::task::{DueDate, State, Task};
+
+/// How often a task repeats, from the `every:` token.
+#[derive(Debug, Hash, Clone, Copy, PartialEq, Eq)]
+pub enum Recurrence {
+    Days(u32),
+    Weeks(u32),
+    Months(u32),
+    Years(u32),
+    /// Every given day of the week
+    Weekday(Weekday),
+}
+
+impl Display for Recurrence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Days(1) => write!(f, "day"),
+            Self::Weeks(1) => write!(f, "week"),
+            Self::Months(1) => write!(f, "month"),
+            Self::Years(1) => write!(f, "year"),
+            Self::Days(n) => write!(f, "{n} days"),
+            Self::Weeks(n) => write!(f, "{n} weeks"),
+            Self::Months(n) => write!(f, "{n} months"),
+            Self::Years(n) => write!(f, "{n} years"),
+            Self::Weekday(day) => write!(
+                f,
+                "{}",
+                match day {
+                    Weekday::Mon => "monday",
+                    Weekday::Tue => "tuesday",
+                    Weekday::Wed => "wednesday",
+                    Weekday::Thu => "thursday",
+                    Weekday::Fri => "friday",
+                    Weekday::Sat => "saturday",
+                    Weekday::Sun => "sunday",
+                }
+            ),
+        }
+    }
+}
+
+impl Recurrence {
+    /// Returns the first occurrence strictly after `date`.
+    #[must_use]
+    pub fn next(&self, date: NaiveDate) -> NaiveDate {
+        match *self {
+            Self::Days(n) => date + TimeDelta::days(n.into()),
+            Self::Weeks(n) => date + TimeDelta::weeks(n.into()),
+            Self::Months(n) => date
+                .checked_add_months(Months::new(n))
+                .unwrap_or(NaiveDate::MAX),
+            Self::Years(n) => date
+                .checked_add_months(Months::new(n.saturating_mul(12)))
+                .unwrap_or(NaiveDate::MAX),
+            Self::Weekday(day) => {
+                let days = (i64::from(day.num_days_from_monday())
+                    - i64::from(date.weekday().num_days_from_monday()))
+                .rem_euclid(7);
+                date + TimeDelta::days(if days == 0 { 7 } else { days })
+            }
+        }
+    }
+
+    /// Returns `due_date` moved to its next occurrence, the time of day is kept.
+    #[must_use]
+    pub fn next_due_date(&self, due_date: &DueDate) -> DueDate {
+        match due_date {
+            DueDate::NoDate => DueDate::NoDate,
+            DueDate::Day(date) => DueDate::Day(self.next(*date)),
+            DueDate::DayTime(date_time) => {
+                DueDate::DayTime(self.next(date_time.date()).and_time(date_time.time()))
+            }
+        }
+    }
+}
+
+/// Returns the next occurrence of a recurring task marked Done, to write in the vault.
+///
+/// Its due date and reminder are moved to the next occurrence, its subtasks and description are not copied.
+#[must_use]
+pub fn next_occurrence(task: &Task) -> Option<Task> {
+    let recurrence = task.recurrence?;
+    if task.state != State::Done || matches!(task.due_date, DueDate::NoDate) {
+        return None;
+    }
+    Some(Task {
+        due_date: recurrence.next_due_date(&task.due_date),
+        reminder: recurrence.next_due_date(&task.reminder),
+        state: State::ToDo,
+        completed: None,
+        completion: task.completion.map(|_| 0),
+        block_id: None,
+        hidden_meta: vec![],
+        subtasks: vec![],
+        description: None,
+        ..task.clone()
+    })
+}
+
+/// Returns the next `count` occurrences of an unfinished recurring task, marked as projected.
+///
+/// Projected occurrences only exist in memory, they have no reminder and are never written to the vault.
+#[must_use]
+pub fn projected_occurrences(task: &Task, count: usize) -> Vec<Task> {
+    let Some(recurrence) = task.recurrence else {
+        return vec![];
+    };
+    if matches!(task.due_date, DueDate::NoDate) || task.state.is_finished() {
+        return vec![];
+    }
+    let mut due_date = task.due_date.clone();
+    (0..count)
+        .map(|_| {
+            due_date = recurrence.next_due_date(&due_date);
+            Task {
+                due_date: due_date.clone(),
+                state: State::ToDo,
+                reminder: DueDate::NoDate,
+                is_today: false,
+                completion: None,
+                completed: None,
+                block_id: None,
+                projected: true,
+                ..task.clone()
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, Weekday};
+    use pretty_assertions::assert_eq;
+
+    use super::{next_occurrence, projected_occurrences, Recurrence};
+    use crate::task::{DueDate, State, Task};
+
+    #[test]
+    fn test_next() {
+        // A Wednesday
+        let date = NaiveDate::from_ymd_opt(2020, 1, 1).unwrap();
+        let day = |d| NaiveDate::from_ymd_opt(2020, 1, d).unwrap();
+        assert_eq!(Recurrence::Days(3).next(date), day(4));
+        assert_eq!(Recurrence::Weeks(2).next(date), day(15));
+        assert_eq!(
+            Recurrence::Months(1).next(NaiveDate::from_ymd_opt(2020, 1, 31).unwrap()),
+            NaiveDate::from_ymd_opt(2020, 2, 29).unwrap()
+        );
+        assert_eq!(
+            Recurrence::Years(1).next(date),
+            NaiveDate::from_ymd_opt(2021, 1, 1).unwrap()
+        );
+        assert_eq!(Recurrence::Weekday(Weekday::Fri).next(date), day(3));
+        assert_eq!(Recurrence::Weekday(Weekday::Wed).next(date), day(8));
+    }
+
+    #[test]
+    fn test_projected_occurrences() {
+        let task = Task {
+            name: "water plants".to_owned(),
+            due_date: DueDate::DayTime(
+                NaiveDate::from_ymd_opt(2020, 1, 1)
+                    .unwrap()
+                    .and_hms_opt(9, 0, 0)
+                    .unwrap(),
+            ),
+            recurrence: Some(Recurrence::Weeks(1)),
+            ..Default::default()
+        };
+        let res = projected_occurrences(&task, 3);
+        assert_eq!(
+            res.iter()
+                .map(|t| t.due_date.to_string())
+                .collect::<Vec<String>>(),
+            vec![
+                "2020-01-08 09:00:00",
+                "2020-01-15 09:00:00",
+                "2020-01-22 09:00:00"
+            ]
+        );
+        assert!(res.iter().all(|t| t.projected));
+
+        let done = Task {
+            state: State::Done,
+            ..task.clone()
+        };
+        assert!(projected_occurrences(&done, 3).is_empty());
+        let not_recurring = Task {
+            recurrence: None,
+            ..task
+        };
+        assert!(projected_occurrences(&not_recurring, 3).is_empty());
+    }
+
+    #[test]
+    fn test_next_occurrence() {
+        let date = NaiveDate::from_ymd_opt(2020, 1, 1).unwrap();
+        let task = Task {
+            name: "standup".to_owned(),
+            state: State::Done,
+            due_date: DueDate::Day(date),
+            completed: Some(date),
+            recurrence: Some(Recurrence::Weekday(Weekday::Mon)),
+            block_id: Some("standup".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(
+            next_occurrence(&task),
+            Some(Task {
+                state: State::ToDo,
+                due_date: DueDate::Day(NaiveDate::from_ymd_opt(2020, 1, 6).unwrap()),
+                completed: None,
+                block_id: None,
+                ..task.clone()
+            })
+        );
+        let todo = Task {
+            state: State::ToDo,
+            ..task
+        };
+        assert_eq!(next_occurrence(&todo), None);
+    }
+}
@@ -0,0 +1,362 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::{eyre::bail, Result};
+
+use super::{changeset::Changeset, is_markdown_file, TasksConfig};
+
+/// Checks that `name` can be used as a file or header name, returning it trimmed.
+fn check_name(name: &str) -> Result<&str> {
+    let name = name.trim();
+    if name.is_empty() {
+        bail!("Name can't be empty");
+    }
+    if name.contains(['/', '\\', '[', ']', '|', '#']) {
+        bail!("Name can't contain `/`, `\\`, `[`, `]`, `|` or `#`: {name:?}");
+    }
+    Ok(name)
+}
+
+/// Returns every note of the vault, ignored paths excluded.
+///
+/// # Errors
+///
+/// Will return an error if a directory of the vault can't be read.
+pub fn vault_notes(config: &TasksConfig) -> Result<Vec<PathBuf>> {
+    fn aux(config: &TasksConfig, path: &Path, res: &mut Vec<PathBuf>) -> Result<()> {
+        for entry in path.read_dir()?.flatten() {
+            let entry_path = entry.path();
+            if config.ignored.contains(&entry_path) {
+                continue;
+            }
+            if entry_path.is_dir() {
+                aux(config, &entry_path, res)?;
+            } else if is_markdown_file(&entry_path) {
+                res.push(entry_path);
+            }
+        }
+        Ok(())
+    }
+    let mut res = vec![];
+    if config.vault_path.is_dir() {
+        aux(config, &config.vault_path, &mut res)?;
+    } else {
+        res.push(config.vault_path.clone());
+    }
+    Ok(res)
+}
+
+/// Rewrites the wiki-links of `content` pointing at the note `old_note`, relative to the vault, so they point at `new_stem`.
+///
+/// Links with directories (`[[Work/Project]]`) only point at the note if they are the last directories of `old_note`.
+/// If `heading` is set, only links to the `(old, new)` heading of the note are rewritten, and their heading is renamed.
+/// Links without a target (`[[#Header]]`) point at the note itself when `same_file` is true.
+fn rewrite_links(
+    content: &str,
+    old_note: &Path,
+    new_stem: &str,
+    heading: Option<(&str, &str)>,
+    same_file: bool,
+) -> String {
+    let old_stem = old_note
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let old_directories = old_note
+        .parent()
+        .into_iter()
+        .flatten()
+        .map(|d| d.to_string_lossy().to_string())
+        .collect::<Vec<String>>();
+    let mut res = String::with_capacity(content.len());
+    let mut offset = 0;
+    while let Some(start) = content[offset..].find("[[").map(|i| i + offset + 2) {
+        let Some(end) = content[start..].find("]]").map(|i| i + start) else {
+            break;
+        };
+        res.push_str(&content[offset..start]);
+        offset = end;
+
+        let link = &content[start..end];
+        let target_end = link.find(['|', '#']).unwrap_or(link.len());
+        let (target, rest) = link.split_at(target_end);
+        let (directories, file_name) = target
+            .rsplit_once('/')
+            .map_or(("", target), |(d, f)| (&link[..=d.len()], f));
+        let (stem, extension) = file_name
+            .len()
+            .checked_sub(3)
+            .filter(|i| file_name.is_char_boundary(*i))
+            .map(|i| file_name.split_at(i))
+            .filter(|(_, ext)| ext.eq_ignore_ascii_case(".md"))
+            .unwrap_or((file_name, ""));
+        let points_at_note = if target.trim().is_empty() {
+            same_file
+        } else {
+            let link_directories = directories
+                .split('/')
+                .map(str::trim)
+                .filter(|d| !d.is_empty() && *d != ".")
+                .map(str::to_owned)
+                .collect::<Vec<String>>();
+            stem.trim() == old_stem && old_directories.ends_with(&link_directories)
+        };
+        if !points_at_note {
+            res.push_str(link);
+            continue;
+        }
+        let rest = match heading {
+            None => rest.to_owned(),
+            Some((old_heading, new_heading)) => {
+                let Some(section) = rest.strip_prefix('#') else {
+                    res.push_str(link);
+                    continue;
+                };
+                let (section, alias) = section.split_at(section.find('|').unwrap_or(section.len()));
+                if section.trim() != old_heading {
+                    res.push_str(link);
+                    continue;
+                }
+                format!("#{new_heading}{alias}")
+            }
+        };
+        if target.trim().is_empty() {
+            res.push_str(&format!("{target}{rest}"));
+        } else {
+            res.push_str(&format!("{directories}{new_stem}{extension}{rest}"));
+        }
+    }
+    res.push_str(&content[offset..]);
+    res
+}
+
+/// Rewrites the links of every other note of the vault, see [`rewrite_links`].
+fn rewrite_vault_links(
+    config: &TasksConfig,
+    path: &Path,
+    new_stem: &str,
+    heading: Option<(&str, &str)>,
+    changes: &mut Changeset,
+) -> Result<()> {
+    let old_note = path.strip_prefix(&config.vault_path).unwrap_or(path);
+    for note in vault_notes(config)? {
+        if note == path {
+            continue;
+        }
+        let content = fs::read_to_string(&note)?;
+        let new = rewrite_links(&content, old_note, new_stem, heading, false);
+        changes.add(&note, content, new);
+    }
+    Ok(())
+}
+
+/// Renames a file or a directory of the vault, keeping its extension if `new_name` has none.
+///
+/// The wiki-links pointing at a renamed note are rewritten if `update_links_on_rename` is set.
+/// Returns the new path and the other files that were written.
+///
+/// # Errors
+///
+/// Will return an error if the vault is read-only, if the name is invalid or taken, or if a file can't be written.
+pub fn rename_file(
+    config: &TasksConfig,
+    path: &Path,
+    new_name: &str,
+) -> Result<(PathBuf, Vec<PathBuf>)> {
+    config.check_writable()?;
+    let mut new_name = check_name(new_name)?.to_owned();
+    if let Some(extension) = path.extension().filter(|_| path.is_file()) {
+        if Path::new(&new_name).extension() != Some(extension) {
+            new_name = format!("{new_name}.{}", extension.to_string_lossy());
+        }
+    }
+    let new_path = path.with_file_name(&new_name);
+    if new_path == path {
+        return Ok((new_path, vec![]));
+    }
+    if new_path.exists() {
+        bail!("{new_path:?} already exists");
+    }
+
+    let mut changes = Changeset::default();
+    if config.update_links_on_rename && is_markdown_file(path) {
+        let new_stem = Path::new(&new_name)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        rewrite_vault_links(config, path, &new_stem, None, &mut changes)?;
+    }
+    fs::rename(path, &new_path)?;
+    changes.apply()?;
+    Ok((
+        new_path,
+        changes.changes.into_iter().map(|c| c.path).collect(),
+    ))
+}
+
+/// Renames the first header of `level` named `old_name` in the note at `path`.
+///
+/// The wiki-links pointing at the header are rewritten if `update_links_on_rename` is set.
+/// Returns the files that were written.
+///
+/// # Errors
+///
+/// Will return an error if the vault is read-only, if the name is invalid, if the header is not found or if a file can't be written.
+pub fn rename_header(
+    config: &TasksConfig,
+    path: &Path,
+    level: usize,
+    old_name: &str,
+    new_name: &str,
+) -> Result<Vec<PathBuf>> {
+    config.check_writable()?;
+    let new_name = check_name(new_name)?;
+    let content = fs::read_to_string(path)?;
+    let prefix = "#".repeat(level);
+    let mut lines = content.split('\n').map(str::to_owned).collect::<Vec<_>>();
+    let Some(line) = lines.iter_mut().find(|l| {
+        l.strip_prefix(&prefix)
+            .and_then(|rest| rest.strip_prefix([' ', '\t']))
+            .is_some_and(|rest| rest.trim() == old_name)
+    }) else {
+        bail!("Header {prefix} {old_name} not found in {path:?}");
+    };
+    *line = format!("{prefix} {new_name}");
+    let mut new = lines.join("\n");
+
+    let mut changes = Changeset::default();
+    if config.update_links_on_rename {
+        let stem = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let note = path.strip_prefix(&config.vault_path).unwrap_or(path);
+        new = rewrite_links(&new, note, &stem, Some((old_name, new_name)), true);
+        rewrite_vault_links(
+            config,
+            path,
+            &stem,
+            Some((old_name, new_name)),
+            &mut changes,
+        )?;
+    }
+    changes.add(path, content, new);
+    changes.apply()?;
+    Ok(changes.changes.into_iter().map(|c| c.path).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use pretty_assertions::assert_eq;
+
+    use super::{rename_file, rename_header, rewrite_links};
+    use crate::TasksConfig;
+
+    #[test]
+    fn test_rewrite_links() {
+        let project = Path::new("Work/Project.md");
+        let content = "See [[Project]], [[Work/Project.md|the project]] and ![[Project#Goals]].\n\
+                       Not [[Projects]], [[Other#Project]] nor [[Other/Project]].";
+        assert_eq!(
+            rewrite_links(content, project, "Plan", None, false),
+            "See [[Plan]], [[Work/Plan.md|the project]] and ![[Plan#Goals]].\n\
+             Not [[Projects]], [[Other#Project]] nor [[Other/Project]]."
+        );
+        let content = "[[Project#Goals]] [[Project#Goals|goals]] [[Project#Notes]] [[#Goals]]";
+        assert_eq!(
+            rewrite_links(content, project, "Project", Some(("Goals", "Aims")), false),
+            "[[Project#Aims]] [[Project#Aims|goals]] [[Project#Notes]] [[#Goals]]"
+        );
+        assert_eq!(
+            rewrite_links(content, project, "Project", Some(("Goals", "Aims")), true),
+            "[[Project#Aims]] [[Project#Aims|goals]] [[Project#Notes]] [[#Aims]]"
+        );
+        assert_eq!(
+            rewrite_links("[[unclosed", Path::new("a.md"), "b", None, false),
+            "[[unclosed"
+        );
+    }
+
+    #[test]
+    fn test_rename() {
+        let vault_path = std::env::temp_dir().join("vault-tasks-rename");
+        let _ = fs::remove_dir_all(&vault_path);
+        fs::create_dir_all(vault_path.join("Work")).unwrap();
+        fs::write(
+            vault_path.join("Work/Project.md"),
+            "# Goals\n- [ ] task\n[[#Goals]]\n",
+        )
+        .unwrap();
+        fs::write(
+            vault_path.join("Index.md"),
+            "[[Project]] [[Project#Goals]]\n",
+        )
+        .unwrap();
+        let mut config = TasksConfig {
+            vault_path: vault_path.clone(),
+            ..Default::default()
+        };
+
+        // Links are left alone
+        let (path, changed) =
+            rename_file(&config, &vault_path.join("Work/Project.md"), "Plan").unwrap();
+        assert_eq!(path, vault_path.join("Work/Plan.md"));
+        assert!(changed.is_empty());
+        assert!(rename_file(&config, &vault_path.join("Index.md"), "Work/Plan").is_err());
+
+        config.update_links_on_rename = true;
+        fs::write(vault_path.join("Work/Other.md"), "").unwrap();
+        assert!(rename_file(&config, &path, "Other.md").is_err());
+        fs::write(vault_path.join("Index.md"), "[[Plan]] [[Plan#Goals]]\n").unwrap();
+        let (path, changed) = rename_file(&config, &path, "Project.md").unwrap();
+        assert_eq!(changed, vec![vault_path.join("Index.md")]);
+        assert_eq!(
+            fs::read_to_string(vault_path.join("Index.md")).unwrap(),
+            "[[Project]] [[Project#Goals]]\n"
+        );
+
+        let mut changed = rename_header(&config, &path, 1, "Goals", "Aims").unwrap();
+        changed.sort();
+        assert_eq!(changed, vec![vault_path.join("Index.md"), path.clone()]);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "# Aims\n- [ ] task\n[[#Aims]]\n"
+        );
+        assert!(rename_header(&config, &path, 2, "Aims", "Goals").is_err());
+
+        config.read_only = true;
+        assert!(rename_header(&config, &path, 1, "Aims", "Goals").is_err());
+    }
+
+    #[test]
+    fn test_rename_shared_stem() {
+        let vault_path = std::env::temp_dir().join("vault-tasks-rename-shared-stem");
+        let _ = fs::remove_dir_all(&vault_path);
+        fs::create_dir_all(vault_path.join("Projects")).unwrap();
+        fs::create_dir_all(vault_path.join("Other")).unwrap();
+        fs::write(vault_path.join("Projects/note.md"), "").unwrap();
+        fs::write(vault_path.join("Other/note.md"), "").unwrap();
+        fs::write(
+            vault_path.join("Index.md"),
+            "[[Projects/note]] [[Other/note]] [[Other/note#Goals|goals]]\n",
+        )
+        .unwrap();
+        let config = TasksConfig {
+            vault_path: vault_path.clone(),
+            update_links_on_rename: true,
+            ..Default::default()
+        };
+
+        let (_, changed) =
+            rename_file(&config, &vault_path.join("Projects/note.md"), "plan").unwrap();
+        assert_eq!(changed, vec![vault_path.join("Index.md")]);
+        assert_eq!(
+            fs::read_to_string(vault_path.join("Index.md")).unwrap(),
+            "[[Projects/plan]] [[Other/note]] [[Other/note#Goals|goals]]\n"
+        );
+    }
+}
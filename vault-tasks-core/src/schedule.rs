@@ -0,0 +1,325 @@
+use std::fs;
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
+use color_eyre::Result;
+
+use super::{
+    changeset::Changeset,
+    daily_notes::{check_pattern, daily_note_path},
+    task::{DueDate, Task},
+    TasksConfig,
+};
+
+/// Duration of timed tasks without an estimate, in minutes.
+pub const DEFAULT_DURATION: u32 = 60;
+
+/// Heading of the schedule written to daily notes by `plan_schedule`.
+pub const SCHEDULE_HEADING: &str = "## Schedule";
+
+/// A task due at a given time, lasting its estimate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduledTask {
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+    pub task: Task,
+    /// Whether it overlaps another task of the day
+    pub conflict: bool,
+}
+
+/// Estimate of the task, or `DEFAULT_DURATION` minutes without one.
+fn duration(task: &Task) -> TimeDelta {
+    TimeDelta::minutes(i64::from(task.estimate.unwrap_or(DEFAULT_DURATION)))
+}
+
+/// Returns the tasks due at a given time on `date`, sorted by time.
+///
+/// Tasks last their estimate, or `DEFAULT_DURATION` minutes without one.
+#[must_use]
+pub fn day_schedule(tasks: &[Task], date: NaiveDate) -> Vec<ScheduledTask> {
+    let mut res = tasks
+        .iter()
+        .filter_map(|task| match task.due_date {
+            DueDate::DayTime(start) if start.date() == date => Some(ScheduledTask {
+                start,
+                end: start + duration(task),
+                task: task.clone(),
+                conflict: false,
+            }),
+            _ => None,
+        })
+        .collect::<Vec<ScheduledTask>>();
+    res.sort_by_key(|t| (t.start, t.end));
+
+    for i in 0..res.len() {
+        for j in i + 1..res.len() {
+            // Sorted by start, so later tasks can't overlap this one either
+            if res[j].start >= res[i].end {
+                break;
+            }
+            res[i].conflict = true;
+            res[j].conflict = true;
+        }
+    }
+    res
+}
+
+/// Lays out the unfinished tasks planned on `date`, flagged `@today` or due that day, in time blocks sorted by time.
+///
+/// Tasks due at a given time keep it, the others fill the free time from `day_start` on, highest priority first.
+/// Tasks last their estimate, or `DEFAULT_DURATION` minutes without one.
+#[must_use]
+pub fn time_blocks(tasks: &[Task], date: NaiveDate, day_start: NaiveTime) -> Vec<ScheduledTask> {
+    let planned = tasks
+        .iter()
+        .filter(|t| {
+            !t.projected
+                && !t.state.is_finished()
+                && (t.is_today
+                    || t.due_date
+                        .to_naive_date_time()
+                        .is_some_and(|d| d.date() == date))
+        })
+        .cloned()
+        .collect::<Vec<Task>>();
+    let timed = day_schedule(&planned, date);
+    let mut untimed = planned
+        .into_iter()
+        .filter(|t| !matches!(t.due_date, DueDate::DayTime(d) if d.date() == date))
+        .collect::<Vec<Task>>();
+    // Tasks without priority come last
+    untimed.sort_by_key(|t| {
+        if t.priority == 0 {
+            usize::MAX
+        } else {
+            t.priority
+        }
+    });
+
+    let mut res = vec![];
+    let mut cursor = date.and_time(day_start);
+    for task in untimed {
+        let length = duration(&task);
+        // Timed tasks are sorted by start, so the block only moves past the ones it overlaps
+        let mut start = cursor;
+        for block in &timed {
+            if start < block.end && block.start < start + length {
+                start = start.max(block.end);
+            }
+        }
+        cursor = start + length;
+        res.push(ScheduledTask {
+            start,
+            end: cursor,
+            task,
+            conflict: false,
+        });
+    }
+    res.extend(timed);
+    res.sort_by_key(|t| (t.start, t.end));
+    res
+}
+
+/// Writes `blocks` as a Markdown table, e.g. `| 09:00 - 09:30 | standup |`, overlapping timed tasks being marked.
+#[must_use]
+pub fn schedule_table(blocks: &[ScheduledTask]) -> String {
+    let mut res = String::from("| Time | Task |\n| ---- | ---- |\n");
+    for block in blocks {
+        res.push_str(&format!(
+            "| {} - {} | {}{} |\n",
+            block.start.format("%H:%M"),
+            block.end.format("%H:%M"),
+            block.task.name.replace('|', "\\|"),
+            if block.conflict { " ⚠️" } else { "" }
+        ));
+    }
+    res
+}
+
+/// Computes the changes writing the time blocks of `today` to today's daily note, as a table under `SCHEDULE_HEADING`.
+///
+/// The schedule of a previous run is replaced up to the next header, otherwise it is appended to the note.
+/// Returns the number of scheduled tasks and the changes.
+///
+/// # Errors
+///
+/// Will return an error if daily notes are not configured or if the note can't be read.
+pub fn plan_schedule(
+    config: &TasksConfig,
+    tasks: &[Task],
+    today: NaiveDate,
+    day_start: NaiveTime,
+) -> Result<(usize, Changeset)> {
+    check_pattern(config)?;
+    let mut changes = Changeset::default();
+    let blocks = time_blocks(tasks, today, day_start);
+    if blocks.is_empty() {
+        return Ok((0, changes));
+    }
+    let path = daily_note_path(config, today);
+    let old = if path.exists() {
+        fs::read_to_string(&path)?
+    } else {
+        String::new()
+    };
+    let section = format!("{SCHEDULE_HEADING}\n\n{}", schedule_table(&blocks));
+
+    let lines = old.lines().collect::<Vec<&str>>();
+    let mut new = String::new();
+    if let Some(start) = lines.iter().position(|l| l.trim_end() == SCHEDULE_HEADING) {
+        let end = lines[start + 1..]
+            .iter()
+            .position(|l| l.starts_with("# ") || l.starts_with("##"))
+            .map_or(lines.len(), |i| start + 1 + i);
+        for line in &lines[..start] {
+            new.push_str(line);
+            new.push('\n');
+        }
+        new.push_str(&section);
+        if end < lines.len() {
+            new.push('\n');
+            for line in &lines[end..] {
+                new.push_str(line);
+                new.push('\n');
+            }
+        }
+    } else {
+        new.push_str(&old);
+        if !new.is_empty() && !new.ends_with('\n') {
+            new.push('\n');
+        }
+        if !new.is_empty() {
+            new.push('\n');
+        }
+        new.push_str(&section);
+    }
+    changes.add(&path, old, new);
+    Ok((blocks.len(), changes))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use chrono::{NaiveDate, NaiveTime};
+    use pretty_assertions::assert_eq;
+
+    use super::{day_schedule, plan_schedule, schedule_table, time_blocks};
+    use crate::{
+        task::{DueDate, State, Task},
+        TasksConfig,
+    };
+
+    #[test]
+    fn test_day_schedule() {
+        let date = NaiveDate::from_ymd_opt(2020, 1, 1).unwrap();
+        let task = |name: &str, h: u32, m: u32, estimate: Option<u32>| Task {
+            name: name.to_owned(),
+            due_date: DueDate::DayTime(date.and_hms_opt(h, m, 0).unwrap()),
+            estimate,
+            ..Default::default()
+        };
+        let tasks = vec![
+            task("lunch", 12, 0, Some(45)),
+            task("standup", 9, 0, Some(15)),
+            task("review", 9, 10, None),
+            task("call", 14, 0, Some(30)),
+            task("next day", 23, 30, Some(90)),
+            Task {
+                name: "no time".to_owned(),
+                due_date: DueDate::Day(date),
+                ..Default::default()
+            },
+        ];
+        let schedule = day_schedule(&tasks, date);
+        assert_eq!(
+            schedule
+                .iter()
+                .map(|t| (t.task.name.as_str(), t.conflict))
+                .collect::<Vec<(&str, bool)>>(),
+            vec![
+                ("standup", true),
+                ("review", true),
+                ("lunch", false),
+                ("call", false),
+                ("next day", false)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_time_blocks() {
+        let date = NaiveDate::from_ymd_opt(2020, 1, 1).unwrap();
+        let task = |name: &str, due_date: DueDate, priority: usize, estimate: Option<u32>| Task {
+            name: name.to_owned(),
+            due_date,
+            priority,
+            estimate,
+            ..Default::default()
+        };
+        let at = |h: u32, m: u32| DueDate::DayTime(date.and_hms_opt(h, m, 0).unwrap());
+        let tasks = vec![
+            task("write report", DueDate::Day(date), 0, Some(90)),
+            task("standup", at(9, 0), 0, Some(15)),
+            task("review | merge", DueDate::Day(date), 1, Some(30)),
+            Task {
+                is_today: true,
+                ..task("call", DueDate::NoDate, 2, None)
+            },
+            Task {
+                state: State::Done,
+                ..task("done", DueDate::Day(date), 1, None)
+            },
+            task("tomorrow", DueDate::Day(date.succ_opt().unwrap()), 1, None),
+        ];
+        let blocks = time_blocks(&tasks, date, NaiveTime::from_hms_opt(8, 45, 0).unwrap());
+        assert_eq!(
+            schedule_table(&blocks),
+            "| Time | Task |
+| ---- | ---- |
+| 09:00 - 09:15 | standup |
+| 09:15 - 09:45 | review \\| merge |
+| 09:45 - 10:45 | call |
+| 10:45 - 12:15 | write report |
+"
+        );
+    }
+
+    #[test]
+    fn test_plan_schedule() {
+        let vault_path = std::env::temp_dir().join("vault-tasks-schedule");
+        let _ = fs::remove_dir_all(&vault_path);
+        fs::create_dir_all(&vault_path).unwrap();
+        let config = TasksConfig {
+            vault_path: vault_path.clone(),
+            daily_notes_pattern: String::from("%Y-%m-%d.md"),
+            ..Default::default()
+        };
+        let date = NaiveDate::from_ymd_opt(2020, 1, 1).unwrap();
+        let nine = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+        let tasks = vec![Task {
+            name: "standup".to_owned(),
+            due_date: DueDate::Day(date),
+            estimate: Some(15),
+            ..Default::default()
+        }];
+
+        let (count, changes) = plan_schedule(&config, &tasks, date, nine).unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(
+            changes.changes[0].new,
+            "## Schedule\n\n| Time | Task |\n| ---- | ---- |\n| 09:00 - 09:15 | standup |\n"
+        );
+
+        // The schedule of a previous run is replaced
+        fs::write(
+            vault_path.join("2020-01-01.md"),
+            "# Notes\n## Schedule\n\n| Time | Task |\n| old |\n\n## Tasks\n- [ ] standup\n",
+        )
+        .unwrap();
+        let (_, changes) = plan_schedule(&config, &tasks, date, nine).unwrap();
+        assert_eq!(
+            changes.changes[0].new,
+            "# Notes\n## Schedule\n\n| Time | Task |\n| ---- | ---- |\n| 09:00 - 09:15 | standup |\n\n## Tasks\n- [ ] standup\n"
+        );
+    }
+}
@@ -0,0 +1,472 @@
+use std::cmp::Ordering;
+
+use chrono::{NaiveDate, NaiveTime};
+use lexical_sort::{lexical_cmp, natural_cmp, natural_lexical_cmp};
+use serde::Deserialize;
+use strum::EnumIter;
+use strum_macros::FromRepr;
+
+use super::{
+    task::{DueDate, Task},
+    urgency::urgency,
+    TasksConfig,
+};
+
+#[derive(Default, Clone, Copy, FromRepr, EnumIter, strum_macros::Display)]
+pub enum SortingMode {
+    #[default]
+    #[strum(to_string = "Due Date")]
+    ByDueDate,
+    #[strum(to_string = "Title")]
+    ByName,
+}
+
+impl SortingMode {
+    #[must_use]
+    pub fn next(self) -> Self {
+        match self {
+            Self::ByDueDate => Self::ByName,
+            Self::ByName => Self::ByDueDate,
+        }
+    }
+    /// See `cmp_due_date` for `untimed_last`.
+    pub fn sort(tasks: &mut [Task], sorter: Self, untimed_last: bool) {
+        tasks.sort_by(|t1, t2| Self::cmp(t1, t2, sorter, untimed_last));
+    }
+
+    /// Compare two tasks by due date
+    ///
+    /// Tasks due on a day without a time come before the timed tasks of that day, or after them if `untimed_last` is set.
+    pub fn cmp_due_date(t1: &Task, t2: &Task, untimed_last: bool) -> Ordering {
+        let day_time = if untimed_last {
+            NaiveTime::from_hms_nano_opt(23, 59, 59, 999_999_999).unwrap()
+        } else {
+            NaiveTime::MIN
+        };
+        match (&t1.due_date, &t2.due_date) {
+            (DueDate::Day(d1), DueDate::Day(d2)) => d1.cmp(d2),
+            (DueDate::DayTime(d1), DueDate::DayTime(d2)) => d1.cmp(d2),
+            (DueDate::Day(d1), DueDate::DayTime(d2)) => d1.and_time(day_time).cmp(d2),
+            (DueDate::DayTime(d1), DueDate::Day(d2)) => d1.cmp(&d2.and_time(day_time)),
+            (DueDate::NoDate, DueDate::Day(_) | DueDate::DayTime(_)) => Ordering::Greater,
+            (DueDate::Day(_) | DueDate::DayTime(_), DueDate::NoDate) => Ordering::Less,
+            _ => Ordering::Equal,
+        }
+    }
+    /// The sorting mode expressed as sort keys, see `SortKey`.
+    /// Sorting mode is used first
+    /// If equal, other attribues will be used:
+    /// - State: `ToDo` < `Done` (in Ord impl of `State`)
+    /// - The other sorting mode
+    /// - Priority: usual number ordering
+    /// - Tags: not used
+    #[must_use]
+    pub fn keys(self) -> Vec<SortKey> {
+        match self {
+            Self::ByDueDate => vec![
+                SortKey::Due,
+                SortKey::State,
+                SortKey::Name,
+                SortKey::Priority,
+            ],
+            Self::ByName => vec![
+                SortKey::Name,
+                SortKey::State,
+                SortKey::Due,
+                SortKey::Priority,
+            ],
+        }
+    }
+    /// Compares two tasks with the specified sorting mode
+    fn cmp(t1: &Task, t2: &Task, sorter: Self, untimed_last: bool) -> Ordering {
+        SortKey::cmp_tasks(&sorter.keys(), (&[], t1), (&[], t2), untimed_last)
+    }
+}
+
+/// A key of the `sort:` search token, e.g. `sort:path,due`.
+///
+/// Keys are compared in order, the next one is only used when tasks are equal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    /// Directories, file and headers leading to the task
+    Path,
+    Due,
+    Name,
+    Priority,
+    State,
+}
+
+impl SortKey {
+    fn parse(key: &str) -> Option<Self> {
+        match key {
+            "path" => Some(Self::Path),
+            "due" => Some(Self::Due),
+            "name" | "title" => Some(Self::Name),
+            "priority" | "p" => Some(Self::Priority),
+            "state" => Some(Self::State),
+            _ => None,
+        }
+    }
+
+    /// Parses a search word like `sort:path,due`.
+    #[must_use]
+    pub fn parse_keys(word: &str) -> Option<Vec<Self>> {
+        word.strip_prefix("sort:")?
+            .split(',')
+            .map(Self::parse)
+            .collect()
+    }
+
+    /// Sorts tasks along with their paths, see `filter_to_vec_with_paths`. The sort is stable.
+    pub fn sort(tasks: &mut [(Vec<String>, Task)], keys: &[Self], untimed_last: bool) {
+        tasks.sort_by(|(p1, t1), (p2, t2)| Self::cmp_tasks(keys, (p1, t1), (p2, t2), untimed_last));
+    }
+
+    /// Compares two tasks along with their paths, see `filter_to_vec_with_paths`.
+    ///
+    /// See `SortingMode::cmp_due_date` for `untimed_last`.
+    #[must_use]
+    pub fn cmp_tasks(
+        keys: &[Self],
+        t1: (&[String], &Task),
+        t2: (&[String], &Task),
+        untimed_last: bool,
+    ) -> Ordering {
+        let ((path1, t1), (path2, t2)) = (t1, t2);
+        keys.iter()
+            .map(|key| match key {
+                Self::Path => path1
+                    .iter()
+                    .zip(path2)
+                    .map(|(a, b)| lexical_cmp(a, b))
+                    .find(|o| o.is_ne())
+                    .unwrap_or_else(|| path1.len().cmp(&path2.len())),
+                Self::Due => SortingMode::cmp_due_date(t1, t2, untimed_last),
+                Self::Name => lexical_cmp(&t1.name, &t2.name),
+                Self::Priority => t1.priority.cmp(&t2.priority),
+                Self::State => t1.state.cmp(&t2.state),
+            })
+            .find(|o| o.is_ne())
+            .unwrap_or(Ordering::Equal)
+    }
+}
+/// How entries of a directory are sorted in the explorer. Directories always come first.
+#[derive(
+    Default,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Deserialize,
+    FromRepr,
+    EnumIter,
+    strum_macros::Display,
+)]
+pub enum ExplorerSortingMode {
+    #[default]
+    #[strum(to_string = "Name")]
+    Name,
+    /// Most recently modified first
+    #[strum(to_string = "Modified")]
+    ModifiedTime,
+    /// Most tasks first
+    #[strum(to_string = "Tasks")]
+    TaskCount,
+}
+
+impl ExplorerSortingMode {
+    #[must_use]
+    pub fn next(self) -> Self {
+        match self {
+            Self::Name => Self::ModifiedTime,
+            Self::ModifiedTime => Self::TaskCount,
+            Self::TaskCount => Self::Name,
+        }
+    }
+
+    /// Compares two entry names.
+    /// Natural sorting compares numbers by value (`note2` < `note10`).
+    #[must_use]
+    pub fn cmp_names(s1: &str, s2: &str, natural: bool, case_insensitive: bool) -> Ordering {
+        match (natural, case_insensitive) {
+            (true, true) => natural_lexical_cmp(s1, s2),
+            (true, false) => natural_cmp(s1, s2),
+            (false, true) => lexical_cmp(s1, s2),
+            (false, false) => s1.cmp(s2),
+        }
+    }
+}
+
+/// Order of the tasks printed by `vault-tasks stdout` and `vault-tasks list`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputSort {
+    /// Order of the notes
+    #[default]
+    File,
+    Due,
+    Priority,
+    Name,
+    /// Most urgent first, see `urgency::urgency`
+    Urgency,
+}
+
+impl OutputSort {
+    /// Compares two tasks, `today` is used to compute their urgency.
+    #[must_use]
+    pub fn cmp(self, t1: &Task, t2: &Task, today: NaiveDate, config: &TasksConfig) -> Ordering {
+        let keys = match self {
+            Self::File => return Ordering::Equal,
+            Self::Due | Self::Urgency => SortingMode::ByDueDate.keys(),
+            Self::Name => SortingMode::ByName.keys(),
+            Self::Priority => vec![
+                SortKey::Priority,
+                SortKey::Due,
+                SortKey::State,
+                SortKey::Name,
+            ],
+        };
+        let urgency_order = if self == Self::Urgency {
+            urgency(t2, today, &config.urgency).total_cmp(&urgency(t1, today, &config.urgency))
+        } else {
+            Ordering::Equal
+        };
+        urgency_order.then_with(|| {
+            SortKey::cmp_tasks(&keys, (&[], t1), (&[], t2), config.untimed_tasks_last)
+        })
+    }
+
+    /// Sorts `items` by their tasks. The sort is stable, `reverse` reverses the whole order, file order included.
+    pub fn sort<T>(
+        self,
+        items: &mut [T],
+        task: impl Fn(&T) -> &Task,
+        today: NaiveDate,
+        config: &TasksConfig,
+        reverse: bool,
+    ) {
+        items.sort_by(|a, b| self.cmp(task(a), task(b), today, config));
+        if reverse {
+            items.reverse();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use insta::{assert_debug_snapshot, with_settings};
+    use pretty_assertions::assert_eq;
+
+    use chrono::NaiveDate;
+
+    use super::{OutputSort, SortingMode};
+    use crate::{
+        parser::task::parse_task,
+        task::{DueDate, State, Task},
+        TasksConfig,
+    };
+    #[test]
+    fn task_sort_by_name() {
+        let mut source = [
+            "- [ ] test 10/11",
+            "- [ ] test 10/9",
+            "- [ ] test 10/10 p5",
+            "- [ ] test 10/10 10:00",
+            "- [x] zèbre",
+            "- [x] zzz",
+            "- [ ] zzz",
+            "- [ ] test 10/10 p2",
+            "- [x] test",
+            "- [ ] test2",
+            "- [ ] test 10/10 5:00",
+            "- [ ] abc",
+        ];
+        let config = TasksConfig {
+            use_american_format: true,
+            ..Default::default()
+        };
+        let mut tasks: Vec<Task> = source
+            .iter_mut()
+            .map(|input| parse_task(input, String::new(), &config).unwrap())
+            .collect();
+
+        let sorting_mode = SortingMode::ByName;
+        SortingMode::sort(&mut tasks, sorting_mode, false);
+
+        let tasks = tasks
+            .iter()
+            .map(|task| task.get_fixed_attributes(&config, 2))
+            .collect::<Vec<String>>();
+
+        with_settings!({
+            info=>&source,
+            description => "", // the template source code
+        }, {
+                assert_debug_snapshot!(tasks);
+        });
+    }
+    #[test]
+    fn task_sort_by_due_date() {
+        let mut source = [
+            "- [ ] test 2025/10/11",
+            "- [ ] test 2025/10/9",
+            "- [ ] test 2025/10/10 p5",
+            "- [ ] test 2025/10/10 10:00",
+            "- [x] zèbre",
+            "- [x] zzz",
+            "- [ ] zzz",
+            "- [ ] test 2025/10/10 p2",
+            "- [x] test",
+            "- [ ] test2",
+            "- [ ] test 2025/10/10 5:00",
+            "- [ ] abc",
+        ];
+        let config = TasksConfig {
+            use_american_format: true,
+            ..Default::default()
+        };
+        let mut tasks: Vec<Task> = source
+            .iter_mut()
+            .map(|input| parse_task(input, String::new(), &config).unwrap())
+            .collect();
+
+        let sorting_mode = SortingMode::ByDueDate;
+        SortingMode::sort(&mut tasks, sorting_mode, false);
+
+        let tasks = tasks
+            .iter()
+            .map(|task| task.get_fixed_attributes(&config, 2))
+            .collect::<Vec<String>>();
+
+        with_settings!({
+            info=>&source,
+            description => "", // the template source code
+        }, {
+                assert_debug_snapshot!(tasks);
+        });
+    }
+    #[test]
+    fn task_sort_states() {
+        let mut source = ["- [ ] test", "- [x] test", "- [/] test", "- [-] test"];
+        let config = TasksConfig {
+            use_american_format: true,
+            ..Default::default()
+        };
+        let mut tasks: Vec<Task> = source
+            .iter_mut()
+            .map(|input| parse_task(input, String::new(), &config).unwrap())
+            .collect();
+
+        let sorting_mode = SortingMode::ByDueDate;
+        SortingMode::sort(&mut tasks, sorting_mode, false);
+
+        let tasks = tasks
+            .iter()
+            .map(|task| task.get_fixed_attributes(&config, 2))
+            .collect::<Vec<String>>();
+
+        with_settings!({
+            info=>&source,
+            description => "", // the template source code
+        }, {
+                assert_debug_snapshot!(tasks);
+        });
+    }
+    #[test]
+    fn task_sort_untimed_last() {
+        let mut source = [
+            "- [ ] untimed 2020/01/10",
+            "- [ ] morning 2020/01/10 9:00",
+            "- [ ] before 2020/01/09 23:00",
+        ];
+        let config = TasksConfig {
+            use_american_format: true,
+            ..Default::default()
+        };
+        let tasks: Vec<Task> = source
+            .iter_mut()
+            .map(|input| parse_task(input, String::new(), &config).unwrap())
+            .collect();
+        let sorted_names = |untimed_last| {
+            let mut tasks = tasks.clone();
+            SortingMode::sort(&mut tasks, SortingMode::ByDueDate, untimed_last);
+            tasks.into_iter().map(|t| t.name).collect::<Vec<String>>()
+        };
+        assert_eq!(sorted_names(false), vec!["before", "untimed", "morning"]);
+        assert_eq!(sorted_names(true), vec!["before", "morning", "untimed"]);
+    }
+
+    #[test]
+    fn test_output_sort_urgency() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 10).unwrap();
+        let task = |name: &str, day: Option<u32>, priority, state| Task {
+            name: name.to_owned(),
+            due_date: day.map_or(DueDate::NoDate, |d| {
+                DueDate::Day(NaiveDate::from_ymd_opt(2025, 3, d).unwrap())
+            }),
+            priority,
+            state,
+            ..Default::default()
+        };
+        let mut tasks = vec![
+            task("someday", None, 0, State::ToDo),
+            task("urgent someday", None, 1, State::ToDo),
+            task("next week", Some(17), 0, State::ToDo),
+            task("done overdue", Some(1), 0, State::Done),
+            task("overdue", Some(8), 0, State::ToDo),
+            task("today", Some(10), 2, State::ToDo),
+        ];
+        let config = TasksConfig::default();
+        let names = |tasks: &[Task]| tasks.iter().map(|t| t.name.clone()).collect::<Vec<_>>();
+        OutputSort::Urgency.sort(&mut tasks, |t| t, today, &config, false);
+        assert_eq!(
+            names(&tasks),
+            vec![
+                "today",
+                "overdue",
+                "urgent someday",
+                "next week",
+                "done overdue",
+                "someday"
+            ]
+        );
+        OutputSort::File.sort(&mut tasks, |t| t, today, &config, true);
+        assert_eq!(names(&tasks)[0], "someday");
+        OutputSort::Due.sort(&mut tasks, |t| t, today, &config, true);
+        assert_eq!(names(&tasks)[..2], ["urgent someday", "someday"]);
+    }
+}
+
+#[cfg(test)]
+mod tests_explorer {
+    use pretty_assertions::assert_eq;
+
+    use super::ExplorerSortingMode;
+
+    #[test]
+    fn test_cmp_names() {
+        let sorted = |natural, case_insensitive| {
+            let mut names = vec!["note10.md", "Note3.md", "note2.md", "note1.md"];
+            names.sort_by(|a, b| ExplorerSortingMode::cmp_names(a, b, natural, case_insensitive));
+            names
+        };
+        assert_eq!(
+            sorted(false, false),
+            vec!["Note3.md", "note1.md", "note10.md", "note2.md"]
+        );
+        assert_eq!(
+            sorted(false, true),
+            vec!["note1.md", "note10.md", "note2.md", "Note3.md"]
+        );
+        assert_eq!(
+            sorted(true, false),
+            vec!["Note3.md", "note1.md", "note2.md", "note10.md"]
+        );
+        assert_eq!(
+            sorted(true, true),
+            vec!["note1.md", "note2.md", "Note3.md", "note10.md"]
+        );
+    }
+}
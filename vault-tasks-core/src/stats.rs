@@ -0,0 +1,530 @@
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Days, NaiveDate, NaiveDateTime, Weekday};
+use serde_json::{json, Value};
+
+use super::{
+    filter::{filter_to_vec_with_paths, is_overdue, Filter},
+    recurrence::Recurrence,
+    task::{DueDate, State, Task},
+    vault_data::VaultData,
+};
+
+/// Number of tasks created and completed during a week.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeekStats {
+    /// Monday of the week
+    pub start: NaiveDate,
+    pub created: usize,
+    pub completed: usize,
+}
+
+/// Returns the Monday of the week of `date`.
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Days::new(u64::from(date.weekday().num_days_from_monday()))
+}
+
+fn collect_tasks<'a>(vault_data: &'a VaultData, res: &mut Vec<&'a Task>) {
+    fn add_task<'a>(task: &'a Task, res: &mut Vec<&'a Task>) {
+        res.push(task);
+        task.subtasks.iter().for_each(|t| add_task(t, res));
+    }
+    match vault_data {
+        VaultData::Directory(_, children) | VaultData::Header(_, _, children) => {
+            children.iter().for_each(|c| collect_tasks(c, res));
+        }
+        VaultData::Task(task) => add_task(task, res),
+    }
+}
+
+/// Counts tasks created and completed, according to their `created:` and `done:` tokens, during each of the last `weeks` weeks up to `today`.
+///
+/// Weeks start on Monday and are sorted chronologically.
+#[must_use]
+pub fn weekly_stats(vault_data: &VaultData, weeks: usize, today: NaiveDate) -> Vec<WeekStats> {
+    let current_week = week_start(today);
+    let mut res = (0..weeks)
+        .rev()
+        .map(|i| WeekStats {
+            start: current_week - Days::new(7 * i as u64),
+            created: 0,
+            completed: 0,
+        })
+        .collect::<Vec<WeekStats>>();
+
+    let mut tasks = vec![];
+    collect_tasks(vault_data, &mut tasks);
+    for task in tasks {
+        if let Some(week) = task
+            .created
+            .and_then(|d| res.iter_mut().find(|w| w.start == week_start(d)))
+        {
+            week.created += 1;
+        }
+        if let Some(week) = task
+            .completed
+            .and_then(|d| res.iter_mut().find(|w| w.start == week_start(d)))
+        {
+            week.completed += 1;
+        }
+    }
+    res
+}
+
+/// Writes weekly stats as CSV, with a header.
+#[must_use]
+pub fn to_csv(stats: &[WeekStats]) -> String {
+    let mut res = String::from("week,created,completed\n");
+    for week in stats {
+        res.push_str(&format!(
+            "{},{},{}\n",
+            week.start.format("%Y-%m-%d"),
+            week.created,
+            week.completed
+        ));
+    }
+    res
+}
+
+/// Number of tasks in each state.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StateCounts {
+    pub todo: usize,
+    pub incomplete: usize,
+    pub done: usize,
+    pub canceled: usize,
+}
+
+impl StateCounts {
+    fn add(&mut self, state: &State) {
+        match state {
+            State::ToDo => self.todo += 1,
+            State::Incomplete => self.incomplete += 1,
+            State::Done => self.done += 1,
+            State::Canceled => self.canceled += 1,
+            State::Custom(custom) if custom.finished => self.done += 1,
+            State::Custom(_) => self.todo += 1,
+        }
+    }
+    fn merge(&mut self, other: &Self) {
+        self.todo += other.todo;
+        self.incomplete += other.incomplete;
+        self.done += other.done;
+        self.canceled += other.canceled;
+    }
+    /// Number of unfinished tasks
+    #[must_use]
+    pub const fn open(&self) -> usize {
+        self.todo + self.incomplete
+    }
+    #[must_use]
+    pub const fn total(&self) -> usize {
+        self.todo + self.incomplete + self.done + self.canceled
+    }
+    /// Percentage of done tasks, canceled tasks excluded. `None` if there is no such task.
+    #[must_use]
+    pub fn completion(&self) -> Option<usize> {
+        let total = self.total() - self.canceled;
+        (total > 0).then(|| self.done * 100 / total)
+    }
+    fn to_json(self) -> Value {
+        json!({
+            "todo": self.todo,
+            "incomplete": self.incomplete,
+            "done": self.done,
+            "canceled": self.canceled,
+            "total": self.total(),
+            "completion": self.completion(),
+        })
+    }
+}
+
+/// Counts the tasks using each tag, subtasks included.
+#[must_use]
+pub fn tag_counts(vault_data: &VaultData) -> BTreeMap<String, StateCounts> {
+    let mut tasks = vec![];
+    collect_tasks(vault_data, &mut tasks);
+    let mut res = BTreeMap::<String, StateCounts>::new();
+    for task in tasks {
+        for tag in task.tags.iter().flatten() {
+            res.entry(tag.clone()).or_default().add(&task.state);
+        }
+    }
+    res
+}
+
+/// Writes the number of open and done tasks of each tag as a table.
+#[must_use]
+pub fn tags_to_table(tags: &BTreeMap<String, StateCounts>) -> String {
+    let width = tags
+        .keys()
+        .map(|tag| tag.chars().count() + 1)
+        .chain(["Tag".len()])
+        .max()
+        .unwrap_or_default();
+    let mut res = format!("{:<width$}  Open  Done\n", "Tag");
+    for (tag, counts) in tags {
+        res.push_str(&format!(
+            "{:<width$}  {:>4}  {:>4}\n",
+            format!("#{tag}"),
+            counts.open(),
+            counts.done
+        ));
+    }
+    res
+}
+
+/// Number of dated tasks a file or header needs before its due days are used for suggestions.
+const MIN_DUE_HISTORY: usize = 3;
+
+/// Suggests a due date keyword for a task added under `target`, the directories, file and headers leading to it, e.g. `saturday`.
+///
+/// The day of the week most of the tasks directly under it are due is suggested, if they have at least
+/// `MIN_DUE_HISTORY` due dates and half of them fall on that day.
+#[must_use]
+pub fn suggest_due_day(vault_data: &VaultData, target: &[String]) -> Option<String> {
+    // Tasks due each day of the week, from Monday
+    let mut counts = [0; 7];
+    for (path, task) in filter_to_vec_with_paths(vault_data, &Filter::default()) {
+        // The path starts with the vault root
+        if path.get(1..) != Some(target) {
+            continue;
+        }
+        let weekday = match task.due_date {
+            DueDate::Day(date) => date.weekday(),
+            DueDate::DayTime(date_time) => date_time.weekday(),
+            DueDate::NoDate => continue,
+        };
+        counts[weekday.num_days_from_monday() as usize] += 1;
+    }
+    let total = counts.iter().sum::<usize>();
+    let (day, count) = counts
+        .into_iter()
+        .enumerate()
+        .max_by_key(|(_, count)| *count)?;
+    if total < MIN_DUE_HISTORY || count * 2 < total {
+        return None;
+    }
+    let weekday = (0..day).fold(Weekday::Mon, |weekday, _| weekday.succ());
+    Some(Recurrence::Weekday(weekday).to_string())
+}
+
+/// Totals of the vault, subtasks included.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VaultStats {
+    pub states: StateCounts,
+    /// Unfinished tasks past their due date
+    pub overdue: usize,
+    pub tags: BTreeMap<String, StateCounts>,
+    /// Directories and files holding tasks, relative to the vault, parents first
+    pub paths: Vec<(String, StateCounts)>,
+}
+
+impl VaultStats {
+    #[must_use]
+    pub fn new(vault_data: &VaultData, now: NaiveDateTime) -> Self {
+        /// Returns the counts of `vault_data`, adding the directories and files to `res.paths`.
+        fn aux(
+            vault_data: &VaultData,
+            path: &mut Vec<String>,
+            now: NaiveDateTime,
+            res: &mut VaultStats,
+        ) -> StateCounts {
+            let mut counts = StateCounts::default();
+            match vault_data {
+                VaultData::Directory(name, children) | VaultData::Header(0, name, children) => {
+                    // The root is the vault itself
+                    let is_root = path.is_empty();
+                    path.push(name.clone());
+                    let index = res.paths.len();
+                    if !is_root {
+                        res.paths.push((path[1..].join("/"), counts));
+                    }
+                    for child in children {
+                        counts.merge(&aux(child, path, now, res));
+                    }
+                    path.pop();
+                    if !is_root {
+                        if counts.total() == 0 {
+                            res.paths.remove(index);
+                        } else {
+                            res.paths[index].1 = counts;
+                        }
+                    }
+                }
+                VaultData::Header(_, _, children) => {
+                    for child in children {
+                        counts.merge(&aux(child, path, now, res));
+                    }
+                }
+                VaultData::Task(_) => {
+                    let mut tasks = vec![];
+                    collect_tasks(vault_data, &mut tasks);
+                    for task in tasks {
+                        counts.add(&task.state);
+                        if is_overdue(task, now) {
+                            res.overdue += 1;
+                        }
+                    }
+                }
+            }
+            counts
+        }
+        let mut res = Self::default();
+        res.states = aux(vault_data, &mut vec![], now, &mut res);
+        res.tags = tag_counts(vault_data);
+        res
+    }
+
+    /// Writes the totals, then the tags and the paths as tables.
+    #[must_use]
+    pub fn to_table(&self) -> String {
+        fn row(name: &str, counts: &StateCounts, width: usize) -> String {
+            format!(
+                "{name:<width$}  {:>5}  {:>10}  {:>4}  {:>8}  {:>8}\n",
+                counts.todo,
+                counts.incomplete,
+                counts.done,
+                counts.canceled,
+                counts
+                    .completion()
+                    .map_or_else(|| "-".to_owned(), |c| format!("{c}%"))
+            )
+        }
+        fn table<'a>(
+            title: &str,
+            rows: impl Iterator<Item = (&'a String, &'a StateCounts)> + Clone,
+        ) -> String {
+            let width = rows
+                .clone()
+                .map(|(name, _)| name.chars().count())
+                .chain([title.len()])
+                .max()
+                .unwrap_or_default();
+            let mut res = format!("{title:<width$}  To do  Incomplete  Done  Canceled  Complete\n");
+            for (name, counts) in rows {
+                res.push_str(&row(name, counts, width));
+            }
+            res
+        }
+        let mut res = format!(
+            "Tasks: {} (to do {}, incomplete {}, done {}, canceled {}), {} complete\nOverdue: {}\n",
+            self.states.total(),
+            self.states.todo,
+            self.states.incomplete,
+            self.states.done,
+            self.states.canceled,
+            self.states
+                .completion()
+                .map_or_else(|| "-".to_owned(), |c| format!("{c}%")),
+            self.overdue
+        );
+        if !self.tags.is_empty() {
+            res.push('\n');
+            res.push_str(&table("Tag", self.tags.iter()));
+        }
+        if !self.paths.is_empty() {
+            res.push('\n');
+            res.push_str(&table("Path", self.paths.iter().map(|(p, c)| (p, c))));
+        }
+        res
+    }
+
+    #[must_use]
+    pub fn to_json(&self) -> Value {
+        let mut res = self.states.to_json();
+        res["overdue"] = json!(self.overdue);
+        res["tags"] = self
+            .tags
+            .iter()
+            .map(|(tag, counts)| (tag.clone(), counts.to_json()))
+            .collect::<serde_json::Map<String, Value>>()
+            .into();
+        res["paths"] = self
+            .paths
+            .iter()
+            .map(|(path, counts)| (path.clone(), counts.to_json()))
+            .collect::<serde_json::Map<String, Value>>()
+            .into();
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use pretty_assertions::assert_eq;
+
+    use super::{
+        suggest_due_day, tag_counts, tags_to_table, to_csv, weekly_stats, VaultStats, WeekStats,
+    };
+    use crate::{
+        task::{DueDate, State, Task},
+        vault_data::VaultData,
+    };
+
+    fn date(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 1, d).unwrap()
+    }
+
+    #[test]
+    fn test_weekly_stats() {
+        let vault_data = VaultData::Directory(
+            "vault".to_owned(),
+            vec![
+                VaultData::Task(Task {
+                    created: Some(date(6)),
+                    completed: Some(date(14)),
+                    subtasks: vec![Task {
+                        created: Some(date(12)),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }),
+                VaultData::Header(
+                    1,
+                    "header".to_owned(),
+                    vec![VaultData::Task(Task {
+                        // Too old
+                        created: Some(date(1)),
+                        completed: Some(date(15)),
+                        ..Default::default()
+                    })],
+                ),
+                VaultData::Task(Task::default()),
+            ],
+        );
+        let stats = weekly_stats(&vault_data, 2, date(15));
+        assert_eq!(
+            stats,
+            vec![
+                WeekStats {
+                    start: date(6),
+                    created: 2,
+                    completed: 0
+                },
+                WeekStats {
+                    start: date(13),
+                    created: 0,
+                    completed: 2
+                }
+            ]
+        );
+        assert_eq!(
+            to_csv(&stats),
+            "week,created,completed\n2025-01-06,2,0\n2025-01-13,0,2\n"
+        );
+    }
+
+    #[test]
+    fn test_suggest_due_day() {
+        let task = |due: Option<u32>| {
+            VaultData::Task(Task {
+                due_date: due.map_or(DueDate::NoDate, |d| DueDate::Day(date(d))),
+                ..Default::default()
+            })
+        };
+        let chores = |tasks| {
+            VaultData::Directory(
+                "./vault".to_owned(),
+                vec![VaultData::Header(
+                    0,
+                    "home.md".to_owned(),
+                    vec![VaultData::Header(1, "Weekly chores".to_owned(), tasks)],
+                )],
+            )
+        };
+        let target = ["home.md".to_owned(), "Weekly chores".to_owned()];
+        // Three Saturdays, a Monday and a task without date
+        let vault_data = chores(vec![
+            task(Some(4)),
+            task(Some(11)),
+            task(Some(6)),
+            task(Some(18)),
+            task(None),
+        ]);
+        assert_eq!(
+            suggest_due_day(&vault_data, &target),
+            Some("saturday".to_owned())
+        );
+        assert_eq!(suggest_due_day(&vault_data, &target[..1]), None);
+        // Not enough history
+        let vault_data = chores(vec![task(Some(4)), task(Some(11))]);
+        assert_eq!(suggest_due_day(&vault_data, &target), None);
+        // No day stands out
+        let vault_data = chores(vec![task(Some(4)), task(Some(6)), task(Some(7))]);
+        assert_eq!(suggest_due_day(&vault_data, &target), None);
+    }
+
+    #[test]
+    fn test_vault_stats() {
+        let task = |state, tags: &[&str], due: Option<u32>| Task {
+            state,
+            tags: Some(tags.iter().map(|t| (*t).to_owned()).collect()),
+            due_date: due.map_or(DueDate::NoDate, |d| DueDate::Day(date(d))),
+            ..Default::default()
+        };
+        let vault_data = VaultData::Directory(
+            "./vault".to_owned(),
+            vec![
+                VaultData::Directory(
+                    "work".to_owned(),
+                    vec![
+                        VaultData::Header(
+                            0,
+                            "project.md".to_owned(),
+                            vec![VaultData::Header(
+                                1,
+                                "Tasks".to_owned(),
+                                vec![
+                                    VaultData::Task(Task {
+                                        subtasks: vec![task(State::Done, &["work"], None)],
+                                        ..task(State::ToDo, &["work"], Some(1))
+                                    }),
+                                    VaultData::Task(task(State::Canceled, &[], None)),
+                                ],
+                            )],
+                        ),
+                        VaultData::Header(0, "empty.md".to_owned(), vec![]),
+                    ],
+                ),
+                VaultData::Header(
+                    0,
+                    "home.md".to_owned(),
+                    vec![VaultData::Task(task(
+                        State::Incomplete,
+                        &["home"],
+                        Some(20),
+                    ))],
+                ),
+            ],
+        );
+        let stats = VaultStats::new(&vault_data, date(15).and_hms_opt(0, 0, 0).unwrap());
+        assert_eq!(stats.overdue, 1);
+        assert_eq!(
+            stats.to_table(),
+            "Tasks: 4 (to do 1, incomplete 1, done 1, canceled 1), 33% complete
+Overdue: 1
+
+Tag   To do  Incomplete  Done  Canceled  Complete
+home      0           1     0         0        0%
+work      1           0     1         0       50%
+
+Path             To do  Incomplete  Done  Canceled  Complete
+work                 1           0     1         1       50%
+work/project.md      1           0     1         1       50%
+home.md              0           1     0         0        0%
+"
+        );
+        let json = stats.to_json();
+        assert_eq!(json["total"], 4);
+        assert_eq!(json["tags"]["work"]["completion"], 50);
+        assert_eq!(json["paths"]["work/project.md"]["canceled"], 1);
+
+        assert_eq!(tag_counts(&vault_data), stats.tags);
+        assert_eq!(
+            tags_to_table(&stats.tags),
+            "Tag    Open  Done\n#home     1     0\n#work     1     1\n"
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::NaiveDateTime;
+use color_eyre::{eyre::eyre, Result};
+use serde::{Deserialize, Serialize};
+
+use super::date_time;
+
+/// Mappings of the sync backends, in the data directory
+pub const SYNC_MAP_FILE_NAME: &str = "sync_map.json";
+
+/// The remote item a task is synced with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncEntry {
+    /// Identifier of the item for the backend, e.g. a CalDAV URL or a Todoist ID
+    pub remote_id: String,
+    /// Version of the item when it was last synced, if the backend has one
+    pub etag: Option<String>,
+    #[serde(with = "date_time")]
+    pub last_sync: NaiveDateTime,
+}
+
+/// Bookkeeping shared by sync backends: the remote item each task is synced with, per backend.
+///
+/// Tasks are identified by their block identifier (`^id`), without the `^`, as it survives edits and moves.
+/// Nothing is written until [`SyncMap::save`] is called.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncMap {
+    path: PathBuf,
+    /// Backend name, then task identifier
+    backends: BTreeMap<String, BTreeMap<String, SyncEntry>>,
+}
+
+impl SyncMap {
+    /// Loads the mappings of the file at `path`, there are none if it doesn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the file can't be read or is invalid, rather than losing track of remote items.
+    pub fn load(path: &Path) -> Result<Self> {
+        let backends = if path.exists() {
+            serde_json::from_str(&fs::read_to_string(path)?)
+                .map_err(|e| eyre!("Invalid sync mappings {path:?}: {e}"))?
+        } else {
+            BTreeMap::new()
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            backends,
+        })
+    }
+
+    /// Writes the mappings to the file they were loaded from.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the file can't be written.
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Write then rename, so a crash never leaves partial mappings
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, serde_json::to_string_pretty(&self.backends)?)?;
+        fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn get(&self, backend: &str, task_id: &str) -> Option<&SyncEntry> {
+        self.backends.get(backend)?.get(task_id)
+    }
+
+    /// Returns the task synced with the remote item `remote_id` of `backend`.
+    #[must_use]
+    pub fn task_id(&self, backend: &str, remote_id: &str) -> Option<&str> {
+        self.backends
+            .get(backend)?
+            .iter()
+            .find(|(_, entry)| entry.remote_id == remote_id)
+            .map(|(task_id, _)| task_id.as_str())
+    }
+
+    /// Maps a task to a remote item of `backend`, returning its previous mapping.
+    pub fn set(&mut self, backend: &str, task_id: &str, entry: SyncEntry) -> Option<SyncEntry> {
+        self.backends
+            .entry(backend.to_owned())
+            .or_default()
+            .insert(task_id.to_owned(), entry)
+    }
+
+    /// Forgets the mapping of a task for `backend`, returning it.
+    pub fn remove(&mut self, backend: &str, task_id: &str) -> Option<SyncEntry> {
+        let entries = self.backends.get_mut(backend)?;
+        let res = entries.remove(task_id);
+        if entries.is_empty() {
+            self.backends.remove(backend);
+        }
+        res
+    }
+
+    /// Returns the backend, task identifier and mapping of every task, sorted by backend and task.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &str, &SyncEntry)> {
+        self.backends.iter().flat_map(|(backend, entries)| {
+            entries
+                .iter()
+                .map(move |(task_id, entry)| (backend.as_str(), task_id.as_str(), entry))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use chrono::NaiveDate;
+    use pretty_assertions::assert_eq;
+
+    use super::{SyncEntry, SyncMap};
+
+    #[test]
+    fn test_sync_map() {
+        let dir = std::env::temp_dir().join("vault-tasks-sync-map");
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("sync_map.json");
+        let entry = |remote_id: &str| SyncEntry {
+            remote_id: remote_id.to_owned(),
+            etag: Some("\"1\"".to_owned()),
+            last_sync: NaiveDate::from_ymd_opt(2025, 3, 1)
+                .unwrap()
+                .and_hms_opt(9, 30, 0)
+                .unwrap(),
+        };
+
+        let mut map = SyncMap::load(&path).unwrap();
+        assert_eq!(map.entries().count(), 0);
+        assert_eq!(map.set("caldav", "standup", entry("a.ics")), None);
+        assert_eq!(map.set("todoist", "standup", entry("42")), None);
+        assert_eq!(
+            map.set("caldav", "standup", entry("b.ics")),
+            Some(entry("a.ics"))
+        );
+        assert_eq!(map.task_id("caldav", "b.ics"), Some("standup"));
+        assert_eq!(map.task_id("caldav", "42"), None);
+        map.save().unwrap();
+
+        let mut map = SyncMap::load(&path).unwrap();
+        assert_eq!(map.get("caldav", "standup"), Some(&entry("b.ics")));
+        assert_eq!(
+            map.entries()
+                .map(|(backend, task_id, entry)| (backend, task_id, entry.remote_id.as_str()))
+                .collect::<Vec<_>>(),
+            vec![("caldav", "standup", "b.ics"), ("todoist", "standup", "42")]
+        );
+        assert_eq!(map.remove("todoist", "standup"), Some(entry("42")));
+        assert_eq!(map.remove("todoist", "standup"), None);
+        assert_eq!(map.entries().count(), 1);
+
+        fs::write(&path, "not json").unwrap();
+        assert!(SyncMap::load(&path).is_err());
+    }
+}
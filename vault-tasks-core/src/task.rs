@@ -0,0 +1,1075 @@
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Weekday};
+use color_eyre::{eyre::bail, Result};
+use core::fmt;
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+    fs::{read_to_string, File},
+    io::Write,
+    path::PathBuf,
+};
+use tracing::{debug, info};
+
+use crate::{
+    is_canvas_file,
+    parser::task::{
+        extract_hidden_meta, CREATED_SIGNIFIER, DONE_SIGNIFIER, DUE_SIGNIFIER, HIDDEN_META_PREFIX,
+        PRIORITY_SIGNIFIERS, RECURRENCE_SIGNIFIER, RELATIVE_META, RESOLVED_META,
+        SCHEDULED_SIGNIFIER, START_SIGNIFIER,
+    },
+    recurrence::{next_occurrence, Recurrence},
+    CompletionDate, CustomState, ParentCompletion, PrettySymbolsConfig, SubtaskProgress,
+    TaskMarkerConfig, TaskSyntax, TasksConfig,
+};
+
+/// Share of `done` among `total`, 100 if `total` is 0.
+fn percentage(done: usize, total: usize) -> u8 {
+    if total == 0 {
+        return 100;
+    }
+    u8::try_from(done * 100 / total).unwrap_or(100)
+}
+
+/// A task's state
+/// Ordering is `Incomplete < ToDo < Custom < Canceled < Done`, custom states are ordered by marker
+#[derive(Debug, Hash, Eq, PartialEq, Clone)]
+pub enum State {
+    ToDo,
+    Done,
+    Incomplete,
+    Canceled,
+    /// A state declared in `task_state_markers.custom`
+    Custom(CustomState),
+}
+
+impl State {
+    const fn rank(&self) -> u8 {
+        match self {
+            Self::Incomplete => 0,
+            Self::ToDo => 1,
+            Self::Custom(_) => 2,
+            Self::Canceled => 3,
+            Self::Done => 4,
+        }
+    }
+}
+
+impl Ord for State {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Custom(a), Self::Custom(b)) => a.marker.cmp(&b.marker),
+            _ => self.rank().cmp(&other.rank()),
+        }
+    }
+}
+
+impl PartialOrd for State {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl State {
+    pub fn display(&self, state_symbols: PrettySymbolsConfig) -> String {
+        match self {
+            Self::Done => state_symbols.task_done,
+            Self::ToDo => state_symbols.task_todo,
+            Self::Incomplete => state_symbols.task_incomplete,
+            Self::Canceled => state_symbols.task_canceled,
+            Self::Custom(custom) if custom.symbol.is_empty() => format!("[{}]", custom.marker),
+            Self::Custom(custom) => custom.symbol.clone(),
+        }
+    }
+    /// Whether the task is over: Done, Canceled or a custom state declared `finished`.
+    pub const fn is_finished(&self) -> bool {
+        match self {
+            Self::Done | Self::Canceled => true,
+            Self::ToDo | Self::Incomplete => false,
+            Self::Custom(custom) => custom.finished,
+        }
+    }
+    /// Returns the state following this one when cycling through the states of `markers.cycle`, or through To-Do,
+    /// Incomplete, Done, Canceled and then the custom states if it is empty. States out of the cycle go to its first state.
+    pub fn next(&self, markers: &TaskMarkerConfig) -> Self {
+        let states = if markers.cycle.is_empty() {
+            [Self::ToDo, Self::Incomplete, Self::Done, Self::Canceled]
+                .into_iter()
+                .chain(markers.custom.iter().cloned().map(Self::Custom))
+                .collect::<Vec<Self>>()
+        } else {
+            markers
+                .cycle
+                .iter()
+                .filter_map(|marker| markers.state(*marker))
+                .collect()
+        };
+        let Some(first) = states.first() else {
+            return Self::ToDo;
+        };
+        states
+            .iter()
+            .position(|state| state == self)
+            .map_or(first, |i| &states[(i + 1) % states.len()])
+            .clone()
+    }
+}
+impl Display for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let default_symbols = PrettySymbolsConfig::default();
+        write!(f, "{}", self.display(default_symbols))?;
+        Ok(())
+    }
+}
+/// `strftime` formats of the dates shown to the user, see `TasksConfig::date_display_format`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DateDisplayFormat {
+    pub date: String,
+    pub date_time: String,
+}
+impl Default for DateDisplayFormat {
+    fn default() -> Self {
+        TasksConfig::default().date_display_format()
+    }
+}
+impl DateDisplayFormat {
+    #[must_use]
+    pub fn format_date(&self, date: NaiveDate) -> String {
+        date.format(&self.date).to_string()
+    }
+}
+
+#[derive(Debug, Hash, Eq, PartialEq, Clone)]
+/// This type accounts for the case where the task has a due date but no exact due time
+pub enum DueDate {
+    NoDate,
+    Day(NaiveDate),
+    DayTime(NaiveDateTime),
+}
+impl Display for DueDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Day(date) => write!(f, "{date}"),
+            Self::DayTime(date) => write!(f, "{date}"),
+            Self::NoDate => Ok(()),
+        }
+    }
+}
+
+impl DueDate {
+    /// Returns the date and time of this `DueDate`, dates without a time are set at midnight.
+    #[must_use]
+    pub fn to_naive_date_time(&self) -> Option<NaiveDateTime> {
+        match self {
+            Self::NoDate => None,
+            Self::Day(date) => Some(date.and_time(NaiveTime::default())),
+            Self::DayTime(date_time) => Some(*date_time),
+        }
+    }
+    /// Same as `to_naive_date_time`, dates without a time are set at `default_time`.
+    #[must_use]
+    pub fn to_naive_date_time_at(&self, default_time: NaiveTime) -> Option<NaiveDateTime> {
+        match self {
+            Self::Day(date) => Some(date.and_time(default_time)),
+            _ => self.to_naive_date_time(),
+        }
+    }
+    #[must_use]
+    pub fn to_display_format(&self, due_date_symbol: &str, format: &DateDisplayFormat) -> String {
+        if matches!(self, Self::NoDate) {
+            String::new()
+        } else {
+            format!("{due_date_symbol} {}", self.to_display_string(format))
+        }
+    }
+    /// Formats the date as shown to the user, unlike `to_string_format` which formats it as written in notes.
+    #[must_use]
+    pub fn to_display_string(&self, format: &DateDisplayFormat) -> String {
+        match self {
+            Self::Day(date) => format.format_date(*date),
+            Self::DayTime(date) => date.format(&format.date_time).to_string(),
+            Self::NoDate => String::new(),
+        }
+    }
+    #[must_use]
+    pub fn to_string_format(&self, not_american_format: bool) -> String {
+        let format_date = if not_american_format {
+            "%d/%m/%Y"
+        } else {
+            "%Y/%m/%d"
+        };
+        let format_datetime = if not_american_format {
+            "%d/%m/%Y %T"
+        } else {
+            "%Y/%m/%d %T"
+        };
+
+        match self {
+            Self::Day(date) => date.format(format_date).to_string(),
+            Self::DayTime(date) => date.format(format_datetime).to_string(),
+            Self::NoDate => String::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn get_relative_str(&self) -> Option<String> {
+        let now = chrono::Local::now();
+        let time_delta = match self {
+            Self::NoDate => return None,
+            Self::Day(date) => now.date_naive().signed_duration_since(*date),
+            Self::DayTime(date_time) => {
+                now.date_naive().signed_duration_since(date_time.date())
+                    + now.time().signed_duration_since(date_time.time())
+            }
+        };
+
+        let (prefix, suffix) = match time_delta.num_seconds().cmp(&0) {
+            Ordering::Less => (String::from("in "), String::new()),
+            Ordering::Equal => (String::new(), String::new()),
+            Ordering::Greater => (String::new(), String::from(" ago")),
+        };
+
+        let time_delta_abs = time_delta.abs();
+
+        if time_delta_abs.is_zero() {
+            return Some(String::from("today"));
+        }
+        if time_delta.num_seconds() < 0 && time_delta_abs.num_days() == 1 {
+            return Some(String::from("tomorrow"));
+        }
+        if time_delta.num_seconds() > 0 && time_delta_abs.num_days() == 1 {
+            return Some(String::from("yesterday"));
+        }
+
+        let res = if 4 * 12 * 2 <= time_delta_abs.num_weeks() {
+            format!("{} years", time_delta_abs.num_weeks() / (12 * 4))
+        } else if 5 <= time_delta_abs.num_weeks() {
+            format!("{} months", time_delta_abs.num_weeks() / 4)
+        } else if 2 <= time_delta_abs.num_weeks() {
+            format!("{} weeks", time_delta_abs.num_weeks())
+        } else if 2 <= time_delta_abs.num_days() {
+            format!("{} days", time_delta_abs.num_days())
+        } else {
+            format!("{} hours", time_delta_abs.num_hours())
+        };
+        Some(format!("{prefix}{res}{suffix}"))
+    }
+    /// Moves the due date by `days`, a missing due date is moved from `today`.
+    #[must_use]
+    pub fn shifted(&self, days: i64, today: NaiveDate) -> Self {
+        let delta = TimeDelta::days(days);
+        match self {
+            Self::NoDate => Self::Day(today + delta),
+            Self::Day(date) => Self::Day(*date + delta),
+            Self::DayTime(date_time) => Self::DayTime(*date_time + delta),
+        }
+    }
+    /// Moves the due date to the first `weekday` after `today`, keeping its time.
+    #[must_use]
+    pub fn next_weekday(&self, weekday: Weekday, today: NaiveDate) -> Self {
+        let days =
+            match (7 + weekday.num_days_from_monday() - today.weekday().num_days_from_monday()) % 7
+            {
+                0 => 7,
+                d => d,
+            };
+        let date = today + TimeDelta::days(i64::from(days));
+        match self {
+            Self::NoDate | Self::Day(_) => Self::Day(date),
+            Self::DayTime(date_time) => Self::DayTime(date.and_time(date_time.time())),
+        }
+    }
+}
+
+/// Formats a duration in minutes the way estimates are written, e.g. `2h`, `45m` or `1h30`.
+#[must_use]
+pub fn format_estimate(minutes: u32) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{m}m"),
+        (h, 0) => format!("{h}h"),
+        (h, m) => format!("{h}h{m:02}"),
+    }
+}
+
+#[derive(Debug, Hash, Eq, PartialEq, Clone)]
+pub struct Task {
+    pub subtasks: Vec<Task>,
+    pub description: Option<String>,
+    pub due_date: DueDate,
+    pub filename: String,
+    pub line_number: usize,
+    pub name: String,
+    pub priority: usize,
+    pub state: State,
+    pub tags: Option<Vec<String>>,
+    pub is_today: bool,
+    pub reminder: DueDate,
+    /// Creation date, from the `created:` token
+    pub created: Option<NaiveDate>,
+    /// Completion date, from the `done:` token
+    pub completed: Option<NaiveDate>,
+    /// Scheduled date, from the `⏳` signifier of Obsidian Tasks
+    pub scheduled: Option<NaiveDate>,
+    /// Start date, from the `start:` token or the `🛫` signifier of Obsidian Tasks
+    pub start: Option<NaiveDate>,
+    /// Estimated duration in minutes, from the `est:` token
+    pub estimate: Option<u32>,
+    /// Completion percentage, from the `[50%]` token
+    pub completion: Option<u8>,
+    /// Recurrence rule, from the `every:` token
+    pub recurrence: Option<Recurrence>,
+    /// Obsidian block identifier (`^id`), without the `^`
+    pub block_id: Option<String>,
+    /// Key-value pairs of `<!-- vt: key=value -->` comments, for integrations, hidden from the UI
+    pub hidden_meta: Vec<(String, String)>,
+    /// Tags given by the `default_tags` frontmatter key of the file, they are not written back
+    pub inherited_tags: Vec<String>,
+    /// Whether the priority was given by the `default_priority` frontmatter key of the file
+    pub inherited_priority: bool,
+    /// Whether this is a future occurrence of a recurring task, see `recurrence::projected_occurrences`
+    pub projected: bool,
+    /// Date tokens of the line depending on the day it is read (`monday`, `remind:tmr`), see `relative_dates`
+    pub relative_dates: Vec<String>,
+    /// Whether the completion was computed from the subtasks, see `subtask_progress`, it is not written back
+    pub computed_completion: bool,
+    /// Done subtasks and subtasks, canceled ones excluded, computed with `subtask_progress = "count"`
+    pub subtask_count: Option<(usize, usize)>,
+}
+
+impl Default for Task {
+    fn default() -> Self {
+        Self {
+            due_date: DueDate::NoDate,
+            name: String::new(),
+            priority: 0,
+            state: State::ToDo,
+            tags: None,
+            description: None,
+            line_number: 1,
+            subtasks: vec![],
+            filename: String::new(),
+            is_today: false,
+            reminder: DueDate::NoDate,
+            created: None,
+            completed: None,
+            scheduled: None,
+            start: None,
+            estimate: None,
+            completion: None,
+            recurrence: None,
+            block_id: None,
+            hidden_meta: vec![],
+            inherited_tags: vec![],
+            inherited_priority: false,
+            projected: false,
+            relative_dates: vec![],
+            computed_completion: false,
+            subtask_count: None,
+        }
+    }
+}
+
+impl fmt::Display for Task {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write_with_symbols(f, &PrettySymbolsConfig::default())
+    }
+}
+impl Task {
+    /// Writes the task like `Display` does, using the given symbols.
+    pub fn write_with_symbols(
+        &self,
+        f: &mut impl fmt::Write,
+        symbols: &PrettySymbolsConfig,
+    ) -> fmt::Result {
+        let state = self.state.display(symbols.clone());
+        let title = format!("{state} {}", self.name);
+        writeln!(f, "{title}")?;
+
+        let mut data_line = String::new();
+        let is_today = if self.is_today {
+            format!("{} ", symbols.today_tag)
+        } else {
+            String::new()
+        };
+        data_line.push_str(&is_today);
+        let due_date_str = self.due_date.to_string();
+
+        if !due_date_str.is_empty() {
+            data_line.push_str(&format!(
+                "{} {due_date_str} ({})",
+                symbols.due_date,
+                self.due_date.get_relative_str().unwrap_or_default()
+            ));
+        }
+        if self.priority > 0 {
+            data_line.push_str(&format!("{}{} ", symbols.priority, self.priority));
+        }
+        if !matches!(self.reminder, DueDate::NoDate) {
+            data_line.push_str(&format!("{} {} ", symbols.reminder, self.reminder));
+        }
+        if !data_line.is_empty() {
+            writeln!(f, "{data_line}")?;
+        }
+        let mut tag_line = String::new();
+        if self.tags.is_some() {
+            tag_line.push_str(
+                &self
+                    .tags
+                    .clone()
+                    .unwrap()
+                    .iter()
+                    .map(|t| format!("#{t}"))
+                    .collect::<Vec<String>>()
+                    .join(" "),
+            );
+        }
+        if !tag_line.is_empty() {
+            writeln!(f, "{tag_line}")?;
+        }
+        if let Some(description) = self.description.clone() {
+            for l in description.lines() {
+                writeln!(f, "{l}")?;
+            }
+        }
+        Ok(())
+    }
+}
+impl Task {
+    /// Sets the state of the task, with `completion_date` its completion date becomes `today` when it is marked
+    /// done and is removed when it is given an unfinished state again.
+    pub fn set_state(&mut self, state: State, config: &TasksConfig, today: NaiveDate) {
+        if config.completion_date != CompletionDate::None {
+            match &state {
+                State::Done if self.state != State::Done => {
+                    self.completed = self.completed.or(Some(today));
+                }
+                unfinished if !unfinished.is_finished() => self.completed = None,
+                _ => (),
+            }
+        }
+        self.state = state;
+    }
+
+    /// Adds `delta` to the priority of the task, a priority of 0 means no priority.
+    pub fn shift_priority(&mut self, delta: isize) {
+        self.priority = self.priority.saturating_add_signed(delta);
+        self.inherited_priority = false;
+    }
+
+    /// Returns the number of done subtasks and of subtasks, canceled ones excluded.
+    fn done_subtasks(&self) -> (usize, usize) {
+        let counted = self
+            .subtasks
+            .iter()
+            .filter(|t| t.state != State::Canceled)
+            .collect::<Vec<&Self>>();
+        let done = counted.iter().filter(|t| t.state == State::Done).count();
+        (done, counted.len())
+    }
+
+    /// Shows the progress of the task from its subtasks, deepest subtasks first, following `subtask_progress`.
+    ///
+    /// It is not written back, and a completion written in the note is kept.
+    pub fn compute_subtask_progress(&mut self, progress: SubtaskProgress) {
+        for subtask in &mut self.subtasks {
+            subtask.compute_subtask_progress(progress);
+        }
+        if self.subtasks.is_empty() || self.completion.is_some() {
+            return;
+        }
+        let (done, total) = self.done_subtasks();
+        match progress {
+            SubtaskProgress::Off => (),
+            SubtaskProgress::Count => self.subtask_count = Some((done, total)),
+            SubtaskProgress::Percentage => {
+                self.completion = Some(percentage(done, total));
+                self.computed_completion = true;
+            }
+        }
+    }
+
+    /// Updates the state or completion of the task from its subtasks, deepest subtasks first, following
+    /// `complete_parents` and `reopen_parents`.
+    pub fn update_from_subtasks(&mut self, config: &TasksConfig, today: NaiveDate) {
+        for subtask in &mut self.subtasks {
+            subtask.update_from_subtasks(config, today);
+        }
+        if self.subtasks.is_empty() {
+            return;
+        }
+        let finished = |task: &Self| task.state.is_finished();
+        match config.complete_parents {
+            ParentCompletion::Done if !finished(self) && self.subtasks.iter().all(finished) => {
+                self.set_state(State::Done, config, today);
+            }
+            ParentCompletion::Percentage => {
+                let (done, total) = self.done_subtasks();
+                self.completion = Some(percentage(done, total));
+            }
+            ParentCompletion::Done | ParentCompletion::Off => (),
+        }
+        if config.reopen_parents && self.state == State::Done && !self.subtasks.iter().all(finished)
+        {
+            self.set_state(State::ToDo, config, today);
+        }
+    }
+
+    pub fn get_fixed_attributes(&self, config: &TasksConfig, indent_length: usize) -> String {
+        let indent = " ".repeat(indent_length);
+
+        let state_str = config.task_state_markers.marker(&self.state);
+
+        let obsidian_tasks = config.syntax == TaskSyntax::ObsidianTasks;
+        let signifier_date = |signifier: &str, date: Option<NaiveDate>| {
+            date.map_or_else(String::new, |date| {
+                format!("{signifier} {} ", date.format("%Y-%m-%d"))
+            })
+        };
+        // Obsidian Tasks reads its fields from the end of the line
+        let mut signifiers = String::new();
+
+        let priority = if self.priority == 0 || self.inherited_priority {
+            String::new()
+        } else if obsidian_tasks {
+            let signifier = PRIORITY_SIGNIFIERS[self.priority.min(PRIORITY_SIGNIFIERS.len()) - 1];
+            signifiers.push_str(&format!("{signifier} "));
+            String::new()
+        } else {
+            format!("p{} ", self.priority)
+        };
+
+        let recurrence = match self.recurrence {
+            None => String::new(),
+            Some(r) if obsidian_tasks => {
+                signifiers.push_str(&format!("{RECURRENCE_SIGNIFIER} every {r} "));
+                String::new()
+            }
+            Some(r) => format!("every {r} "),
+        };
+
+        let mut due_date = match (&self.due_date, obsidian_tasks) {
+            // Obsidian Tasks has no due times, the time is read with the date nonetheless
+            (DueDate::DayTime(date_time), true) => date_time.format("%T").to_string(),
+            (_, true) => String::new(),
+            (_, false) => self.due_date.to_string_format(!config.use_american_format),
+        };
+        if !due_date.is_empty() {
+            due_date.push(' ');
+        }
+
+        let reminder = if matches!(self.reminder, DueDate::NoDate) {
+            String::new()
+        } else {
+            format!(
+                "remind:{} ",
+                self.reminder.to_string_format(!config.use_american_format)
+            )
+        };
+
+        let mut history = String::new();
+        if obsidian_tasks {
+            let due = self.due_date.to_naive_date_time().map(|d| d.date());
+            for (signifier, date) in [
+                (CREATED_SIGNIFIER, self.created),
+                (START_SIGNIFIER, self.start),
+                (SCHEDULED_SIGNIFIER, self.scheduled),
+                (DUE_SIGNIFIER, due),
+                (DONE_SIGNIFIER, self.completed),
+            ] {
+                signifiers.push_str(&signifier_date(signifier, date));
+            }
+        } else {
+            let emoji_completion = config.completion_date == CompletionDate::Emoji;
+            let completed = self.completed.filter(|_| !emoji_completion);
+            for (prefix, date) in [
+                ("created", self.created),
+                ("start", self.start),
+                ("done", completed),
+            ] {
+                if let Some(date) = date {
+                    history.push_str(&format!(
+                        "{prefix}:{} ",
+                        DueDate::Day(date).to_string_format(!config.use_american_format)
+                    ));
+                }
+            }
+            // The syntax of vault-tasks has no scheduled dates
+            signifiers.push_str(&signifier_date(SCHEDULED_SIGNIFIER, self.scheduled));
+            if emoji_completion {
+                signifiers.push_str(&signifier_date(DONE_SIGNIFIER, self.completed));
+            }
+        }
+
+        let estimate = self
+            .estimate
+            .map_or_else(String::new, |e| format!("est:{} ", format_estimate(e)));
+        let completion = self
+            .completion
+            .filter(|_| !self.computed_completion)
+            .map_or_else(String::new, |c| format!("[{c}%] "));
+
+        let tags_str = self.tags.as_ref().map_or_else(String::new, |tags| {
+            tags.iter()
+                .filter(|t| !self.inherited_tags.contains(t))
+                .map(|t| format!("#{t}"))
+                .collect::<Vec<String>>()
+                .join(" ")
+        });
+
+        let today_tag = if self.is_today {
+            String::from(" @today")
+        } else {
+            String::new()
+        };
+
+        let res = format!(
+            "{}- [{}] {} {}{}{}{}{}{}{}{}{}",
+            indent,
+            state_str,
+            self.name,
+            due_date,
+            reminder,
+            history,
+            priority,
+            estimate,
+            completion,
+            recurrence,
+            tags_str,
+            today_tag
+        );
+        let mut res = res.trim_end().to_string();
+        if !signifiers.is_empty() {
+            res.push(' ');
+            res.push_str(signifiers.trim_end());
+        }
+        // Dates are written literally, so the day relative dates were first read on is dropped
+        res.push_str(&format_hidden_meta(
+            self.hidden_meta
+                .iter()
+                .filter(|(key, _)| key != RELATIVE_META && key != RESOLVED_META),
+        ));
+        // Obsidian expects block identifiers at the end of the line
+        if let Some(id) = &self.block_id {
+            res.push_str(&format!(" ^{id}"));
+        }
+        res
+    }
+
+    pub fn fix_task_attributes(&self, config: &TasksConfig, path: &PathBuf) -> Result<()> {
+        if is_canvas_file(path) {
+            bail!("Canvas files are read-only: {path:?}");
+        }
+        if self.projected {
+            bail!("Projected occurrences of recurring tasks can't be edited");
+        }
+        config.check_writable()?;
+        let content = read_to_string(path.clone())?;
+        let Some(new_content) = self.fix_attributes_in(config, &content) else {
+            bail!(
+                "Task's line number {} was greater than length of file {:?}",
+                self.line_number,
+                path
+            );
+        };
+        if new_content != content {
+            let mut file = File::create(path)?;
+            file.write_all(new_content.as_bytes())?;
+
+            info!("Wrote to {path:?} at line {}", self.line_number);
+        }
+        Ok(())
+    }
+
+    /// Returns the day the relative dates of the task were first read on, recorded by `record_relative_dates`.
+    ///
+    /// Returns `None` if nothing was recorded or the relative dates were edited since.
+    #[must_use]
+    pub fn relative_dates_resolved_on(&self) -> Option<NaiveDate> {
+        let meta = |key: &str| {
+            self.hidden_meta
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, value)| value)
+        };
+        if self.relative_dates.is_empty()
+            || meta(RELATIVE_META) != Some(&self.relative_dates.join(" "))
+        {
+            return None;
+        }
+        NaiveDate::parse_from_str(meta(RESOLVED_META)?, "%Y-%m-%d").ok()
+    }
+
+    /// Records the relative dates of the task and `today` in the hidden metadata of its line, which is otherwise left as written.
+    ///
+    /// They are then resolved against `today` on later loads, see `RelativeDates::Keep`.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the file can't be written or the line of the task is past its end.
+    pub fn record_relative_dates(
+        &self,
+        config: &TasksConfig,
+        path: &PathBuf,
+        today: NaiveDate,
+    ) -> Result<()> {
+        config.check_writable()?;
+        let content = read_to_string(path)?;
+        let mut lines = content.split('\n').collect::<Vec<&str>>();
+        let Some(line) = self.line_number.checked_sub(1).and_then(|i| lines.get(i)) else {
+            bail!(
+                "Task's line number {} was greater than length of file {:?}",
+                self.line_number,
+                path
+            );
+        };
+        let (rest, _) = extract_hidden_meta(line);
+        let mut rest = rest.trim_end();
+        let block_id = self.block_id.as_ref().map(|id| format!(" ^{id}"));
+        if let Some(stripped) = block_id
+            .as_ref()
+            .and_then(|id| rest.strip_suffix(id.as_str()))
+        {
+            rest = stripped.trim_end();
+        }
+        let meta = self
+            .hidden_meta
+            .iter()
+            .filter(|(key, _)| key != RELATIVE_META && key != RESOLVED_META)
+            .cloned()
+            .chain([
+                (RELATIVE_META.to_owned(), self.relative_dates.join(" ")),
+                (
+                    RESOLVED_META.to_owned(),
+                    today.format("%Y-%m-%d").to_string(),
+                ),
+            ])
+            .collect::<Vec<(String, String)>>();
+        let new_line = format!(
+            "{rest}{}{}",
+            format_hidden_meta(meta.iter()),
+            block_id.unwrap_or_default()
+        );
+        lines[self.line_number - 1] = &new_line;
+        let mut file = File::create(path)?;
+        file.write_all(lines.join("\n").as_bytes())?;
+        info!(
+            "Recorded relative dates in {path:?} at line {}",
+            self.line_number
+        );
+        Ok(())
+    }
+
+    /// Returns `content` with the line of the task replaced as `fix_task_attributes` would write it.
+    ///
+    /// Returns `None` if the line of the task is past the end of `content`.
+    #[must_use]
+    pub fn fix_attributes_in(&self, config: &TasksConfig, content: &str) -> Option<String> {
+        let mut lines = content.split('\n').collect::<Vec<&str>>();
+
+        if self.line_number == 0 || lines.len() < self.line_number {
+            return None;
+        }
+
+        let indent_length = lines[self.line_number - 1]
+            .chars()
+            .take_while(|c| c.is_whitespace())
+            .count();
+
+        // The next occurrence of a done recurring task is written above it, the done task stops recurring
+        let next_line =
+            next_occurrence(self).map(|t| t.get_fixed_attributes(config, indent_length));
+        let fixed_line = if next_line.is_some() {
+            Self {
+                recurrence: None,
+                ..self.clone()
+            }
+            .get_fixed_attributes(config, indent_length)
+        } else {
+            self.get_fixed_attributes(config, indent_length)
+        };
+
+        if lines[self.line_number - 1] != fixed_line {
+            debug!(
+                "\nReplacing\n{}\nWith\n{}\n",
+                lines[self.line_number - 1],
+                fixed_line
+            );
+            lines[self.line_number - 1] = &fixed_line;
+            if let Some(next_line) = &next_line {
+                lines.insert(self.line_number - 1, next_line);
+            }
+        }
+        Some(lines.join("\n"))
+    }
+}
+
+/// Formats key-value pairs as a `<!-- vt: key=value -->` comment preceded by a space, or nothing without pairs.
+fn format_hidden_meta<'a>(meta: impl Iterator<Item = &'a (String, String)>) -> String {
+    let pairs = meta
+        .map(|(key, value)| {
+            if value.is_empty() {
+                key.clone()
+            } else if value.contains(char::is_whitespace) {
+                format!("{key}=\"{value}\"")
+            } else {
+                format!("{key}={value}")
+            }
+        })
+        .collect::<Vec<String>>();
+    if pairs.is_empty() {
+        String::new()
+    } else {
+        format!(" <!-- {HIDDEN_META_PREFIX} {} -->", pairs.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests_tasks {
+    use chrono::NaiveDate;
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        parser::task::parse_task,
+        task::{DueDate, State, Task},
+        CompletionDate, CustomState, TasksConfig,
+    };
+
+    #[test]
+    fn test_fix_attributes() {
+        let config = TasksConfig {
+            use_american_format: true,
+            ..Default::default()
+        };
+        let task = Task {
+            due_date: DueDate::Day(NaiveDate::from_ymd_opt(2021, 12, 3).unwrap()),
+            name: String::from("Test Task"),
+            priority: 1,
+            state: State::ToDo,
+            tags: Some(vec![String::from("tag1"), String::from("tag2")]),
+            description: Some(String::from("This is a test task.")),
+            line_number: 2,
+            ..Default::default()
+        };
+        let res = task.get_fixed_attributes(&config, 0);
+        assert_eq!(res, "- [ ] Test Task 2021/12/03 p1 #tag1 #tag2");
+    }
+
+    #[test]
+    fn test_fix_attributes_with_no_date() {
+        let config = TasksConfig {
+            ..Default::default()
+        };
+        let task = Task {
+            due_date: DueDate::NoDate,
+            name: String::from("Test Task with No Date"),
+            priority: 2,
+            state: State::Done,
+            tags: Some(vec![String::from("tag3")]),
+            description: None,
+            line_number: 3,
+            ..Default::default()
+        };
+
+        let res = task.get_fixed_attributes(&config, 0);
+        assert_eq!(res, "- [x] Test Task with No Date p2 #tag3");
+    }
+    #[test]
+    fn test_fix_attributes_with_today_tag() {
+        let config = TasksConfig {
+            ..Default::default()
+        };
+        let task = Task {
+            due_date: DueDate::NoDate,
+            name: String::from("Test Task with Today tag"),
+            priority: 2,
+            state: State::Done,
+            tags: Some(vec![String::from("tag3")]),
+            description: None,
+            line_number: 3,
+            is_today: true,
+            ..Default::default()
+        };
+
+        let res = task.get_fixed_attributes(&config, 0);
+        assert_eq!(res, "- [x] Test Task with Today tag p2 #tag3 @today");
+    }
+    #[test]
+    fn test_fix_attributes_with_reminder() {
+        let config = TasksConfig {
+            use_american_format: true,
+            ..Default::default()
+        };
+        let task = Task {
+            due_date: DueDate::Day(NaiveDate::from_ymd_opt(2025, 3, 1).unwrap()),
+            name: String::from("Test Task with reminder"),
+            reminder: DueDate::DayTime(
+                NaiveDate::from_ymd_opt(2025, 3, 2)
+                    .unwrap()
+                    .and_hms_opt(9, 0, 0)
+                    .unwrap(),
+            ),
+            ..Default::default()
+        };
+
+        let res = task.get_fixed_attributes(&config, 0);
+        assert_eq!(
+            res,
+            "- [ ] Test Task with reminder 2025/03/01 remind:2025/03/02 09:00:00"
+        );
+    }
+
+    #[test]
+    fn test_set_state_completion_date() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 4).unwrap();
+        let mut config = TasksConfig::default();
+        let mut task = Task {
+            name: String::from("water plants"),
+            ..Default::default()
+        };
+        task.set_state(State::Done, &config, today);
+        assert_eq!(task.completed, None);
+
+        config.completion_date = CompletionDate::Token;
+        task.set_state(State::ToDo, &config, today);
+        task.set_state(State::Done, &config, today);
+        assert_eq!(task.completed, Some(today));
+        assert_eq!(
+            task.get_fixed_attributes(&config, 0),
+            "- [x] water plants done:04/03/2025"
+        );
+        // Marking it done again keeps the first completion date
+        task.set_state(State::Done, &config, today.succ_opt().unwrap());
+        assert_eq!(task.completed, Some(today));
+
+        config.completion_date = CompletionDate::Emoji;
+        let line = task.get_fixed_attributes(&config, 0);
+        assert_eq!(line, "- [x] water plants ✅ 2025-03-04");
+        let parsed = parse_task(&mut line.as_str(), String::new(), &config).unwrap();
+        assert_eq!(parsed.completed, Some(today));
+
+        task.set_state(State::ToDo, &config, today);
+        assert_eq!(task.completed, None);
+    }
+
+    #[test]
+    fn test_custom_states() {
+        let mut config = TasksConfig::default();
+        let forwarded = CustomState {
+            marker: '>',
+            name: "Forwarded".to_owned(),
+            symbol: "➡️".to_owned(),
+            finished: true,
+        };
+        config.task_state_markers.custom = vec![forwarded.clone()];
+        let mut line = "- [>] call the bank";
+        let mut task = parse_task(&mut line, String::new(), &config).unwrap();
+        assert_eq!(task.state, State::Custom(forwarded.clone()));
+        assert!(task.state.is_finished());
+        assert_eq!(task.get_fixed_attributes(&config, 0), "- [>] call the bank");
+        assert_eq!(
+            task.state.display(config.pretty_symbols.clone()),
+            "➡️".to_owned()
+        );
+
+        // Cycling goes through the custom states after the built-in ones and wraps around
+        let markers = &config.task_state_markers;
+        task.state = State::Canceled;
+        assert_eq!(task.state.next(markers), State::Custom(forwarded.clone()));
+        assert_eq!(State::Custom(forwarded.clone()).next(markers), State::ToDo);
+        assert_eq!(State::ToDo.next(markers), State::Incomplete);
+
+        config.task_state_markers.cycle = vec![' ', '>', 'x'];
+        let markers = &config.task_state_markers;
+        assert_eq!(State::ToDo.next(markers), State::Custom(forwarded.clone()));
+        assert_eq!(State::Custom(forwarded).next(markers), State::Done);
+        assert_eq!(State::Done.next(markers), State::ToDo);
+        // States out of the cycle start it again
+        assert_eq!(State::Canceled.next(markers), State::ToDo);
+    }
+}
+#[cfg(test)]
+mod tests_due_date {
+    use chrono::{NaiveDate, TimeDelta, Weekday};
+    use pretty_assertions::assert_eq;
+
+    use crate::{task::DueDate, TasksConfig};
+
+    #[test]
+    fn test_display_string() {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 7).unwrap();
+        let date_time = DueDate::DayTime(date.and_hms_opt(9, 30, 0).unwrap());
+        let mut config = TasksConfig::default();
+        let format = config.date_display_format();
+        assert_eq!(DueDate::Day(date).to_display_string(&format), "07/03/2025");
+        assert_eq!(date_time.to_display_string(&format), "07/03/2025 09:30:00");
+
+        config.date_display_format = "%a %d %b".to_owned();
+        config.time_display_format = "%H:%M".to_owned();
+        let format = config.date_display_format();
+        assert_eq!(DueDate::Day(date).to_display_string(&format), "Fri 07 Mar");
+        assert_eq!(date_time.to_display_string(&format), "Fri 07 Mar 09:30");
+        assert_eq!(DueDate::NoDate.to_display_string(&format), "");
+
+        // Invalid formats are replaced
+        config.date_display_format = "%Q".to_owned();
+        config.use_american_format = true;
+        let format = config.date_display_format();
+        assert_eq!(DueDate::Day(date).to_display_string(&format), "2025/03/07");
+    }
+
+    #[test]
+    fn test_relative_date() {
+        let now = chrono::Local::now();
+
+        let tests = vec![
+            (-1, "yesterday"),
+            (0, "today"),
+            (1, "tomorrow"),
+            (7, "in 7 days"),
+            (17, "in 2 weeks"),
+            (65, "in 2 months"),
+            (800, "in 2 years"),
+        ];
+        for (days, res) in tests {
+            let due_date = DueDate::Day(
+                now.checked_add_signed(TimeDelta::days(days))
+                    .unwrap()
+                    .date_naive(),
+            );
+            assert_eq!(due_date.get_relative_str(), Some(String::from(res)));
+        }
+    }
+
+    #[test]
+    fn test_nudge_due_date() {
+        // A Wednesday
+        let today = NaiveDate::from_ymd_opt(2020, 1, 1).unwrap();
+        let due = NaiveDate::from_ymd_opt(2020, 1, 10).unwrap();
+        let at_nine = due.and_hms_opt(9, 0, 0).unwrap();
+
+        assert_eq!(
+            DueDate::NoDate.shifted(1, today),
+            DueDate::Day(NaiveDate::from_ymd_opt(2020, 1, 2).unwrap())
+        );
+        assert_eq!(
+            DueDate::Day(due).shifted(7, today),
+            DueDate::Day(NaiveDate::from_ymd_opt(2020, 1, 17).unwrap())
+        );
+        assert_eq!(
+            DueDate::DayTime(at_nine).shifted(1, today),
+            DueDate::DayTime(at_nine + TimeDelta::days(1))
+        );
+        assert_eq!(
+            DueDate::Day(due).next_weekday(Weekday::Mon, today),
+            DueDate::Day(NaiveDate::from_ymd_opt(2020, 1, 6).unwrap())
+        );
+        assert_eq!(
+            DueDate::DayTime(at_nine).next_weekday(Weekday::Wed, today),
+            DueDate::DayTime(
+                NaiveDate::from_ymd_opt(2020, 1, 8)
+                    .unwrap()
+                    .and_hms_opt(9, 0, 0)
+                    .unwrap()
+            )
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::NaiveDate;
+use color_eyre::{eyre::bail, Result};
+use tracing::{debug, info};
+
+use super::{task::Task, TasksConfig};
+
+/// Content of task notes when no `task_note_template` is set.
+const DEFAULT_TEMPLATE: &str = "# {{title}}\n\nCreated on {{date}} from [[{{source}}]].\n";
+
+/// Characters that can't be used in a note name.
+const FORBIDDEN_CHARS: [char; 13] = [
+    '\\', '/', ':', '*', '?', '"', '<', '>', '|', '#', '^', '[', ']',
+];
+
+/// Returns the name of the note of a task: its name without links nor characters forbidden in file names.
+#[must_use]
+pub fn task_note_name(task: &Task) -> String {
+    let mut name = String::new();
+    let mut rest = task.name.as_str();
+    while let Some(start) = rest.find("[[") {
+        name.push_str(&rest[..start]);
+        match rest[start..].find("]]") {
+            Some(end) => rest = &rest[start + end + 2..],
+            None => rest = "",
+        }
+    }
+    name.push_str(rest);
+    name.replace(FORBIDDEN_CHARS, "")
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+/// Returns the directory where the notes of the tasks of `source_path` are created.
+fn task_notes_dir(config: &TasksConfig, source_path: &Path) -> PathBuf {
+    let source_dir = source_path.parent().unwrap_or(Path::new(""));
+    if config.task_notes_folder.as_os_str().is_empty() {
+        source_dir.to_path_buf()
+    } else if config.vault_path.is_dir() {
+        config.vault_path.join(&config.task_notes_folder)
+    } else {
+        source_dir.join(&config.task_notes_folder)
+    }
+}
+
+/// Creates the note of a task if it does not exist yet and links it from the task.
+///
+/// The note is named after the task and filled from `task_note_template`, where `{{title}}`, `{{date}}` and `{{source}}` (the note containing the task) are replaced.
+/// Returns the path of the note.
+///
+/// # Errors
+///
+/// Will return an error if the task has no usable name, if the vault is read-only or if files can't be read or written.
+pub fn create_task_note(
+    config: &TasksConfig,
+    task: &Task,
+    source_path: &PathBuf,
+    today: NaiveDate,
+) -> Result<PathBuf> {
+    config.check_writable()?;
+    let name = task_note_name(task);
+    if name.is_empty() {
+        bail!("Can't name a note after task {:?}", task.name);
+    }
+    let note_path = task_notes_dir(config, source_path).join(format!("{name}.md"));
+
+    if note_path.exists() {
+        debug!("Task note {note_path:?} already exists");
+    } else {
+        let template = if config.task_note_template.as_os_str().is_empty() {
+            DEFAULT_TEMPLATE.to_owned()
+        } else {
+            fs::read_to_string(config.vault_path.join(&config.task_note_template))?
+        };
+        let source = source_path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let content = template
+            .replace("{{title}}", &name)
+            .replace("{{date}}", &today.to_string())
+            .replace("{{source}}", &source);
+        if let Some(parent) = note_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&note_path, content)?;
+        info!("Created task note {note_path:?}");
+    }
+
+    let link = format!("[[{name}]]");
+    if !task.name.contains(&link) {
+        let mut task = task.clone();
+        task.name = format!("{} {link}", task.name);
+        task.fix_task_attributes(config, source_path)?;
+    }
+    Ok(note_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use chrono::NaiveDate;
+    use pretty_assertions::assert_eq;
+
+    use super::{create_task_note, task_note_name};
+    use crate::{task::Task, TasksConfig};
+
+    #[test]
+    fn test_task_note_name() {
+        let task = Task {
+            name: "Plan: the Q3 offsite? [[Offsite]] #1".to_owned(),
+            ..Default::default()
+        };
+        assert_eq!(task_note_name(&task), "Plan the Q3 offsite 1");
+    }
+
+    #[test]
+    fn test_create_task_note() {
+        let vault_path = std::env::temp_dir().join("vault-tasks-create-task-note");
+        let _ = fs::remove_dir_all(&vault_path);
+        fs::create_dir_all(vault_path.join("Templates")).unwrap();
+        let source_path = vault_path.join("work.md");
+        fs::write(&source_path, "# Work\n- [ ] Write the report p1\n").unwrap();
+        fs::write(
+            vault_path.join("Templates/task.md"),
+            "# {{title}}\nfrom {{source}}\n",
+        )
+        .unwrap();
+        let config = TasksConfig {
+            vault_path: vault_path.clone(),
+            task_notes_folder: "Projects".into(),
+            task_note_template: "Templates/task.md".into(),
+            ..Default::default()
+        };
+        let task = Task {
+            name: "Write the report".to_owned(),
+            priority: 1,
+            line_number: 2,
+            ..Default::default()
+        };
+        let today = NaiveDate::from_ymd_opt(2025, 1, 3).unwrap();
+
+        let note_path = create_task_note(&config, &task, &source_path, today).unwrap();
+        assert_eq!(note_path, vault_path.join("Projects/Write the report.md"));
+        assert_eq!(
+            fs::read_to_string(&note_path).unwrap(),
+            "# Write the report\nfrom work\n"
+        );
+        assert_eq!(
+            fs::read_to_string(&source_path).unwrap(),
+            "# Work\n- [ ] Write the report [[Write the report]] p1\n"
+        );
+
+        // The note is kept and the link is not duplicated
+        fs::write(&note_path, "edited").unwrap();
+        let linked_task = Task {
+            name: "Write the report [[Write the report]]".to_owned(),
+            ..task
+        };
+        create_task_note(&config, &linked_task, &source_path, today).unwrap();
+        assert_eq!(fs::read_to_string(&note_path).unwrap(), "edited");
+        assert_eq!(
+            fs::read_to_string(&source_path).unwrap(),
+            "# Work\n- [ ] Write the report [[Write the report]] p1\n"
+        );
+    }
+}
@@ -0,0 +1,180 @@
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use super::{details::TaskDetails, task::Task};
+
+/// Weights of the terms of the urgency of a task, every term being between 0 and 1.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct UrgencyConfig {
+    /// From 0.2 two weeks ahead to 1 a week late
+    pub due: f64,
+    /// From 0.2 for `p5` to 1 for `p1`
+    pub priority: f64,
+    /// 0.8 for one tag, 0.9 for two and 1 for more
+    pub tags: f64,
+    /// Days since the `created:` date over a year
+    pub age: f64,
+}
+
+// Same weights as Taskwarrior
+impl Default for UrgencyConfig {
+    fn default() -> Self {
+        Self {
+            due: 12.0,
+            priority: 6.0,
+            tags: 1.0,
+            age: 2.0,
+        }
+    }
+}
+
+fn due_term(task: &Task, today: NaiveDate) -> f64 {
+    let Some(due) = task.due_date.to_naive_date_time() else {
+        return 0.0;
+    };
+    // Days late, negative when the task is due later
+    let late = (today - due.date()).num_days().clamp(-14, 7);
+    // Within i32 once clamped
+    #[allow(clippy::cast_possible_truncation)]
+    let late = f64::from(late as i32);
+    0.2 + (late + 14.0) * 0.8 / 21.0
+}
+
+fn priority_term(task: &Task) -> f64 {
+    match task.priority {
+        1..=5 => f64::from(6 - u8::try_from(task.priority).unwrap_or(5)) / 5.0,
+        _ => 0.0,
+    }
+}
+
+fn tags_term(task: &Task) -> f64 {
+    match task.tags.as_ref().map_or(0, Vec::len) {
+        0 => 0.0,
+        1 => 0.8,
+        2 => 0.9,
+        _ => 1.0,
+    }
+}
+
+fn age_term(task: &Task, today: NaiveDate) -> f64 {
+    task.created.map_or(0.0, |created| {
+        let days = (today - created).num_days().clamp(0, 365);
+        #[allow(clippy::cast_possible_truncation)]
+        let days = f64::from(days as i32);
+        days / 365.0
+    })
+}
+
+/// Taskwarrior-like urgency of a task: the sum of its weighted due date, priority, tags and age terms.
+///
+/// Finished tasks are not urgent.
+#[must_use]
+pub fn urgency(task: &Task, today: NaiveDate, config: &UrgencyConfig) -> f64 {
+    if task.state.is_finished() {
+        return 0.0;
+    }
+    config.due * due_term(task, today)
+        + config.priority * priority_term(task)
+        + config.tags * tags_term(task)
+        + config.age * age_term(task, today)
+}
+
+/// Returns the `count` most urgent unfinished tasks with their urgency, most urgent first.
+#[must_use]
+pub fn next_tasks(
+    tasks: Vec<TaskDetails>,
+    today: NaiveDate,
+    config: &UrgencyConfig,
+    count: usize,
+) -> Vec<(f64, TaskDetails)> {
+    let mut res = tasks
+        .into_iter()
+        .filter(|details| !details.task.state.is_finished())
+        .map(|details| (urgency(&details.task, today, config), details))
+        .collect::<Vec<(f64, TaskDetails)>>();
+    res.sort_by(|(u1, _), (u2, _)| u2.total_cmp(u1));
+    res.truncate(count);
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use pretty_assertions::assert_eq;
+
+    use super::{next_tasks, urgency, UrgencyConfig};
+    use crate::{
+        details::TaskDetails,
+        task::{DueDate, State, Task},
+    };
+
+    #[test]
+    fn test_urgency() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 10).unwrap();
+        let config = UrgencyConfig::default();
+        let due = |d| DueDate::Day(NaiveDate::from_ymd_opt(2025, 3, d).unwrap());
+        let score = |task: Task| (urgency(&task, today, &config) * 100.0).round() / 100.0;
+
+        assert_eq!(score(Task::default()), 0.0);
+        assert_eq!(
+            score(Task {
+                due_date: due(10),
+                ..Default::default()
+            }),
+            8.8
+        );
+        assert_eq!(
+            score(Task {
+                due_date: due(1),
+                priority: 1,
+                tags: Some(vec!["a".to_owned()]),
+                ..Default::default()
+            }),
+            12.0 + 6.0 + 0.8
+        );
+        assert_eq!(
+            score(Task {
+                priority: 5,
+                created: NaiveDate::from_ymd_opt(2024, 3, 10),
+                ..Default::default()
+            }),
+            1.2 + 2.0
+        );
+        assert_eq!(
+            score(Task {
+                due_date: due(1),
+                state: State::Done,
+                ..Default::default()
+            }),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_next_tasks() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 10).unwrap();
+        let details = |name: &str, priority, state| TaskDetails {
+            breadcrumb: vec![],
+            file: "note.md".into(),
+            task: Task {
+                name: name.to_owned(),
+                priority,
+                state,
+                ..Default::default()
+            },
+        };
+        let tasks = vec![
+            details("later", 0, State::ToDo),
+            details("done", 1, State::Done),
+            details("soon", 3, State::Incomplete),
+            details("now", 1, State::ToDo),
+        ];
+        let res = next_tasks(tasks, today, &UrgencyConfig::default(), 2);
+        assert_eq!(
+            res.iter()
+                .map(|(_, d)| d.task.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["now", "soon"]
+        );
+    }
+}
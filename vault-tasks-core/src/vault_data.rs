@@ -0,0 +1,552 @@
+use std::{
+    collections::HashMap,
+    fmt::{Display, Write},
+};
+
+use super::{
+    task::{format_estimate, Task},
+    PrettySymbolsConfig, TasksConfig,
+};
+
+// Tasks make up most of a vault, boxing them would cost more than it saves
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VaultData {
+    /// Name, Content
+    Directory(String, Vec<VaultData>),
+    /// Name, Content
+    Header(usize, String, Vec<VaultData>),
+    /// Task, Subtasks
+    Task(Task),
+}
+
+/// Unfinished tasks of an entry and the sum of their estimates.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct EstimateSummary {
+    pub tasks: usize,
+    /// Remaining time in minutes
+    pub remaining: u32,
+    /// Whether at least one unfinished task has an estimate
+    pub has_estimates: bool,
+}
+
+impl EstimateSummary {
+    fn add_task(&mut self, task: &Task) {
+        if !task.state.is_finished() {
+            self.tasks += 1;
+            if let Some(estimate) = task.estimate {
+                self.remaining += estimate;
+                self.has_estimates = true;
+            }
+        }
+        task.subtasks.iter().for_each(|t| self.add_task(t));
+    }
+    /// Describes the summary, e.g. `"Backlog — 7 tasks, ~5h30 remaining"`.
+    ///
+    /// Returns `None` if no task has an estimate.
+    #[must_use]
+    pub fn describe(&self, name: &str) -> Option<String> {
+        self.has_estimates.then(|| {
+            format!(
+                "{name} — {} task{}, ~{} remaining",
+                self.tasks,
+                if self.tasks == 1 { "" } else { "s" },
+                format_estimate(self.remaining)
+            )
+        })
+    }
+}
+
+/// Kinds of entries kept by [`VaultData::outline`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    Task,
+    /// Directories, files and headers
+    Header,
+}
+
+/// What [`VaultData::outline`] keeps of a vault.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OutlineOptions {
+    /// Deepest level of entries kept, the outlined entry being at level 0
+    pub depth: Option<usize>,
+    pub only: Option<EntryKind>,
+    /// Only keep unfinished (`true`) or finished (`false`) tasks, and the tasks, headers and files holding them
+    pub open: Option<bool>,
+}
+
+impl VaultData {
+    /// Returns the entries of this one kept by `options`, to print them.
+    ///
+    /// With `only` set to [`EntryKind::Task`], tasks are returned without the directories and headers holding them.
+    #[must_use]
+    pub fn outline(&self, options: &OutlineOptions) -> Vec<Self> {
+        fn is_kept(task: &Task, open: Option<bool>) -> bool {
+            open.is_none_or(|open| open != task.state.is_finished())
+        }
+        fn has_kept_tasks(entry: &VaultData, open: Option<bool>) -> bool {
+            fn aux(task: &Task, open: Option<bool>) -> bool {
+                is_kept(task, open) || task.subtasks.iter().any(|t| aux(t, open))
+            }
+            match entry {
+                VaultData::Directory(_, children) | VaultData::Header(_, _, children) => {
+                    children.iter().any(|c| has_kept_tasks(c, open))
+                }
+                VaultData::Task(task) => aux(task, open),
+            }
+        }
+        fn outline_task(task: &Task, options: &OutlineOptions, depth: usize) -> Option<Task> {
+            let subtasks = if options.depth.is_some_and(|d| depth >= d) {
+                vec![]
+            } else {
+                task.subtasks
+                    .iter()
+                    .filter_map(|t| outline_task(t, options, depth + 1))
+                    .collect()
+            };
+            (is_kept(task, options.open) || !subtasks.is_empty()).then(|| Task {
+                subtasks,
+                ..task.clone()
+            })
+        }
+        fn aux(entry: &VaultData, options: &OutlineOptions, depth: usize) -> Vec<VaultData> {
+            if options.depth.is_some_and(|d| depth > d)
+                || (options.open.is_some() && !has_kept_tasks(entry, options.open))
+            {
+                return vec![];
+            }
+            let outline_children = |children: &[VaultData]| {
+                children
+                    .iter()
+                    .flat_map(|c| aux(c, options, depth + 1))
+                    .collect::<Vec<VaultData>>()
+            };
+            match entry {
+                VaultData::Directory(_, children) | VaultData::Header(_, _, children)
+                    if options.only == Some(EntryKind::Task) =>
+                {
+                    outline_children(children)
+                }
+                VaultData::Directory(name, children) => {
+                    vec![VaultData::Directory(
+                        name.clone(),
+                        outline_children(children),
+                    )]
+                }
+                VaultData::Header(level, name, children) => vec![VaultData::Header(
+                    *level,
+                    name.clone(),
+                    outline_children(children),
+                )],
+                VaultData::Task(_) if options.only == Some(EntryKind::Header) => vec![],
+                VaultData::Task(task) => outline_task(task, options, depth)
+                    .map(VaultData::Task)
+                    .into_iter()
+                    .collect(),
+            }
+        }
+        aux(self, options, 0)
+    }
+    /// Aggregates the estimates of the unfinished tasks and subtasks of this entry.
+    #[must_use]
+    pub fn estimate_summary(&self) -> EstimateSummary {
+        let mut res = EstimateSummary::default();
+        self.add_to_summary(&mut res);
+        res
+    }
+    fn add_to_summary(&self, summary: &mut EstimateSummary) {
+        match self {
+            Self::Directory(_, children) | Self::Header(_, _, children) => {
+                children.iter().for_each(|c| c.add_to_summary(summary));
+            }
+            Self::Task(task) => summary.add_task(task),
+        }
+    }
+    /// Counts every task and subtask of this entry.
+    #[must_use]
+    pub fn count_tasks(&self) -> usize {
+        fn count_task(task: &Task) -> usize {
+            1 + task.subtasks.iter().map(count_task).sum::<usize>()
+        }
+        match self {
+            Self::Directory(_, children) | Self::Header(_, _, children) => {
+                children.iter().map(Self::count_tasks).sum()
+            }
+            Self::Task(task) => count_task(task),
+        }
+    }
+    /// Returns the names of the parent tasks of every subtask, outermost first.
+    ///
+    /// Subtasks are identified by their file name and line number.
+    #[must_use]
+    pub fn parent_tasks(&self) -> HashMap<(String, usize), Vec<String>> {
+        fn aux(
+            task: &Task,
+            parents: &mut Vec<String>,
+            res: &mut HashMap<(String, usize), Vec<String>>,
+        ) {
+            if !parents.is_empty() {
+                res.insert((task.filename.clone(), task.line_number), parents.clone());
+            }
+            parents.push(task.name.clone());
+            task.subtasks.iter().for_each(|t| aux(t, parents, res));
+            parents.pop();
+        }
+        fn aux_vault_data(vault_data: &VaultData, res: &mut HashMap<(String, usize), Vec<String>>) {
+            match vault_data {
+                VaultData::Directory(_, children) | VaultData::Header(_, _, children) => {
+                    children.iter().for_each(|c| aux_vault_data(c, res));
+                }
+                VaultData::Task(task) => aux(task, &mut vec![], res),
+            }
+        }
+        let mut res = HashMap::new();
+        aux_vault_data(self, &mut res);
+        res
+    }
+    /// Sorts the tasks of every directory and header and the subtasks of every task with `sort`.
+    ///
+    /// Directories and headers stay where they are, tasks are only sorted among themselves.
+    pub fn sort_tasks(&mut self, sort: &impl Fn(&mut [Task])) {
+        fn aux(task: &mut Task, sort: &impl Fn(&mut [Task])) {
+            task.subtasks.iter_mut().for_each(|t| aux(t, sort));
+            sort(&mut task.subtasks);
+        }
+        match self {
+            Self::Directory(_, children) | Self::Header(_, _, children) => {
+                children.iter_mut().for_each(|c| c.sort_tasks(sort));
+                let mut tasks = children
+                    .iter()
+                    .filter_map(|c| match c {
+                        Self::Task(task) => Some(task.clone()),
+                        _ => None,
+                    })
+                    .collect::<Vec<Task>>();
+                sort(&mut tasks);
+                let mut tasks = tasks.into_iter();
+                for child in children.iter_mut().filter(|c| matches!(c, Self::Task(_))) {
+                    if let Some(task) = tasks.next() {
+                        *child = Self::Task(task);
+                    }
+                }
+            }
+            Self::Task(task) => aux(task, sort),
+        }
+    }
+}
+
+impl Display for VaultData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write_with_symbols(f, &PrettySymbolsConfig::default(), '‾')
+    }
+}
+
+impl VaultData {
+    /// Formats the entry like `Display` does, using the symbols of the config.
+    #[must_use]
+    pub fn to_string_with_config(&self, config: &TasksConfig) -> String {
+        let underline = if config.ascii_mode { '-' } else { '‾' };
+        let mut res = String::new();
+        // Writing to a `String` can't fail
+        let _ = self.write_with_symbols(&mut res, &config.pretty_symbols, underline);
+        res
+    }
+
+    fn write_with_symbols(
+        &self,
+        f: &mut impl Write,
+        symbols: &PrettySymbolsConfig,
+        underline: char,
+    ) -> std::fmt::Result {
+        fn write_indent(indent_length: usize, f: &mut impl Write) -> std::fmt::Result {
+            (1..=indent_length).try_for_each(|_| write!(f, "\t"))?;
+            Ok(())
+        }
+        fn write_underline_with_indent(
+            text: &str,
+            indent_length: usize,
+            underline: char,
+            f: &mut impl Write,
+        ) -> std::fmt::Result {
+            write_indent(indent_length, f)?;
+            writeln!(f, "{text}")?;
+            write_indent(indent_length, f)?;
+            for _i in 0..(text.len()) {
+                write!(f, "{underline}")?;
+            }
+            writeln!(f)?;
+            Ok(())
+        }
+        fn write_task(
+            task: &Task,
+            f: &mut impl Write,
+            symbols: &PrettySymbolsConfig,
+            depth: usize,
+        ) -> std::fmt::Result {
+            let mut task_str = String::new();
+            task.write_with_symbols(&mut task_str, symbols)?;
+            for line in task_str.split('\n') {
+                write_indent(depth, f)?;
+                writeln!(f, "{line}")?;
+            }
+            Ok(())
+        }
+        fn fmt_aux(
+            file_entry: &VaultData,
+            f: &mut impl Write,
+            symbols: &PrettySymbolsConfig,
+            underline: char,
+            depth: usize,
+        ) -> std::fmt::Result {
+            match file_entry {
+                VaultData::Header(_, header, entries) => {
+                    write_underline_with_indent(&header.to_string(), depth, underline, f)?;
+                    for entry in entries {
+                        fmt_aux(entry, f, symbols, underline, depth + 1)?;
+                    }
+                }
+                VaultData::Directory(name, entries) => {
+                    write_underline_with_indent(&name.to_string(), depth, underline, f)?;
+                    for entry in entries {
+                        fmt_aux(entry, f, symbols, underline, depth + 1)?;
+                    }
+                }
+                VaultData::Task(task) => {
+                    write_task(task, f, symbols, depth)?;
+                    for subtask in &task.subtasks {
+                        let mut subtask_str = String::new();
+                        fmt_aux(
+                            &VaultData::Task(subtask.clone()),
+                            &mut subtask_str,
+                            symbols,
+                            underline,
+                            0,
+                        )?;
+                        for line in subtask_str.split('\n') {
+                            write_indent(depth + 1, f)?;
+                            writeln!(f, "{line}")?;
+                        }
+                    }
+                }
+            }
+            Ok(())
+        }
+        fmt_aux(self, f, symbols, underline, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::{EntryKind, OutlineOptions, VaultData};
+    use crate::{
+        task::{State, Task},
+        PrettySymbolsConfig, TasksConfig,
+    };
+
+    #[test]
+    fn test_to_string_ascii_mode() {
+        let vault_data = VaultData::Directory(
+            "note.md".to_owned(),
+            vec![VaultData::Task(Task {
+                name: "task".to_owned(),
+                state: State::Done,
+                priority: 2,
+                is_today: true,
+                subtasks: vec![Task {
+                    name: "subtask".to_owned(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            })],
+        );
+        let config = TasksConfig {
+            ascii_mode: true,
+            pretty_symbols: PrettySymbolsConfig::ascii(),
+            ..Default::default()
+        };
+        assert_eq!(
+            vault_data.to_string_with_config(&config),
+            "note.md\n-------\n\t[x] task\n\t+ !2 \n\t\n\t\t[ ] subtask\n\t\t\n\t\t\n"
+        );
+        assert!(vault_data.to_string_with_config(&config).is_ascii());
+    }
+
+    #[test]
+    fn test_estimate_summary() {
+        let vault_data = VaultData::Header(
+            1,
+            "Backlog".to_owned(),
+            vec![
+                VaultData::Task(Task {
+                    estimate: Some(90),
+                    subtasks: vec![
+                        Task {
+                            estimate: Some(30),
+                            ..Default::default()
+                        },
+                        Task {
+                            state: State::Done,
+                            estimate: Some(60),
+                            ..Default::default()
+                        },
+                    ],
+                    ..Default::default()
+                }),
+                VaultData::Task(Task::default()),
+            ],
+        );
+        let summary = vault_data.estimate_summary();
+        assert_eq!(summary.tasks, 3);
+        assert_eq!(summary.remaining, 120);
+        assert_eq!(
+            summary.describe("Backlog"),
+            Some(String::from("Backlog — 3 tasks, ~2h remaining"))
+        );
+        assert_eq!(
+            VaultData::Task(Task::default())
+                .estimate_summary()
+                .describe("Nothing"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parent_tasks() {
+        let task = |name: &str, line_number, subtasks| Task {
+            name: name.to_owned(),
+            filename: "note.md".to_owned(),
+            line_number,
+            subtasks,
+            ..Default::default()
+        };
+        let vault_data = VaultData::Directory(
+            "note.md".to_owned(),
+            vec![VaultData::Task(task(
+                "release",
+                1,
+                vec![task("tests", 2, vec![task("unit", 3, vec![])])],
+            ))],
+        );
+        let parents = vault_data.parent_tasks();
+        assert_eq!(parents.len(), 2);
+        assert_eq!(parents[&("note.md".to_owned(), 2)], vec!["release"]);
+        assert_eq!(
+            parents[&("note.md".to_owned(), 3)],
+            vec!["release", "tests"]
+        );
+    }
+
+    #[test]
+    fn test_sort_tasks() {
+        let task = |name: &str, subtasks| Task {
+            name: name.to_owned(),
+            subtasks,
+            ..Default::default()
+        };
+        let mut vault_data = VaultData::Header(
+            0,
+            "note.md".to_owned(),
+            vec![
+                VaultData::Task(task("b", vec![task("d", vec![]), task("c", vec![])])),
+                VaultData::Header(1, "z".to_owned(), vec![]),
+                VaultData::Task(task("a", vec![])),
+            ],
+        );
+        vault_data.sort_tasks(&|tasks: &mut [Task]| tasks.sort_by(|a, b| a.name.cmp(&b.name)));
+        let VaultData::Header(_, _, children) = vault_data else {
+            panic!("not a header");
+        };
+        let names = children
+            .iter()
+            .map(|c| match c {
+                VaultData::Task(t) => {
+                    let subtasks = t
+                        .subtasks
+                        .iter()
+                        .map(|t| t.name.as_str())
+                        .collect::<String>();
+                    format!("{}{subtasks}", t.name)
+                }
+                VaultData::Header(_, name, _) | VaultData::Directory(name, _) => name.clone(),
+            })
+            .collect::<Vec<String>>();
+        assert_eq!(names, vec!["a", "z", "bcd"]);
+    }
+
+    #[test]
+    fn test_outline() {
+        let task = |name: &str, state, subtasks| Task {
+            name: name.to_owned(),
+            state,
+            subtasks,
+            ..Default::default()
+        };
+        let vault_data = VaultData::Directory(
+            "note.md".to_owned(),
+            vec![
+                VaultData::Header(
+                    1,
+                    "Backlog".to_owned(),
+                    vec![
+                        VaultData::Task(task(
+                            "open",
+                            State::ToDo,
+                            vec![task("done sub", State::Done, vec![])],
+                        )),
+                        VaultData::Header(2, "Later".to_owned(), vec![]),
+                    ],
+                ),
+                VaultData::Task(task("done", State::Canceled, vec![])),
+            ],
+        );
+        let outline = |options: OutlineOptions| {
+            vault_data
+                .outline(&options)
+                .iter()
+                .map(ToString::to_string)
+                .collect::<String>()
+                .replace('‾', "")
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+                .collect::<Vec<&str>>()
+                .join(", ")
+        };
+
+        assert_eq!(
+            outline(OutlineOptions::default()),
+            "note.md, Backlog, ❌ open, ✅ done sub, Later, 🚫 done"
+        );
+        assert_eq!(
+            outline(OutlineOptions {
+                depth: Some(2),
+                ..Default::default()
+            }),
+            "note.md, Backlog, ❌ open, Later, 🚫 done"
+        );
+        assert_eq!(
+            outline(OutlineOptions {
+                only: Some(EntryKind::Header),
+                ..Default::default()
+            }),
+            "note.md, Backlog, Later"
+        );
+        assert_eq!(
+            outline(OutlineOptions {
+                only: Some(EntryKind::Task),
+                open: Some(true),
+                ..Default::default()
+            }),
+            "❌ open"
+        );
+        assert_eq!(
+            outline(OutlineOptions {
+                open: Some(false),
+                ..Default::default()
+            }),
+            "note.md, Backlog, ❌ open, ✅ done sub, 🚫 done"
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::Result;
+
+use super::{
+    details::{filter_to_details, state_name, TaskDetails},
+    filter::Filter,
+    mark::write_changes,
+    task::Task,
+    vault_data::VaultData,
+    TasksConfig,
+};
+
+/// A task found in both copies of a vault, in different states.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateDiff {
+    /// The task in this vault
+    pub ours: TaskDetails,
+    /// The task in the other vault
+    pub theirs: Task,
+}
+
+impl StateDiff {
+    /// Whether the task was only finished in the other vault, its state is then the one to keep.
+    #[must_use]
+    pub const fn finished_there(&self) -> bool {
+        !self.ours.task.state.is_finished() && self.theirs.state.is_finished()
+    }
+}
+
+impl Display for StateDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}: {}: {} here, {} there",
+            self.ours.file.display(),
+            self.ours.task.line_number,
+            self.ours.task.name,
+            state_name(&self.ours.task.state),
+            state_name(&self.theirs.state)
+        )
+    }
+}
+
+/// Returns the tasks of a vault by file, relative to the vault, and identifier.
+///
+/// Tasks are identified by their block identifier (`^id`), or else by their name and how many tasks of the file have the same name before them.
+fn keyed_tasks(
+    vault_data: &VaultData,
+    vault_path: &Path,
+) -> HashMap<(PathBuf, String), TaskDetails> {
+    let mut res = HashMap::new();
+    let mut occurrences = HashMap::<(PathBuf, String), usize>::new();
+    for details in filter_to_details(vault_data, &Filter::default()) {
+        let file = details
+            .file
+            .strip_prefix(vault_path)
+            .unwrap_or(&details.file)
+            .to_path_buf();
+        let id = if let Some(id) = &details.task.block_id {
+            format!("^{id}")
+        } else {
+            let count = occurrences
+                .entry((file.clone(), details.task.name.clone()))
+                .or_default();
+            *count += 1;
+            format!("{}#{count}", details.task.name)
+        };
+        res.insert((file, id), details);
+    }
+    res
+}
+
+/// Compares the states of the tasks found in both copies of a vault, sorted by file and line.
+///
+/// Tasks found in one copy only are left out.
+#[must_use]
+pub fn compare_vaults(
+    ours: &VaultData,
+    ours_path: &Path,
+    theirs: &VaultData,
+    theirs_path: &Path,
+) -> Vec<StateDiff> {
+    let mut theirs = keyed_tasks(theirs, theirs_path);
+    let mut res = keyed_tasks(ours, ours_path)
+        .into_iter()
+        .filter_map(|(key, ours)| {
+            let theirs = theirs.remove(&key)?.task;
+            (ours.task.state != theirs.state).then_some(StateDiff { ours, theirs })
+        })
+        .collect::<Vec<StateDiff>>();
+    res.sort_by(|a, b| {
+        a.ours
+            .file
+            .cmp(&b.ours.file)
+            .then(a.ours.task.line_number.cmp(&b.ours.task.line_number))
+    });
+    res
+}
+
+/// Writes the state and completion date of the tasks only finished in the other vault, see [`StateDiff::finished_there`].
+///
+/// Returns the number of tasks written.
+///
+/// # Errors
+///
+/// Will return an error if the vault is read-only or if a task can't be written.
+pub fn apply_finished(config: &TasksConfig, diffs: &[StateDiff]) -> Result<usize> {
+    let tasks = diffs
+        .iter()
+        .filter(|diff| diff.finished_there())
+        .map(|diff| {
+            let mut details = diff.ours.clone();
+            details.task.state = diff.theirs.state.clone();
+            details.task.completed = diff.theirs.completed.or(details.task.completed);
+            details
+        })
+        .collect::<Vec<TaskDetails>>();
+    write_changes(config, &tasks, &|_| ())?;
+    Ok(tasks.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{apply_finished, compare_vaults, StateDiff};
+    use crate::{TaskManager, TasksConfig};
+
+    #[test]
+    fn test_compare_vaults() {
+        let dir = std::env::temp_dir().join("vault-tasks-diff");
+        let _ = fs::remove_dir_all(&dir);
+        let (laptop, desktop) = (dir.join("laptop"), dir.join("desktop"));
+        for (path, content) in [
+            (
+                &laptop,
+                "- [ ] water plants\n- [ ] call bank ^bank\n- [x] cook\n- [ ] cook\n- [ ] laptop only\n",
+            ),
+            (
+                &desktop,
+                "- [ ] desktop only\n- [x] call bank ^bank\n- [x] cook\n- [-] cook\n- [ ] water plants\n",
+            ),
+        ] {
+            fs::create_dir_all(path).unwrap();
+            fs::write(path.join("chores.md"), content).unwrap();
+        }
+        let config = |path: &std::path::Path| TasksConfig {
+            vault_path: path.to_path_buf(),
+            indent_length: 2,
+            ..Default::default()
+        };
+        let compare = || {
+            compare_vaults(
+                &TaskManager::load_read_only(&config(&laptop)).unwrap().tasks,
+                &laptop,
+                &TaskManager::load_read_only(&config(&desktop))
+                    .unwrap()
+                    .tasks,
+                &desktop,
+            )
+        };
+
+        let diffs = compare();
+        assert_eq!(
+            diffs
+                .iter()
+                .map(|d| d.to_string().replace(&laptop.display().to_string(), ""))
+                .collect::<Vec<String>>(),
+            vec![
+                "/chores.md:2: call bank: todo here, done there",
+                "/chores.md:4: cook: todo here, canceled there"
+            ]
+        );
+        assert!(diffs.iter().all(StateDiff::finished_there));
+
+        assert_eq!(apply_finished(&config(&laptop), &diffs).unwrap(), 2);
+        assert_eq!(
+            fs::read_to_string(laptop.join("chores.md")).unwrap(),
+            "- [ ] water plants\n- [x] call bank ^bank\n- [x] cook\n- [-] cook\n- [ ] laptop only\n"
+        );
+        assert!(compare().is_empty());
+    }
+}
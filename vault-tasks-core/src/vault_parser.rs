@@ -0,0 +1,318 @@
+use chrono::{DateTime, Local, NaiveDateTime};
+use color_eyre::{eyre::bail, Result};
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    fs::{self, DirEntry},
+    path::{Path, PathBuf},
+};
+use tracing::{debug, error, info};
+
+use crate::{parser::parser_file_entry::ParserFileEntry, TasksConfig};
+
+use super::vault_data::VaultData;
+
+/// An Obsidian Canvas file, only text nodes are kept.
+#[derive(Deserialize)]
+struct Canvas {
+    #[serde(default)]
+    nodes: Vec<CanvasNode>,
+}
+#[derive(Deserialize)]
+struct CanvasNode {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    text: String,
+}
+
+pub struct VaultParser {
+    config: TasksConfig,
+    /// Last modification time of every parsed file
+    pub modification_times: HashMap<PathBuf, NaiveDateTime>,
+}
+
+impl VaultParser {
+    pub fn new(config: TasksConfig) -> Self {
+        Self {
+            config,
+            modification_times: HashMap::new(),
+        }
+    }
+    pub fn scan_vault(&mut self) -> Result<VaultData> {
+        if !self.config.scope.as_os_str().is_empty() {
+            return self.scan_scope(&self.config.scope.clone());
+        }
+        let mut tasks =
+            VaultData::Directory(self.config.vault_path.to_str().unwrap().to_owned(), vec![]);
+        info!("Scanning {:?}", self.config.vault_path);
+        self.scan(&self.config.vault_path.clone(), &mut tasks)?;
+        Ok(tasks)
+    }
+
+    /// Scans only `scope`, a file or directory relative to the vault.
+    ///
+    /// Directories leading to the scope are kept so paths stay relative to the vault.
+    fn scan_scope(&mut self, scope: &Path) -> Result<VaultData> {
+        let root_name = self.config.vault_path.to_str().unwrap().to_owned();
+        let scope_path = self.config.vault_path.join(scope);
+        if !self.config.vault_path.is_dir() || !scope_path.exists() {
+            bail!("Scope {scope:?} is not part of the vault");
+        }
+        info!("Scanning {scope_path:?}");
+
+        let dirs = if scope_path.is_dir() {
+            scope
+        } else {
+            scope.parent().unwrap_or(Path::new(""))
+        }
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<String>>();
+
+        let mut tasks = VaultData::Directory(
+            dirs.last().cloned().unwrap_or_else(|| root_name.clone()),
+            vec![],
+        );
+        self.scan(&scope_path, &mut tasks)?;
+        if dirs.is_empty() {
+            return Ok(tasks);
+        }
+        for name in dirs.iter().rev().skip(1) {
+            tasks = VaultData::Directory(name.clone(), vec![tasks]);
+        }
+        Ok(VaultData::Directory(root_name, vec![tasks]))
+    }
+
+    fn scan(&mut self, path: &Path, tasks: &mut VaultData) -> Result<()> {
+        if self.config.ignored.contains(&path.to_owned()) {
+            debug!("Ignoring {path:?} (ignored list)");
+            return Ok(());
+        }
+
+        let entries = if path.is_dir() {
+            path.read_dir()?
+                .collect::<Vec<Result<DirEntry, std::io::Error>>>()
+        } else {
+            path.parent()
+                .unwrap()
+                .read_dir()?
+                .filter(|e| {
+                    let e = e.as_ref().unwrap();
+                    e.file_name().eq(&path.file_name().unwrap())
+                })
+                .collect::<Vec<Result<DirEntry, std::io::Error>>>()
+        };
+
+        for entry_err in entries {
+            let Ok(entry) = entry_err else { continue };
+            let name = entry.file_name().into_string().unwrap();
+            if !self.config.parse_dot_files && name.starts_with('.') {
+                debug!("Ignoring {name:?} (dot file)");
+                continue;
+            }
+            if self.config.ignored.contains(&entry.path()) {
+                debug!("Ignoring {name:?} (ignored list)");
+                continue;
+            }
+
+            if let VaultData::Directory(_, children) = tasks {
+                if entry.path().is_dir() {
+                    // recursive call for this subdir
+                    let mut new_child = VaultData::Directory(
+                        entry.file_name().to_str().unwrap().to_owned(),
+                        vec![],
+                    );
+
+                    self.scan(&entry.path(), &mut new_child)?;
+
+                    if let VaultData::Directory(_, c) = new_child.clone() {
+                        if !c.is_empty() {
+                            children.push(new_child);
+                        }
+                    }
+                } else if self.is_parsed_file(&entry.path()) {
+                    if let Some(file_tasks) = self.parse_path(&entry.path()) {
+                        children.push(file_tasks);
+                    }
+                } else {
+                    debug!("Ignoring {name:?} (not a .md file)");
+                }
+            } else {
+                bail!("Error while scanning directories, FileEntry was not a Directory");
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether `path` is a file whose tasks are parsed: a Markdown file, or a canvas file if enabled.
+    pub fn is_parsed_file(&self, path: &Path) -> bool {
+        is_markdown_file(path) || (self.config.parse_canvas_files && is_canvas_file(path))
+    }
+
+    /// Parses a Markdown or canvas file and records its modification time.
+    pub fn parse_path(&mut self, path: &Path) -> Option<VaultData> {
+        self.record_modification_time(path);
+        if is_canvas_file(path) {
+            self.parse_canvas(path)
+        } else {
+            self.parse_file(path)
+        }
+    }
+
+    fn record_modification_time(&mut self, path: &Path) {
+        if let Ok(modified) = fs::metadata(path).and_then(|m| m.modified()) {
+            self.modification_times.insert(
+                path.to_path_buf(),
+                DateTime::<Local>::from(modified).naive_local(),
+            );
+        }
+    }
+
+    fn parse_file(&self, path: &Path) -> Option<VaultData> {
+        debug!("Parsing {:?}", path.file_name()?);
+        let content = fs::read_to_string(path).unwrap_or_default();
+        let mut parser = ParserFileEntry {
+            config: &self.config,
+            filename: String::new(),
+        };
+
+        parser.parse_file(path.file_name()?.to_str()?, &content.as_str())
+    }
+
+    /// Parses the text nodes of a canvas file, each node is parsed as a separate Markdown file.
+    fn parse_canvas(&self, path: &Path) -> Option<VaultData> {
+        debug!("Parsing canvas {:?}", path.file_name()?);
+        let content = fs::read_to_string(path).unwrap_or_default();
+        let canvas = match serde_json::from_str::<Canvas>(&content) {
+            Ok(canvas) => canvas,
+            Err(e) => {
+                error!("Failed to parse canvas {path:?}: {e}");
+                return None;
+            }
+        };
+        let filename = path.file_name()?.to_str()?.to_owned();
+        let mut parser = ParserFileEntry {
+            config: &self.config,
+            filename: String::new(),
+        };
+
+        let children = canvas
+            .nodes
+            .iter()
+            .filter(|node| node.kind == "text")
+            .filter_map(|node| parser.parse_file(&filename, &node.text.as_str()))
+            .flat_map(|file_entry| match file_entry {
+                VaultData::Directory(_, children) => children,
+                other => vec![other],
+            })
+            .collect::<Vec<VaultData>>();
+
+        if children.is_empty() {
+            None
+        } else {
+            Some(VaultData::Directory(filename, children))
+        }
+    }
+}
+
+/// Whether `path` is a Markdown file.
+pub fn is_markdown_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("md"))
+}
+
+/// Whether `path` is an Obsidian Canvas file. Those are read-only.
+pub fn is_canvas_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("canvas"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use insta::assert_snapshot;
+
+    use super::{VaultData, VaultParser};
+    use crate::TasksConfig;
+
+    #[test]
+    fn test_parse_canvas() {
+        let vault_path = std::env::temp_dir().join("vault-tasks-canvas");
+        let _ = fs::remove_dir_all(&vault_path);
+        fs::create_dir_all(&vault_path).unwrap();
+        fs::write(
+            vault_path.join("board.canvas"),
+            r##"{"nodes":[
+                {"id":"1","type":"text","text":"# Ideas\n- [ ] from canvas #idea\n- [x] done"},
+                {"id":"2","type":"file","file":"notes.md"},
+                {"id":"3","type":"text","text":"- [ ] another node"}
+            ],"edges":[]}"##,
+        )
+        .unwrap();
+
+        let mut config = TasksConfig {
+            vault_path: vault_path.clone(),
+            indent_length: 2,
+            ..Default::default()
+        };
+        let res = VaultParser::new(config.clone()).scan_vault().unwrap();
+        assert_eq!(res.to_string().lines().count(), 2);
+
+        config.parse_canvas_files = true;
+        let mut parser = VaultParser::new(config);
+        let mut res = parser.scan_vault().unwrap();
+        assert!(parser
+            .modification_times
+            .contains_key(&vault_path.join("board.canvas")));
+        if let crate::vault_data::VaultData::Directory(name, _) = &mut res {
+            *name = String::from("vault");
+        }
+        assert_snapshot!(res);
+    }
+
+    #[test]
+    fn test_scan_scope() {
+        let vault_path = std::env::temp_dir().join("vault-tasks-scope");
+        let _ = fs::remove_dir_all(&vault_path);
+        fs::create_dir_all(vault_path.join("projects/acme")).unwrap();
+        fs::write(
+            vault_path.join("projects/acme/plan.md"),
+            "- [ ] acme task\n",
+        )
+        .unwrap();
+        fs::write(vault_path.join("projects/other.md"), "- [ ] other task\n").unwrap();
+        fs::write(vault_path.join("root.md"), "- [ ] root task\n").unwrap();
+
+        let mut config = TasksConfig {
+            vault_path: vault_path.clone(),
+            indent_length: 2,
+            scope: "projects/acme".into(),
+            ..Default::default()
+        };
+        let root = vault_path.to_str().unwrap().to_owned();
+        let res = VaultParser::new(config.clone()).scan_vault().unwrap();
+        let VaultData::Directory(name, children) = &res else {
+            panic!("Root is not a directory")
+        };
+        assert_eq!(name, &root);
+        let [VaultData::Directory(projects, children)] = children.as_slice() else {
+            panic!("Expected a single directory, got {children:?}")
+        };
+        assert_eq!(projects, "projects");
+        let [VaultData::Directory(acme, children)] = children.as_slice() else {
+            panic!("Expected a single directory, got {children:?}")
+        };
+        assert_eq!(acme, "acme");
+        assert_eq!(children.len(), 1);
+
+        config.scope = "root.md".into();
+        let res = VaultParser::new(config.clone()).scan_vault().unwrap();
+        assert!(res.to_string().contains("root task"));
+        assert!(!res.to_string().contains("other task"));
+
+        config.scope = "missing".into();
+        assert!(VaultParser::new(config).scan_vault().is_err());
+    }
+}
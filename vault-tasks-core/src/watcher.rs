@@ -0,0 +1,238 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::mpsc::{channel, Receiver},
+    time::{Duration, SystemTime},
+};
+
+use notify::{EventKind, RecursiveMode, Watcher};
+
+use super::{vault_parser::VaultParser, TasksConfig};
+
+/// Returns the directory or file of the vault that is parsed.
+fn watched_root(config: &TasksConfig) -> PathBuf {
+    if config.scope.as_os_str().is_empty() {
+        config.vault_path.clone()
+    } else {
+        config.vault_path.join(&config.scope)
+    }
+}
+
+/// Whether `path` is a file the vault parser reads, outside of ignored paths and hidden directories.
+fn is_watched(config: &TasksConfig, parser: &VaultParser, path: &Path) -> bool {
+    let Ok(relative_path) = path.strip_prefix(&config.vault_path) else {
+        return false;
+    };
+    parser.is_parsed_file(path)
+        && !config
+            .ignored
+            .iter()
+            .any(|ignored| path.starts_with(ignored))
+        && (config.parse_dot_files
+            || !relative_path
+                .iter()
+                .any(|c| c.to_string_lossy().starts_with('.')))
+}
+
+/// Watches the vault with the notifications of the filesystem, see `TaskManager::watch`.
+///
+/// # Errors
+///
+/// Will return an error if the filesystem can't notify changes to the vault.
+pub fn watch_vault(
+    config: &TasksConfig,
+    interval: Duration,
+) -> notify::Result<Receiver<Vec<PathBuf>>> {
+    let (events_tx, events) = channel();
+    let mut watcher = notify::recommended_watcher(events_tx)?;
+    watcher.watch(&watched_root(config), RecursiveMode::Recursive)?;
+    let (tx, rx) = channel();
+    let config = config.clone();
+    std::thread::spawn(move || {
+        // Notifications stop when the watcher is dropped along with the thread
+        let _watcher = watcher;
+        let parser = VaultParser::new(config.clone());
+        loop {
+            let mut changed = events
+                .recv_timeout(interval)
+                .into_iter()
+                .chain(events.try_iter())
+                .filter_map(Result::ok)
+                .filter(|event| !matches!(event.kind, EventKind::Access(_)))
+                .flat_map(|event| event.paths)
+                .filter(|path| is_watched(&config, &parser, path))
+                .collect::<Vec<PathBuf>>();
+            changed.sort();
+            changed.dedup();
+            // Sent even if empty, to stop as soon as the receiver is dropped
+            if tx.send(changed).is_err() {
+                break;
+            }
+        }
+    });
+    Ok(rx)
+}
+
+/// Polls the vault for parsed files that were created, modified or removed, for filesystems that can't notify changes.
+pub struct VaultWatcher {
+    config: TasksConfig,
+    /// Modification times seen at the last poll
+    modification_times: HashMap<PathBuf, SystemTime>,
+}
+
+impl VaultWatcher {
+    /// Creates a watcher, files of the vault are considered unchanged until the next poll.
+    #[must_use]
+    pub fn new(config: TasksConfig) -> Self {
+        let mut res = Self {
+            config,
+            modification_times: HashMap::new(),
+        };
+        res.modification_times = res.scan();
+        res
+    }
+
+    /// Returns the files created, modified or removed since the last poll, sorted.
+    pub fn poll(&mut self) -> Vec<PathBuf> {
+        let modification_times = self.scan();
+        let mut res = modification_times
+            .iter()
+            .filter(|(path, modified)| self.modification_times.get(*path) != Some(modified))
+            .map(|(path, _)| path.clone())
+            .chain(
+                self.modification_times
+                    .keys()
+                    .filter(|path| !modification_times.contains_key(*path))
+                    .cloned(),
+            )
+            .collect::<Vec<PathBuf>>();
+        res.sort();
+        self.modification_times = modification_times;
+        res
+    }
+
+    /// Returns every parsed file and the ones removed since the last poll, sorted, to reload them whatever their modification times.
+    pub fn all_files(&mut self) -> Vec<PathBuf> {
+        let modification_times = self.scan();
+        let mut res = modification_times
+            .keys()
+            .chain(
+                self.modification_times
+                    .keys()
+                    .filter(|path| !modification_times.contains_key(*path)),
+            )
+            .cloned()
+            .collect::<Vec<PathBuf>>();
+        res.sort();
+        self.modification_times = modification_times;
+        res
+    }
+
+    /// Returns the modification time of every parsed file, following the same rules as the vault parser.
+    fn scan(&self) -> HashMap<PathBuf, SystemTime> {
+        let mut res = HashMap::new();
+        let root = watched_root(&self.config);
+        self.scan_rec(&VaultParser::new(self.config.clone()), &root, &mut res);
+        res
+    }
+
+    fn scan_rec(&self, parser: &VaultParser, path: &Path, res: &mut HashMap<PathBuf, SystemTime>) {
+        if self.config.ignored.contains(&path.to_path_buf()) {
+            return;
+        }
+        if path.is_dir() {
+            let Ok(entries) = path.read_dir() else {
+                return;
+            };
+            for entry in entries.flatten() {
+                if !self.config.parse_dot_files
+                    && entry.file_name().to_string_lossy().starts_with('.')
+                {
+                    continue;
+                }
+                self.scan_rec(parser, &entry.path(), res);
+            }
+        } else if parser.is_parsed_file(path) {
+            if let Ok(modified) = fs::metadata(path).and_then(|m| m.modified()) {
+                res.insert(path.to_path_buf(), modified);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf, time::Duration};
+
+    use pretty_assertions::assert_eq;
+
+    use super::{watch_vault, VaultWatcher};
+    use crate::TasksConfig;
+
+    #[test]
+    fn test_watch_vault() {
+        let vault_path = std::env::temp_dir().join("vault-tasks-watch-vault");
+        let _ = fs::remove_dir_all(&vault_path);
+        fs::create_dir_all(vault_path.join(".obsidian")).unwrap();
+        let config = TasksConfig {
+            vault_path: vault_path.clone(),
+            ..Default::default()
+        };
+        let rx = watch_vault(&config, Duration::from_millis(50)).unwrap();
+        fs::write(vault_path.join(".obsidian/hidden.md"), "").unwrap();
+        fs::write(vault_path.join("image.png"), "").unwrap();
+        fs::write(vault_path.join("note.md"), "- [ ] task\n").unwrap();
+
+        let mut changed = vec![];
+        for _ in 0..100 {
+            changed.extend(rx.recv_timeout(Duration::from_secs(1)).unwrap());
+            if !changed.is_empty() {
+                break;
+            }
+        }
+        changed.dedup();
+        assert_eq!(changed, vec![vault_path.join("note.md")]);
+    }
+
+    #[test]
+    fn test_poll() {
+        let vault_path = std::env::temp_dir().join("vault-tasks-watcher");
+        let _ = fs::remove_dir_all(&vault_path);
+        fs::create_dir_all(vault_path.join(".obsidian")).unwrap();
+        fs::write(vault_path.join("note.md"), "- [ ] task\n").unwrap();
+        fs::write(vault_path.join("old.md"), "- [ ] old\n").unwrap();
+        let config = TasksConfig {
+            vault_path: vault_path.clone(),
+            ..Default::default()
+        };
+        let mut watcher = VaultWatcher::new(config);
+        assert!(watcher.poll().is_empty());
+
+        // Make sure the modification time changes
+        std::thread::sleep(Duration::from_millis(10));
+        fs::write(vault_path.join("note.md"), "- [x] task\n").unwrap();
+        fs::write(vault_path.join("new.md"), "- [ ] new\n").unwrap();
+        fs::write(vault_path.join("image.png"), "").unwrap();
+        fs::write(vault_path.join(".obsidian/hidden.md"), "").unwrap();
+        fs::remove_file(vault_path.join("old.md")).unwrap();
+        assert_eq!(
+            watcher.poll(),
+            ["new.md", "note.md", "old.md"]
+                .iter()
+                .map(|f| vault_path.join(f))
+                .collect::<Vec<PathBuf>>()
+        );
+        assert!(watcher.poll().is_empty());
+
+        fs::remove_file(vault_path.join("new.md")).unwrap();
+        assert_eq!(
+            watcher.all_files(),
+            ["new.md", "note.md"]
+                .iter()
+                .map(|f| vault_path.join(f))
+                .collect::<Vec<PathBuf>>()
+        );
+        assert_eq!(watcher.all_files(), vec![vault_path.join("note.md")]);
+    }
+}